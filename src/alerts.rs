@@ -0,0 +1,125 @@
+use std::{fmt::Display, process};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum AlertCondition {
+    Above,
+    Below,
+}
+
+impl Display for AlertCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AlertCondition::Above => "above",
+            AlertCondition::Below => "below",
+        }
+        .fmt(f)
+    }
+}
+
+/// Fires desktop notification once price of symbol crosses threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Alert {
+    pub(crate) symbol: String,
+    pub(crate) condition: AlertCondition,
+    pub(crate) price: f64,
+    #[serde(default)]
+    pub(crate) triggered: bool,
+}
+
+impl Alert {
+    pub(crate) fn new(symbol: String, condition: AlertCondition, price: f64) -> Self {
+        Self {
+            symbol,
+            condition,
+            price,
+            triggered: false,
+        }
+    }
+
+    pub(crate) fn is_reached(&self, price: f64) -> bool {
+        match self.condition {
+            AlertCondition::Above => price >= self.price,
+            AlertCondition::Below => price <= self.price,
+        }
+    }
+}
+
+impl Display for Alert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.symbol, self.condition, self.price)
+    }
+}
+
+/// Shows desktop notification using tools provided by OS
+///
+/// Failures are only logged, alert is still shown in app
+pub(crate) fn notify(alert: &Alert) {
+    let body = format!("{alert}");
+
+    let command = if cfg!(target_os = "macos") {
+        process::Command::new("osascript")
+            .arg("-e")
+            .arg(format!(
+                "display notification {body:?} with title \"Dynasty price alert\""
+            ))
+            .spawn()
+    } else if cfg!(target_os = "windows") {
+        process::Command::new("powershell")
+            .arg("-c")
+            .arg(balloon_script(&body))
+            .spawn()
+    } else {
+        process::Command::new("notify-send")
+            .args(["Dynasty price alert", &body])
+            .spawn()
+    };
+
+    match command {
+        // waited for on own thread so that exited notifier does not stay a zombie
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(err) => tracing::error!("failed to show notification for {alert}: {err}"),
+    }
+}
+
+/// PowerShell showing tray balloon, icon is kept until balloon times out
+fn balloon_script(body: &str) -> String {
+    // single quoted strings only treat doubled quote specially
+    let body = body.replace('\'', "''");
+
+    format!(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         $icon = New-Object System.Windows.Forms.NotifyIcon; \
+         $icon.Icon = [System.Drawing.SystemIcons]::Information; \
+         $icon.Visible = $true; \
+         $icon.ShowBalloonTip(5000, 'Dynasty price alert', '{body}', 'Info'); \
+         Start-Sleep -Seconds 5; \
+         $icon.Dispose()"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alert_conditions() {
+        let above = Alert::new("BTCUSDT".to_owned(), AlertCondition::Above, 100.0);
+        assert!(above.is_reached(100.0));
+        assert!(above.is_reached(101.0));
+        assert!(!above.is_reached(99.0));
+
+        let below = Alert::new("BTCUSDT".to_owned(), AlertCondition::Below, 100.0);
+        assert!(below.is_reached(99.0));
+        assert!(!below.is_reached(101.0));
+    }
+
+    #[test]
+    fn balloon_body_is_quoted() {
+        let script = balloon_script("BTC's price");
+        assert!(script.contains("'BTC''s price'"));
+    }
+}
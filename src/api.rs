@@ -1,171 +1,785 @@
-use std::sync::{Arc, OnceLock};
+use std::{
+    future::Future,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
 
+use ahash::AHashMap;
 use binance::{
     account::Account,
     api::Binance,
+    config::Config as BinanceConfig,
+    futures::{account::FuturesAccount, market::FuturesMarket},
     market::Market,
-    rest_model::{OrderSide, OrderStatus},
+    rest_model::{OrderSide, OrderStatus, TimeInForce},
+    util::build_signed_request,
 };
-use iced::Command;
+use iced::{Command, Subscription};
 use iced_futures::futures::future::join_all;
 use regex::Regex;
+use serde::Deserialize;
 
-use crate::message::Message;
+use crate::{
+    data::{Fetch, SymbolFilter, Ticker24h, Trade},
+    message::Message,
+    rate_limit::RateLimiter,
+    views::panes::chart::Timeframe,
+    ws::futures::FundingRate,
+};
 
 static SPLIT_SYMBOL_REGEX: OnceLock<Regex> = OnceLock::new();
 
-pub(crate) struct Client {
+const API_V3_24H_TICKER: &str = "/api/v3/ticker/24hr";
+const API_V3_EXCHANGE_INFO: &str = "/api/v3/exchangeInfo";
+const FAPI_V1_EXCHANGE_INFO: &str = "/fapi/v1/exchangeInfo";
+const API_V3_MY_TRADES: &str = "/api/v3/myTrades";
+
+/// Request weights of endpoints, as documented by Binance
+mod weight {
+    pub(super) const ALL_ORDERS: u32 = 20;
+    pub(super) const ACCOUNT: u32 = 20;
+    pub(super) const KLINES: u32 = 2;
+    pub(super) const MY_TRADES: u32 = 20;
+    pub(super) const ALL_TICKERS_24H: u32 = 80;
+    pub(super) const MARK_PRICES: u32 = 10;
+    pub(super) const EXCHANGE_INFO: u32 = 20;
+    pub(super) const FUTURES_EXCHANGE_INFO: u32 = 1;
+    pub(super) const ORDER: u32 = 1;
+}
+
+/// Kind of order placed with `trade_spot`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum OrderType {
+    Limit,
+    Market,
+    StopLimit { stop_price: f64 },
+}
+
+impl OrderType {
+    /// Filled at whatever price market has, entered price is not used
+    pub(crate) fn is_market(&self) -> bool {
+        matches!(self, OrderType::Market)
+    }
+}
+
+/// Executed part of market order, fees are not subtracted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct MarketFill {
+    /// Base asset bought or sold
+    pub(crate) qty: f64,
+    /// Quote asset spent or received
+    pub(crate) quote_qty: f64,
+}
+
+/// Part of `/api/v3/ticker/24hr` response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Ticker24hResponse {
+    symbol: String,
+    price_change_percent: String,
+    volume: String,
+}
+
+/// Element of `/api/v3/myTrades` response, `TradeHistory` of binance crate lacks order id
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MyTradeResponse {
+    id: u64,
+    order_id: u64,
+    price: String,
+    qty: String,
+    commission: String,
+    commission_asset: String,
+    time: u64,
+    is_buyer: bool,
+}
+
+/// Part of `/api/v3/exchangeInfo` and `/fapi/v1/exchangeInfo` responses
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExchangeInfoResponse {
+    rate_limits: Vec<RateLimitResponse>,
+    symbols: Vec<SymbolInfoResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RateLimitResponse {
+    rate_limit_type: String,
+    interval: String,
+    interval_num: u64,
+    limit: u32,
+}
+
+impl RateLimitResponse {
+    fn interval(&self) -> Option<Duration> {
+        let unit = match self.interval.as_str() {
+            "SECOND" => 1,
+            "MINUTE" => 60,
+            "HOUR" => 60 * 60,
+            "DAY" => 24 * 60 * 60,
+            _ => return None,
+        };
+
+        Some(Duration::from_secs(unit * self.interval_num))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SymbolInfoResponse {
+    symbol: String,
+    filters: Vec<SymbolFilterResponse>,
+}
+
+/// Only tick size of price filter, step size of lot size filter and minimal notional are used
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SymbolFilterResponse {
+    filter_type: String,
+    tick_size: Option<String>,
+    step_size: Option<String>,
+    min_notional: Option<String>,
+    /// Minimal notional of futures symbols
+    notional: Option<String>,
+}
+
+/// First error if every one of per symbol requests failed, some failing still shows the rest
+fn all_failed<T, E: std::fmt::Display>(responses: &[Result<T, E>]) -> Result<(), String> {
+    match responses.first() {
+        Some(Err(err)) if responses.iter().all(Result::is_err) => Err(err.to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Filters of every symbol in exchange info
+fn symbol_filters(symbols: Vec<SymbolInfoResponse>) -> AHashMap<String, SymbolFilter> {
+    symbols
+        .into_iter()
+        .map(|s| {
+            let mut filter = SymbolFilter::default();
+            for f in s.filters {
+                match (f.filter_type.as_str(), f.tick_size, f.step_size) {
+                    ("PRICE_FILTER", Some(tick), _) => {
+                        filter.price_decimals = SymbolFilter::decimals(&tick);
+                    }
+                    ("LOT_SIZE", _, Some(step)) => {
+                        filter.qty_decimals = SymbolFilter::decimals(&step);
+                        filter.step_size = step.parse().unwrap_or(filter.step_size);
+                    }
+                    // older symbols still use MIN_NOTIONAL, futures have it as notional
+                    ("NOTIONAL" | "MIN_NOTIONAL", _, _) => {
+                        filter.min_notional = f
+                            .min_notional
+                            .or(f.notional)
+                            .and_then(|n| n.parse().ok())
+                            .unwrap_or_default();
+                    }
+                    _ => {}
+                }
+            }
+            (s.symbol, filter)
+        })
+        .collect()
+}
+
+/// Exchange operations used by views, every call produces message with result
+pub(crate) trait TradingClient {
+    /// Replace credentials in inner client
+    fn update_credentials(&mut self, public: String, secret: String);
+
+    /// Filled and open orders of last 8 weeks, newest first
+    fn orders_history(&self, assets: Vec<String>) -> Command<Message>;
+
+    fn balances(&self, assets: Vec<String>) -> Command<Message>;
+
+    fn klines(&self, pair: String, tf: Timeframe) -> Command<Message>;
+
+    /// Up to `limit` own trades of pair starting at trade id `from_id` if it is set, newest ones
+    /// otherwise
+    fn get_trade_history(&self, symbol: &str, limit: u32, from_id: Option<u64>)
+        -> Command<Message>;
+
+    /// 24 hour price change in percent for every symbol
+    fn get_24hr_all_tickers(&self) -> Command<Message>;
+
+    /// Last funding rate of every perpetual futures symbol, not in percent
+    fn get_funding_rates(&self) -> Command<Message>;
+
+    /// Price and quantity precision for every symbol
+    fn exchange_info(&self) -> Command<Message>;
+
+    fn trade_spot(
+        &self,
+        pair: String,
+        price: f64,
+        amt: f64,
+        side: OrderSide,
+        order_type: OrderType,
+    ) -> Command<Message>;
+
+    /// Spot market order reporting its fill to `reply`, buys spend `amount` of quote asset and
+    /// sells sell `amount` of base asset
+    fn market_order(
+        &self,
+        pair: String,
+        side: OrderSide,
+        amount: f64,
+        reply: fn(Result<MarketFill, String>) -> Message,
+    ) -> Command<Message>;
+
+    /// Cancels open spot order
+    fn cancel_order(&self, symbol: &str, order_id: u64) -> Command<Message>;
+
+    /// Quantity precision of USD-M futures symbols
+    fn futures_exchange_info(&self) -> Command<Message>;
+
+    /// Reduce only trailing stop market order on USD-M futures, closes position instead of
+    /// opening one, `callback_rate` is in percent
+    fn place_trailing_stop(
+        &self,
+        pair: String,
+        amt: f64,
+        side: OrderSide,
+        callback_rate: f64,
+    ) -> Command<Message>;
+
+    /// Limit order on USD-M futures
+    fn trade_futures(
+        &self,
+        pair: String,
+        price: f64,
+        amt: f64,
+        side: OrderSide,
+        leverage: u8,
+    ) -> Command<Message>;
+
+    /// Market data pushed by client itself instead of websockets
+    fn subscription(&self) -> Subscription<Message> {
+        Subscription::none()
+    }
+}
+
+/// REST and websocket endpoints of TestNet or mainnet
+pub(crate) fn binance_config(testnet: bool) -> BinanceConfig {
+    if testnet {
+        BinanceConfig::testnet()
+    } else {
+        BinanceConfig::default()
+    }
+}
+
+pub(crate) struct BinanceClient {
+    config: BinanceConfig,
     binance_account: Arc<Account>,
     binance_market: Arc<Market>,
+    binance_futures: Arc<FuturesAccount>,
+    binance_futures_market: Arc<FuturesMarket>,
+    rate_limiter: Arc<RateLimiter>,
 }
 
-impl Client {
-    fn make_client(public: String, secret: String) -> Arc<Account> {
-        Arc::new(Binance::new(Some(public), Some(secret)))
+impl BinanceClient {
+    fn make<T: Binance>(public: String, secret: String, config: &BinanceConfig) -> Arc<T> {
+        Arc::new(T::new_with_config(Some(public), Some(secret), config))
     }
 
-    fn make_market(public: String, secret: String) -> Arc<Market> {
-        Arc::new(Binance::new(Some(public), Some(secret)))
-    }
+    /// Talks to `testnet.binance.vision` instead of `api.binance.com` if `testnet` is set
+    pub(crate) fn new(public: String, secret: String, testnet: bool) -> Self {
+        let config = binance_config(testnet);
 
-    pub(crate) fn new(public: String, secret: String) -> Self {
         Self {
-            binance_account: Self::make_client(public.clone(), secret.clone()),
-            binance_market: Self::make_market(public, secret),
+            binance_account: Self::make(public.clone(), secret.clone(), &config),
+            binance_market: Self::make(public.clone(), secret.clone(), &config),
+            binance_futures: Self::make(public.clone(), secret.clone(), &config),
+            binance_futures_market: Self::make(public, secret, &config),
+            config,
+            rate_limiter: Arc::new(RateLimiter::new()),
         }
     }
 
-    /// Replace credentials in inner client
-    pub(crate) fn update_credentials(&mut self, public: String, secret: String) {
-        self.binance_account = Self::make_client(public, secret);
+    /// Like `Command::perform`, but request is sent once rate limiter allows `weight`
+    fn perform<T: Send + 'static>(
+        &self,
+        weight: u32,
+        request: impl Future<Output = T> + Send + 'static,
+        f: impl FnOnce(T) -> Message + Send + 'static,
+    ) -> Command<Message> {
+        let rate_limiter = Arc::clone(&self.rate_limiter);
+
+        Command::perform(
+            async move {
+                rate_limiter.acquire(weight).await?;
+                Ok(request.await)
+            },
+            |result| match result {
+                Ok(response) => f(response),
+                Err(warning) => Message::RateLimitWarning(warning),
+            },
+        )
+    }
+}
+
+impl TradingClient for BinanceClient {
+    fn update_credentials(&mut self, public: String, secret: String) {
+        self.binance_account = Self::make(public.clone(), secret.clone(), &self.config);
+        self.binance_futures = Self::make(public, secret, &self.config);
     }
 
-    pub(crate) fn orders_history(&self, assets: Vec<String>) -> Command<Message> {
+    fn orders_history(&self, assets: Vec<String>) -> Command<Message> {
         let binance_account = Arc::clone(&self.binance_account);
 
-        Command::perform(
+        self.perform(
+            // one request per asset
+            weight::ALL_ORDERS * assets.len() as u32,
             async move {
                 let now = chrono::offset::Local::now();
                 let ago = now
                     .checked_sub_signed(chrono::Duration::try_weeks(8).unwrap())
                     .unwrap();
-                let mut os: Vec<_> = {
-                    join_all(assets.into_iter().map(|a| {
-                        binance_account.get_all_orders(binance::account::OrdersQuery {
-                            symbol: a,
-                            order_id: None,
-                            start_time: Some(ago.timestamp_millis() as u64),
-                            end_time: None,
-                            limit: None,
-                            recv_window: None,
-                        })
-                    }))
-                    .await
+                let responses = join_all(assets.into_iter().map(|a| {
+                    binance_account.get_all_orders(binance::account::OrdersQuery {
+                        symbol: a,
+                        order_id: None,
+                        start_time: Some(ago.timestamp_millis() as u64),
+                        end_time: None,
+                        limit: None,
+                        recv_window: None,
+                    })
+                }))
+                .await;
+                all_failed(&responses)?;
+
+                let mut os: Vec<_> = responses
                     .into_iter()
                     .flatten()
                     .flatten()
                     .filter(|o| {
-                        matches!(o.status, OrderStatus::Filled | OrderStatus::PartiallyFilled)
+                        matches!(
+                            o.status,
+                            OrderStatus::New | OrderStatus::Filled | OrderStatus::PartiallyFilled
+                        )
                     })
-                    .collect()
-                };
+                    .collect();
 
-                os.sort_by(|o, n| n.time.cmp(&o.time));
-                os
+                os.sort_by_key(|o| std::cmp::Reverse(o.time));
+                Ok(os)
+            },
+            |result| match result {
+                Ok(orders) => Message::OrdersRecieved(orders),
+                Err(err) => Message::FetchFailed(Fetch::Orders, err),
             },
-            Message::OrdersRecieved,
         )
     }
 
-    pub(crate) fn balances(&self, assets: Vec<String>) -> Command<Message> {
+    fn balances(&self, assets: Vec<String>) -> Command<Message> {
         let binance_account = Arc::clone(&self.binance_account);
 
-        Command::perform(
+        self.perform(
+            weight::ACCOUNT * assets.len() as u32,
             async move {
-                join_all(assets.iter().map(|a| binance_account.get_balance(a)))
+                let responses =
+                    join_all(assets.iter().map(|a| binance_account.get_balance(a))).await;
+                all_failed(&responses)?;
+
+                Ok(responses.into_iter().flatten().collect())
+            },
+            |result| match result {
+                Ok(balances) => Message::BalancesRecieved(balances),
+                Err(err) => Message::FetchFailed(Fetch::Balances, err),
+            },
+        )
+    }
+
+    fn klines(&self, pair: String, tf: Timeframe) -> Command<Message> {
+        let market = Arc::clone(&self.binance_market);
+        self.perform(
+            weight::KLINES,
+            async move { market.get_klines(pair, tf.as_str(), 500, None, None).await },
+            |result| match result {
+                Ok(klines) => Message::KlinesRecieved(klines),
+                Err(err) => Message::DispatchErr(("klines".to_owned(), err.to_string())),
+            },
+        )
+    }
+
+    fn get_trade_history(
+        &self,
+        symbol: &str,
+        limit: u32,
+        from_id: Option<u64>,
+    ) -> Command<Message> {
+        let binance_account = Arc::clone(&self.binance_account);
+        let symbol = symbol.to_owned();
+
+        self.perform(
+            weight::MY_TRADES,
+            {
+                let symbol = symbol.clone();
+                async move {
+                    let mut parameters = vec![("symbol", symbol), ("limit", limit.to_string())];
+                    if let Some(from_id) = from_id {
+                        parameters.push(("fromId", from_id.to_string()));
+                    }
+                    let request = build_signed_request(parameters, binance_account.recv_window)?;
+
+                    binance_account
+                        .client
+                        .get_signed::<Vec<MyTradeResponse>>(API_V3_MY_TRADES, &request)
+                        .await
+                }
+            },
+            move |result| match result {
+                Ok(trades) => {
+                    let trades = trades
+                        .into_iter()
+                        .map(|t| Trade {
+                            id: t.id,
+                            order_id: t.order_id,
+                            symbol: symbol.clone(),
+                            time: t.time,
+                            is_buyer: t.is_buyer,
+                            price: t.price.parse().unwrap_or_default(),
+                            qty: t.qty.parse().unwrap_or_default(),
+                            commission: t.commission.parse().unwrap_or_default(),
+                            commission_asset: t.commission_asset,
+                        })
+                        .collect();
+                    Message::TradeHistoryRecieved(symbol, trades)
+                }
+                Err(err) => Message::DispatchErr(("trade history".to_owned(), err.to_string())),
+            },
+        )
+    }
+
+    fn get_24hr_all_tickers(&self) -> Command<Message> {
+        let market = Arc::clone(&self.binance_market);
+
+        self.perform(
+            weight::ALL_TICKERS_24H,
+            async move {
+                market
+                    .client
+                    .get::<Vec<Ticker24hResponse>>(API_V3_24H_TICKER, None)
                     .await
-                    .into_iter()
-                    .flatten()
-                    .collect()
+                    .map(|tickers| {
+                        tickers
+                            .into_iter()
+                            .filter_map(|t| {
+                                let ticker = Ticker24h {
+                                    change: t.price_change_percent.parse().ok()?,
+                                    volume: t.volume.parse().ok()?,
+                                };
+                                Some((t.symbol, ticker))
+                            })
+                            .collect::<AHashMap<_, _>>()
+                    })
+            },
+            |result| match result {
+                Ok(tickers) => Message::TickersRecieved(tickers),
+                Err(err) => Message::DispatchErr(("24h tickers".to_owned(), err.to_string())),
+            },
+        )
+    }
+
+    fn get_funding_rates(&self) -> Command<Message> {
+        let market = Arc::clone(&self.binance_futures_market);
+
+        self.perform(
+            weight::MARK_PRICES,
+            async move {
+                market.get_mark_prices(None).await.map(|prices| {
+                    prices
+                        .into_iter()
+                        .map(|p| FundingRate {
+                            symbol: p.symbol,
+                            rate: p.last_funding_rate,
+                            mark_price: p.mark_price,
+                            next_funding_time: p.next_funding_time,
+                        })
+                        .collect()
+                })
+            },
+            |result| match result {
+                Ok(rates) => Message::FundingRatesRecieved(rates),
+                Err(err) => Message::DispatchErr(("funding rates".to_owned(), err.to_string())),
             },
-            Message::BalancesRecieved,
         )
     }
 
-    pub(crate) fn klines(&self, pair: String, tf: String) -> Command<Message> {
+    fn exchange_info(&self) -> Command<Message> {
         let market = Arc::clone(&self.binance_market);
-        Command::perform(
+        let rate_limiter = Arc::clone(&self.rate_limiter);
+
+        self.perform(
+            weight::EXCHANGE_INFO,
             async move {
-                let acc = market;
-                acc.get_klines(
-                    pair,
-                    if tf.is_empty() { "5m" } else { &tf },
-                    500,
-                    None,
-                    None,
-                )
-                .await
-                .unwrap()
-            },
-            Message::KlinesRecieved,
+                market
+                    .client
+                    .get::<ExchangeInfoResponse>(API_V3_EXCHANGE_INFO, None)
+                    .await
+                    .map(|info| {
+                        if let Some((limit, interval)) = info
+                            .rate_limits
+                            .iter()
+                            .filter(|l| l.rate_limit_type == "REQUEST_WEIGHT")
+                            .find_map(|l| Some((l.limit, l.interval()?)))
+                        {
+                            rate_limiter.set_limit(limit, interval);
+                        }
+
+                        symbol_filters(info.symbols)
+                    })
+            },
+            |result| match result {
+                Ok(filters) => Message::SymbolFiltersRecieved(filters),
+                Err(err) => Message::DispatchErr(("exchange info".to_owned(), err.to_string())),
+            },
         )
     }
 
-    pub(crate) fn trade_spot(
+    fn trade_spot(
         &self,
         pair: String,
         price: f64,
         amt: f64,
         side: OrderSide,
+        order_type: OrderType,
     ) -> Command<Message> {
         let binance_account = Arc::clone(&self.binance_account);
 
-        Command::perform(
+        let (order_type, time_in_force, price, stop_price) = match order_type {
+            OrderType::Limit => (
+                binance::rest_model::OrderType::Limit,
+                Some(binance::rest_model::TimeInForce::GTC),
+                Some(price),
+                None,
+            ),
+            OrderType::Market => (binance::rest_model::OrderType::Market, None, None, None),
+            OrderType::StopLimit { stop_price } => (
+                binance::rest_model::OrderType::StopLossLimit,
+                Some(binance::rest_model::TimeInForce::GTC),
+                Some(price),
+                Some(stop_price),
+            ),
+        };
+
+        self.perform(
+            weight::ORDER,
             async move {
                 binance_account
                     .place_order(binance::account::OrderRequest {
                         symbol: pair,
                         side,
-                        order_type: binance::rest_model::OrderType::Limit,
-                        time_in_force: Some(binance::rest_model::TimeInForce::GTC),
+                        order_type,
+                        time_in_force,
                         quantity: Some(amt),
                         quote_order_qty: None,
-                        price: Some(price),
+                        price,
                         new_client_order_id: None,
-                        stop_price: None,
+                        stop_price,
                         iceberg_qty: None,
                         new_order_resp_type: None,
                         recv_window: None,
                     })
                     .await
             },
-            |m| {
-                println!("{m:?}");
-                Message::MarketChanged("REEEEE".to_string())
+            // placed order arrives through user stream
+            |result| match result {
+                Ok(_) => Message::NoOp,
+                Err(err) => Message::ToastError(format!("order rejected: {err}")),
+            },
+        )
+    }
+
+    fn market_order(
+        &self,
+        pair: String,
+        side: OrderSide,
+        amount: f64,
+        reply: fn(Result<MarketFill, String>) -> Message,
+    ) -> Command<Message> {
+        let binance_account = Arc::clone(&self.binance_account);
+        let rate_limiter = Arc::clone(&self.rate_limiter);
+        let (quantity, quote_order_qty) = match side {
+            OrderSide::Buy => (None, Some(amount)),
+            OrderSide::Sell => (Some(amount), None),
+        };
+
+        // not through perform, caller waits for reply even if request is never sent
+        Command::perform(
+            async move {
+                rate_limiter.acquire(weight::ORDER).await?;
+                binance_account
+                    .place_order(binance::account::OrderRequest {
+                        symbol: pair,
+                        side,
+                        order_type: binance::rest_model::OrderType::Market,
+                        time_in_force: None,
+                        quantity,
+                        quote_order_qty,
+                        price: None,
+                        new_client_order_id: None,
+                        stop_price: None,
+                        iceberg_qty: None,
+                        new_order_resp_type: Some(binance::rest_model::OrderResponse::Result),
+                        recv_window: None,
+                    })
+                    .await
+                    .map(|transaction| MarketFill {
+                        qty: transaction.executed_qty,
+                        quote_qty: transaction.cummulative_quote_qty,
+                    })
+                    .map_err(|err| err.to_string())
+            },
+            reply,
+        )
+    }
+
+    fn cancel_order(&self, symbol: &str, order_id: u64) -> Command<Message> {
+        let binance_account = Arc::clone(&self.binance_account);
+        let symbol = symbol.to_owned();
+
+        self.perform(
+            weight::ORDER,
+            async move {
+                binance_account
+                    .cancel_order(binance::account::OrderCancellation {
+                        symbol,
+                        order_id: Some(order_id),
+                        orig_client_order_id: None,
+                        new_client_order_id: None,
+                        recv_window: None,
+                    })
+                    .await
+            },
+            move |result| match result {
+                Ok(_) => Message::OrderCanceled(order_id),
+                Err(err) => Message::ToastError(format!("failed to cancel order: {err}")),
+            },
+        )
+    }
+
+    fn futures_exchange_info(&self) -> Command<Message> {
+        let market = Arc::clone(&self.binance_futures_market);
+
+        self.perform(
+            weight::FUTURES_EXCHANGE_INFO,
+            async move {
+                market
+                    .client
+                    .get::<ExchangeInfoResponse>(FAPI_V1_EXCHANGE_INFO, None)
+                    .await
+                    .map(|info| symbol_filters(info.symbols))
+            },
+            |result| match result {
+                Ok(filters) => Message::FuturesFiltersRecieved(filters),
+                Err(err) => {
+                    Message::DispatchErr(("futures exchange info".to_owned(), err.to_string()))
+                }
+            },
+        )
+    }
+
+    fn place_trailing_stop(
+        &self,
+        pair: String,
+        amt: f64,
+        side: OrderSide,
+        callback_rate: f64,
+    ) -> Command<Message> {
+        let binance_futures = Arc::clone(&self.binance_futures);
+
+        self.perform(
+            weight::ORDER,
+            async move {
+                binance_futures
+                    .place_order(binance::futures::account::OrderRequest {
+                        symbol: pair,
+                        side,
+                        order_type: binance::futures::rest_model::OrderType::TrailingStopMarket,
+                        quantity: Some(amt),
+                        callback_rate: Some(callback_rate),
+                        reduce_only: Some(true),
+                        ..Default::default()
+                    })
+                    .await
+            },
+            |result| match result {
+                Ok(_) => Message::NoOp,
+                Err(err) => Message::ToastError(format!("trailing stop failed: {err}")),
             },
         )
     }
 
-    pub(crate) fn split_symbol(symbol: &str) -> Option<[&str; 2]> {
-        let regex = SPLIT_SYMBOL_REGEX.get_or_init(|| {
-            let quote_assets = vec![
-                "BTC", "ETH", "USDT", "BNB", "TUSD", "PAX", "USDC", "XRP", "USDS", "TRX", "BUSD",
-                "NGN", "RUB", "TRY", "EUR", "ZAR", "BKRW", "IDRT", "GBP", "UAH", "BIDR", "AUD",
-                "DAI", "BRL", "BVND", "VAI", "USDP", "DOGE", "UST", "DOT", "PLN", "RON", "ARS",
-            ];
+    /// Limit order on USD-M futures, leverage of symbol is set first
+    fn trade_futures(
+        &self,
+        pair: String,
+        price: f64,
+        amt: f64,
+        side: OrderSide,
+        leverage: u8,
+    ) -> Command<Message> {
+        let binance_futures = Arc::clone(&self.binance_futures);
 
-            let quote_assets_regex = quote_assets.join("|");
-            Regex::new(&format!(r"^([0-9A-Z]+)({quote_assets_regex})$")).unwrap()
-        });
+        self.perform(
+            // leverage change and order
+            2 * weight::ORDER,
+            async move {
+                binance_futures
+                    .change_initial_leverage(pair.clone(), leverage)
+                    .await?;
 
-        regex.captures(symbol).map(|captures| captures.extract().1)
+                match side {
+                    OrderSide::Buy => {
+                        binance_futures
+                            .limit_buy(pair, amt, price, TimeInForce::GTC)
+                            .await
+                    }
+                    OrderSide::Sell => {
+                        binance_futures
+                            .limit_sell(pair, amt, price, TimeInForce::GTC)
+                            .await
+                    }
+                }
+            },
+            |result| match result {
+                Ok(_) => Message::NoOp,
+                Err(err) => Message::ToastError(format!("futures order failed: {err}")),
+            },
+        )
     }
 }
 
+pub(crate) fn split_symbol(symbol: &str) -> Option<[&str; 2]> {
+    let regex = SPLIT_SYMBOL_REGEX.get_or_init(|| {
+        let quote_assets = vec![
+            "BTC", "ETH", "USDT", "BNB", "TUSD", "PAX", "USDC", "XRP", "USDS", "TRX", "BUSD",
+            "NGN", "RUB", "TRY", "EUR", "ZAR", "BKRW", "IDRT", "GBP", "UAH", "BIDR", "AUD", "DAI",
+            "BRL", "BVND", "VAI", "USDP", "DOGE", "UST", "DOT", "PLN", "RON", "ARS",
+        ];
+
+        let quote_assets_regex = quote_assets.join("|");
+        Regex::new(&format!(r"^([0-9A-Z]+)({quote_assets_regex})$")).unwrap()
+    });
+
+    regex.captures(symbol).map(|captures| captures.extract().1)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn partial_failure_is_not_error() {
+        let ok: Result<(), &str> = Ok(());
+        assert_eq!(all_failed(&[ok, Err("a")]), Ok(()));
+        assert_eq!(
+            all_failed(&[Err::<(), _>("a"), Err("b")]),
+            Err("a".to_owned())
+        );
+        assert_eq!(all_failed::<(), &str>(&[]), Ok(()));
+    }
+
     #[test]
     fn split_base_qty() {
         let test_cases = vec![
@@ -220,7 +834,7 @@ mod tests {
         ];
 
         for symbol in test_cases {
-            match Client::split_symbol(symbol) {
+            match split_symbol(symbol) {
                 Some([base, quote]) => {
                     assert_eq!(
                         format!("{base}{quote}"),
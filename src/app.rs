@@ -1,57 +1,141 @@
-use crate::api::Client;
-use crate::config::Config;
+use crate::alerts::{self, AlertCondition};
+use crate::api::{BinanceClient, TradingClient};
+use crate::config::{self, Config, ConfigEvent};
 use crate::data::AppData;
-use crate::data::PriceFilter;
+use crate::data::CandleData;
+use crate::data::{Fetch, PriceFilter, PriceSort};
 use crate::message::MaybeError;
 use crate::message::Message;
+use crate::mock::MockClient;
+use crate::network::{self, NetworkStatus};
+use crate::news;
+use crate::notification::{Notification, Severity};
+use crate::paper::PaperBroker;
+use crate::sound::SoundEffect;
 use crate::svg_logos;
-use crate::views::dashboard::DashboardView;
+use crate::theme::{color, Role, ThemeProvider};
+use crate::views::components::better_btn::BetterBtn;
+use crate::views::components::floating::{Anchor, Floating};
+use crate::views::components::loading;
+use crate::views::dashboard::{DashboardMessage, DashboardView, PaneType};
+use crate::views::futures_dashboard::FuturesDashboardView;
+use crate::views::panes::calculator::{CalculatorPaneMessage, FeeType};
+use crate::views::panes::market::MarketPanelMessage;
+use crate::views::panes::style;
+use crate::views::panes::watchlist::WatchlistMessage;
 use crate::views::settings::SettingsView;
-use crate::ws::Websockets;
+use crate::views::splash::{self, LoaderMessage, SplashView};
+use crate::views::Tab;
+use crate::ws::{Websockets, WsEvent, WsMessage};
 
 use std::env;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use binance::rest_model::KlineSummaries;
+use binance::rest_model::{KlineSummaries, OrderStatus};
+use binance::ws_model::WebsocketEvent;
+use iced::event;
 use iced::executor;
 use iced::font;
 use iced::widget::button;
+use iced::widget::pick_list;
 use iced::widget::scrollable;
 use iced::widget::svg;
+use iced::widget::Column;
 use iced::widget::Row;
 use iced::widget::Space;
 use iced::widget::{column, container, row, text};
+use iced::window;
 use iced::{Application, Color, Command, Element, Length, Subscription, Theme};
-use ringbuf::Rb;
+
+/// Config changed rapidly is saved once it stays unchanged for this long
+const CONFIG_SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Synthetic client in demo mode, exchange otherwise
+fn trading_client(config: &Config) -> Box<dyn TradingClient> {
+    if config.demo_mode {
+        Box::new(MockClient::new())
+    } else {
+        let (public, secret) = config.credentials();
+        Box::new(BinanceClient::new(
+            public.to_owned(),
+            secret.to_owned(),
+            config.testnet,
+        ))
+    }
+}
+
+/// Sound goes through dashboard which knows whether sounds are enabled
+fn play_sound(effect: SoundEffect) -> Command<Message> {
+    Command::perform(async {}, move |_| {
+        DashboardMessage::PlaySound(effect).into()
+    })
+}
 
 pub(crate) struct App {
     config: Config,
     data: AppData,
-    api: Client,
+    api: Box<dyn TradingClient>,
+    paper: PaperBroker,
     errors: Vec<String>,
     settings_opened: bool,
+    tab: Tab,
     dashboard: DashboardView,
+    futures: FuturesDashboardView,
     settings: SettingsView,
+    /// Covers dashboards until initial data is loaded
+    splash: SplashView,
     ws: Websockets,
+    /// Incremented on every debounced config change
+    config_generation: u64,
+    /// Debounced config change was not saved yet
+    config_save_pending: bool,
+    /// Incremented on every pane layout change
+    layout_generation: u64,
 }
 
 impl App {
     fn new(config: Config) -> Self {
-        let api = Client::new(config.api_key.clone(), config.api_secret_key.clone());
+        let api = trading_client(&config);
+        loading::set_target_fps(config.target_fps);
+        ThemeProvider::set(&config.theme);
+        style::set_config(&config.pane_style);
 
-        let mut data = AppData::default();
+        let mut data = AppData {
+            fee_tier: config.fee_taker,
+            ..AppData::default()
+        };
+        data.prices.set_sort(PriceSort::Filter, true);
         data.prices
             .set_filter(PriceFilter::Matches(config.watchlist_favorites.clone()));
 
+        let paper = PaperBroker::new();
+        if config.paper_trade {
+            data.balances = paper.balances().to_vec();
+            data.paper_pnl = Some(paper.realized_pnl());
+        }
+
         App {
             config: config.clone(),
             data,
             api,
+            paper,
             errors: Vec::new(),
             settings_opened: !config.complete(),
-            dashboard: DashboardView::new(),
-            ws: Websockets::new(config.api_key.clone(), "BTCUSDT"),
+            tab: Tab::Spot,
+            dashboard: DashboardView::new(&config),
+            futures: FuturesDashboardView::new(&config),
+            ws: Websockets::new(
+                config.credentials().0.to_owned(),
+                &config.default_pair,
+                config.testnet,
+                config.tls_pin.clone(),
+                config.use_agg_trades,
+            ),
             settings: SettingsView::new(config),
+            splash: SplashView::new(),
+            config_generation: 0,
+            config_save_pending: false,
+            layout_generation: 0,
         }
     }
 
@@ -76,17 +160,195 @@ impl App {
                     .map(ToOwned::to_owned)
                     .collect(),
             ),
+            self.api.get_24hr_all_tickers(),
+            self.api.exchange_info(),
+            self.api.futures_exchange_info(),
+            self.api.get_funding_rates(),
+            news::fetch(self.config.news_feed_url.clone()),
             self.api.klines(
-                if self.data.quote.is_empty() {
-                    "BTCUSDT".into()
+                if self.dashboard.pair().is_empty() {
+                    self.config.default_pair.clone()
                 } else {
-                    self.data.quote.clone()
+                    self.dashboard.pair().to_owned()
                 },
-                String::new(),
+                self.dashboard.chart_timeframe(),
             ),
         ])
     }
 
+    /// Persist config changed outside of settings
+    fn config_changed(&mut self) -> Command<Message> {
+        // settings keep their own copy which would overwrite changes on save
+        self.settings = SettingsView::new(self.config.clone());
+
+        let config = self.config.clone();
+        Command::perform(async move { config.save() }, |result| match result {
+            Ok(()) => Message::NoOp,
+            Err(err) => Message::DispatchErr(("config".to_string(), err.to_string())),
+        })
+    }
+
+    /// Persist config after it stops changing for `CONFIG_SAVE_DEBOUNCE`
+    fn config_changed_debounced(&mut self) -> Command<Message> {
+        self.settings = SettingsView::new(self.config.clone());
+        self.config_generation += 1;
+        self.config_save_pending = true;
+
+        let generation = self.config_generation;
+        Command::perform(tokio::time::sleep(CONFIG_SAVE_DEBOUNCE), move |_| {
+            Message::SaveConfig(generation)
+        })
+    }
+
+    /// Persist pane layout after it stops changing for `CONFIG_SAVE_DEBOUNCE`, so that it
+    /// survives crashes
+    fn layout_changed_debounced(&mut self) -> Command<Message> {
+        self.layout_generation += 1;
+
+        let generation = self.layout_generation;
+        Command::perform(tokio::time::sleep(CONFIG_SAVE_DEBOUNCE), move |_| {
+            Message::SaveLayout(generation)
+        })
+    }
+
+    /// Notify about alerts that reached their price
+    fn check_alerts(&mut self) -> Command<Message> {
+        let mut sounds = Vec::new();
+
+        for alert in self.config.alerts.iter_mut().filter(|a| !a.triggered) {
+            let Some(price) = self.data.prices.get(&alert.symbol) else {
+                continue;
+            };
+
+            if alert.is_reached(f64::from(price)) {
+                alert.triggered = true;
+                alerts::notify(alert);
+                sounds.push(play_sound(match alert.condition {
+                    AlertCondition::Above => SoundEffect::PriceUp,
+                    AlertCondition::Below => SoundEffect::PriceDown,
+                }));
+            }
+        }
+
+        if sounds.is_empty() {
+            Command::none()
+        } else {
+            sounds.push(self.config_changed());
+            Command::batch(sounds)
+        }
+    }
+
+    fn tab_button(&self, label: &str, tab: Tab) -> Element<'_, Message> {
+        button(text(label).size(14))
+            .padding(8)
+            .style(if self.tab == tab {
+                iced::theme::Button::Custom(Box::new(BetterBtn {}))
+            } else {
+                iced::theme::Button::Text
+            })
+            .on_press(Message::SwitchTab(tab))
+            .into()
+    }
+
+    /// Fill paper orders reached by last book update
+    fn match_paper_orders(&mut self) -> Command<Message> {
+        let Some((bid, ask)) = self.data.best_prices() else {
+            return Command::none();
+        };
+
+        let filled = self.paper.match_book(&self.data.book.0, bid, ask);
+        if filled.is_empty() {
+            return Command::none();
+        }
+
+        self.paper_changed();
+
+        play_sound(SoundEffect::Fill)
+    }
+
+    /// Shows orders and balances of paper broker after it accepted or filled orders
+    fn paper_changed(&mut self) {
+        self.data.orders = self.paper.orders();
+        self.data.track_realized_pnl();
+        self.data.balances = self.paper.balances().to_vec();
+        self.data.paper_pnl = Some(self.paper.realized_pnl());
+    }
+
+    /// Recreates client with credentials of active account, data of previous one is dropped
+    fn account_switched(&mut self) -> Command<Message> {
+        self.api = trading_client(&self.config);
+        self.ws.relogin_user(self.config.credentials().0);
+        self.data.clear_orders();
+
+        // simulated balances do not belong to any account
+        if self.config.paper_trade {
+            Command::none()
+        } else {
+            self.data.balances.clear();
+            self.fetch_data()
+        }
+    }
+
+    /// Applies settings or edited file, reconnecting if market or account changed
+    fn apply_config(&mut self, new_config: Config) -> Command<Message> {
+        let credentials_updated = self.config.credentials() != new_config.credentials();
+        let paper_toggled = self.config.paper_trade != new_config.paper_trade;
+        let demo_toggled = self.config.demo_mode != new_config.demo_mode;
+        let testnet_toggled = self.config.testnet != new_config.testnet;
+        let account_switched = self.config.active_account != new_config.active_account;
+
+        self.config = new_config;
+        self.data.fee_tier = self.config.fee_taker;
+        self.ws.set_agg_trades(self.config.use_agg_trades);
+        loading::set_target_fps(self.config.target_fps);
+        ThemeProvider::set(&self.config.theme);
+        style::set_config(&self.config.pane_style);
+
+        let (refetched, command) = if demo_toggled || testnet_toggled {
+            // data of different markets should not mix
+            self.api = trading_client(&self.config);
+            self.ws = Websockets::new(
+                self.config.credentials().0.to_owned(),
+                self.dashboard.pair(),
+                self.config.testnet,
+                self.config.tls_pin.clone(),
+                self.config.use_agg_trades,
+            );
+            self.data.clear_chart();
+            self.data.clear_orders();
+            (true, self.fetch_data())
+        } else if account_switched {
+            (true, self.account_switched())
+        } else if credentials_updated {
+            let (public, secret) = self.config.credentials();
+            self.api
+                .update_credentials(public.to_owned(), secret.to_owned());
+            self.ws.relogin_user(public);
+            (true, self.fetch_data())
+        } else {
+            (false, Command::none())
+        };
+
+        // can be toggled in the same save as market or account
+        let paper_command = if !paper_toggled {
+            Command::none()
+        } else if self.config.paper_trade {
+            self.data.clear_orders();
+            self.paper_changed();
+            Command::none()
+        } else {
+            self.data.paper_pnl = None;
+            if refetched {
+                Command::none()
+            } else {
+                self.data.clear_orders();
+                self.fetch_data()
+            }
+        };
+
+        Command::batch([command, paper_command])
+    }
+
     fn toggle_settings(&mut self) {
         self.settings_opened = !(self.settings_opened && self.config.complete());
     }
@@ -147,51 +409,211 @@ impl Application for App {
     }
 
     fn title(&self) -> String {
-        String::from("Dynasty")
+        match self.config.account() {
+            Some(account) if !self.config.demo_mode && !self.config.testnet => {
+                format!("Dynasty - {}", account.name)
+            }
+            _ => String::from("Dynasty"),
+        }
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::Tick => {
-                self.dashboard.tick(&self.data);
+                self.dashboard.tick(&mut self.data, &self.config);
+                self.data
+                    .expire_notifications(Duration::from_millis(self.config.toast_duration_ms));
+                self.data
+                    .expire_sentiment(chrono::Utc::now().timestamp_millis() as u64);
+
                 Command::none()
             }
+            Message::CloseRequested(id) => {
+                if let Err(err) = self.dashboard.save_layout() {
+                    tracing::error!("failed to save layout: {err}");
+                }
+                if self.config_save_pending {
+                    if let Err(err) = self.config.save() {
+                        tracing::error!("failed to save config: {err}");
+                    }
+                }
+
+                window::close(id)
+            }
             Message::ConfigUpdated(update) => match update {
                 Ok(new_config) => {
-                    let credentials_updated = self.config.credentials() != new_config.credentials();
-
-                    self.config = new_config;
                     self.toggle_settings();
-
-                    if credentials_updated {
-                        self.api.update_credentials(
-                            self.config.api_key.clone(),
-                            self.config.api_secret_key.clone(),
-                        );
-                        self.ws.relogin_user(&self.config.api_key);
-                        self.fetch_data()
-                    } else {
-                        Command::none()
-                    }
+                    self.data
+                        .push_notification(Notification::new(Severity::Success, "Settings saved"));
+                    self.apply_config(*new_config)
                 }
                 Err(err) => Command::perform(async {}, move |_| {
                     Message::DispatchErr(("config".to_string(), err.to_string()))
                 }),
             },
+            Message::ConfigWatch(ConfigEvent::Changed(new_config)) => {
+                // own saves change the file too, unsaved changes of app are newer than file
+                let unchanged = serde_json::to_value(&*new_config).ok()
+                    == serde_json::to_value(&self.config).ok();
+                if unchanged || self.config_save_pending {
+                    return Command::none();
+                }
+
+                let command = self.apply_config(*new_config);
+                // settings keep their own copy which would overwrite reloaded one on save
+                self.settings = SettingsView::new(self.config.clone());
+                self.data.push_notification(Notification::new(
+                    Severity::Info,
+                    "Config reloaded from file",
+                ));
+                Command::batch([
+                    command,
+                    Command::perform(async {}, |_| DashboardMessage::ConfigReloaded.into()),
+                ])
+            }
+            Message::ConfigWatch(ConfigEvent::Failed(err)) => self.update(Message::ToastError(
+                format!("failed to reload config: {err}"),
+            )),
             Message::Ws(msg) => {
+                let is_price = matches!(msg, WsMessage::Price(WsEvent::Message(_)));
+                let is_book = matches!(msg, WsMessage::Book(WsEvent::Message(_)));
+                let is_fill = matches!(
+                    &msg,
+                    WsMessage::User(WsEvent::Message(WebsocketEvent::OrderUpdate(o)))
+                        if o.current_order_status == OrderStatus::Filled
+                );
+                if let WsMessage::Book(WsEvent::PinError(fingerprint))
+                | WsMessage::Trade(WsEvent::PinError(fingerprint)) = &msg
+                {
+                    self.data.push_notification(Notification::new(
+                        Severity::Error,
+                        format!("Certificate {fingerprint} does not match pin, not connecting"),
+                    ));
+                }
+
+                // real account updates would overwrite simulated balances
+                if self.config.paper_trade && matches!(msg, WsMessage::User(WsEvent::Message(_))) {
+                    return Command::none();
+                }
                 self.ws.update(msg, &mut self.data, &mut self.dashboard);
+
+                if is_price {
+                    self.data
+                        .update_price_series(&self.config.watchlist_favorites, Instant::now());
+                }
+
+                if is_book && self.config.paper_trade {
+                    self.match_paper_orders()
+                } else if is_fill {
+                    play_sound(SoundEffect::Fill)
+                } else if is_price {
+                    // watchlist rows are rendered page by page once first prices arrive
+                    let load_pages = if self.data.prices_loaded_page == 0 {
+                        self.data.prices_loaded_page = 1;
+                        Command::perform(async {}, |_| DashboardMessage::LoadNextPricePage.into())
+                    } else {
+                        Command::none()
+                    };
+                    Command::batch([self.check_alerts(), load_pages])
+                } else {
+                    Command::none()
+                }
+            }
+            // paper trading keeps its own orders and balances
+            Message::OrdersRecieved(_) | Message::BalancesRecieved(_)
+                if self.config.paper_trade =>
+            {
+                self.data.loader.loaded(Fetch::Orders, Ok(()));
+                self.data.loader.loaded(Fetch::Balances, Ok(()));
                 Command::none()
             }
             Message::OrdersRecieved(orders) => {
+                self.data.loader.loaded(Fetch::Orders, Ok(()));
                 self.data.orders = orders;
+                self.data.track_realized_pnl();
+                Command::none()
+            }
+            Message::TradeHistoryRecieved(symbol, trades) => {
+                self.dashboard.trade_history_received(trades.len());
+                self.data.add_trade_history(&symbol, trades);
+                Command::none()
+            }
+            Message::OrderCanceled(order_id) => {
+                self.data.orders.retain(|o| o.order_id != order_id);
                 Command::none()
             }
             Message::BalancesRecieved(bals) => {
+                self.data.loader.loaded(Fetch::Balances, Ok(()));
                 self.data.balances = bals;
                 Command::none()
             }
-            Message::MarketChanged(new_market) => {
-                self.data.quote = new_market;
+            Message::PaperOrder {
+                pair,
+                price,
+                amt,
+                side,
+                order_type,
+            } => match self.paper.place(pair, price, amt, side, order_type) {
+                Ok(()) => {
+                    self.paper_changed();
+                    Command::none()
+                }
+                Err(err) => Command::perform(async {}, move |_| {
+                    Message::ToastError(format!("paper order rejected: {err}"))
+                }),
+            },
+            Message::PaperCancel(order_id) => match self.paper.cancel(order_id) {
+                Ok(()) => {
+                    self.paper_changed();
+                    Command::none()
+                }
+                Err(err) => Command::perform(async {}, move |_| {
+                    Message::ToastError(format!("paper cancel failed: {err}"))
+                }),
+            },
+            Message::PaperMarketOrder {
+                pair,
+                side,
+                amount,
+                reply,
+            } => {
+                let fill = match self.data.book_tickers.get(&pair).copied() {
+                    Some(tick) => self
+                        .paper
+                        .fill_market(pair, side, amount, tick.bid, tick.ask),
+                    None => Err(format!("no book ticker of {pair}")),
+                };
+                if fill.is_ok() {
+                    self.paper_changed();
+                }
+
+                Command::perform(async move { fill }, reply)
+            }
+            Message::TickersRecieved(tickers) => {
+                self.data.prices.set_ticker_24h(tickers);
+                Command::none()
+            }
+            Message::SymbolFiltersRecieved(filters) => {
+                self.data.loader.exchange_info = true;
+                self.data.symbol_filters = filters;
+                Command::none()
+            }
+            Message::FuturesFiltersRecieved(filters) => {
+                self.data.futures.symbol_filters = filters;
+                Command::none()
+            }
+            Message::FundingRatesRecieved(rates) => {
+                self.data.futures.add_funding(rates);
+                Command::none()
+            }
+            Message::NewsRecieved(news) => {
+                self.data.loader.loaded(Fetch::News, Ok(()));
+                self.data.news = news;
+                Command::none()
+            }
+            Message::FetchFailed(fetch, error) => {
+                tracing::error!("error: {fetch:?}: {error}");
+                self.data.loader.loaded(fetch, Err(error));
                 Command::none()
             }
             Message::DispatchErr((source, message)) => {
@@ -201,21 +623,171 @@ impl Application for App {
 
                 Command::none()
             }
+            Message::NetworkStatus(status) => {
+                tracing::info!("network status changed to {status:?}");
+                self.data.network_status = status;
+                Command::none()
+            }
+            Message::PushNotification(notification) => {
+                self.data.push_notification(notification);
+                Command::none()
+            }
+            Message::ToastError(message) => self.update(Message::PushNotification(
+                Notification::new(Severity::Error, message),
+            )),
+            Message::RateLimitWarning(message) => self.update(Message::PushNotification(
+                Notification::new(Severity::Warning, message),
+            )),
+            Message::Loader(LoaderMessage::TimedOut) => {
+                self.splash.update(LoaderMessage::TimedOut);
+                self.data.push_notification(Notification::new(
+                    Severity::Error,
+                    format!(
+                        "failed to load {} in {}s",
+                        self.data.loader.missing().join(", "),
+                        splash::LOAD_TIMEOUT.as_secs()
+                    ),
+                ));
+                Command::none()
+            }
+            Message::Loader(msg) => {
+                self.splash.update(msg);
+                Command::none()
+            }
+            Message::NotificationDismissed(created) => {
+                self.data.notifications.retain(|n| n.created != created);
+                Command::none()
+            }
+            // newer change has scheduled its own save
+            Message::SaveConfig(generation) if generation == self.config_generation => {
+                self.config_save_pending = false;
+                self.config_changed()
+            }
+            Message::SaveConfig(_) => Command::none(),
+            Message::SaveLayout(generation) if generation == self.layout_generation => {
+                let layout = self.dashboard.layout();
+                Command::perform(async move { layout.save() }, |result| match result {
+                    Ok(()) => Message::NoOp,
+                    Err(err) => Message::DispatchErr(("layout".to_owned(), err.to_string())),
+                })
+            }
+            Message::SaveLayout(_) => Command::none(),
+            Message::AccountSelected(choice) => {
+                if choice.index == self.config.active_account {
+                    return Command::none();
+                }
+                self.config.active_account = choice.index;
+
+                Command::batch([self.config_changed(), self.account_switched()])
+            }
             Message::SettingsToggled => {
                 self.toggle_settings();
 
                 Command::none()
             }
+            Message::ToggleTheme => {
+                self.config.theme = self
+                    .config
+                    .theme
+                    .cycled(self.config.custom_palette.as_ref());
+                ThemeProvider::set(&self.config.theme);
+                self.config_changed()
+            }
             Message::Dashboard(msg) => {
-                self.dashboard
-                    .update(msg, &self.api, &mut self.data, &self.ws, &self.config)
+                // alerts and favorites are part of config which is owned here
+                let config_command = match &msg {
+                    DashboardMessage::Market(MarketPanelMessage::AddAlert(alert)) => {
+                        self.config.alerts.push(alert.clone());
+                        self.config_changed()
+                    }
+                    DashboardMessage::Market(MarketPanelMessage::RemoveAlert(i))
+                        if *i < self.config.alerts.len() =>
+                    {
+                        self.config.alerts.remove(*i);
+                        self.config_changed()
+                    }
+                    DashboardMessage::Watchlist(WatchlistMessage::Reorder { from, to }) => {
+                        let favorites = &mut self.config.watchlist_favorites;
+                        if *from < favorites.len() && *to < favorites.len() {
+                            let moved = favorites.remove(*from);
+                            favorites.insert(*to, moved);
+                        }
+                        self.config_changed()
+                    }
+                    DashboardMessage::Calculator(CalculatorPaneMessage::FeeChanged(
+                        fee_type,
+                        value,
+                    )) => {
+                        // rates are entered in percent, pane keeps invalid input
+                        match value.parse::<f64>() {
+                            Ok(pct) if (0.0..100.0).contains(&pct) => {
+                                let rate = pct / 100.0;
+                                match fee_type {
+                                    FeeType::Maker => self.config.fee_maker = rate,
+                                    FeeType::Taker => {
+                                        self.config.fee_taker = rate;
+                                        self.data.fee_tier = rate;
+                                    }
+                                }
+                                self.config_changed_debounced()
+                            }
+                            _ => Command::none(),
+                        }
+                    }
+                    DashboardMessage::Watchlist(WatchlistMessage::ToggleFavorite(symbol)) => {
+                        let favorites = &mut self.config.watchlist_favorites;
+                        match favorites.iter().position(|f| f == symbol) {
+                            Some(i) => {
+                                favorites.remove(i);
+                            }
+                            None => favorites.push(symbol.clone()),
+                        }
+                        self.config_changed_debounced()
+                    }
+                    _ => Command::none(),
+                };
+
+                let pair = self.dashboard.pair().to_owned();
+                let layout = self.dashboard.layout();
+                let command = self.dashboard.update(
+                    msg,
+                    self.api.as_ref(),
+                    &mut self.data,
+                    &self.ws,
+                    &self.config,
+                );
+                // liquidations subscription follows market pair
+                if self.dashboard.pair() != pair {
+                    self.data.clear_liquidations();
+                }
+                let layout_command = if self.dashboard.layout() != layout {
+                    self.layout_changed_debounced()
+                } else {
+                    Command::none()
+                };
+
+                Command::batch([config_command, command, layout_command])
+            }
+            Message::FuturesDashboard(msg) => {
+                self.futures
+                    .update(msg, self.api.as_ref(), &self.data, self.config.paper_trade)
+            }
+            Message::SwitchTab(tab) => {
+                self.tab = tab;
+                Command::none()
             }
             Message::Settings(msg) => self.settings.update(msg),
             Message::NoOp => Command::none(),
             Message::KlinesRecieved(kr) => match kr {
                 KlineSummaries::AllKlineSummaries(klines) => {
-                    let closes = klines.iter().map(|kline| kline.close);
-                    self.data.price_chart.push_iter_overwrite(closes);
+                    let closes: Vec<_> = klines.iter().map(|kline| kline.close).collect();
+                    self.data.prepend_chart_data(&closes);
+                    let volumes: Vec<_> = klines.iter().map(|kline| kline.volume).collect();
+                    self.data.prepend_volume_data(&volumes);
+                    self.dashboard.price_history_changed(&self.data);
+
+                    let candles: Vec<_> = klines.iter().map(CandleData::from).collect();
+                    self.data.prepend_candle_data(&candles);
 
                     Command::none()
                 }
@@ -224,9 +796,64 @@ impl Application for App {
     }
 
     fn subscription(&self) -> Subscription<Message> {
+        let market_data = if self.config.demo_mode {
+            self.api.subscription()
+        } else {
+            Subscription::batch([
+                self.ws.subscription(),
+                self.ws.klines_subscription(
+                    self.dashboard.chart_symbols(),
+                    self.dashboard.pair(),
+                    self.dashboard.chart_timeframe(),
+                ),
+                if self.dashboard.is_pane_open(PaneType::Liquidations) {
+                    self.ws.liquidations_subscription(self.dashboard.pair())
+                } else {
+                    Subscription::none()
+                },
+                self.ws.book_ticker_subscription(
+                    &self
+                        .dashboard
+                        .book_ticker_symbols(&self.config.watchlist_favorites),
+                ),
+                if self.dashboard.is_pane_open(PaneType::Sentiment) {
+                    self.ws
+                        .symbol_trades_subscription(&self.config.watchlist_favorites)
+                } else {
+                    Subscription::none()
+                },
+            ])
+        };
+
+        let network = if self.config.demo_mode {
+            Subscription::none()
+        } else {
+            network::watch(self.config.testnet).map(Message::NetworkStatus)
+        };
+
+        // futures streams are heavy, only listen while they are visible
+        let futures_ws = if self.tab == Tab::Futures && !self.config.demo_mode {
+            self.ws.futures_subscription()
+        } else {
+            Subscription::none()
+        };
+
         Subscription::batch([
             iced::time::every(Duration::from_millis(1000)).map(|_| Message::Tick),
-            self.ws.subscription(),
+            market_data,
+            network,
+            futures_ws,
+            self.dashboard.subscription().map(Message::from),
+            config::watch().map(Message::ConfigWatch),
+            self.splash
+                .subscription(&self.data.loader)
+                .map(Message::from),
+            event::listen_with(|event, _| match event {
+                iced::Event::Window(id, window::Event::CloseRequested) => {
+                    Some(Message::CloseRequested(id))
+                }
+                _ => None,
+            }),
             /*
             keyboard::on_key_press(|key_code, modifiers| {
                 if !modifiers.command() {
@@ -238,7 +865,20 @@ impl Application for App {
         ])
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
+        // TestNet and demo do not use mainnet accounts
+        let account_switcher =
+            (self.config.accounts.len() > 1 && !self.config.demo_mode && !self.config.testnet)
+                .then(|| {
+                    pick_list(
+                        self.config.account_choices(),
+                        self.config.active_account_choice(),
+                        Message::AccountSelected,
+                    )
+                    .text_size(14)
+                    .padding([4, 8])
+                });
+
         let header = container(
             row![
                 Row::with_children(
@@ -258,14 +898,29 @@ impl Application for App {
                             let svg = svg(handle)
                                 .width(Length::Fixed(16.0))
                                 .height(Length::Fixed(16.0));
-                            return row![svg, text(format!("{price_now:.2}")).size(14)]
+                            row![svg, text(format!("{price_now:.2}")).size(14)]
                                 .spacing(4)
-                                .align_items(iced::Alignment::Center);
+                                .align_items(iced::Alignment::Center)
                         })
                         .map(Element::from)
                 )
                 .spacing(12),
                 Space::new(Length::Fill, 1),
+                Row::with_children(account_switcher.map(Element::from)),
+                self.tab_button("Spot", Tab::Spot),
+                self.tab_button("Futures", Tab::Futures),
+                button(
+                    // icon shows current theme
+                    text(match self.config.theme {
+                        crate::theme::Theme::Light => '\u{F5A1}',
+                        crate::theme::Theme::Dark | crate::theme::Theme::Custom(_) => '\u{F497}',
+                    })
+                    .size(14)
+                    .font(iced::Font::with_name("bootstrap-icons"))
+                )
+                .padding(8)
+                .style(iced::theme::Button::Text)
+                .on_press(Message::ToggleTheme),
                 button(text("Settings").size(14))
                     .padding(8)
                     .style(iced::theme::Button::Text)
@@ -275,7 +930,7 @@ impl Application for App {
         )
         .padding([0, 16])
         .style(container::Appearance {
-            background: Some(iced::Background::Color(Color::from_rgb(0.07, 0.07, 0.07))),
+            background: Some(iced::Background::Color(color(Role::Surface))),
             border: iced::Border {
                 radius: 16.0.into(),
                 ..Default::default()
@@ -304,37 +959,133 @@ impl Application for App {
             ..Default::default()
         });
 
+        // live and TestNet dashboards look the same otherwise
+        let testnet_banner = (self.config.testnet && !self.config.demo_mode).then(|| {
+            container(text("TESTNET").size(14).style(color(Role::Background)))
+                .width(Length::Fill)
+                .center_x()
+                .padding(4)
+                .style(container::Appearance {
+                    background: Some(iced::Background::Color(color(Role::Warning))),
+                    border: iced::Border {
+                        radius: 16.0.into(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .into()
+        });
+
+        // websockets stay paused until ping succeeds again
+        let network_banner = match self.data.network_status {
+            NetworkStatus::Online => None,
+            NetworkStatus::Offline => Some((
+                "OFFLINE, reconnecting once Binance can be reached".to_owned(),
+                color(Role::Danger),
+            )),
+            NetworkStatus::Degraded(latency) => Some((
+                format!("Slow connection, ping took {} ms", latency.as_millis()),
+                color(Role::Warning),
+            )),
+        }
+        .filter(|_| !self.config.demo_mode)
+        .map(|(message, background)| {
+            container(text(message).size(14).style(color(Role::Background)))
+                .width(Length::Fill)
+                .center_x()
+                .padding(4)
+                .style(container::Appearance {
+                    background: Some(iced::Background::Color(background)),
+                    border: iced::Border {
+                        radius: 16.0.into(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .into()
+        });
+
+        let body = if self.settings_opened {
+            container(self.settings.view())
+        } else {
+            let dashboard = match self.tab {
+                Tab::Spot => self
+                    .dashboard
+                    .view(&self.data, &self.config)
+                    .map(Message::from),
+                Tab::Futures => self.futures.view(&self.data).map(Message::from),
+            };
+            let splash = self
+                .splash
+                .is_visible()
+                .then(|| self.splash.view(&self.data.loader).map(Message::from));
+
+            container(Floating::new(dashboard, splash, Anchor::Cover))
+        };
+
         let message_log = scrollable(column![container(
-            column![
-                if self.errors.is_empty() {
-                    header
-                } else {
-                    err_header
-                },
-                if self.settings_opened {
-                    container(self.settings.view())
-                } else {
-                    container(self.dashboard.view(&self.data).map(Message::from))
-                }
-            ]
+            Column::with_children(
+                network_banner.into_iter().chain(testnet_banner).chain([
+                    if self.errors.is_empty() {
+                        header
+                    } else {
+                        err_header
+                    }
+                    .into(),
+                    body.into(),
+                ])
+            )
             .spacing(8)
         )
         .width(Length::Fill)
         .height(1000.0)
         .padding(10),]);
 
-        container(message_log)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .padding(20)
-            .style(|_: &_| container::Appearance {
-                background: Some(iced::Background::Color(Color::BLACK)),
-                ..Default::default()
-            })
+        let notifications = (!self.data.notifications.is_empty()).then(|| {
+            Column::with_children(self.data.notifications.iter().map(|notification| {
+                container(
+                    row![
+                        text(&notification.text).size(14).width(Length::Fill),
+                        button(text("X").size(14))
+                            .padding(8)
+                            .style(iced::theme::Button::Text)
+                            .on_press(Message::NotificationDismissed(notification.created))
+                    ]
+                    .spacing(12)
+                    .align_items(iced::Alignment::Center),
+                )
+                .width(320.0)
+                .padding([0, 0, 0, 16])
+                .style(container::Appearance {
+                    background: Some(iced::Background::Color(notification.severity.color())),
+                    border: iced::Border {
+                        radius: 16.0.into(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .into()
+            }))
+            .spacing(8)
             .into()
+        });
+
+        container(Floating::new(
+            message_log,
+            notifications,
+            Anchor::BottomRight,
+        ))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .padding(20)
+        .style(|_: &_| container::Appearance {
+            background: Some(iced::Background::Color(Color::BLACK)),
+            ..Default::default()
+        })
+        .into()
     }
 
     fn theme(&self) -> Self::Theme {
-        Theme::Dark
+        ThemeProvider::iced_theme()
     }
 }
@@ -1,7 +1,61 @@
 use std::{fmt::Display, fs, io};
 
+use iced::{
+    subscription::{self, Subscription},
+    widget::pane_grid::{self, Configuration},
+};
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    alerts::Alert,
+    data::TRADE_FEE,
+    indicators::IndicatorConfig,
+    news,
+    shortcuts::KeyboardShortcuts,
+    theme::{Palette, Theme},
+    views::dashboard::Pane,
+};
+
+fn default_news_feed_url() -> String {
+    news::DEFAULT_FEED_URL.to_owned()
+}
+
+fn default_target_fps() -> u32 {
+    30
+}
+
+fn default_chart_scroll_speed_ms() -> u32 {
+    250
+}
+
+fn default_arb_threshold_pct() -> f64 {
+    0.1
+}
+
+fn default_fee() -> f64 {
+    TRADE_FEE
+}
+
+fn default_use_agg_trades() -> bool {
+    true
+}
+
+fn default_toast_duration_ms() -> u64 {
+    5000
+}
+
+fn default_sound_volume() -> f32 {
+    0.5
+}
+
+fn default_pair() -> String {
+    "BTCUSDT".to_owned()
+}
+
+fn default_quote_asset() -> String {
+    "USDT".to_owned()
+}
+
 fn default_favorites() -> Vec<String> {
     [
         "BTCUSDT", "ETHUSDT", "LINKUSDT", "UNIUSDT", "ARBUSDT", "SYNUSDT", "OPUSDT",
@@ -11,30 +65,314 @@ fn default_favorites() -> Vec<String> {
     .collect()
 }
 
+/// Candle and volume colors as hex strings like `32D993`
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Config {
+#[serde(default)]
+pub(crate) struct ChartColors {
+    pub(crate) bull_body: String,
+    pub(crate) bear_body: String,
+    pub(crate) wick: String,
+    pub(crate) volume: String,
+    /// Bars well above average volume
+    pub(crate) high_volume: String,
+}
+
+impl Default for ChartColors {
+    fn default() -> Self {
+        Self {
+            bull_body: "32D993".to_owned(),
+            bear_body: "FF707E".to_owned(),
+            wick: "B7BDB7".to_owned(),
+            volume: "4A4F4A".to_owned(),
+            high_volume: "F5C451".to_owned(),
+        }
+    }
+}
+
+/// Look of dashboard panes, colors are hex strings like `32D993`
+///
+/// Empty colors follow active theme
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct PaneStyleConfig {
+    pub(crate) border_width: f32,
+    pub(crate) border_color_focused: String,
+    pub(crate) border_color_active: String,
+    /// Tint of title bar of pinned panes
+    pub(crate) border_color_pinned: String,
+    pub(crate) background: String,
+    pub(crate) corner_radius: f32,
+    /// Gap between panes
+    pub(crate) spacing: f32,
+    /// Vertical and horizontal padding of title bars
+    pub(crate) title_padding: [u16; 2],
+    /// Panes cannot be resized below this width or height
+    pub(crate) min_pane_size: u16,
+}
+
+impl Default for PaneStyleConfig {
+    fn default() -> Self {
+        Self {
+            border_width: 0.0,
+            border_color_focused: String::new(),
+            border_color_active: String::new(),
+            border_color_pinned: String::new(),
+            background: String::new(),
+            corner_radius: 16.0,
+            spacing: 10.0,
+            title_padding: [8, 12],
+            min_pane_size: 60,
+        }
+    }
+}
+
+/// Named key pair of one Binance account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct AccountConfig {
+    pub(crate) name: String,
     pub(crate) api_key: String,
-    pub(crate) api_secret_key: String,
+    pub(crate) api_secret: String,
+}
+
+impl AccountConfig {
+    pub(crate) fn new(name: String) -> Self {
+        Self {
+            name,
+            api_key: String::new(),
+            api_secret: String::new(),
+        }
+    }
+}
+
+/// Account in switcher, index is kept because names can repeat
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AccountChoice {
+    pub(crate) index: usize,
+    name: String,
+}
+
+impl Display for AccountChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.name.fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// Mainnet accounts, credentials of active one are used
+    #[serde(default)]
+    pub(crate) accounts: Vec<AccountConfig>,
+    #[serde(default)]
+    pub(crate) active_account: usize,
+    /// Credentials from before accounts existed, moved to first account on load
+    #[serde(default, skip_serializing)]
+    api_key: String,
+    #[serde(default, skip_serializing)]
+    api_secret_key: String,
+    /// Pair opened on launch
+    #[serde(default = "default_pair")]
+    pub(crate) default_pair: String,
+    /// Quote paired with assets selected without one, like balances
+    #[serde(default = "default_quote_asset")]
+    pub(crate) preferred_quote_asset: String,
     #[serde(default = "default_favorites")]
     pub(crate) watchlist_favorites: Vec<String>,
+    /// Arbitrage routes of favorites gaining less than this percent are hidden
+    #[serde(default = "default_arb_threshold_pct")]
+    pub(crate) arb_threshold_pct: f64,
+    #[serde(default)]
+    pub(crate) keyboard_shortcuts: KeyboardShortcuts,
+    #[serde(default)]
+    pub(crate) chart_colors: ChartColors,
+    #[serde(default)]
+    pub(crate) pane_style: PaneStyleConfig,
+    #[serde(default)]
+    pub(crate) alerts: Vec<Alert>,
+    #[serde(default)]
+    pub(crate) indicators: IndicatorConfig,
+    /// Fee rate of orders resting in book, not in percent
+    #[serde(default = "default_fee")]
+    pub(crate) fee_maker: f64,
+    /// Fee rate of orders filled right away, not in percent
+    #[serde(default = "default_fee")]
+    pub(crate) fee_taker: f64,
+    /// Trades pane gets trades of one taker order merged, raw stream sends every fill
+    #[serde(default = "default_use_agg_trades")]
+    pub(crate) use_agg_trades: bool,
+    /// Time notifications stay shown
+    #[serde(default = "default_toast_duration_ms")]
+    pub(crate) toast_duration_ms: u64,
+    /// Beep on fills and triggered alerts
+    #[serde(default)]
+    pub(crate) sound_alerts: bool,
+    /// Between 0 and 1
+    #[serde(default = "default_sound_volume")]
+    pub(crate) sound_volume: f32,
+    /// Upper limit of redraws per second caused by animations
+    #[serde(default = "default_target_fps")]
+    pub(crate) target_fps: u32,
+    /// Time line chart takes to scroll by one new price, 0 jumps right away
+    #[serde(default = "default_chart_scroll_speed_ms")]
+    pub(crate) chart_scroll_speed_ms: u32,
+    /// RSS or Atom feed shown in news pane
+    #[serde(default = "default_news_feed_url")]
+    pub(crate) news_feed_url: String,
+    /// Orders are simulated locally instead of being sent to exchange
+    #[serde(default)]
+    pub(crate) paper_trade: bool,
+    /// Synthetic market data and fills instead of exchange, credentials are not needed
+    #[serde(default)]
+    pub(crate) demo_mode: bool,
+    /// Binance TestNet is used instead of mainnet, with its own credentials
+    #[serde(default)]
+    pub(crate) testnet: bool,
+    #[serde(default)]
+    pub(crate) testnet_api_key: String,
+    #[serde(default)]
+    pub(crate) testnet_api_secret: String,
+    /// SHA-256 fingerprint of websocket server certificate, book and trade streams refuse other
+    /// certificates if set
+    #[serde(default)]
+    pub(crate) tls_pin: Option<String>,
+    #[serde(default)]
+    pub(crate) theme: Theme,
+    /// Last loaded custom palette, kept while other themes are active so that cycling returns to it
+    #[serde(default)]
+    pub(crate) custom_palette: Option<Palette>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            accounts: Vec::new(),
+            active_account: 0,
             api_key: String::new(),
             api_secret_key: String::new(),
+            default_pair: default_pair(),
+            preferred_quote_asset: default_quote_asset(),
             watchlist_favorites: default_favorites(),
+            arb_threshold_pct: default_arb_threshold_pct(),
+            keyboard_shortcuts: KeyboardShortcuts::default(),
+            chart_colors: ChartColors::default(),
+            pane_style: PaneStyleConfig::default(),
+            alerts: Vec::new(),
+            indicators: IndicatorConfig::default(),
+            fee_maker: default_fee(),
+            fee_taker: default_fee(),
+            use_agg_trades: default_use_agg_trades(),
+            toast_duration_ms: default_toast_duration_ms(),
+            sound_alerts: false,
+            sound_volume: default_sound_volume(),
+            target_fps: default_target_fps(),
+            chart_scroll_speed_ms: default_chart_scroll_speed_ms(),
+            news_feed_url: default_news_feed_url(),
+            paper_trade: false,
+            demo_mode: false,
+            testnet: false,
+            testnet_api_key: String::new(),
+            testnet_api_secret: String::new(),
+            tls_pin: None,
+            theme: Theme::default(),
+            custom_palette: None,
         }
     }
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "pane_grid::Axis")]
+enum AxisDef {
+    Horizontal,
+    Vertical,
+}
+
+/// Serializable copy of pane grid tree
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum LayoutNode {
+    Split {
+        #[serde(with = "AxisDef")]
+        axis: pane_grid::Axis,
+        ratio: f32,
+        a: Box<LayoutNode>,
+        b: Box<LayoutNode>,
+    },
+    Pane(Pane),
+}
+
+impl From<LayoutNode> for Configuration<Pane> {
+    fn from(value: LayoutNode) -> Self {
+        match value {
+            LayoutNode::Split { axis, ratio, a, b } => Configuration::Split {
+                axis,
+                ratio,
+                a: Box::new((*a).into()),
+                b: Box::new((*b).into()),
+            },
+            LayoutNode::Pane(pane) => Configuration::Pane(pane),
+        }
+    }
+}
+
+/// Dashboard pane layout, stored next to config because `pane_grid::State` is not serializable
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct LayoutConfig(LayoutNode);
+
+impl LayoutConfig {
+    pub(crate) const FILE_NAME: &'static str = "layout.json";
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn save(&self) -> Result<(), SaveError> {
+        let json = serde_json::to_string_pretty(self).expect("layout serializer is valid");
+        let dir = Config::dir();
+        fs::create_dir_all(&dir).map_err(SaveError::File)?;
+        fs::write(dir.join(Self::FILE_NAME), json.as_bytes()).map_err(SaveError::Write)
+    }
+
+    pub(crate) fn from_state(panes: &pane_grid::State<Pane>) -> Self {
+        fn walk(node: &pane_grid::Node, panes: &pane_grid::State<Pane>) -> LayoutNode {
+            match node {
+                pane_grid::Node::Split {
+                    axis, ratio, a, b, ..
+                } => LayoutNode::Split {
+                    axis: *axis,
+                    ratio: *ratio,
+                    a: Box::new(walk(a, panes)),
+                    b: Box::new(walk(b, panes)),
+                },
+                pane_grid::Node::Pane(pane) => LayoutNode::Pane(
+                    panes
+                        .get(*pane)
+                        .expect("pane from layout exists in state")
+                        .clone(),
+                ),
+            }
+        }
+
+        Self(walk(panes.layout(), panes))
+    }
+}
+
+impl From<LayoutConfig> for Configuration<Pane> {
+    fn from(value: LayoutConfig) -> Self {
+        value.0.into()
+    }
+}
+
 #[derive(Debug)]
 pub enum LoadError {
     IO(io::Error),
     Format(String),
 }
 
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::IO(err) => err.fmt(f),
+            LoadError::Format(err) => err.fmt(f),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum SaveError {
     File(io::Error),
@@ -50,20 +388,211 @@ impl Display for SaveError {
     }
 }
 
+/// How often config file is checked for edits where inotify is not available
+#[cfg(not(target_arch = "wasm32"))]
+const WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Editors save in several writes, file is loaded once it has been quiet this long
+#[cfg(not(target_arch = "wasm32"))]
+const WATCH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(300);
+
+#[derive(Debug, Clone)]
+pub(crate) enum ConfigEvent {
+    /// File was edited outside of app
+    Changed(Box<Config>),
+    /// Edited file could not be loaded
+    Failed(String),
+}
+
+#[cfg(target_os = "linux")]
+mod inotify {
+    use std::{
+        ffi::{CString, OsStr},
+        io,
+        mem::size_of,
+        os::{
+            fd::{AsRawFd, FromRawFd, OwnedFd},
+            unix::ffi::OsStrExt,
+        },
+        path::Path,
+    };
+
+    use iced_futures::futures::channel::mpsc::UnboundedSender;
+
+    /// Signals writes, creations and renames of `name` in `dir` from background thread
+    ///
+    /// Directory is watched instead of file because editors often replace file with a new one
+    pub(super) fn watch(dir: &Path, name: &OsStr, tx: UnboundedSender<()>) -> io::Result<()> {
+        // SAFETY: plain syscall, returned descriptor is owned below
+        let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: descriptor was just created and is not used anywhere else
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let dir = CString::new(dir.as_os_str().as_bytes())?;
+        let mask = libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO | libc::IN_CREATE;
+        // SAFETY: path is a valid C string that outlives call
+        if unsafe { libc::inotify_add_watch(fd.as_raw_fd(), dir.as_ptr(), mask) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let name = name.as_bytes().to_vec();
+        std::thread::Builder::new()
+            .name("config-watch".to_owned())
+            .spawn(move || read_events(&fd, &name, &tx))?;
+
+        Ok(())
+    }
+
+    /// Blocks on inotify until receiver of `tx` is dropped or read fails
+    fn read_events(fd: &OwnedFd, name: &[u8], tx: &UnboundedSender<()>) {
+        const HEADER: usize = size_of::<libc::inotify_event>();
+        let mut buf = [0u8; 4096];
+
+        loop {
+            // SAFETY: buffer is valid for its whole length
+            let read = unsafe { libc::read(fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+            let Ok(read) = usize::try_from(read) else {
+                if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return;
+            };
+
+            let mut offset = 0;
+            while offset + HEADER <= read {
+                // SAFETY: kernel writes whole events, header is in bounds, buffer is not aligned
+                let event: libc::inotify_event =
+                    unsafe { std::ptr::read_unaligned(buf[offset..].as_ptr().cast()) };
+                let name_end = (offset + HEADER + event.len as usize).min(read);
+                // name is padded with zeros
+                let event_name = buf[offset + HEADER..name_end]
+                    .split(|b| *b == 0)
+                    .next()
+                    .unwrap_or_default();
+
+                if event_name == name && tx.unbounded_send(()).is_err() {
+                    return;
+                }
+                offset = name_end;
+            }
+        }
+    }
+}
+
+/// Polls modification time of config file off UI thread, for systems without inotify
+#[cfg(not(target_arch = "wasm32"))]
+async fn poll_modified(tx: iced_futures::futures::channel::mpsc::UnboundedSender<()>) {
+    let modified = || async {
+        tokio::task::spawn_blocking(Config::modified)
+            .await
+            .ok()
+            .flatten()
+    };
+    let mut last = modified().await;
+
+    loop {
+        tokio::time::sleep(WATCH_INTERVAL).await;
+
+        let now = modified().await;
+        if now != last {
+            last = now;
+            if tx.unbounded_send(()).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Reloads config file after it is edited and stays unchanged for `WATCH_DEBOUNCE`
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn watch() -> Subscription<ConfigEvent> {
+    use iced_futures::futures::{channel::mpsc, SinkExt, StreamExt};
+
+    struct Watch;
+
+    subscription::channel(
+        std::any::TypeId::of::<Watch>(),
+        1,
+        |mut output| async move {
+            let (tx, mut changes) = mpsc::unbounded();
+
+            #[cfg(target_os = "linux")]
+            let tx = {
+                let path = Config::path();
+                let watched = match (path.parent(), path.file_name()) {
+                    (Some(dir), Some(name)) => inotify::watch(dir, name, tx.clone()),
+                    _ => Err(io::ErrorKind::NotFound.into()),
+                };
+                match watched {
+                    Ok(()) => None,
+                    // directory is created on first save
+                    Err(err) => {
+                        tracing::warn!("inotify is unavailable, polling config file: {err}");
+                        Some(tx)
+                    }
+                }
+            };
+            #[cfg(not(target_os = "linux"))]
+            let tx = Some(tx);
+
+            if let Some(tx) = tx {
+                tokio::spawn(poll_modified(tx));
+            }
+
+            while changes.next().await.is_some() {
+                // every further change restarts wait
+                let debounce = WATCH_DEBOUNCE;
+                while let Ok(Some(())) = tokio::time::timeout(debounce, changes.next()).await {
+                    tracing::debug!("config file is still changing");
+                }
+
+                let event = match tokio::task::spawn_blocking(Config::load).await {
+                    Ok(Ok(Some(config))) => ConfigEvent::Changed(Box::new(config)),
+                    // removed file is written again on next save
+                    Ok(Ok(None)) => continue,
+                    Ok(Err(err)) => ConfigEvent::Failed(err.to_string()),
+                    Err(err) => ConfigEvent::Failed(err.to_string()),
+                };
+                let _ = output.send(event).await;
+            }
+
+            // watcher is gone, subscription must not end
+            std::future::pending().await
+        },
+    )
+}
+
+/// Storage cannot be edited outside of app
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn watch() -> Subscription<ConfigEvent> {
+    Subscription::none()
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 impl Config {
-    fn path() -> std::path::PathBuf {
-        let mut path = if let Some(project_dirs) =
-            directories_next::ProjectDirs::from("rs", "x86y", "Dynasty")
-        {
+    /// Directory where config and other persistent state is stored
+    pub(crate) fn dir() -> std::path::PathBuf {
+        if let Some(project_dirs) = directories_next::ProjectDirs::from("rs", "x86y", "Dynasty") {
             project_dirs.config_dir().into()
         } else {
             std::env::current_dir().unwrap_or_default()
-        };
+        }
+    }
+
+    fn path() -> std::path::PathBuf {
+        let mut path = Self::dir();
         path.push("config.json");
         path
     }
 
+    /// Last modification time of config file, for noticing edits made outside of app
+    fn modified() -> Option<std::time::SystemTime> {
+        fs::metadata(Self::path()).and_then(|m| m.modified()).ok()
+    }
+
     pub(crate) fn load() -> Result<Option<Config>, LoadError> {
         let contents = match fs::read_to_string(Self::path()) {
             Ok(contents) => Ok(contents),
@@ -75,7 +604,9 @@ impl Config {
             }
         }?;
 
-        serde_json::from_str(&contents).map_err(|err| LoadError::Format(err.to_string()))
+        serde_json::from_str(&contents)
+            .map(|config: Option<Config>| config.map(Config::migrated))
+            .map_err(|err| LoadError::Format(err.to_string()))
     }
 
     pub(crate) fn save(&self) -> Result<(), SaveError> {
@@ -87,16 +618,135 @@ impl Config {
         fs::write(path, json.as_bytes()).map_err(SaveError::Write)
     }
 
+    /// Copy with API keys of accounts and testnet removed, for sharing exported config
+    pub(crate) fn without_secrets(&self) -> Self {
+        let mut config = self.clone();
+        for account in &mut config.accounts {
+            account.api_key.clear();
+            account.api_secret.clear();
+        }
+        config.testnet_api_key.clear();
+        config.testnet_api_secret.clear();
+        config
+    }
+
+    /// Writes config with dashboard layout under `layout` key, so it can be moved to another machine
+    pub(crate) fn export_to_file(
+        &self,
+        layout: &LayoutConfig,
+        path: &std::path::Path,
+    ) -> Result<(), SaveError> {
+        let mut json = serde_json::to_value(self).expect("config serializer is valid");
+        json["layout"] = serde_json::to_value(layout).expect("layout serializer is valid");
+
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(SaveError::File)?;
+        }
+        let json = serde_json::to_string_pretty(&json).expect("json serializer is valid");
+        fs::write(path, json.as_bytes()).map_err(SaveError::Write)
+    }
+
+    /// Reads file written by `export_to_file`, layout is missing in plain config files
+    pub(crate) fn import_from_file(
+        path: &std::path::Path,
+    ) -> Result<(Config, Option<LayoutConfig>), LoadError> {
+        let contents = fs::read_to_string(path).map_err(LoadError::IO)?;
+
+        Self::from_export(&contents).map_err(|err| LoadError::Format(err.to_string()))
+    }
+
+    fn from_export(contents: &str) -> serde_json::Result<(Config, Option<LayoutConfig>)> {
+        let mut json: serde_json::Value = serde_json::from_str(contents)?;
+        let layout = match json.get_mut("layout").map(serde_json::Value::take) {
+            Some(layout) => Some(serde_json::from_value(layout)?),
+            None => None,
+        };
+
+        Ok((Config::deserialize(json)?.migrated(), layout))
+    }
+
+    /// Moves credentials of old config to account
+    fn migrated(mut self) -> Self {
+        let (api_key, api_secret) = (
+            std::mem::take(&mut self.api_key),
+            std::mem::take(&mut self.api_secret_key),
+        );
+        if self.accounts.is_empty() && !(api_key.is_empty() && api_secret.is_empty()) {
+            self.accounts.push(AccountConfig {
+                name: "Main".to_owned(),
+                api_key,
+                api_secret,
+            });
+        }
+        self.active_account = self
+            .active_account
+            .min(self.accounts.len().saturating_sub(1));
+
+        // palette was only stored in theme before
+        if let (Theme::Custom(palette), None) = (&self.theme, &self.custom_palette) {
+            self.custom_palette = Some(palette.clone());
+        }
+
+        self
+    }
+
+    /// Mainnet account in use, None until one is added
+    pub(crate) fn account(&self) -> Option<&AccountConfig> {
+        self.accounts.get(self.active_account)
+    }
+
+    /// Same as `account`, first account is created if there are none
+    pub(crate) fn account_mut(&mut self) -> &mut AccountConfig {
+        if self.accounts.is_empty() {
+            self.accounts.push(AccountConfig::new("Main".to_owned()));
+        }
+        self.active_account = self.active_account.min(self.accounts.len() - 1);
+
+        &mut self.accounts[self.active_account]
+    }
+
+    pub(crate) fn account_choices(&self) -> Vec<AccountChoice> {
+        self.accounts
+            .iter()
+            .enumerate()
+            .map(|(index, account)| AccountChoice {
+                index,
+                name: account.name.clone(),
+            })
+            .collect()
+    }
+
+    pub(crate) fn active_account_choice(&self) -> Option<AccountChoice> {
+        self.account_choices().into_iter().nth(self.active_account)
+    }
+
+    /// Key pair of TestNet or active mainnet account, whichever is used
     pub(crate) fn credentials(&self) -> (&str, &str) {
-        (&self.api_key, &self.api_secret_key)
+        if self.testnet {
+            (&self.testnet_api_key, &self.testnet_api_secret)
+        } else {
+            self.account()
+                .map_or(("", ""), |a| (&a.api_key, &a.api_secret))
+        }
+    }
+
+    /// Same as `credentials`, for editing
+    pub(crate) fn credentials_mut(&mut self) -> (&mut String, &mut String) {
+        if self.testnet {
+            (&mut self.testnet_api_key, &mut self.testnet_api_secret)
+        } else {
+            let account = self.account_mut();
+            (&mut account.api_key, &mut account.api_secret)
+        }
     }
 
     fn crendentials_empty(&self) -> bool {
-        self.api_key.is_empty() || self.api_secret_key.is_empty()
+        let (public, secret) = self.credentials();
+        public.is_empty() || secret.is_empty()
     }
 
     pub(crate) fn complete(&self) -> bool {
-        !self.crendentials_empty()
+        self.demo_mode || !self.crendentials_empty()
     }
 }
 
@@ -129,3 +779,82 @@ impl Config {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::dashboard::DashboardView;
+
+    #[test]
+    fn old_credentials_become_account() {
+        let config: Config =
+            serde_json::from_str(r#"{"api_key": "public", "api_secret_key": "secret"}"#).unwrap();
+        let config = config.migrated();
+
+        assert_eq!(config.accounts.len(), 1);
+        assert_eq!(config.credentials(), ("public", "secret"));
+        assert!(!serde_json::to_string(&config)
+            .unwrap()
+            .contains("api_secret_key"));
+    }
+
+    #[test]
+    fn export_roundtrip() {
+        let config = Config {
+            watchlist_favorites: vec!["DOGEUSDT".to_owned()],
+            ..Config::default()
+        };
+        let layout = LayoutConfig::from_state(&DashboardView::default_layout());
+        let path = std::env::temp_dir().join("dynasty-export-roundtrip.json");
+
+        config.export_to_file(&layout, &path).unwrap();
+        let (imported, imported_layout) = Config::import_from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(imported.watchlist_favorites, config.watchlist_favorites);
+        assert!(imported_layout.is_some());
+        assert!(Config::from_export("{}").unwrap().1.is_none());
+    }
+
+    #[test]
+    fn export_strips_secrets() {
+        let config = Config {
+            accounts: vec![AccountConfig {
+                name: "main".to_owned(),
+                api_key: "key".to_owned(),
+                api_secret: "secret".to_owned(),
+            }],
+            testnet_api_secret: "testnet secret".to_owned(),
+            ..Config::default()
+        }
+        .without_secrets();
+
+        assert_eq!(config.accounts[0].name, "main");
+        assert!(config.accounts[0].api_key.is_empty());
+        assert!(config.accounts[0].api_secret.is_empty());
+        assert!(config.testnet_api_secret.is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn inotify_reports_writes_of_file() {
+        use iced_futures::futures::channel::mpsc;
+
+        let dir = std::env::temp_dir().join(format!("dynasty-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (tx, mut rx) = mpsc::unbounded();
+        inotify::watch(&dir, "config.json".as_ref(), tx).unwrap();
+
+        fs::write(dir.join("other.json"), "{}").unwrap();
+        fs::write(dir.join("config.json"), "{}").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+
+        // creation and close after write, other file is ignored
+        let mut signals = 0;
+        while let Ok(Some(())) = rx.try_next() {
+            signals += 1;
+        }
+        assert_eq!(signals, 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
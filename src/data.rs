@@ -1,14 +1,49 @@
-use ahash::AHashMap;
-use std::{collections::BTreeMap, mem::MaybeUninit};
+use ahash::{AHashMap, AHashSet};
+use ringbuf::Rb;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    mem::MaybeUninit,
+    time::{Duration, Instant},
+};
 use tracing::trace;
 
-use binance::rest_model::{Balance, Order};
+use binance::rest_model::{Balance, KlineSummary, Order, OrderSide, OrderStatus, OrderType};
 
-use crate::ws::{prices::AssetDetails, trades::TradesEvent};
+use crate::api::split_symbol;
+use crate::network::NetworkStatus;
+use crate::news::NewsItem;
+use crate::notification::{Notification, NOTIFICATIONS_LEN};
+use crate::ws::{
+    book_ticker::BookTick,
+    futures::{FundingRate, Liquidation},
+    prices::AssetDetails,
+    trades::TradesEvent,
+};
 
 /// Stack-allocated thread-local ring buffer with static capacity.
 pub(crate) type StaticLocalRb<T, const N: usize> = ringbuf::LocalRb<T, [MaybeUninit<T>; N]>;
 
+/// Number of points kept by price chart and everything drawn aligned with it
+pub(crate) const CHART_LEN: usize = 500;
+
+/// Funding rates above this in either direction are highlighted, 0.1%
+pub(crate) const EXTREME_FUNDING_RATE: f64 = 0.001;
+
+/// Portfolio snapshots kept for sparklines, one is taken every tick
+pub(crate) const PORTFOLIO_HISTORY_LEN: usize = 300;
+
+/// Older liquidations are dropped
+pub(crate) const LIQUIDATIONS_LEN: usize = 200;
+
+/// Time between closes of price series
+pub(crate) const PRICE_SERIES_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Day of closed minutes plus the one still updating
+pub(crate) const PRICE_SERIES_LEN: usize = 24 * 60 + 2;
+
+/// Trades older than this in milliseconds do not count towards sentiment
+const SENTIMENT_WINDOW: u64 = 5 * 60 * 1000;
+
 /// Filter strategy
 #[derive(Debug)]
 pub(crate) enum PriceFilter {
@@ -18,6 +53,9 @@ pub(crate) enum PriceFilter {
     /// Includes value
     Contains(String),
 
+    /// Matches pattern anywhere in value
+    Regex(regex::Regex),
+
     /// No filtering
     All,
 }
@@ -27,18 +65,104 @@ impl PriceFilter {
         match self {
             PriceFilter::Matches(filters) => filters.iter().any(|filter| value == *filter),
             PriceFilter::Contains(filter) => value.contains(filter),
+            PriceFilter::Regex(regex) => regex.is_match(value),
             PriceFilter::All => true,
         }
     }
 }
 
+/// Value prices are sorted by
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum PriceSort {
+    Price,
+
+    /// 24 hour price change, in percent
+    Change,
+
+    /// 24 hour volume in base asset
+    Volume,
+
+    /// Order of values in `PriceFilter::Matches`, other filters keep price order
+    Filter,
+}
+
+/// Part of 24 hour rolling window statistics of symbol
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Ticker24h {
+    /// Price change in percent
+    pub(crate) change: f64,
+    /// Traded amount in base asset
+    pub(crate) volume: f64,
+}
+
+/// Display precision and order limits of symbol from its exchange filters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SymbolFilter {
+    pub(crate) price_decimals: u8,
+    pub(crate) qty_decimals: u8,
+    /// Order quantity must be a multiple of this
+    pub(crate) step_size: f64,
+    /// Smallest price times quantity of order, in quote asset
+    pub(crate) min_notional: f64,
+}
+
+impl Default for SymbolFilter {
+    /// Used until exchange info is loaded or for unknown symbols
+    fn default() -> Self {
+        Self {
+            price_decimals: 2,
+            qty_decimals: 4,
+            step_size: 0.0001,
+            min_notional: 0.0,
+        }
+    }
+}
+
+impl SymbolFilter {
+    /// Number of meaningful decimals in tick or step size like `0.00100000`
+    pub(crate) fn decimals(step: &str) -> u8 {
+        step.split_once('.').map_or(0, |(_, fraction)| {
+            fraction.trim_end_matches('0').len() as u8
+        })
+    }
+
+    pub(crate) fn price(&self, value: f64) -> String {
+        format!("{value:.prec$}", prec = usize::from(self.price_decimals))
+    }
+
+    pub(crate) fn qty(&self, value: f64) -> String {
+        format!("{value:.prec$}", prec = usize::from(self.qty_decimals))
+    }
+
+    /// Nearest quantity allowed by step size
+    pub(crate) fn round_qty(&self, value: f64) -> f64 {
+        if self.step_size > 0.0 {
+            (value / self.step_size).round() * self.step_size
+        } else {
+            value
+        }
+    }
+
+    /// Quantity rounded down to step size, never more than what is available
+    pub(crate) fn floor_qty(&self, value: f64) -> f64 {
+        if self.step_size > 0.0 {
+            // tolerance keeps exact multiples from falling a step below
+            (value / self.step_size + 1e-9).floor() * self.step_size
+        } else {
+            value
+        }
+    }
+}
+
 /// Precomputes price data
 ///
 /// Data is pushed to buffer before being available. Buffer is drained on pushes no more often than
 /// one second
 pub(crate) struct Prices {
     map: AHashMap<String, f32>,
+    ticker_24h: AHashMap<String, Ticker24h>,
     ordered: Vec<(String, f32)>,
+    sort: PriceSort,
     sort_descending: bool,
     filter: PriceFilter,
 }
@@ -53,14 +177,31 @@ impl Prices {
     fn new() -> Self {
         Self {
             map: AHashMap::default(),
+            ticker_24h: AHashMap::default(),
             ordered: Vec::new(),
+            sort: PriceSort::Price,
             sort_descending: true,
             filter: PriceFilter::Matches(Vec::new()),
         }
     }
 
     pub(crate) fn price(&self, name: &str) -> f32 {
-        *self.map.get(name).unwrap_or(&0.0)
+        self.get(name).unwrap_or(0.0)
+    }
+
+    /// Price if it was received
+    pub(crate) fn get(&self, name: &str) -> Option<f32> {
+        self.map.get(name).copied()
+    }
+
+    /// 24 hour price change in percent if it was received
+    pub(crate) fn change(&self, name: &str) -> Option<f64> {
+        self.ticker_24h.get(name).map(|t| t.change)
+    }
+
+    /// 24 hour volume in base asset if it was received
+    pub(crate) fn volume(&self, name: &str) -> Option<f64> {
+        self.ticker_24h.get(name).map(|t| t.volume)
     }
 
     fn filter_now(&mut self) {
@@ -81,12 +222,44 @@ impl Prices {
     }
 
     fn sort_now(&mut self) {
-        let sort_pred = if self.sort_descending {
-            |(_, p1): &(_, f32), (_, p2): &(_, f32)| p2.partial_cmp(p1).unwrap()
-        } else {
-            |(_, p1): &(_, f32), (_, p2): &(_, f32)| p1.partial_cmp(p2).unwrap()
-        };
-        self.ordered.sort_by(sort_pred);
+        match (self.sort, &self.filter) {
+            (PriceSort::Filter, PriceFilter::Matches(names)) => {
+                let position = |name: &String| names.iter().position(|n| n == name);
+                self.ordered
+                    .sort_by_key(|(name, _)| position(name).unwrap_or(usize::MAX));
+                if !self.sort_descending {
+                    self.ordered.reverse();
+                }
+            }
+            (PriceSort::Price | PriceSort::Filter, _) => {
+                let sort_pred = if self.sort_descending {
+                    |(_, p1): &(_, f32), (_, p2): &(_, f32)| p2.partial_cmp(p1).unwrap()
+                } else {
+                    |(_, p1): &(_, f32), (_, p2): &(_, f32)| p1.partial_cmp(p2).unwrap()
+                };
+                self.ordered.sort_by(sort_pred);
+            }
+            (sort @ (PriceSort::Change | PriceSort::Volume), _) => {
+                let tickers = &self.ticker_24h;
+                let descending = self.sort_descending;
+                let value = |name: &String| {
+                    tickers.get(name).map(|t| match sort {
+                        PriceSort::Volume => t.volume,
+                        _ => t.change,
+                    })
+                };
+
+                // unknown values always go last
+                self.ordered
+                    .sort_by(|(n1, _), (n2, _)| match (value(n1), value(n2)) {
+                        (Some(v1), Some(v2)) if descending => v2.total_cmp(&v1),
+                        (Some(v1), Some(v2)) => v1.total_cmp(&v2),
+                        (Some(_), None) => std::cmp::Ordering::Less,
+                        (None, Some(_)) => std::cmp::Ordering::Greater,
+                        (None, None) => std::cmp::Ordering::Equal,
+                    });
+            }
+        }
     }
 
     pub(crate) fn add_many(&mut self, assets: Vec<AssetDetails>) {
@@ -98,6 +271,16 @@ impl Prices {
         self.filter_now();
     }
 
+    /// Replaces 24 hour ticker statistics
+    ///
+    /// This immediately applies
+    pub(crate) fn set_ticker_24h(&mut self, tickers: AHashMap<String, Ticker24h>) {
+        trace!("adding {} 24 hour tickers", tickers.len());
+
+        self.ticker_24h = tickers;
+        self.sort_now();
+    }
+
     /// Sets filter
     ///
     /// This immediately applies
@@ -113,20 +296,42 @@ impl Prices {
     /// This immediately applies
     pub(crate) fn flip_sort(&mut self) {
         self.sort_descending = !self.sort_descending;
-        self.ordered.reverse();
+        self.sort_now();
+    }
+
+    /// Sets sorting value and order
+    ///
+    /// This does not immediately apply
+    pub(crate) fn set_sort(&mut self, sort: PriceSort, descending: bool) {
+        self.sort = sort;
+        self.sort_descending = descending;
     }
 
     /// Restores original sorting order
     ///
     /// This does not immediately apply
     pub(crate) fn reset_sort(&mut self) {
-        self.sort_descending = true;
+        self.set_sort(PriceSort::Price, true);
+    }
+
+    pub(crate) fn sort(&self) -> PriceSort {
+        self.sort
     }
 
     pub(crate) fn is_empty(&self) -> bool {
         self.map.is_empty()
     }
 
+    /// Number of all known symbols, regardless of filter
+    pub(crate) fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Number of symbols passing filter
+    pub(crate) fn filtered_len(&self) -> usize {
+        self.ordered.len()
+    }
+
     pub(crate) fn all(&self) -> impl Iterator<Item = (&String, &f32)> {
         self.map.iter()
     }
@@ -136,6 +341,270 @@ impl Prices {
     }
 }
 
+/// OHLC bar
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CandleData {
+    pub(crate) open: f64,
+    pub(crate) high: f64,
+    pub(crate) low: f64,
+    pub(crate) close: f64,
+    pub(crate) volume: f64,
+
+    /// Open time in milliseconds
+    pub(crate) ts: u64,
+}
+
+impl CandleData {
+    /// Moves close price, extending high and low if needed
+    pub(crate) fn update(&mut self, price: f64) {
+        self.close = price;
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+    }
+}
+
+impl From<&KlineSummary> for CandleData {
+    fn from(kline: &KlineSummary) -> Self {
+        Self {
+            open: kline.open,
+            high: kline.high,
+            low: kline.low,
+            close: kline.close,
+            volume: kline.volume,
+            ts: kline.open_time as u64,
+        }
+    }
+}
+
+/// Points of realized profit chart, oldest are dropped
+pub(crate) const PNL_HISTORY_LEN: usize = 1000;
+
+/// Fee taken from both sides of every fill, default spot rate of 0.1%
+pub(crate) const TRADE_FEE: f64 = 0.001;
+
+/// Average cost of held amount per symbol, used to realize profit of sells
+#[derive(Debug, Default)]
+pub(crate) struct CostBasis {
+    /// Held quantity and average price paid for it, fees included
+    positions: AHashMap<String, (f64, f64)>,
+    /// Orders already accounted for
+    seen: AHashSet<u64>,
+}
+
+impl CostBasis {
+    /// Profit of fill in quote asset, None for buys and sells of amount bought before history
+    fn fill(&mut self, order: &Order) -> Option<f64> {
+        if order.status != OrderStatus::Filled || !self.seen.insert(order.order_id) {
+            return None;
+        }
+
+        let price = if order.order_type == OrderType::Market {
+            order.cummulative_quote_qty / order.executed_qty
+        } else {
+            order.price
+        };
+        let qty = order.executed_qty;
+        let (held, basis) = self.positions.entry(order.symbol.clone()).or_default();
+
+        match order.side {
+            OrderSide::Buy => {
+                *basis = (*basis * *held + price * qty * (1.0 + TRADE_FEE)) / (*held + qty);
+                *held += qty;
+                None
+            }
+            OrderSide::Sell => {
+                let matched = qty.min(*held);
+                if matched <= 0.0 {
+                    return None;
+                }
+                *held -= matched;
+
+                Some(matched * (price * (1.0 - TRADE_FEE) - *basis))
+            }
+        }
+    }
+}
+
+/// Value of balances in USDT at one moment
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PortfolioSnapshot {
+    pub(crate) total: f64,
+    pub(crate) assets: AHashMap<String, f64>,
+}
+
+/// Volume weighted average price of trades since start of UTC day
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Vwap {
+    day: chrono::NaiveDate,
+    price_volume: f64,
+    volume: f64,
+}
+
+impl Vwap {
+    fn new(day: chrono::NaiveDate) -> Self {
+        Self {
+            day,
+            price_volume: 0.0,
+            volume: 0.0,
+        }
+    }
+
+    /// Adds trade, starting over if it happened on another day
+    fn add(&mut self, price: f64, qty: f64, day: chrono::NaiveDate) {
+        if day != self.day {
+            *self = Self::new(day);
+        }
+        self.price_volume += price * qty;
+        self.volume += qty;
+    }
+
+    pub(crate) fn value(&self) -> Option<f64> {
+        (self.volume > 0.0).then(|| self.price_volume / self.volume)
+    }
+}
+
+/// Taker buy and sell volume of recent trades
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SentimentData {
+    pub(crate) buy_vol: f64,
+    pub(crate) sell_vol: f64,
+    /// Time in milliseconds, quantity and whether taker bought, oldest first
+    pub(crate) window: VecDeque<(u64, f64, bool)>,
+}
+
+impl SentimentData {
+    fn add(&mut self, time: u64, qty: f64, is_buy: bool) {
+        if is_buy {
+            self.buy_vol += qty;
+        } else {
+            self.sell_vol += qty;
+        }
+        self.window.push_back((time, qty, is_buy));
+    }
+
+    /// Drops trades that left window ending at `now`
+    fn expire(&mut self, now: u64) {
+        while let Some(&(time, qty, is_buy)) = self.window.front() {
+            if time + SENTIMENT_WINDOW > now {
+                break;
+            }
+            if is_buy {
+                self.buy_vol -= qty;
+            } else {
+                self.sell_vol -= qty;
+            }
+            self.window.pop_front();
+        }
+
+        // subtraction leaves rounding errors behind
+        if self.window.is_empty() {
+            self.buy_vol = 0.0;
+            self.sell_vol = 0.0;
+        }
+    }
+
+    /// Share of buy volume in percent, None without trades
+    pub(crate) fn buy_pct(&self) -> Option<f64> {
+        let total = self.buy_vol + self.sell_vol;
+        (total > 0.0).then(|| self.buy_vol / total * 100.0)
+    }
+}
+
+/// Fill of own order from account trade list
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Trade {
+    pub(crate) id: u64,
+    /// Order trade filled, one order can have several trades
+    pub(crate) order_id: u64,
+    pub(crate) symbol: String,
+    /// Milliseconds
+    pub(crate) time: u64,
+    pub(crate) is_buyer: bool,
+    pub(crate) price: f64,
+    pub(crate) qty: f64,
+    pub(crate) commission: f64,
+    pub(crate) commission_asset: String,
+}
+
+/// Perpetual futures market state
+#[derive(Default)]
+pub(crate) struct FuturesData {
+    /// Latest funding rate by symbol
+    pub(crate) funding: BTreeMap<String, FundingRate>,
+    pub(crate) liquidations: StaticLocalRb<Liquidation, LIQUIDATIONS_LEN>,
+    /// Precision of futures symbols, differs from spot ones
+    pub(crate) symbol_filters: AHashMap<String, SymbolFilter>,
+}
+
+impl FuturesData {
+    /// Funding rates from HTTP poll or mark price stream, replacing older ones of same symbols
+    pub(crate) fn add_funding(&mut self, rates: Vec<FundingRate>) {
+        self.funding
+            .extend(rates.into_iter().map(|rate| (rate.symbol.clone(), rate)));
+    }
+
+    /// Precision of futures symbol, default one if exchange info has not arrived
+    pub(crate) fn symbol_filter(&self, symbol: &str) -> SymbolFilter {
+        self.symbol_filters
+            .get(&symbol.to_uppercase())
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// Request whose failure is shown by its pane instead of loader
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Fetch {
+    Orders,
+    Balances,
+    News,
+}
+
+/// Which of initial requests have completed, splash is shown until all have
+#[derive(Debug, Default, Clone)]
+pub(crate) struct LoaderState {
+    pub(crate) prices: bool,
+    pub(crate) balances: bool,
+    pub(crate) orders: bool,
+    pub(crate) exchange_info: bool,
+    /// Splash does not wait for news, pane does
+    pub(crate) news: bool,
+    /// Errors of last failed requests, cleared by successful ones
+    pub(crate) orders_error: Option<String>,
+    pub(crate) balances_error: Option<String>,
+    pub(crate) news_error: Option<String>,
+}
+
+impl LoaderState {
+    /// Marks request as completed so that panes stop showing loader
+    pub(crate) fn loaded(&mut self, fetch: Fetch, result: Result<(), String>) {
+        let (loaded, error) = match fetch {
+            Fetch::Orders => (&mut self.orders, &mut self.orders_error),
+            Fetch::Balances => (&mut self.balances, &mut self.balances_error),
+            Fetch::News => (&mut self.news, &mut self.news_error),
+        };
+        *loaded = true;
+        *error = result.err();
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.prices && self.balances && self.orders && self.exchange_info
+    }
+
+    /// Names of data splash still waits for
+    pub(crate) fn missing(&self) -> Vec<&'static str> {
+        [
+            (self.prices, "prices"),
+            (self.balances, "balances"),
+            (self.orders, "orders"),
+            (self.exchange_info, "exchange info"),
+        ]
+        .into_iter()
+        .filter_map(|(loaded, name)| (!loaded).then_some(name))
+        .collect()
+    }
+}
+
 #[derive(Default)]
 pub(crate) struct AppData {
     pub(crate) prices: Prices,
@@ -143,6 +612,685 @@ pub(crate) struct AppData {
     pub(crate) trades: StaticLocalRb<TradesEvent, 1000>,
     pub(crate) balances: Vec<Balance>,
     pub(crate) orders: Vec<Order>,
-    pub(crate) quote: String,
-    pub(crate) price_chart: StaticLocalRb<f64, 500>,
+    pub(crate) price_chart: StaticLocalRb<f64, CHART_LEN>,
+    /// Traded amount for every point of price chart
+    pub(crate) volume: StaticLocalRb<f64, CHART_LEN>,
+    /// Traded amount since last price was pushed to chart
+    pub(crate) pending_volume: f64,
+    pub(crate) candles: StaticLocalRb<CandleData, CHART_LEN>,
+    pub(crate) futures: FuturesData,
+    /// Headlines, newest first
+    pub(crate) news: Vec<NewsItem>,
+    /// Realized profit of simulated orders, None when trading for real
+    pub(crate) paper_pnl: Option<f64>,
+    /// Display precision by symbol
+    pub(crate) symbol_filters: AHashMap<String, SymbolFilter>,
+    pub(crate) portfolio_history: StaticLocalRb<PortfolioSnapshot, PORTFOLIO_HISTORY_LEN>,
+    pub(crate) cost_basis: CostBasis,
+    /// Fill time in milliseconds and cumulative realized profit after it
+    pub(crate) realized_pnl_history: VecDeque<(u64, f64)>,
+    /// Live candles of symbols charted in their own panes
+    pub(crate) symbol_candles: AHashMap<String, VecDeque<CandleData>>,
+    /// Taker fee rate of account
+    pub(crate) fee_tier: f64,
+    /// Futures liquidations of market pair
+    pub(crate) liquidations: StaticLocalRb<Liquidation, LIQUIDATIONS_LEN>,
+    /// Daily VWAP by symbol, accumulated from trades stream
+    pub(crate) vwap: AHashMap<String, Vwap>,
+    /// Own trades of one pair, newest first
+    pub(crate) trade_history: Vec<Trade>,
+    pub(crate) loader: LoaderState,
+    /// Pages of watchlist rows rendered so far, rows are added gradually to keep UI responsive
+    pub(crate) prices_loaded_page: usize,
+    /// Minute closes of favorites, last one is current price
+    pub(crate) price_series: AHashMap<String, VecDeque<f64>>,
+    /// Start of minute last in price series
+    pub(crate) price_series_minute: Option<Instant>,
+    /// Best bid and ask of favorites while spread monitor is open
+    pub(crate) book_tickers: AHashMap<String, BookTick>,
+    /// Buy and sell volume of favorites while sentiment pane is open
+    pub(crate) sentiment: AHashMap<String, SentimentData>,
+    /// Whether Binance can be reached at all
+    pub(crate) network_status: NetworkStatus,
+    /// Shown stacked in corner, oldest first
+    pub(crate) notifications: VecDeque<Notification>,
+}
+
+impl AppData {
+    /// Shows notification, oldest one is dropped if too many are shown
+    pub(crate) fn push_notification(&mut self, notification: Notification) {
+        self.notifications.push_back(notification);
+        if self.notifications.len() > NOTIFICATIONS_LEN {
+            self.notifications.pop_front();
+        }
+    }
+
+    /// Drops notifications shown for longer than `duration`
+    pub(crate) fn expire_notifications(&mut self, duration: Duration) {
+        self.notifications
+            .retain(|notification| notification.created.elapsed() < duration);
+    }
+
+    /// Updates current minute of `symbols` with latest prices, starting new one every interval
+    pub(crate) fn update_price_series(&mut self, symbols: &[String], now: Instant) {
+        let new_minute = self
+            .price_series_minute
+            .is_none_or(|start| now.duration_since(start) >= PRICE_SERIES_INTERVAL);
+        if new_minute {
+            self.price_series_minute = Some(now);
+        }
+
+        self.price_series
+            .retain(|symbol, _| symbols.contains(symbol));
+
+        for symbol in symbols {
+            let Some(price) = self.prices.get(symbol) else {
+                continue;
+            };
+            let series = self.price_series.entry(symbol.clone()).or_default();
+
+            match series.back_mut() {
+                Some(last) if !new_minute => *last = f64::from(price),
+                _ => {
+                    series.push_back(f64::from(price));
+                    if series.len() > PRICE_SERIES_LEN {
+                        series.pop_front();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds profit of orders filled since last call to realized profit history
+    pub(crate) fn track_realized_pnl(&mut self) {
+        let mut fills: Vec<_> = self
+            .orders
+            .iter()
+            .filter(|o| o.status == OrderStatus::Filled)
+            .collect();
+        fills.sort_by_key(|o| o.update_time);
+
+        for order in fills {
+            let Some(pnl) = self.cost_basis.fill(order) else {
+                continue;
+            };
+            let total = self
+                .realized_pnl_history
+                .back()
+                .map_or(0.0, |(_, total)| *total);
+
+            self.realized_pnl_history
+                .push_back((order.update_time, total + pnl));
+            if self.realized_pnl_history.len() > PNL_HISTORY_LEN {
+                self.realized_pnl_history.pop_front();
+            }
+        }
+    }
+
+    /// Replaces last candle of main chart if it is still open, appends newer one
+    pub(crate) fn push_candle(&mut self, candle: CandleData) {
+        match self.candles.iter_mut().last() {
+            Some(last) if last.ts == candle.ts => *last = candle,
+            // history has not arrived yet or stream is behind it
+            Some(last) if last.ts > candle.ts => (),
+            _ => {
+                self.candles.push_overwrite(candle);
+            }
+        }
+    }
+
+    /// Replaces last candle of symbol if it is still open, appends it otherwise
+    pub(crate) fn push_symbol_candle(&mut self, symbol: String, candle: CandleData) {
+        let candles = self.symbol_candles.entry(symbol).or_default();
+
+        match candles.back_mut() {
+            Some(last) if last.ts == candle.ts => *last = candle,
+            _ => {
+                candles.push_back(candle);
+                if candles.len() > CHART_LEN {
+                    candles.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Merges page of own trades, history of other pair is replaced
+    pub(crate) fn add_trade_history(&mut self, symbol: &str, trades: Vec<Trade>) {
+        if self
+            .trade_history
+            .first()
+            .is_some_and(|trade| trade.symbol != symbol)
+        {
+            self.trade_history.clear();
+        }
+
+        self.trade_history.extend(trades);
+        self.trade_history
+            .sort_by_key(|trade| std::cmp::Reverse(trade.id));
+        self.trade_history.dedup_by_key(|trade| trade.id);
+    }
+
+    /// Drops orders with profit computed from them, for switching between accounts
+    pub(crate) fn clear_orders(&mut self) {
+        self.orders.clear();
+        self.trade_history.clear();
+        self.cost_basis = CostBasis::default();
+        self.realized_pnl_history.clear();
+    }
+
+    /// Starts realized profit from zero, fills that were seen are not counted again
+    pub(crate) fn reset_realized_pnl(&mut self) {
+        self.realized_pnl_history.clear();
+    }
+
+    /// Price of amount of asset in USDT if there is a USDT market for it
+    pub(crate) fn usdt_value(&self, asset: &str, amount: f64) -> Option<f64> {
+        if asset == "USDT" {
+            return Some(amount);
+        }
+
+        Some(amount * f64::from(self.prices.get(&format!("{asset}USDT"))?))
+    }
+
+    /// Symbols with base asset that has non-zero balance, most valuable holdings first
+    pub(crate) fn owned_symbols(&self) -> Vec<String> {
+        let mut owned: Vec<_> = self
+            .balances
+            .iter()
+            .map(|b| (b.asset.as_str(), b.free + b.locked))
+            .filter(|(_, amount)| *amount > 0.0)
+            .map(|(asset, amount)| (asset, self.usdt_value(asset, amount).unwrap_or_default()))
+            .collect();
+        owned.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        let mut symbols = Vec::new();
+        for (asset, _) in owned {
+            let mut pairs: Vec<_> = self
+                .prices
+                .all()
+                .map(|(symbol, _)| symbol)
+                .filter(|symbol| split_symbol(symbol).is_some_and(|[base, _]| base == asset))
+                .cloned()
+                .collect();
+            pairs.sort();
+            symbols.extend(pairs);
+        }
+
+        symbols
+    }
+
+    /// Records current value of balances, skipped until any of them has a price
+    pub(crate) fn snapshot_portfolio(&mut self) {
+        let assets: AHashMap<_, _> = self
+            .balances
+            .iter()
+            .filter_map(|b| {
+                Some((
+                    b.asset.clone(),
+                    self.usdt_value(&b.asset, b.free + b.locked)?,
+                ))
+            })
+            .collect();
+        if assets.is_empty() {
+            return;
+        }
+
+        self.portfolio_history.push_overwrite(PortfolioSnapshot {
+            total: assets.values().sum(),
+            assets,
+        });
+    }
+
+    /// Recorded values of one asset, or of whole portfolio if `asset` is None
+    pub(crate) fn portfolio_values(&self, asset: Option<&str>) -> Vec<f64> {
+        self.portfolio_history
+            .iter()
+            .filter_map(|snapshot| match asset {
+                Some(asset) => snapshot.assets.get(asset).copied(),
+                None => Some(snapshot.total),
+            })
+            .collect()
+    }
+
+    /// Latest recorded value of all balances
+    pub(crate) fn portfolio_total(&self) -> Option<f64> {
+        self.portfolio_history.iter().last().map(|s| s.total)
+    }
+
+    /// Value current balances had 24 hours ago, derived from 24 hour price changes
+    pub(crate) fn portfolio_value_24h_ago(&self) -> Option<f64> {
+        let snapshot = self.portfolio_history.iter().last()?;
+
+        Some(
+            snapshot
+                .assets
+                .iter()
+                .map(|(asset, value)| {
+                    let change = self.prices.change(&format!("{asset}USDT")).unwrap_or(0.0);
+                    value / (1.0 + change / 100.0)
+                })
+                .sum(),
+        )
+    }
+
+    /// Funding rate of symbol if it is beyond `EXTREME_FUNDING_RATE`
+    pub(crate) fn extreme_funding_rate(&self, symbol: &str) -> Option<f64> {
+        self.futures
+            .funding
+            .get(symbol)
+            .map(|funding| funding.rate)
+            .filter(|rate| rate.abs() > EXTREME_FUNDING_RATE)
+    }
+
+    /// Precision of symbol, default one if exchange info has not arrived
+    pub(crate) fn symbol_filter(&self, symbol: &str) -> SymbolFilter {
+        self.symbol_filters
+            .get(&symbol.to_uppercase())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Puts historical prices before ones received live
+    pub(crate) fn prepend_chart_data(&mut self, history: &[f64]) {
+        let live: Vec<_> = self.price_chart.pop_iter().collect();
+        self.price_chart
+            .push_iter_overwrite(history.iter().copied().chain(live));
+    }
+
+    /// Highest bid and lowest ask of last book update
+    pub(crate) fn best_prices(&self) -> Option<(f64, f64)> {
+        let parse = |prices: &BTreeMap<String, f64>| {
+            prices
+                .keys()
+                .filter_map(|price| price.parse::<f64>().ok())
+                .collect::<Vec<_>>()
+        };
+
+        let bid = parse(&self.book.1).into_iter().reduce(f64::max)?;
+        let ask = parse(&self.book.2).into_iter().reduce(f64::min)?;
+
+        Some((bid, ask))
+    }
+
+    /// Same as `prepend_chart_data` for volume
+    pub(crate) fn prepend_volume_data(&mut self, history: &[f64]) {
+        let live: Vec<_> = self.volume.pop_iter().collect();
+        self.volume
+            .push_iter_overwrite(history.iter().copied().chain(live));
+    }
+
+    /// Adds live price to chart with volume traded since previous one
+    pub(crate) fn push_chart_price(&mut self, price: f64) {
+        self.price_chart.push_overwrite(price);
+        self.volume
+            .push_overwrite(std::mem::take(&mut self.pending_volume));
+    }
+
+    /// Adds trade of symbol to its sentiment, `is_buy` is side of taker
+    pub(crate) fn add_sentiment_trade(&mut self, symbol: &str, time: u64, qty: f64, is_buy: bool) {
+        match self.sentiment.get_mut(symbol) {
+            Some(sentiment) => {
+                sentiment.add(time, qty, is_buy);
+                sentiment.expire(time);
+            }
+            None => {
+                let mut sentiment = SentimentData::default();
+                sentiment.add(time, qty, is_buy);
+                self.sentiment.insert(symbol.to_owned(), sentiment);
+            }
+        }
+    }
+
+    /// Drops trades older than sentiment window, `now` is in milliseconds
+    pub(crate) fn expire_sentiment(&mut self, now: u64) {
+        for sentiment in self.sentiment.values_mut() {
+            sentiment.expire(now);
+        }
+    }
+
+    /// Adds trade of symbol to its VWAP
+    pub(crate) fn add_vwap_trade(&mut self, symbol: &str, price: f64, qty: f64) {
+        let day = chrono::Utc::now().date_naive();
+        match self.vwap.get_mut(symbol) {
+            Some(vwap) => vwap.add(price, qty, day),
+            None => {
+                let mut vwap = Vwap::new(day);
+                vwap.add(price, qty, day);
+                self.vwap.insert(symbol.to_owned(), vwap);
+            }
+        }
+    }
+
+    /// Drops chart prices, volumes and candles
+    pub(crate) fn clear_chart(&mut self) {
+        self.price_chart.clear();
+        self.volume.clear();
+        self.candles.clear();
+        self.pending_volume = 0.0;
+    }
+
+    /// Drops liquidations of previous market pair
+    pub(crate) fn clear_liquidations(&mut self) {
+        self.liquidations.clear();
+    }
+
+    /// Puts historical candles before live ones, dropping live candles that history covers
+    pub(crate) fn prepend_candle_data(&mut self, history: &[CandleData]) {
+        let last_ts = history.last().map_or(0, |candle| candle.ts);
+        let live: Vec<_> = self
+            .candles
+            .pop_iter()
+            .filter(|candle| candle.ts > last_ts)
+            .collect();
+        self.candles
+            .push_iter_overwrite(history.iter().copied().chain(live));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notification::Severity;
+
+    #[test]
+    fn volume_follows_chart() {
+        let mut data = AppData {
+            pending_volume: 3.0,
+            ..Default::default()
+        };
+        data.push_chart_price(10.0);
+        data.prepend_chart_data(&[8.0, 9.0]);
+        data.prepend_volume_data(&[1.0, 2.0]);
+
+        assert_eq!(
+            data.price_chart.iter().copied().collect::<Vec<_>>(),
+            [8.0, 9.0, 10.0]
+        );
+        assert_eq!(
+            data.volume.iter().copied().collect::<Vec<_>>(),
+            [1.0, 2.0, 3.0]
+        );
+        assert_eq!(data.pending_volume, 0.0);
+    }
+
+    #[test]
+    fn failed_fetch_stops_loading() {
+        let mut loader = LoaderState::default();
+
+        loader.loaded(Fetch::News, Err("timed out".to_owned()));
+        assert!(loader.news);
+        assert_eq!(loader.news_error.as_deref(), Some("timed out"));
+
+        loader.loaded(Fetch::News, Ok(()));
+        assert_eq!(loader.news_error, None);
+        assert!(!loader.orders);
+    }
+
+    #[test]
+    fn portfolio_snapshots() {
+        let mut data = AppData::default();
+        data.prices.add_many(vec![AssetDetails {
+            name: "ETHUSDT".to_owned(),
+            price: 2_000.0,
+        }]);
+        data.prices.set_ticker_24h(AHashMap::from([(
+            "ETHUSDT".to_owned(),
+            Ticker24h {
+                change: 100.0,
+                volume: 0.0,
+            },
+        )]));
+        data.balances = ["USDT", "ETH", "UNLISTED"]
+            .map(|asset| Balance {
+                asset: asset.to_owned(),
+                free: 1.0,
+                locked: 1.0,
+            })
+            .into();
+
+        data.snapshot_portfolio();
+
+        assert_eq!(data.portfolio_values(None), [4_002.0]);
+        assert_eq!(data.portfolio_values(Some("ETH")), [4_000.0]);
+        assert!(data.portfolio_values(Some("UNLISTED")).is_empty());
+        assert_eq!(data.portfolio_value_24h_ago(), Some(2_002.0));
+    }
+
+    #[test]
+    fn realized_pnl_from_fills() {
+        let order = |order_id, side, price, status| Order {
+            symbol: "ETHUSDT".to_owned(),
+            order_id,
+            order_list_id: -1,
+            client_order_id: String::new(),
+            price,
+            orig_qty: 2.0,
+            executed_qty: 2.0,
+            cummulative_quote_qty: price * 2.0,
+            status,
+            time_in_force: binance::rest_model::TimeInForce::GTC,
+            order_type: OrderType::Limit,
+            side,
+            stop_price: 0.0,
+            iceberg_qty: 0.0,
+            time: order_id,
+            update_time: order_id,
+            is_working: false,
+            orig_quote_order_qty: 0.0,
+        };
+
+        let mut data = AppData {
+            orders: vec![
+                order(3, OrderSide::Sell, 1_000.0, OrderStatus::New),
+                order(2, OrderSide::Sell, 1_500.0, OrderStatus::Filled),
+                order(1, OrderSide::Buy, 1_000.0, OrderStatus::Filled),
+            ],
+            ..Default::default()
+        };
+        data.track_realized_pnl();
+        // same fills are not counted twice
+        data.track_realized_pnl();
+
+        let fees = 2.0 * (1_000.0 + 1_500.0) * TRADE_FEE;
+        assert_eq!(data.realized_pnl_history.len(), 1);
+        let (ts, pnl) = data.realized_pnl_history[0];
+        assert_eq!(ts, 2);
+        assert!((pnl - (1_000.0 - fees)).abs() < 1e-9);
+
+        data.reset_realized_pnl();
+        data.track_realized_pnl();
+        assert!(data.realized_pnl_history.is_empty());
+    }
+
+    #[test]
+    fn symbol_candles_update_open_candle() {
+        let candle = |ts, close| CandleData {
+            open: 1.0,
+            high: 2.0,
+            low: 0.5,
+            close,
+            volume: 1.0,
+            ts,
+        };
+
+        let mut data = AppData::default();
+        data.push_symbol_candle("ETHUSDT".to_owned(), candle(0, 1.0));
+        data.push_symbol_candle("ETHUSDT".to_owned(), candle(0, 1.5));
+        data.push_symbol_candle("ETHUSDT".to_owned(), candle(60_000, 1.2));
+
+        let closes: Vec<_> = data.symbol_candles["ETHUSDT"]
+            .iter()
+            .map(|c| c.close)
+            .collect();
+        assert_eq!(closes, [1.5, 1.2]);
+
+        data.prepend_candle_data(&[candle(0, 1.0), candle(60_000, 1.1)]);
+        data.push_candle(candle(60_000, 1.3));
+        data.push_candle(candle(0, 0.9));
+        data.push_candle(candle(120_000, 1.4));
+        let closes: Vec<_> = data.candles.iter().map(|c| c.close).collect();
+        assert_eq!(closes, [1.0, 1.3, 1.4]);
+    }
+
+    #[test]
+    fn regex_filter() {
+        let mut prices = Prices::default();
+        prices.add_many(
+            ["BTCUSDT", "ETHUSDT", "ETHBTC"]
+                .map(|name| AssetDetails {
+                    name: name.to_owned(),
+                    price: 1.0,
+                })
+                .into(),
+        );
+        prices.set_filter(PriceFilter::Regex(regex::Regex::new("^ETH").unwrap()));
+
+        let mut names: Vec<_> = prices.sorted_and_filtered().map(|(n, _)| n).collect();
+        names.sort();
+        assert_eq!(names, ["ETHBTC", "ETHUSDT"]);
+    }
+
+    #[test]
+    fn symbol_filter_decimals() {
+        assert_eq!(SymbolFilter::decimals("0.01000000"), 2);
+        assert_eq!(SymbolFilter::decimals("0.00000001"), 8);
+        assert_eq!(SymbolFilter::decimals("1.00000000"), 0);
+        assert_eq!(SymbolFilter::decimals("10"), 0);
+
+        let filter = SymbolFilter {
+            price_decimals: 8,
+            qty_decimals: 0,
+            step_size: 1.0,
+            min_notional: 0.0,
+        };
+        assert_eq!(filter.price(0.000_000_010_000_000_03), "0.00000001");
+        assert_eq!(filter.qty(12.6), "13");
+
+        let filter = SymbolFilter {
+            step_size: 0.05,
+            qty_decimals: 2,
+            ..Default::default()
+        };
+        assert_eq!(filter.qty(filter.round_qty(1.234)), "1.25");
+        assert_eq!(filter.qty(filter.round_qty(1.224)), "1.20");
+        assert_eq!(filter.qty(filter.floor_qty(1.249)), "1.20");
+        assert_eq!(filter.qty(filter.floor_qty(1.25)), "1.25");
+    }
+
+    #[test]
+    fn vwap_resets_on_new_day() {
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut vwap = Vwap::new(day);
+        assert_eq!(vwap.value(), None);
+
+        vwap.add(100.0, 1.0, day);
+        vwap.add(130.0, 2.0, day);
+        assert!((vwap.value().unwrap() - 120.0).abs() < 1e-9);
+
+        vwap.add(50.0, 1.0, day.succ_opt().unwrap());
+        assert_eq!(vwap.value(), Some(50.0));
+    }
+
+    #[test]
+    fn owned_symbols_by_value() {
+        let mut data = AppData::default();
+        data.prices.add_many(
+            [
+                ("ETHUSDT", 2_000.0),
+                ("BTCUSDT", 40_000.0),
+                ("ETHBTC", 0.05),
+                ("SOLUSDT", 100.0),
+            ]
+            .map(|(name, price)| AssetDetails {
+                name: name.to_owned(),
+                price,
+            })
+            .into(),
+        );
+        data.balances = [("ETH", 1.0), ("BTC", 0.01), ("SOL", 0.0), ("USDT", 50.0)]
+            .map(|(asset, free)| Balance {
+                asset: asset.to_owned(),
+                free,
+                locked: 0.0,
+            })
+            .into();
+
+        assert_eq!(
+            data.owned_symbols(),
+            ["ETHBTC", "ETHUSDT", "BTCUSDT"].map(str::to_owned)
+        );
+    }
+
+    #[test]
+    fn trade_history_pages_merge() {
+        let trade = |id, symbol: &str| Trade {
+            id,
+            order_id: id,
+            symbol: symbol.to_owned(),
+            time: id * 1000,
+            is_buyer: true,
+            price: 1.0,
+            qty: 1.0,
+            commission: 0.0,
+            commission_asset: "USDT".to_owned(),
+        };
+        let ids =
+            |data: &AppData| -> Vec<u64> { data.trade_history.iter().map(|t| t.id).collect() };
+
+        let mut data = AppData::default();
+        data.add_trade_history("BTCUSDT", vec![trade(3, "BTCUSDT"), trade(5, "BTCUSDT")]);
+        data.add_trade_history("BTCUSDT", vec![trade(1, "BTCUSDT"), trade(3, "BTCUSDT")]);
+        assert_eq!(ids(&data), [5, 3, 1]);
+
+        data.add_trade_history("ETHUSDT", vec![trade(2, "ETHUSDT")]);
+        assert_eq!(ids(&data), [2]);
+    }
+
+    #[test]
+    fn sentiment_forgets_old_trades() {
+        let mut data = AppData::default();
+        data.add_sentiment_trade("BTCUSDT", 0, 3.0, true);
+        data.add_sentiment_trade("BTCUSDT", 1000, 1.0, false);
+        assert_eq!(data.sentiment["BTCUSDT"].buy_pct(), Some(75.0));
+
+        data.expire_sentiment(SENTIMENT_WINDOW);
+        assert_eq!(data.sentiment["BTCUSDT"].buy_pct(), Some(0.0));
+
+        data.expire_sentiment(SENTIMENT_WINDOW + 1000);
+        assert_eq!(data.sentiment["BTCUSDT"].buy_pct(), None);
+    }
+
+    #[test]
+    fn notifications_are_capped() {
+        let mut data = AppData::default();
+        for i in 0..7 {
+            data.push_notification(Notification::new(Severity::Info, i.to_string()));
+        }
+
+        let texts: Vec<_> = data.notifications.iter().map(|n| n.text.as_str()).collect();
+        assert_eq!(texts, ["2", "3", "4", "5", "6"]);
+
+        data.expire_notifications(Duration::ZERO);
+        assert!(data.notifications.is_empty());
+    }
+
+    #[test]
+    fn funding_rates_replace_older_ones() {
+        let rate = |symbol: &str, rate| FundingRate {
+            symbol: symbol.to_owned(),
+            rate,
+            mark_price: 1.0,
+            next_funding_time: 0,
+        };
+        let mut data = AppData::default();
+
+        data.futures
+            .add_funding(vec![rate("BTCUSDT", 0.002), rate("ETHUSDT", 0.0001)]);
+        assert_eq!(data.extreme_funding_rate("BTCUSDT"), Some(0.002));
+        assert_eq!(data.extreme_funding_rate("ETHUSDT"), None);
+
+        data.futures.add_funding(vec![rate("BTCUSDT", 0.0005)]);
+        assert_eq!(data.extreme_funding_rate("BTCUSDT"), None);
+        assert_eq!(data.futures.funding.len(), 2);
+    }
 }
@@ -0,0 +1,248 @@
+//! Technical indicators updated one price at a time
+
+use std::collections::VecDeque;
+
+use ringbuf::Rb;
+use serde::{Deserialize, Serialize};
+
+use crate::data::{StaticLocalRb, CHART_LEN};
+
+/// Period used by RSI
+const RSI_PERIOD: usize = 14;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct IndicatorConfig {
+    pub(crate) sma_periods: Vec<usize>,
+    pub(crate) ema_periods: Vec<usize>,
+    pub(crate) show_rsi: bool,
+    /// Hex colors of period lines, reused from start if there are more lines
+    pub(crate) palette: Vec<String>,
+}
+
+impl Default for IndicatorConfig {
+    fn default() -> Self {
+        Self {
+            sma_periods: vec![20],
+            ema_periods: vec![50],
+            show_rsi: false,
+            palette: ["F5C451", "5B8DEF", "B57BFF", "4DD0E1"]
+                .into_iter()
+                .map(ToOwned::to_owned)
+                .collect(),
+        }
+    }
+}
+
+/// Computed values of indicator, oldest first
+///
+/// Same length as price chart so lines stay aligned
+pub(crate) type Series = StaticLocalRb<f64, CHART_LEN>;
+
+/// Simple moving average using running sum
+struct Sma {
+    period: usize,
+    window: VecDeque<f64>,
+    sum: f64,
+    values: Series,
+}
+
+impl Sma {
+    fn new(period: usize) -> Self {
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: 0.0,
+            values: Series::default(),
+        }
+    }
+
+    fn push(&mut self, price: f64) {
+        self.window.push_back(price);
+        self.sum += price;
+
+        if self.window.len() > self.period {
+            self.sum -= self.window.pop_front().expect("window is not empty");
+        }
+
+        if self.window.len() == self.period {
+            self.values.push_overwrite(self.sum / self.period as f64);
+        }
+    }
+}
+
+/// Exponential moving average, seeded with first price
+struct Ema {
+    period: usize,
+    last: Option<f64>,
+    values: Series,
+}
+
+impl Ema {
+    fn new(period: usize) -> Self {
+        Self {
+            period,
+            last: None,
+            values: Series::default(),
+        }
+    }
+
+    fn push(&mut self, price: f64) {
+        let alpha = 2.0 / (self.period as f64 + 1.0);
+        let value = self
+            .last
+            .map_or(price, |last| last + alpha * (price - last));
+
+        self.last = Some(value);
+        self.values.push_overwrite(value);
+    }
+}
+
+/// Relative strength index with Wilder smoothing
+struct Rsi {
+    previous: Option<f64>,
+    avg_gain: f64,
+    avg_loss: f64,
+    count: usize,
+    values: Series,
+}
+
+impl Rsi {
+    fn new() -> Self {
+        Self {
+            previous: None,
+            avg_gain: 0.0,
+            avg_loss: 0.0,
+            count: 0,
+            values: Series::default(),
+        }
+    }
+
+    fn push(&mut self, price: f64) {
+        let Some(previous) = self.previous.replace(price) else {
+            return;
+        };
+
+        let change = price - previous;
+        let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+
+        // plain average until there is enough data, then smoothing
+        self.count += 1;
+        let n = self.count.min(RSI_PERIOD) as f64;
+        self.avg_gain += (gain - self.avg_gain) / n;
+        self.avg_loss += (loss - self.avg_loss) / n;
+
+        if self.count >= RSI_PERIOD {
+            let rsi = if self.avg_loss == 0.0 {
+                100.0
+            } else {
+                100.0 - 100.0 / (1.0 + self.avg_gain / self.avg_loss)
+            };
+            self.values.push_overwrite(rsi);
+        }
+    }
+}
+
+pub(crate) struct Indicators {
+    config: IndicatorConfig,
+    sma: Vec<Sma>,
+    ema: Vec<Ema>,
+    rsi: Option<Rsi>,
+}
+
+impl Indicators {
+    pub(crate) fn new(config: &IndicatorConfig) -> Self {
+        Self {
+            config: config.clone(),
+            sma: config
+                .sma_periods
+                .iter()
+                .filter(|p| **p > 0)
+                .map(|p| Sma::new(*p))
+                .collect(),
+            ema: config
+                .ema_periods
+                .iter()
+                .filter(|p| **p > 0)
+                .map(|p| Ema::new(*p))
+                .collect(),
+            rsi: config.show_rsi.then(Rsi::new),
+        }
+    }
+
+    pub(crate) fn push(&mut self, price: f64) {
+        for sma in &mut self.sma {
+            sma.push(price);
+        }
+        for ema in &mut self.ema {
+            ema.push(price);
+        }
+        if let Some(rsi) = &mut self.rsi {
+            rsi.push(price);
+        }
+    }
+
+    /// Recompute everything after price history was replaced
+    pub(crate) fn rebuild<'a>(&mut self, prices: impl Iterator<Item = &'a f64>) {
+        *self = Self::new(&self.config);
+
+        for price in prices {
+            self.push(*price);
+        }
+    }
+
+    /// Moving averages with labels and hex colors
+    pub(crate) fn lines(&self) -> impl Iterator<Item = (String, &str, &Series)> {
+        let sma = self
+            .sma
+            .iter()
+            .map(|sma| (format!("SMA {}", sma.period), &sma.values));
+        let ema = self
+            .ema
+            .iter()
+            .map(|ema| (format!("EMA {}", ema.period), &ema.values));
+
+        sma.chain(ema).enumerate().map(|(i, (label, values))| {
+            let color = match self.config.palette.len() {
+                0 => "B7BDB7",
+                len => self.config.palette[i % len].as_str(),
+            };
+
+            (label, color, values)
+        })
+    }
+
+    pub(crate) fn rsi(&self) -> Option<&Series> {
+        self.rsi.as_ref().map(|rsi| &rsi.values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moving_averages() {
+        let mut sma = Sma::new(3);
+        let mut ema = Ema::new(3);
+        for price in [1.0, 2.0, 3.0, 4.0] {
+            sma.push(price);
+            ema.push(price);
+        }
+
+        assert_eq!(sma.values.iter().copied().collect::<Vec<_>>(), [2.0, 3.0]);
+        assert_eq!(
+            ema.values.iter().copied().collect::<Vec<_>>(),
+            [1.0, 1.5, 2.25, 3.125]
+        );
+    }
+
+    #[test]
+    fn rsi_bounds() {
+        let mut rsi = Rsi::new();
+        for price in 0..=RSI_PERIOD {
+            rsi.push(price as f64);
+        }
+
+        assert_eq!(rsi.values.iter().copied().collect::<Vec<_>>(), [100.0]);
+    }
+}
@@ -1,8 +1,23 @@
+mod alerts;
 mod api;
 mod app;
 mod config;
 mod data;
+mod indicators;
 mod message;
+mod mock;
+mod network;
+mod news;
+mod notification;
+#[cfg(feature = "options")]
+mod options;
+mod pair;
+mod paper;
+mod rate_limit;
+mod screenshot;
+mod script;
+mod shortcuts;
+mod sound;
 mod svg_logos;
 mod theme;
 mod views;
@@ -23,7 +38,10 @@ fn main() -> iced::Result {
         .with_line_number(true)
         .init();
 
-    let config = Config::load().unwrap_or_default();
+    let config = Config::load().unwrap_or_else(|err| {
+        tracing::error!("failed to load config: {err}");
+        None
+    });
 
     App::run(Settings {
         window: iced::window::Settings {
@@ -42,6 +60,8 @@ fn main() -> iced::Result {
                 )
                 .unwrap(),
             ),
+            // layout is saved before closing
+            exit_on_close_request: false,
             ..Default::default()
         },
         default_font: Font {
@@ -1,14 +1,25 @@
 use std::fmt::Debug;
 use std::fmt::Display;
+use std::time::Instant;
 
 use crate::{
-    config::Config,
-    views::{dashboard::DashboardMessage, settings::SettingsMessage},
-    ws::WsMessage,
+    config::{AccountChoice, Config, ConfigEvent},
+    data::{Fetch, SymbolFilter, Ticker24h, Trade},
+    network::NetworkStatus,
+    news::NewsItem,
+    notification::Notification,
+    views::{
+        dashboard::DashboardMessage, futures_dashboard::FuturesDashboardMessage,
+        settings::SettingsMessage, splash::LoaderMessage, Tab,
+    },
+    ws::{futures::FundingRate, WsMessage},
 };
 
+use ahash::AHashMap;
 use binance::rest_model::KlineSummaries;
-use binance::rest_model::{Balance, Order};
+use binance::rest_model::{Balance, Order, OrderSide};
+
+use crate::api::{MarketFill, OrderType};
 
 /// Converts Result Err variant into string, stores error source
 ///
@@ -63,20 +74,86 @@ pub(crate) enum Message {
     /// You cannot close settings while config is invalid
     SettingsToggled,
 
+    /// Switch between dark and light theme, saved to config
+    ToggleTheme,
+
     /// Manually triggered at interval
     Tick,
 
+    /// User tried to close window
+    CloseRequested(iced::window::Id),
+
     /// Error source and message
     DispatchErr((String, String)),
 
+    /// Result of periodic ping changed
+    NetworkStatus(NetworkStatus),
+
+    /// Show notification in corner for a few seconds
+    PushNotification(Notification),
+
+    /// Shorthand for error notification
+    ToastError(String),
+
+    /// REST request was not sent because too many are waiting for rate limit, shown as warning
+    RateLimitWarning(String),
+
+    /// Hide notification, identified by its creation time
+    NotificationDismissed(Instant),
+
     /// Config update happened
-    ConfigUpdated(Result<Config, String>),
+    ConfigUpdated(Result<Box<Config>, String>),
+
+    /// Config file changed on disk
+    ConfigWatch(ConfigEvent),
+
+    /// Mainnet account picked in switcher
+    AccountSelected(AccountChoice),
+
+    /// Debounce of config save passed, holds change generation
+    SaveConfig(u64),
+    /// Debounce of layout save passed, holds change generation
+    SaveLayout(u64),
 
     /// API responses
     OrdersRecieved(Vec<Order>),
+    /// Id of order that was canceled
+    OrderCanceled(u64),
     BalancesRecieved(Vec<Balance>),
     KlinesRecieved(KlineSummaries),
-    MarketChanged(String),
+    /// Page of own trades of symbol
+    TradeHistoryRecieved(String, Vec<Trade>),
+    /// 24 hour statistics by symbol
+    TickersRecieved(AHashMap<String, Ticker24h>),
+    /// Display precision by symbol
+    SymbolFiltersRecieved(AHashMap<String, SymbolFilter>),
+    /// Filters of USD-M futures symbols
+    FuturesFiltersRecieved(AHashMap<String, SymbolFilter>),
+    /// Futures funding rate by symbol
+    FundingRatesRecieved(Vec<FundingRate>),
+    NewsRecieved(Vec<NewsItem>),
+    /// Request failed, its pane shows error instead of loader
+    FetchFailed(Fetch, String),
+
+    /// Order to be filled by paper broker
+    PaperOrder {
+        pair: String,
+        price: f64,
+        amt: f64,
+        side: OrderSide,
+        order_type: OrderType,
+    },
+
+    /// Open order canceled by paper broker instead of exchange
+    PaperCancel(u64),
+
+    /// Market order filled by paper broker at book ticker of pair right away
+    PaperMarketOrder {
+        pair: String,
+        side: OrderSide,
+        amount: f64,
+        reply: fn(Result<MarketFill, String>) -> Message,
+    },
 
     /// Settings view events
     Settings(SettingsMessage),
@@ -84,6 +161,15 @@ pub(crate) enum Message {
     /// Dashboard view events
     Dashboard(DashboardMessage),
 
+    /// Futures dashboard view events
+    FuturesDashboard(FuturesDashboardMessage),
+
+    /// Loading splash events
+    Loader(LoaderMessage),
+
+    /// Tab bar button pressed
+    SwitchTab(Tab),
+
     /// Event from one of websockets
     Ws(WsMessage),
 
@@ -112,6 +198,18 @@ impl From<SettingsMessage> for Message {
     }
 }
 
+impl From<FuturesDashboardMessage> for Message {
+    fn from(value: FuturesDashboardMessage) -> Self {
+        Self::FuturesDashboard(value)
+    }
+}
+
+impl From<LoaderMessage> for Message {
+    fn from(value: LoaderMessage) -> Self {
+        Self::Loader(value)
+    }
+}
+
 impl From<DashboardMessage> for Message {
     fn from(value: DashboardMessage) -> Self {
         Self::Dashboard(value)
@@ -0,0 +1,397 @@
+//! Offline client with synthetic market used in demo mode
+
+use std::{
+    f64::consts::TAU,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use ahash::AHashMap;
+use binance::rest_model::{Balance, KlineSummaries, KlineSummary, OrderSide, OrderStatus};
+use iced::{Command, Subscription};
+
+use crate::{
+    api::{MarketFill, OrderType, TradingClient},
+    data::{Ticker24h, Trade},
+    message::Message,
+    paper::PaperBroker,
+    views::panes::chart::Timeframe,
+    ws::{futures::FundingRate, prices::AssetDetails, WsEvent, WsMessage},
+};
+
+/// Symbols traded in demo with their average prices
+const SYMBOLS: [(&str, f64); 8] = [
+    ("BTCUSDT", 60_000.0),
+    ("ETHUSDT", 3_000.0),
+    ("BNBUSDT", 500.0),
+    ("SOLUSDT", 150.0),
+    ("LINKUSDT", 15.0),
+    ("UNIUSDT", 8.0),
+    ("ARBUSDT", 1.2),
+    ("ETHBTC", 0.05),
+];
+
+/// Time of one full price wave
+const WAVE_PERIOD: f64 = 600.0;
+
+/// Prices swing this much around average in either direction
+const WAVE_AMPLITUDE: f64 = 0.02;
+
+fn now_secs() -> f64 {
+    chrono::Utc::now().timestamp_millis() as f64 / 1000.0
+}
+
+/// Price of symbol at unix time, unknown symbols cost 1
+fn price_at(symbol: &str, secs: f64) -> f64 {
+    let (i, average) = SYMBOLS
+        .iter()
+        .enumerate()
+        .find(|(_, (s, _))| *s == symbol)
+        .map_or((0, 1.0), |(i, (_, price))| (i, *price));
+
+    // symbols are out of phase so they do not move together
+    let phase = i as f64;
+    average * (1.0 + WAVE_AMPLITUDE * (secs / WAVE_PERIOD * TAU + phase).sin())
+}
+
+fn prices_now() -> Vec<AssetDetails> {
+    let now = now_secs();
+
+    SYMBOLS
+        .iter()
+        .map(|(symbol, _)| AssetDetails {
+            name: (*symbol).to_owned(),
+            price: price_at(symbol, now) as f32,
+        })
+        .collect()
+}
+
+/// Fills demo orders that current prices have reached, true if any was filled
+fn match_prices(broker: &mut PaperBroker) -> bool {
+    let now = now_secs();
+
+    SYMBOLS.iter().fold(false, |filled, (symbol, _)| {
+        let price = price_at(symbol, now);
+        !broker.match_book(symbol, price, price).is_empty() || filled
+    })
+}
+
+/// Orders and balances after every fill of resting demo orders
+fn fills(broker: Arc<Mutex<PaperBroker>>) -> Subscription<Message> {
+    struct Fills;
+
+    iced::subscription::unfold(
+        std::any::TypeId::of::<Fills>(),
+        (broker, None),
+        |(broker, balances): (_, Option<Vec<Balance>>)| async move {
+            // balances of previous fill are sent right after its orders
+            if let Some(balances) = balances {
+                return (Message::BalancesRecieved(balances), (broker, None));
+            }
+
+            loop {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+
+                let mut locked = broker.lock().expect("demo broker lock is not poisoned");
+                if match_prices(&mut locked) {
+                    let (orders, balances) = (locked.orders(), locked.balances().to_vec());
+                    drop(locked);
+                    return (Message::OrdersRecieved(orders), (broker, Some(balances)));
+                }
+            }
+        },
+    )
+}
+
+/// Generates sine wave prices, orders are simulated by paper broker at those prices
+pub(crate) struct MockClient {
+    broker: Arc<Mutex<PaperBroker>>,
+}
+
+impl MockClient {
+    pub(crate) fn new() -> Self {
+        Self {
+            broker: Arc::new(Mutex::new(PaperBroker::without_log())),
+        }
+    }
+
+    fn broker(&self) -> std::sync::MutexGuard<'_, PaperBroker> {
+        self.broker
+            .lock()
+            .expect("demo broker lock is not poisoned")
+    }
+
+    /// Orders and balances after broker accepted order, error is shown as toast
+    fn changed(&self, result: Result<(), String>) -> Command<Message> {
+        match result {
+            Ok(()) => {
+                let broker = self.broker();
+                let (orders, balances) = (broker.orders(), broker.balances().to_vec());
+                Command::batch([
+                    Command::perform(async move { orders }, Message::OrdersRecieved),
+                    Command::perform(async move { balances }, Message::BalancesRecieved),
+                ])
+            }
+            Err(err) => Command::perform(async {}, move |_| {
+                Message::ToastError(format!("demo order rejected: {err}"))
+            }),
+        }
+    }
+}
+
+impl TradingClient for MockClient {
+    fn update_credentials(&mut self, _: String, _: String) {}
+
+    fn orders_history(&self, _: Vec<String>) -> Command<Message> {
+        let orders = self.broker().orders();
+        Command::perform(async move { orders }, Message::OrdersRecieved)
+    }
+
+    fn balances(&self, _: Vec<String>) -> Command<Message> {
+        let balances = self.broker().balances().to_vec();
+        Command::perform(async move { balances }, Message::BalancesRecieved)
+    }
+
+    fn klines(&self, pair: String, tf: Timeframe) -> Command<Message> {
+        const COUNT: i64 = 500;
+
+        let step = tf.duration().as_millis() as i64;
+        let now = chrono::Utc::now().timestamp_millis();
+        let first = (now / step - COUNT + 1) * step;
+
+        let klines = (0..COUNT)
+            .map(|i| {
+                let open_time = first + i * step;
+                let price = |ms: i64| price_at(&pair, ms as f64 / 1000.0);
+                let (open, close) = (price(open_time), price(open_time + step));
+                let mid = price(open_time + step / 2);
+
+                KlineSummary {
+                    open_time,
+                    open,
+                    high: open.max(close).max(mid),
+                    low: open.min(close).min(mid),
+                    close,
+                    volume: 100.0 + 50.0 * (i as f64 / 7.0).sin().abs(),
+                    close_time: open_time + step - 1,
+                    quote_asset_volume: 0.0,
+                    number_of_trades: 0,
+                    taker_buy_base_asset_volume: 0.0,
+                    taker_buy_quote_asset_volume: 0.0,
+                }
+            })
+            .collect();
+
+        Command::perform(
+            async move { KlineSummaries::AllKlineSummaries(klines) },
+            Message::KlinesRecieved,
+        )
+    }
+
+    /// Filled demo orders are trades, no fee is charged
+    fn get_trade_history(
+        &self,
+        symbol: &str,
+        limit: u32,
+        from_id: Option<u64>,
+    ) -> Command<Message> {
+        let mut trades: Vec<_> = self
+            .broker()
+            .orders()
+            .into_iter()
+            .filter(|o| o.symbol == symbol && o.status == OrderStatus::Filled)
+            .filter(|o| from_id.is_none_or(|from_id| o.order_id >= from_id))
+            .map(|o| Trade {
+                id: o.order_id,
+                order_id: o.order_id,
+                symbol: o.symbol,
+                time: o.time,
+                is_buyer: o.side == OrderSide::Buy,
+                price: o.price,
+                qty: o.executed_qty,
+                commission: 0.0,
+                commission_asset: String::new(),
+            })
+            .collect();
+        // pages by id go up from it
+        match from_id {
+            Some(_) => trades.sort_by_key(|t| t.id),
+            None => trades.sort_by_key(|t| std::cmp::Reverse(t.id)),
+        }
+        trades.truncate(limit as usize);
+
+        let symbol = symbol.to_owned();
+        Command::perform(async move { trades }, move |trades| {
+            Message::TradeHistoryRecieved(symbol, trades)
+        })
+    }
+
+    fn get_24hr_all_tickers(&self) -> Command<Message> {
+        let now = now_secs();
+        let day_ago = now - Duration::from_secs(24 * 60 * 60).as_secs_f64();
+
+        let tickers = SYMBOLS
+            .iter()
+            .map(|(symbol, average)| {
+                let (then, now) = (price_at(symbol, day_ago), price_at(symbol, now));
+                let ticker = Ticker24h {
+                    change: (now - then) / then * 100.0,
+                    volume: 1_000_000.0 / average,
+                };
+                ((*symbol).to_owned(), ticker)
+            })
+            .collect::<AHashMap<_, _>>();
+
+        Command::perform(async move { tickers }, Message::TickersRecieved)
+    }
+
+    fn get_funding_rates(&self) -> Command<Message> {
+        let now = now_secs();
+        // funding happens every 8 hours
+        let period = 8 * 60 * 60 * 1000;
+        let next_funding_time = ((now * 1000.0) as u64 / period + 1) * period;
+
+        let rates = SYMBOLS
+            .iter()
+            .filter(|(symbol, _)| symbol.ends_with("USDT"))
+            .enumerate()
+            .map(|(i, (symbol, average))| FundingRate {
+                symbol: (*symbol).to_owned(),
+                rate: 0.0015 * (now / WAVE_PERIOD * TAU + i as f64 * 2.0).sin(),
+                mark_price: *average,
+                next_funding_time,
+            })
+            .collect::<Vec<_>>();
+
+        Command::perform(async move { rates }, Message::FundingRatesRecieved)
+    }
+
+    /// Default precision is used for every symbol
+    /// Every symbol uses default precision
+    fn exchange_info(&self) -> Command<Message> {
+        Command::perform(async { AHashMap::new() }, Message::SymbolFiltersRecieved)
+    }
+
+    fn futures_exchange_info(&self) -> Command<Message> {
+        Command::none()
+    }
+
+    fn trade_spot(
+        &self,
+        pair: String,
+        price: f64,
+        amt: f64,
+        side: OrderSide,
+        order_type: OrderType,
+    ) -> Command<Message> {
+        let price_now = price_at(&pair, now_secs());
+        let result = {
+            let mut broker = self.broker();
+            match order_type {
+                OrderType::Market => {
+                    let amount = match side {
+                        OrderSide::Buy => amt * price_now,
+                        OrderSide::Sell => amt,
+                    };
+                    broker
+                        .fill_market(pair, side, amount, price_now, price_now)
+                        .map(|_| ())
+                }
+                // limits already crossed by price fill right away
+                OrderType::Limit | OrderType::StopLimit { .. } => {
+                    broker.place(pair, price, amt, side, order_type).map(|()| {
+                        match_prices(&mut broker);
+                    })
+                }
+            }
+        };
+
+        self.changed(result)
+    }
+
+    fn market_order(
+        &self,
+        pair: String,
+        side: OrderSide,
+        amount: f64,
+        reply: fn(Result<MarketFill, String>) -> Message,
+    ) -> Command<Message> {
+        let price_now = price_at(&pair, now_secs());
+        let fill = self
+            .broker()
+            .fill_market(pair, side, amount, price_now, price_now);
+
+        Command::batch([
+            Command::perform(async move { fill }, reply),
+            self.changed(Ok(())),
+        ])
+    }
+
+    fn cancel_order(&self, _: &str, order_id: u64) -> Command<Message> {
+        let result = self.broker().cancel(order_id);
+        self.changed(result)
+    }
+
+    fn place_trailing_stop(&self, _: String, _: f64, _: OrderSide, _: f64) -> Command<Message> {
+        Command::perform(async {}, |_| {
+            Message::ToastError("futures are not available in demo mode".to_owned())
+        })
+    }
+
+    fn trade_futures(&self, _: String, _: f64, _: f64, _: OrderSide, _: u8) -> Command<Message> {
+        Command::perform(async {}, |_| {
+            Message::ToastError("futures are not available in demo mode".to_owned())
+        })
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        Subscription::batch([
+            iced::time::every(Duration::from_secs(1))
+                .map(|_| Message::Ws(WsMessage::Price(WsEvent::Message(prices_now())))),
+            fills(Arc::clone(&self.broker)),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prices_stay_around_average() {
+        for secs in [0.0, 123.0, WAVE_PERIOD / 4.0, 1e9] {
+            let price = price_at("BTCUSDT", secs);
+            assert!((price / 60_000.0 - 1.0).abs() <= WAVE_AMPLITUDE + f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn limit_orders_rest_until_crossed() {
+        let client = MockClient::new();
+        let price = price_at("ETHUSDT", now_secs());
+
+        let _ = client.trade_spot(
+            "ETHUSDT".to_owned(),
+            price / 2.0,
+            1.0,
+            OrderSide::Buy,
+            OrderType::Limit,
+        );
+        let _ = client.trade_spot(
+            "ETHUSDT".to_owned(),
+            price * 2.0,
+            1.0,
+            OrderSide::Buy,
+            OrderType::Limit,
+        );
+
+        let orders = client.broker().orders();
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[0].status, OrderStatus::New);
+        // limit above price crossed it at once
+        assert_eq!(orders[1].status, OrderStatus::Filled);
+
+        let _ = client.cancel_order("ETHUSDT", orders[0].order_id);
+        assert_eq!(client.broker().orders()[0].status, OrderStatus::Canceled);
+    }
+}
@@ -0,0 +1,131 @@
+//! Reachability of Binance, checked by pinging REST api
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use iced::subscription::{self, Subscription};
+use tokio::sync::Notify;
+
+use crate::api::binance_config;
+
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Pings slower than this mean degraded connection
+const DEGRADED_LATENCY: Duration = Duration::from_secs(1);
+
+/// Set while ping fails, websockets wait with reconnecting until it is cleared
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+static BACK_ONLINE: Notify = Notify::const_new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum NetworkStatus {
+    #[default]
+    Online,
+    Offline,
+    /// Reachable, but ping took this long
+    Degraded(Duration),
+}
+
+impl NetworkStatus {
+    fn from_ping(latency: Option<Duration>) -> Self {
+        match latency {
+            None => NetworkStatus::Offline,
+            Some(latency) if latency > DEGRADED_LATENCY => NetworkStatus::Degraded(latency),
+            Some(_) => NetworkStatus::Online,
+        }
+    }
+}
+
+/// Returns right away unless network is offline, then waits until it comes back
+pub(crate) async fn wait_online() {
+    loop {
+        let back_online = BACK_ONLINE.notified();
+        if !OFFLINE.load(Ordering::Relaxed) {
+            return;
+        }
+        back_online.await;
+    }
+}
+
+/// Round trip of ping request, None if it failed
+async fn ping(url: &str) -> Option<Duration> {
+    let started = tokio::time::Instant::now();
+
+    let response = tokio::time::timeout(PING_INTERVAL, reqwest::get(url)).await;
+    match response {
+        Ok(Ok(response)) if response.status().is_success() => Some(started.elapsed()),
+        Ok(Ok(response)) => {
+            tracing::warn!("ping returned {}", response.status());
+            None
+        }
+        Ok(Err(err)) => {
+            tracing::warn!("ping failed: {err}");
+            None
+        }
+        Err(_) => {
+            tracing::warn!("ping timed out");
+            None
+        }
+    }
+}
+
+/// Emits status whenever it changes
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn watch(testnet: bool) -> Subscription<NetworkStatus> {
+    use iced_futures::futures::SinkExt;
+
+    struct Watch;
+
+    subscription::channel(
+        (std::any::TypeId::of::<Watch>(), testnet),
+        1,
+        move |mut output| async move {
+            let url = format!("{}/api/v3/ping", binance_config(testnet).rest_api_endpoint);
+            let mut status = NetworkStatus::Online;
+
+            loop {
+                let latency = ping(&url).await;
+                let new_status = NetworkStatus::from_ping(latency);
+
+                OFFLINE.store(new_status == NetworkStatus::Offline, Ordering::Relaxed);
+                if new_status != NetworkStatus::Offline {
+                    BACK_ONLINE.notify_waiters();
+                }
+
+                // latency changes on every ping, only moving in and out of degraded is reported
+                if std::mem::discriminant(&new_status) != std::mem::discriminant(&status) {
+                    let _ = output.send(new_status).await;
+                }
+                status = new_status;
+
+                tokio::time::sleep(PING_INTERVAL.saturating_sub(latency.unwrap_or_default())).await;
+            }
+        },
+    )
+}
+
+/// Tokio timers are not available in browser
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn watch(_testnet: bool) -> Subscription<NetworkStatus> {
+    Subscription::none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_ping_is_degraded() {
+        assert_eq!(NetworkStatus::from_ping(None), NetworkStatus::Offline);
+        assert_eq!(
+            NetworkStatus::from_ping(Some(Duration::from_millis(200))),
+            NetworkStatus::Online
+        );
+        assert_eq!(
+            NetworkStatus::from_ping(Some(Duration::from_secs(3))),
+            NetworkStatus::Degraded(Duration::from_secs(3))
+        );
+    }
+}
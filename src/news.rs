@@ -0,0 +1,157 @@
+//! Headlines from RSS or Atom feed
+
+use std::process;
+
+use chrono::{DateTime, FixedOffset};
+use iced::Command;
+
+use crate::{data::Fetch, message::Message};
+
+pub(crate) const DEFAULT_FEED_URL: &str = "https://cryptopanic.com/news/rss/";
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NewsItem {
+    pub(crate) title: String,
+    pub(crate) url: String,
+    pub(crate) published_at: Option<DateTime<FixedOffset>>,
+}
+
+/// Reads RSS `item` or Atom `entry` elements, skipping ones without title or link
+pub(crate) fn parse_feed(xml: &str) -> Result<Vec<NewsItem>, String> {
+    let document = roxmltree::Document::parse(xml).map_err(|err| err.to_string())?;
+
+    let child_text = |node: roxmltree::Node, name: &str| {
+        node.children()
+            .find(|child| child.has_tag_name(name))
+            .and_then(|child| child.text())
+            .map(|text| text.trim().to_owned())
+    };
+
+    Ok(document
+        .descendants()
+        .filter(|node| node.has_tag_name("item") || node.has_tag_name("entry"))
+        .filter_map(|node| {
+            let title = child_text(node, "title")?;
+            // atom stores link in attribute
+            let url = child_text(node, "link")
+                .filter(|url| !url.is_empty())
+                .or_else(|| {
+                    node.children()
+                        .find(|child| child.has_tag_name("link"))
+                        .and_then(|link| link.attribute("href"))
+                        .map(ToOwned::to_owned)
+                })?;
+            let published_at = child_text(node, "pubDate")
+                .and_then(|date| DateTime::parse_from_rfc2822(&date).ok())
+                .or_else(|| {
+                    child_text(node, "published")
+                        .or_else(|| child_text(node, "updated"))
+                        .and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+                });
+
+            Some(NewsItem {
+                title,
+                url,
+                published_at,
+            })
+        })
+        .collect())
+}
+
+pub(crate) fn fetch(url: String) -> Command<Message> {
+    Command::perform(
+        async move {
+            let body = reqwest::get(&url)
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .map_err(|err| err.to_string())?
+                .text()
+                .await
+                .map_err(|err| err.to_string())?;
+
+            parse_feed(&body)
+        },
+        |result| match result {
+            Ok(news) => Message::NewsRecieved(news),
+            Err(err) => Message::FetchFailed(Fetch::News, err),
+        },
+    )
+}
+
+/// Opens link in default browser
+///
+/// Feed links are untrusted, only http and https ones are opened. Failures are only logged
+pub(crate) fn open_url(url: &str) {
+    let Some(url) = web_url(url) else {
+        tracing::error!("refusing to open {url}: not a web link");
+        return;
+    };
+
+    let mut command = if cfg!(target_os = "windows") {
+        // passed as own argument, `cmd /C start` would interpret operators in url
+        let mut command = process::Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler").arg(url.as_str());
+        command
+    } else if cfg!(target_os = "macos") {
+        let mut command = process::Command::new("open");
+        command.arg(url.as_str());
+        command
+    } else {
+        let mut command = process::Command::new("xdg-open");
+        command.arg(url.as_str());
+        command
+    };
+
+    match command.spawn() {
+        // waited for on own thread so that exited opener does not stay a zombie
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(err) => tracing::error!("failed to open {url}: {err}"),
+    }
+}
+
+/// Parsed url if it is a http or https one
+fn web_url(url: &str) -> Option<reqwest::Url> {
+    reqwest::Url::parse(url)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_web_urls_are_opened() {
+        assert!(web_url("https://example.com/?a=1&b=2").is_some());
+        assert!(web_url("http://example.com/").is_some());
+        assert!(web_url("file:///etc/passwd").is_none());
+        assert!(web_url("javascript:alert(1)").is_none());
+        assert!(web_url("steam://run/1").is_none());
+        assert!(web_url("example.com").is_none());
+    }
+
+    #[test]
+    fn parse_rss_and_atom() {
+        let rss = r#"<?xml version="1.0"?>
+            <rss><channel><title>Feed</title>
+            <item><title>BTC is up</title><link>https://example.com/1</link>
+            <pubDate>Mon, 01 Jan 2024 10:00:00 +0000</pubDate></item>
+            <item><title>No link</title></item>
+            </channel></rss>"#;
+        let news = parse_feed(rss).unwrap();
+        assert_eq!(news.len(), 1);
+        assert_eq!(news[0].title, "BTC is up");
+        assert_eq!(news[0].url, "https://example.com/1");
+        assert!(news[0].published_at.is_some());
+
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+            <entry><title>ETH is down</title><link href="https://example.com/2"/>
+            <updated>2024-01-01T10:00:00Z</updated></entry>
+            </feed>"#;
+        let news = parse_feed(atom).unwrap();
+        assert_eq!(news[0].url, "https://example.com/2");
+        assert!(news[0].published_at.is_some());
+    }
+}
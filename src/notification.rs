@@ -0,0 +1,46 @@
+use std::time::Instant;
+
+use iced::Color;
+
+use crate::theme::{color, Role};
+
+/// Older notifications are dropped when more are shown at once
+pub(crate) const NOTIFICATIONS_LEN: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub(crate) fn color(self) -> Color {
+        match self {
+            Severity::Info => color(Role::Surface),
+            Severity::Success => color(Role::Accent),
+            Severity::Warning => color(Role::Warning),
+            Severity::Error => color(Role::Danger),
+        }
+    }
+}
+
+/// Message shown in corner for a few seconds
+#[derive(Debug, Clone)]
+pub(crate) struct Notification {
+    pub(crate) severity: Severity,
+    pub(crate) text: String,
+    /// Also identifies notification when it is dismissed
+    pub(crate) created: Instant,
+}
+
+impl Notification {
+    pub(crate) fn new(severity: Severity, text: impl Into<String>) -> Self {
+        Self {
+            severity,
+            text: text.into(),
+            created: Instant::now(),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! European options chain from Binance options api and Black-Scholes pricing
+
+use chrono::{NaiveDate, NaiveTime, Utc};
+use serde::Deserialize;
+
+/// Options market data is public and only exists on mainnet
+const TICKER_URL: &str = "https://eapi.binance.com/eapi/v1/ticker";
+
+/// Options expire at 08:00 UTC of expiry date
+const EXPIRY_TIME: NaiveTime = match NaiveTime::from_hms_opt(8, 0, 0) {
+    Some(time) => time,
+    None => unreachable!(),
+};
+
+const YEAR_SECS: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+
+/// Lowest and highest volatility tried when solving for implied one
+const IV_RANGE: (f64, f64) = (1e-4, 5.0);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum OptionKind {
+    Call,
+    Put,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Ticker {
+    symbol: String,
+    bid_price: String,
+    ask_price: String,
+    strike_price: String,
+    /// Index price of underlying, settlement estimate in last hour before expiry
+    exercise_price: String,
+}
+
+/// Quote of one option with price of its underlying
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct OptionQuote {
+    pub(crate) symbol: String,
+    pub(crate) expiry: NaiveDate,
+    pub(crate) strike: f64,
+    pub(crate) kind: OptionKind,
+    pub(crate) bid: f64,
+    pub(crate) ask: f64,
+    pub(crate) underlying_price: f64,
+}
+
+impl OptionQuote {
+    /// Parses symbols like `BTC-240329-70000-C`, None if ticker is malformed
+    fn from_ticker(ticker: Ticker) -> Option<Self> {
+        let mut parts = ticker.symbol.split('-').skip(1);
+        let expiry = NaiveDate::parse_from_str(parts.next()?, "%y%m%d").ok()?;
+        let kind = match parts.nth(1)? {
+            "C" => OptionKind::Call,
+            "P" => OptionKind::Put,
+            _ => return None,
+        };
+
+        Some(Self {
+            expiry,
+            kind,
+            strike: ticker.strike_price.parse().ok()?,
+            bid: ticker.bid_price.parse().ok()?,
+            ask: ticker.ask_price.parse().ok()?,
+            underlying_price: ticker.exercise_price.parse().ok()?,
+            symbol: ticker.symbol,
+        })
+    }
+
+    /// Years left until expiry, zero once expired
+    pub(crate) fn years_to_expiry(&self) -> f64 {
+        let expires = self.expiry.and_time(EXPIRY_TIME).and_utc();
+        let secs = (expires - Utc::now()).num_seconds().max(0);
+
+        secs as f64 / YEAR_SECS
+    }
+
+    /// Middle of bid and ask, None without both sides
+    pub(crate) fn mid(&self) -> Option<f64> {
+        (self.bid > 0.0 && self.ask > 0.0).then(|| (self.bid + self.ask) / 2.0)
+    }
+}
+
+/// Options of underlying like `BTCUSDT`, nearest expiry and lowest strike first
+pub(crate) async fn fetch_chain(underlying: String) -> Result<Vec<OptionQuote>, String> {
+    let body = reqwest::get(TICKER_URL)
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|err| err.to_string())?
+        .text()
+        .await
+        .map_err(|err| err.to_string())?;
+    let tickers: Vec<Ticker> = serde_json::from_str(&body).map_err(|err| err.to_string())?;
+
+    let prefix = format!(
+        "{}-",
+        underlying.strip_suffix("USDT").unwrap_or(&underlying)
+    );
+    let mut chain: Vec<_> = tickers
+        .into_iter()
+        .filter(|ticker| ticker.symbol.starts_with(&prefix))
+        .filter_map(OptionQuote::from_ticker)
+        .collect();
+    chain.sort_by(|a, b| {
+        a.expiry
+            .cmp(&b.expiry)
+            .then(a.strike.total_cmp(&b.strike))
+            .then((a.kind == OptionKind::Put).cmp(&(b.kind == OptionKind::Put)))
+    });
+
+    Ok(chain)
+}
+
+/// Error function, Abramowitz and Stegun 7.1.26, error below 1.5e-7
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let y = 1.0 - poly * (-x * x).exp();
+
+    y.copysign(x)
+}
+
+fn norm_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+fn norm_pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+/// Sensitivities of option price, theta per day and vega per volatility point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Greeks {
+    pub(crate) delta: f64,
+    pub(crate) gamma: f64,
+    pub(crate) theta: f64,
+    pub(crate) vega: f64,
+}
+
+/// Black-Scholes model without interest rate, which crypto options are usually quoted with
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BlackScholes {
+    pub(crate) kind: OptionKind,
+    pub(crate) spot: f64,
+    pub(crate) strike: f64,
+    /// Years to expiry
+    pub(crate) time: f64,
+}
+
+impl BlackScholes {
+    fn d1_d2(&self, vol: f64) -> (f64, f64) {
+        let vol_time = vol * self.time.sqrt();
+        let d1 = ((self.spot / self.strike).ln() + vol * vol * self.time / 2.0) / vol_time;
+
+        (d1, d1 - vol_time)
+    }
+
+    pub(crate) fn price(&self, vol: f64) -> f64 {
+        let (d1, d2) = self.d1_d2(vol);
+
+        match self.kind {
+            OptionKind::Call => self.spot * norm_cdf(d1) - self.strike * norm_cdf(d2),
+            OptionKind::Put => self.strike * norm_cdf(-d2) - self.spot * norm_cdf(-d1),
+        }
+    }
+
+    /// Volatility at which model gives `price`, found by bisection
+    ///
+    /// None if price is below intrinsic value or above what any volatility gives
+    pub(crate) fn implied_vol(&self, price: f64) -> Option<f64> {
+        if self.time <= 0.0 || self.spot <= 0.0 || self.strike <= 0.0 {
+            return None;
+        }
+
+        let (mut low, mut high) = IV_RANGE;
+        if price < self.price(low) || price > self.price(high) {
+            return None;
+        }
+
+        // price grows with volatility
+        for _ in 0..100 {
+            let mid = (low + high) / 2.0;
+            if self.price(mid) < price {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        Some((low + high) / 2.0)
+    }
+
+    pub(crate) fn greeks(&self, vol: f64) -> Greeks {
+        let (d1, _) = self.d1_d2(vol);
+        let sqrt_time = self.time.sqrt();
+        let density = norm_pdf(d1);
+
+        Greeks {
+            delta: match self.kind {
+                OptionKind::Call => norm_cdf(d1),
+                OptionKind::Put => norm_cdf(d1) - 1.0,
+            },
+            gamma: density / (self.spot * vol * sqrt_time),
+            // same for calls and puts without interest rate
+            theta: -self.spot * density * vol / (2.0 * sqrt_time) / 365.0,
+            vega: self.spot * density * sqrt_time / 100.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn implied_vol_recovers_model_vol() {
+        let call = BlackScholes {
+            kind: OptionKind::Call,
+            spot: 60_000.0,
+            strike: 65_000.0,
+            time: 30.0 / 365.0,
+        };
+        let put = BlackScholes {
+            kind: OptionKind::Put,
+            ..call
+        };
+
+        let price = call.price(0.55);
+        assert!((call.implied_vol(price).unwrap() - 0.55).abs() < 1e-6);
+        // put call parity without interest rate
+        assert!((price - put.price(0.55) - (60_000.0 - 65_000.0)).abs() < 1e-6);
+        assert!((call.greeks(0.55).delta - put.greeks(0.55).delta - 1.0).abs() < 1e-12);
+
+        // below intrinsic value of put
+        assert_eq!(put.implied_vol(4_000.0), None);
+    }
+
+    #[test]
+    fn parses_ticker() {
+        let ticker: Ticker = serde_json::from_str(
+            r#"{"symbol":"BTC-240329-70000-C","bidPrice":"1200","askPrice":"1300","strikePrice":"70000","exercisePrice":"68000.5","lastPrice":"0"}"#,
+        )
+        .unwrap();
+        let quote = OptionQuote::from_ticker(ticker).unwrap();
+
+        assert_eq!(quote.expiry, NaiveDate::from_ymd_opt(2024, 3, 29).unwrap());
+        assert_eq!(quote.kind, OptionKind::Call);
+        assert_eq!(quote.strike, 70_000.0);
+        assert_eq!(quote.mid(), Some(1250.0));
+    }
+}
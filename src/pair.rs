@@ -0,0 +1,72 @@
+use ahash::AHashMap;
+
+use crate::{api::split_symbol, data::SymbolFilter};
+
+/// Turns selected asset or pair into pair that can be traded
+pub(crate) struct PairBuilder<'a> {
+    /// Quote appended to bare assets
+    quote: &'a str,
+    /// Symbols listed on exchange, empty until exchange info arrives
+    symbols: &'a AHashMap<String, SymbolFilter>,
+}
+
+impl<'a> PairBuilder<'a> {
+    pub(crate) fn new(quote: &'a str, symbols: &'a AHashMap<String, SymbolFilter>) -> Self {
+        Self { quote, symbols }
+    }
+
+    /// Pairs are kept as is, assets are paired with preferred quote
+    pub(crate) fn build(&self, selected: &str) -> String {
+        let selected = selected.to_uppercase();
+
+        let is_pair = if self.symbols.is_empty() {
+            // guess by known quote suffix until listed symbols are known
+            split_symbol(&selected).is_some()
+        } else {
+            self.symbols.contains_key(&selected)
+        };
+
+        if is_pair {
+            selected
+        } else {
+            format!("{selected}{}", self.quote)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairs_are_kept() {
+        let symbols = AHashMap::new();
+        let builder = PairBuilder::new("USDT", &symbols);
+
+        for pair in ["ETHBTC", "BTCUSDT", "SHIBUSDT", "ETHUSDT"] {
+            assert_eq!(builder.build(pair), pair);
+        }
+    }
+
+    #[test]
+    fn assets_get_preferred_quote() {
+        let symbols = AHashMap::new();
+
+        assert_eq!(PairBuilder::new("USDT", &symbols).build("eth"), "ETHUSDT");
+        assert_eq!(PairBuilder::new("USDT", &symbols).build("SHIB"), "SHIBUSDT");
+        assert_eq!(PairBuilder::new("BTC", &symbols).build("ETH"), "ETHBTC");
+    }
+
+    #[test]
+    fn listed_symbols_take_priority() {
+        let symbols = ["WBTCUSDT", "ETHBTC"]
+            .map(|s| (s.to_owned(), SymbolFilter::default()))
+            .into_iter()
+            .collect();
+        let builder = PairBuilder::new("USDT", &symbols);
+
+        // looks like pair ending with BTC but is an asset
+        assert_eq!(builder.build("WBTC"), "WBTCUSDT");
+        assert_eq!(builder.build("ETHBTC"), "ETHBTC");
+    }
+}
@@ -0,0 +1,537 @@
+//! Simulated exchange used instead of real API in paper trading mode
+
+use std::{fs, io::Write, path::PathBuf};
+
+use ahash::AHashMap;
+use binance::rest_model::{Balance, Order, OrderSide, OrderStatus, TimeInForce};
+use serde::Serialize;
+
+use crate::{
+    api::{split_symbol, MarketFill, OrderType},
+    config::Config,
+};
+
+/// USDT given to every new paper account
+const STARTING_BALANCE: f64 = 10_000.0;
+
+const LOG_FILE_NAME: &str = "paper_trades.jsonl";
+
+/// Order waiting for book to reach its price
+#[derive(Debug)]
+struct PendingOrder {
+    order: Order,
+    base: String,
+    quote: String,
+    /// Stop price that has not been reached yet
+    trigger: Option<f64>,
+}
+
+/// Written to log after every fill
+#[derive(Debug, Serialize)]
+struct Fill<'a> {
+    time: u64,
+    symbol: &'a str,
+    side: &'a str,
+    price: f64,
+    qty: f64,
+    realized_pnl: f64,
+}
+
+pub(crate) struct PaperBroker {
+    balances: Vec<Balance>,
+    pending: Vec<PendingOrder>,
+    /// Orders no longer open, newest first
+    history: Vec<Order>,
+    /// Average entry price of every base asset bought
+    cost_basis: AHashMap<String, f64>,
+    realized_pnl: f64,
+    next_id: u64,
+    /// Where fills are appended, None disables logging
+    log_path: Option<PathBuf>,
+}
+
+fn now_millis() -> u64 {
+    chrono::Utc::now().timestamp_millis() as u64
+}
+
+impl PaperBroker {
+    pub(crate) fn new() -> Self {
+        Self {
+            balances: vec![Balance {
+                asset: "USDT".to_owned(),
+                free: STARTING_BALANCE,
+                locked: 0.0,
+            }],
+            pending: Vec::new(),
+            history: Vec::new(),
+            cost_basis: AHashMap::new(),
+            realized_pnl: 0.0,
+            next_id: 1,
+            log_path: Some(Config::dir().join(LOG_FILE_NAME)),
+        }
+    }
+
+    /// Broker that does not write fills to log
+    pub(crate) fn without_log() -> Self {
+        Self {
+            log_path: None,
+            ..Self::new()
+        }
+    }
+
+    pub(crate) fn balances(&self) -> &[Balance] {
+        &self.balances
+    }
+
+    /// Open orders followed by closed ones, newest first like exchange history
+    pub(crate) fn orders(&self) -> Vec<Order> {
+        self.pending
+            .iter()
+            .rev()
+            .map(|p| p.order.clone())
+            .chain(self.history.iter().cloned())
+            .collect()
+    }
+
+    /// Profit of closed trades in quote asset
+    pub(crate) fn realized_pnl(&self) -> f64 {
+        self.realized_pnl
+    }
+
+    fn balance(&mut self, asset: &str) -> &mut Balance {
+        match self.balances.iter().position(|b| b.asset == asset) {
+            Some(i) => &mut self.balances[i],
+            None => {
+                self.balances.push(Balance {
+                    asset: asset.to_owned(),
+                    free: 0.0,
+                    locked: 0.0,
+                });
+                self.balances.last_mut().expect("balance was just pushed")
+            }
+        }
+    }
+
+    /// Accepts order, funds are locked until it is filled
+    ///
+    /// Market orders are filled on next book update
+    pub(crate) fn place(
+        &mut self,
+        pair: String,
+        price: f64,
+        qty: f64,
+        side: OrderSide,
+        order_type: OrderType,
+    ) -> Result<(), String> {
+        let Some([base, quote]) = split_symbol(&pair) else {
+            return Err(format!("unknown pair {pair}"));
+        };
+        let (base, quote) = (base.to_owned(), quote.to_owned());
+
+        if qty <= 0.0 {
+            return Err("amount must be a positive number".to_owned());
+        }
+        if order_type != OrderType::Market && price <= 0.0 {
+            return Err("price must be a positive number".to_owned());
+        }
+
+        // market buys are checked when filled because price is not known yet
+        if order_type != OrderType::Market || side == OrderSide::Sell {
+            let (asset, needed) = match side {
+                OrderSide::Buy => (&quote, price * qty),
+                OrderSide::Sell => (&base, qty),
+            };
+            let balance = self.balance(asset);
+            if balance.free < needed {
+                return Err(format!("not enough {asset}"));
+            }
+            balance.free -= needed;
+            balance.locked += needed;
+        }
+
+        let (binance_type, trigger) = match order_type {
+            OrderType::Limit => (binance::rest_model::OrderType::Limit, None),
+            OrderType::Market => (binance::rest_model::OrderType::Market, None),
+            OrderType::StopLimit { stop_price } => (
+                binance::rest_model::OrderType::StopLossLimit,
+                Some(stop_price),
+            ),
+        };
+
+        let now = now_millis();
+        self.pending.push(PendingOrder {
+            order: Order {
+                symbol: pair,
+                order_id: self.next_id,
+                order_list_id: -1,
+                client_order_id: format!("paper-{}", self.next_id),
+                price,
+                orig_qty: qty,
+                executed_qty: 0.0,
+                cummulative_quote_qty: 0.0,
+                status: OrderStatus::New,
+                time_in_force: TimeInForce::GTC,
+                order_type: binance_type,
+                side,
+                stop_price: trigger.unwrap_or_default(),
+                iceberg_qty: 0.0,
+                time: now,
+                update_time: now,
+                is_working: true,
+                orig_quote_order_qty: 0.0,
+            },
+            base,
+            quote,
+            trigger,
+        });
+        self.next_id += 1;
+
+        Ok(())
+    }
+
+    /// Cancels open order, funds locked by it become free again
+    pub(crate) fn cancel(&mut self, order_id: u64) -> Result<(), String> {
+        let Some(i) = self
+            .pending
+            .iter()
+            .position(|p| p.order.order_id == order_id)
+        else {
+            return Err(format!("order {order_id} is not open"));
+        };
+        let PendingOrder {
+            mut order,
+            base,
+            quote,
+            ..
+        } = self.pending.remove(i);
+
+        // market buys lock nothing, price is only known when filled
+        let locked = match order.side {
+            OrderSide::Buy if order.order_type == binance::rest_model::OrderType::Market => None,
+            OrderSide::Buy => Some((quote, order.price * order.orig_qty)),
+            OrderSide::Sell => Some((base, order.orig_qty)),
+        };
+        if let Some((asset, amount)) = locked {
+            let balance = self.balance(&asset);
+            balance.locked -= amount;
+            balance.free += amount;
+        }
+
+        order.status = OrderStatus::Canceled;
+        order.is_working = false;
+        order.update_time = now_millis();
+        self.history.insert(0, order);
+
+        Ok(())
+    }
+
+    /// Market order filled right away at `bid` or `ask`, buys spend `amount` of quote asset and
+    /// sells sell `amount` of base asset
+    pub(crate) fn fill_market(
+        &mut self,
+        pair: String,
+        side: OrderSide,
+        amount: f64,
+        bid: f64,
+        ask: f64,
+    ) -> Result<MarketFill, String> {
+        let (price, qty) = match side {
+            OrderSide::Buy => (ask, amount / ask),
+            OrderSide::Sell => (bid, amount),
+        };
+        if price <= 0.0 {
+            return Err(format!("no price of {pair}"));
+        }
+
+        self.place(pair, price, qty, side, OrderType::Market)?;
+        let placed = self.pending.pop().expect("order was just placed");
+        let order = self.fill(placed, price)?;
+        let fill = MarketFill {
+            qty: order.executed_qty,
+            quote_qty: order.cummulative_quote_qty,
+        };
+        self.history.insert(0, order);
+
+        Ok(fill)
+    }
+
+    /// Fills orders of symbol that book has reached, returns them newest first
+    pub(crate) fn match_book(&mut self, symbol: &str, best_bid: f64, best_ask: f64) -> Vec<Order> {
+        let mut filled = Vec::new();
+
+        for mut p in std::mem::take(&mut self.pending) {
+            if p.order.symbol != symbol {
+                self.pending.push(p);
+                continue;
+            }
+
+            if let Some(stop) = p.trigger {
+                let triggered = match p.order.side {
+                    OrderSide::Buy => best_ask >= stop,
+                    OrderSide::Sell => best_bid <= stop,
+                };
+                if !triggered {
+                    self.pending.push(p);
+                    continue;
+                }
+                p.trigger = None;
+            }
+
+            let fill_price = match (&p.order.side, &p.order.order_type) {
+                (OrderSide::Buy, binance::rest_model::OrderType::Market) => best_ask,
+                (OrderSide::Sell, binance::rest_model::OrderType::Market) => best_bid,
+                (OrderSide::Buy, _) if best_ask <= p.order.price => best_ask,
+                (OrderSide::Sell, _) if best_bid >= p.order.price => best_bid,
+                _ => {
+                    self.pending.push(p);
+                    continue;
+                }
+            };
+
+            match self.fill(p, fill_price) {
+                Ok(order) => filled.push(order),
+                Err(err) => tracing::warn!("paper order rejected: {err}"),
+            }
+        }
+
+        filled.reverse();
+        self.history.splice(0..0, filled.iter().cloned());
+        filled
+    }
+
+    fn fill(&mut self, p: PendingOrder, fill_price: f64) -> Result<Order, String> {
+        let PendingOrder {
+            mut order,
+            base,
+            quote,
+            ..
+        } = p;
+        let qty = order.orig_qty;
+        let is_market = order.order_type == binance::rest_model::OrderType::Market;
+        let mut pnl = 0.0;
+
+        match order.side {
+            OrderSide::Buy => {
+                let cost = fill_price * qty;
+                let quote_balance = self.balance(&quote);
+                if is_market {
+                    if quote_balance.free < cost {
+                        return Err(format!("not enough {quote}"));
+                    }
+                    quote_balance.free -= cost;
+                } else {
+                    // return difference between limit and fill price
+                    quote_balance.locked -= order.price * qty;
+                    quote_balance.free += (order.price - fill_price) * qty;
+                }
+
+                let held = self.balance(&base);
+                let held_before = held.free + held.locked;
+                held.free += qty;
+
+                let basis = self.cost_basis.entry(base).or_insert(0.0);
+                *basis = (*basis * held_before + cost) / (held_before + qty);
+            }
+            OrderSide::Sell => {
+                let base_balance = self.balance(&base);
+                base_balance.locked -= qty;
+                self.balance(&quote).free += fill_price * qty;
+
+                if let Some(basis) = self.cost_basis.get(&base) {
+                    pnl = (fill_price - basis) * qty;
+                    self.realized_pnl += pnl;
+                }
+            }
+        }
+
+        order.executed_qty = qty;
+        order.cummulative_quote_qty = fill_price * qty;
+        order.status = OrderStatus::Filled;
+        order.is_working = false;
+        order.update_time = now_millis();
+
+        self.log(&Fill {
+            time: order.update_time,
+            symbol: &order.symbol,
+            side: match order.side {
+                OrderSide::Buy => "BUY",
+                OrderSide::Sell => "SELL",
+            },
+            price: fill_price,
+            qty,
+            realized_pnl: pnl,
+        });
+
+        Ok(order)
+    }
+
+    /// Appends fill to session log, failures are only logged
+    fn log(&self, fill: &Fill) {
+        let Some(path) = &self.log_path else {
+            return;
+        };
+        let line = serde_json::to_string(fill).expect("fill serializer is valid");
+
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| {
+                fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .and_then(|mut file| writeln!(file, "{line}"))
+            });
+
+        if let Err(err) = result {
+            tracing::error!("failed to write paper trade log: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn free(broker: &PaperBroker, asset: &str) -> f64 {
+        broker
+            .balances()
+            .iter()
+            .find(|b| b.asset == asset)
+            .map_or(0.0, |b| b.free)
+    }
+
+    #[test]
+    fn limit_orders_fill_at_book_price() {
+        let mut broker = PaperBroker::new();
+        broker.log_path = None;
+
+        broker
+            .place(
+                "BTCUSDT".to_owned(),
+                100.0,
+                2.0,
+                OrderSide::Buy,
+                OrderType::Limit,
+            )
+            .unwrap();
+        assert_eq!(free(&broker, "USDT"), STARTING_BALANCE - 200.0);
+
+        // ask above limit, nothing happens
+        assert!(broker.match_book("BTCUSDT", 100.0, 101.0).is_empty());
+
+        let filled = broker.match_book("BTCUSDT", 98.0, 99.0);
+        assert_eq!(filled.len(), 1);
+        assert_eq!(free(&broker, "BTC"), 2.0);
+        // difference between limit and fill is returned
+        assert_eq!(free(&broker, "USDT"), STARTING_BALANCE - 198.0);
+
+        broker
+            .place(
+                "BTCUSDT".to_owned(),
+                0.0,
+                2.0,
+                OrderSide::Sell,
+                OrderType::Market,
+            )
+            .unwrap();
+        broker.match_book("BTCUSDT", 110.0, 111.0);
+        assert_eq!(free(&broker, "BTC"), 0.0);
+        assert_eq!(broker.realized_pnl(), 22.0);
+
+        assert!(broker
+            .place(
+                "BTCUSDT".to_owned(),
+                0.0,
+                1.0,
+                OrderSide::Sell,
+                OrderType::Market
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn market_fill_is_immediate() {
+        let mut broker = PaperBroker::new();
+        broker.log_path = None;
+
+        let fill = broker
+            .fill_market("BTCUSDT".to_owned(), OrderSide::Buy, 200.0, 99.0, 100.0)
+            .unwrap();
+        assert_eq!(
+            fill,
+            MarketFill {
+                qty: 2.0,
+                quote_qty: 200.0
+            }
+        );
+        assert_eq!(free(&broker, "BTC"), 2.0);
+
+        let fill = broker
+            .fill_market("BTCUSDT".to_owned(), OrderSide::Sell, 2.0, 99.0, 100.0)
+            .unwrap();
+        assert_eq!(fill.quote_qty, 198.0);
+        assert_eq!(free(&broker, "USDT"), STARTING_BALANCE - 2.0);
+        assert!(broker.pending.is_empty());
+    }
+
+    #[test]
+    fn orders_are_mirrored() {
+        let mut broker = PaperBroker::new();
+        broker.log_path = None;
+
+        broker
+            .place(
+                "BTCUSDT".to_owned(),
+                100.0,
+                1.0,
+                OrderSide::Buy,
+                OrderType::Limit,
+            )
+            .unwrap();
+        broker
+            .place(
+                "BTCUSDT".to_owned(),
+                90.0,
+                1.0,
+                OrderSide::Buy,
+                OrderType::Limit,
+            )
+            .unwrap();
+        let orders = broker.orders();
+        assert_eq!(orders.len(), 2);
+        assert!(orders.iter().all(|o| o.status == OrderStatus::New));
+        assert_eq!(orders[0].price, 90.0);
+
+        broker.match_book("BTCUSDT", 98.0, 99.0);
+        let orders = broker.orders();
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[0].status, OrderStatus::New);
+        assert_eq!(orders[1].status, OrderStatus::Filled);
+    }
+
+    #[test]
+    fn cancel_refunds_locked_funds() {
+        let mut broker = PaperBroker::new();
+        broker.log_path = None;
+
+        broker
+            .place(
+                "BTCUSDT".to_owned(),
+                100.0,
+                2.0,
+                OrderSide::Buy,
+                OrderType::Limit,
+            )
+            .unwrap();
+        assert_eq!(free(&broker, "USDT"), STARTING_BALANCE - 200.0);
+
+        broker.cancel(1).unwrap();
+        assert_eq!(free(&broker, "USDT"), STARTING_BALANCE);
+        assert_eq!(broker.balances()[0].locked, 0.0);
+        assert_eq!(broker.orders()[0].status, OrderStatus::Canceled);
+        assert!(broker.match_book("BTCUSDT", 98.0, 99.0).is_empty());
+
+        assert!(broker.cancel(1).is_err());
+    }
+}
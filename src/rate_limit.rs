@@ -0,0 +1,116 @@
+//! Request weight limiting of REST calls
+//!
+//! Binance bans IP for a while after repeated 429 responses, so requests wait for weight
+//! to become available instead of being sent immediately
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Default limit of `REQUEST_WEIGHT`, replaced with one from `exchangeInfo`
+const DEFAULT_WEIGHT_PER_MINUTE: u32 = 6000;
+
+/// Token bucket, tokens go below zero for requests waiting in queue
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    per_second: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn new(limit: u32, interval: Duration, now: Instant) -> Self {
+        Self {
+            capacity: f64::from(limit),
+            per_second: f64::from(limit) / interval.as_secs_f64(),
+            tokens: f64::from(limit),
+            last_refill: now,
+        }
+    }
+
+    /// Takes `weight` and returns how long to wait before sending, None if queue is full
+    fn reserve(&mut self, weight: u32, now: Instant) -> Option<Duration> {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.capacity);
+        self.last_refill = now;
+
+        let remaining = self.tokens - f64::from(weight);
+        // waiting more than one full interval means limit is overrun anyway
+        if -remaining > self.capacity {
+            return None;
+        }
+        self.tokens = remaining;
+
+        Some(Duration::from_secs_f64(
+            (-remaining).max(0.0) / self.per_second,
+        ))
+    }
+}
+
+pub(crate) struct RateLimiter {
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new() -> Self {
+        Self {
+            bucket: Mutex::new(Bucket::new(
+                DEFAULT_WEIGHT_PER_MINUTE,
+                Duration::from_secs(60),
+                Instant::now(),
+            )),
+        }
+    }
+
+    /// Applies limit announced by exchange, queued requests are forgotten
+    pub(crate) fn set_limit(&self, limit: u32, interval: Duration) {
+        if limit == 0 || interval.is_zero() {
+            return;
+        }
+
+        *self.bucket.lock().expect("poisoned") = Bucket::new(limit, interval, Instant::now());
+    }
+
+    /// Waits until request of `weight` can be sent, errors if too many requests are queued
+    pub(crate) async fn acquire(&self, weight: u32) -> Result<(), String> {
+        let wait = self
+            .bucket
+            .lock()
+            .expect("poisoned")
+            .reserve(weight, Instant::now());
+
+        match wait {
+            Some(wait) if wait.is_zero() => Ok(()),
+            Some(wait) => {
+                tokio::time::sleep(wait).await;
+                Ok(())
+            }
+            None => Err(format!(
+                "request of weight {weight} dropped, too many requests queued"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_queues_and_refills() {
+        let start = Instant::now();
+        let mut bucket = Bucket::new(10, Duration::from_secs(10), start);
+
+        assert_eq!(bucket.reserve(10, start), Some(Duration::ZERO));
+        assert_eq!(bucket.reserve(5, start), Some(Duration::from_secs(5)));
+        // 15 weight in debt would take longer than the interval
+        assert_eq!(bucket.reserve(6, start), None);
+
+        let later = start + Duration::from_secs(20);
+        assert_eq!(bucket.reserve(5, later), Some(Duration::ZERO));
+    }
+}
@@ -0,0 +1,56 @@
+//! Saving window contents to image file
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local};
+use iced::window::Screenshot;
+
+/// Pictures directory of user, or current directory if there is none
+fn dir() -> PathBuf {
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(dir) = directories_next::UserDirs::new()
+        .as_ref()
+        .and_then(directories_next::UserDirs::picture_dir)
+    {
+        return dir.to_owned();
+    }
+
+    std::env::current_dir().unwrap_or_default()
+}
+
+fn file_name(now: DateTime<Local>) -> String {
+    format!("dynasty-{}.png", now.format("%Y-%m-%d_%H-%M-%S"))
+}
+
+/// Encodes screenshot as PNG on blocking thread, returns path it was written to
+pub(crate) async fn save(screenshot: Screenshot) -> Result<PathBuf, String> {
+    tokio::task::spawn_blocking(move || {
+        let path = dir().join(file_name(Local::now()));
+
+        image::save_buffer(
+            &path,
+            &screenshot.bytes,
+            screenshot.size.width,
+            screenshot.size.height,
+            image::ColorType::Rgba8,
+        )
+        .map_err(|err| err.to_string())?;
+
+        Ok(path)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn file_name_sorts_by_time() {
+        let now = Local.with_ymd_and_hms(2024, 3, 9, 7, 5, 1).unwrap();
+        assert_eq!(file_name(now), "dynasty-2024-03-09_07-05-01.png");
+    }
+}
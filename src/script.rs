@@ -0,0 +1,621 @@
+//! Tiny scripting language for trading automation
+//!
+//! Scripts are made of statements separated by new lines:
+//!
+//! ```text
+//! # buy the dip
+//! let target = price("BTCUSDT") * 0.98
+//! loop {
+//!     if price("BTCUSDT") < target {
+//!         buy(target, 0.001)
+//!         break
+//!     }
+//!     sleep(1000)
+//! }
+//! ```
+//!
+//! Available functions are `price(symbol)`, `buy(price, qty)`, `sell(price, qty)`, `print(..)`
+//! and `sleep(ms)`. Orders are placed on market selected when script was started.
+//!
+//! This is not rhai or lua: neither engine is in the dependency tree, and a general purpose
+//! language would also expose file and network access to scripts pasted from elsewhere.
+//! The interpreter only knows the functions above, so a script cannot do more than trade,
+//! which is capped per minute by algo trading pane.
+
+use std::{fmt, time::Duration};
+
+use ahash::AHashMap;
+use binance::rest_model::OrderSide;
+
+/// What script can do outside of itself
+pub(crate) trait ScriptEnv {
+    fn price(&self, symbol: &str) -> Option<f64>;
+    fn order(&mut self, side: OrderSide, price: f64, qty: f64);
+    fn print(&mut self, line: String);
+    /// Returns false if script was stopped while sleeping
+    fn sleep(&mut self, duration: Duration) -> bool;
+    fn is_stopped(&self) -> bool;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Num(f64),
+    Str(String),
+    Bool(bool),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Num(n) => write!(f, "{n}"),
+            Value::Str(s) => write!(f, "{s}"),
+            Value::Bool(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Str(String),
+    Ident(String),
+    /// Operators and punctuation
+    Sym(&'static str),
+    Newline,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Value(Value),
+    Var(String),
+    Neg(Box<Expr>),
+    Bin(BinOp, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone)]
+enum Stmt {
+    Let(String, Expr),
+    If(Expr, Vec<Stmt>, Vec<Stmt>),
+    Loop(Vec<Stmt>),
+    Break,
+    Expr(Expr),
+}
+
+/// How block execution ended
+enum Flow {
+    Normal,
+    Break,
+    Stopped,
+}
+
+const SYMBOLS: [&str; 16] = [
+    "<=", ">=", "==", "!=", "<", ">", "+", "-", "*", "/", "(", ")", "{", "}", ",", "=",
+];
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, String> {
+    let mut tokens = Vec::new();
+
+    for (i, line) in source.lines().enumerate() {
+        let line_no = i + 1;
+        let mut rest = line.trim_start();
+
+        while !rest.is_empty() && !rest.starts_with('#') {
+            let c = rest.chars().next().expect("rest is not empty");
+
+            if c.is_ascii_digit() || c == '.' {
+                let end = rest
+                    .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                    .unwrap_or(rest.len());
+                let num = rest[..end]
+                    .parse()
+                    .map_err(|_| format!("line {line_no}: bad number {}", &rest[..end]))?;
+                tokens.push((line_no, Token::Num(num)));
+                rest = &rest[end..];
+            } else if c.is_alphabetic() || c == '_' {
+                let end = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                tokens.push((line_no, Token::Ident(rest[..end].to_owned())));
+                rest = &rest[end..];
+            } else if c == '"' {
+                let end = rest[1..]
+                    .find('"')
+                    .ok_or_else(|| format!("line {line_no}: unterminated string"))?;
+                tokens.push((line_no, Token::Str(rest[1..=end].to_owned())));
+                rest = &rest[end + 2..];
+            } else if let Some(sym) = SYMBOLS.iter().find(|s| rest.starts_with(**s)) {
+                tokens.push((line_no, Token::Sym(sym)));
+                rest = &rest[sym.len()..];
+            } else {
+                return Err(format!("line {line_no}: unexpected {c}"));
+            }
+
+            rest = rest.trim_start();
+        }
+
+        tokens.push((line_no, Token::Newline));
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos.min(self.tokens.len().saturating_sub(1)))
+            .map_or(0, |(line, _)| *line)
+    }
+
+    fn error<T>(&self, message: impl fmt::Display) -> Result<T, String> {
+        Err(format!("line {}: {message}", self.line()))
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(_, t)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, sym: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Sym(s)) if *s == sym) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, sym: &str) -> Result<(), String> {
+        if self.eat(sym) {
+            Ok(())
+        } else {
+            self.error(format!("expected {sym}"))
+        }
+    }
+
+    fn skip_newlines(&mut self) {
+        while self.peek() == Some(&Token::Newline) {
+            self.pos += 1;
+        }
+    }
+
+    /// Statements until closing brace or end of script
+    fn block(&mut self, top_level: bool) -> Result<Vec<Stmt>, String> {
+        let mut statements = Vec::new();
+
+        loop {
+            self.skip_newlines();
+            match self.peek() {
+                None if top_level => return Ok(statements),
+                None => return self.error("expected }"),
+                Some(Token::Sym("}")) if !top_level => {
+                    self.pos += 1;
+                    return Ok(statements);
+                }
+                _ => statements.push(self.statement()?),
+            }
+        }
+    }
+
+    fn statement(&mut self) -> Result<Stmt, String> {
+        let stmt = match self.peek() {
+            Some(Token::Ident(word)) if word == "let" => {
+                self.pos += 1;
+                let Some(Token::Ident(name)) = self.next() else {
+                    return self.error("expected variable name");
+                };
+                self.expect("=")?;
+                Stmt::Let(name, self.expr()?)
+            }
+            Some(Token::Ident(word)) if word == "if" => {
+                self.pos += 1;
+                let condition = self.expr()?;
+                self.expect("{")?;
+                let then = self.block(false)?;
+                let otherwise = if matches!(self.peek(), Some(Token::Ident(w)) if w == "else") {
+                    self.pos += 1;
+                    self.expect("{")?;
+                    self.block(false)?
+                } else {
+                    Vec::new()
+                };
+                Stmt::If(condition, then, otherwise)
+            }
+            Some(Token::Ident(word)) if word == "loop" => {
+                self.pos += 1;
+                self.expect("{")?;
+                Stmt::Loop(self.block(false)?)
+            }
+            Some(Token::Ident(word)) if word == "break" => {
+                self.pos += 1;
+                Stmt::Break
+            }
+            // assignment to existing variable
+            Some(Token::Ident(name))
+                if self.tokens.get(self.pos + 1).map(|(_, t)| t) == Some(&Token::Sym("=")) =>
+            {
+                let name = name.clone();
+                self.pos += 2;
+                Stmt::Let(name, self.expr()?)
+            }
+            _ => Stmt::Expr(self.expr()?),
+        };
+
+        match self.peek() {
+            None | Some(Token::Newline) | Some(Token::Sym("}")) => Ok(stmt),
+            Some(token) => {
+                let token = token.clone();
+                self.error(format!("unexpected {token:?}"))
+            }
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let left = self.sum()?;
+
+        let op = match self.peek() {
+            Some(Token::Sym("<")) => BinOp::Lt,
+            Some(Token::Sym(">")) => BinOp::Gt,
+            Some(Token::Sym("<=")) => BinOp::Le,
+            Some(Token::Sym(">=")) => BinOp::Ge,
+            Some(Token::Sym("==")) => BinOp::Eq,
+            Some(Token::Sym("!=")) => BinOp::Ne,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+
+        Ok(Expr::Bin(op, Box::new(left), Box::new(self.sum()?)))
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut left = self.product()?;
+
+        loop {
+            let op = match self.peek() {
+                Some(Token::Sym("+")) => BinOp::Add,
+                Some(Token::Sym("-")) => BinOp::Sub,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            left = Expr::Bin(op, Box::new(left), Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+
+        loop {
+            let op = match self.peek() {
+                Some(Token::Sym("*")) => BinOp::Mul,
+                Some(Token::Sym("/")) => BinOp::Div,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            left = Expr::Bin(op, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+
+        match self.next() {
+            Some(Token::Num(n)) => Ok(Expr::Value(Value::Num(n))),
+            Some(Token::Str(s)) => Ok(Expr::Value(Value::Str(s))),
+            Some(Token::Ident(word)) if word == "true" => Ok(Expr::Value(Value::Bool(true))),
+            Some(Token::Ident(word)) if word == "false" => Ok(Expr::Value(Value::Bool(false))),
+            Some(Token::Ident(name)) => {
+                if !self.eat("(") {
+                    return Ok(Expr::Var(name));
+                }
+
+                let mut args = Vec::new();
+                if !self.eat(")") {
+                    loop {
+                        args.push(self.expr()?);
+                        if self.eat(")") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                Ok(Expr::Call(name, args))
+            }
+            Some(Token::Sym("(")) => {
+                let inner = self.expr()?;
+                self.expect(")")?;
+                Ok(inner)
+            }
+            _ => {
+                self.pos -= 1;
+                self.error("expected value")
+            }
+        }
+    }
+}
+
+/// Parsed script ready to be run
+#[derive(Debug, Clone)]
+pub(crate) struct Script {
+    statements: Vec<Stmt>,
+}
+
+impl Script {
+    pub(crate) fn parse(source: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            pos: 0,
+        };
+
+        Ok(Self {
+            statements: parser.block(true)?,
+        })
+    }
+
+    /// Runs until end of script, error or stop
+    pub(crate) fn run(&self, env: &mut impl ScriptEnv) -> Result<(), String> {
+        let mut vars = AHashMap::new();
+        Interpreter {
+            env,
+            vars: &mut vars,
+        }
+        .block(&self.statements)
+        .map(|_| ())
+    }
+}
+
+struct Interpreter<'a, E> {
+    env: &'a mut E,
+    vars: &'a mut AHashMap<String, Value>,
+}
+
+impl<E: ScriptEnv> Interpreter<'_, E> {
+    fn block(&mut self, statements: &[Stmt]) -> Result<Flow, String> {
+        for stmt in statements {
+            if self.env.is_stopped() {
+                return Ok(Flow::Stopped);
+            }
+
+            let flow = match stmt {
+                Stmt::Let(name, expr) => {
+                    let value = self.eval(expr)?;
+                    self.vars.insert(name.clone(), value);
+                    Flow::Normal
+                }
+                Stmt::If(condition, then, otherwise) => {
+                    let Value::Bool(condition) = self.eval(condition)? else {
+                        return Err("if condition should be a comparison".to_owned());
+                    };
+                    self.block(if condition { then } else { otherwise })?
+                }
+                // checked here too, body may have no statements to check it before
+                Stmt::Loop(body) => loop {
+                    if self.env.is_stopped() {
+                        break Flow::Stopped;
+                    }
+                    match self.block(body)? {
+                        Flow::Normal => {}
+                        Flow::Break => break Flow::Normal,
+                        Flow::Stopped => break Flow::Stopped,
+                    }
+                },
+                Stmt::Break => Flow::Break,
+                Stmt::Expr(expr) => {
+                    self.eval(expr)?;
+                    Flow::Normal
+                }
+            };
+
+            if !matches!(flow, Flow::Normal) {
+                return Ok(flow);
+            }
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    fn num(&mut self, expr: &Expr) -> Result<f64, String> {
+        match self.eval(expr)? {
+            Value::Num(n) => Ok(n),
+            value => Err(format!("expected number, got {value}")),
+        }
+    }
+
+    fn eval(&mut self, expr: &Expr) -> Result<Value, String> {
+        Ok(match expr {
+            Expr::Value(value) => value.clone(),
+            Expr::Var(name) => self
+                .vars
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("unknown variable {name}"))?,
+            Expr::Neg(inner) => Value::Num(-self.num(inner)?),
+            Expr::Bin(op, left, right) => {
+                let (l, r) = (self.num(left)?, self.num(right)?);
+                match op {
+                    BinOp::Add => Value::Num(l + r),
+                    BinOp::Sub => Value::Num(l - r),
+                    BinOp::Mul => Value::Num(l * r),
+                    BinOp::Div => Value::Num(l / r),
+                    BinOp::Lt => Value::Bool(l < r),
+                    BinOp::Gt => Value::Bool(l > r),
+                    BinOp::Le => Value::Bool(l <= r),
+                    BinOp::Ge => Value::Bool(l >= r),
+                    BinOp::Eq => Value::Bool(l == r),
+                    BinOp::Ne => Value::Bool(l != r),
+                }
+            }
+            Expr::Call(name, args) => self.call(name, args)?,
+        })
+    }
+
+    fn call(&mut self, name: &str, args: &[Expr]) -> Result<Value, String> {
+        let arity = |n: usize| {
+            if args.len() == n {
+                Ok(())
+            } else {
+                Err(format!("{name} takes {n} arguments, got {}", args.len()))
+            }
+        };
+
+        match name {
+            "price" => {
+                arity(1)?;
+                let Value::Str(symbol) = self.eval(&args[0])? else {
+                    return Err("price takes symbol string".to_owned());
+                };
+                let symbol = symbol.to_uppercase();
+                self.env
+                    .price(&symbol)
+                    .map(Value::Num)
+                    .ok_or_else(|| format!("no price for {symbol}"))
+            }
+            "buy" | "sell" => {
+                arity(2)?;
+                let (price, qty) = (self.num(&args[0])?, self.num(&args[1])?);
+                if price <= 0.0 || qty <= 0.0 {
+                    return Err(format!("{name} price and quantity should be positive"));
+                }
+
+                let side = if name == "buy" {
+                    OrderSide::Buy
+                } else {
+                    OrderSide::Sell
+                };
+                self.env.order(side, price, qty);
+                Ok(Value::Bool(true))
+            }
+            "print" => {
+                let line = args
+                    .iter()
+                    .map(|arg| self.eval(arg).map(|v| v.to_string()))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(" ");
+                self.env.print(line);
+                Ok(Value::Bool(true))
+            }
+            "sleep" => {
+                arity(1)?;
+                let ms = self.num(&args[0])?.max(0.0);
+                Ok(Value::Bool(
+                    self.env.sleep(Duration::from_millis(ms as u64)),
+                ))
+            }
+            name => Err(format!("unknown function {name}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct TestEnv {
+        orders: Vec<(OrderSide, f64, f64)>,
+        output: Vec<String>,
+        slept: u32,
+    }
+
+    impl ScriptEnv for TestEnv {
+        fn price(&self, symbol: &str) -> Option<f64> {
+            (symbol == "BTCUSDT").then_some(100.0 - f64::from(self.slept))
+        }
+
+        fn order(&mut self, side: OrderSide, price: f64, qty: f64) {
+            self.orders.push((side, price, qty));
+        }
+
+        fn print(&mut self, line: String) {
+            self.output.push(line);
+        }
+
+        fn sleep(&mut self, _: Duration) -> bool {
+            self.slept += 1;
+            true
+        }
+
+        fn is_stopped(&self) -> bool {
+            self.slept > 10
+        }
+    }
+
+    #[test]
+    fn buys_when_price_drops() {
+        let script = Script::parse(
+            r#"
+            # wait for 5% drop
+            let target = price("btcusdt") * 0.95
+            loop {
+                if price("BTCUSDT") <= target {
+                    buy(target, 0.5)
+                    break
+                } else {
+                    sleep(1000)
+                }
+            }
+            print("bought at", target)
+            "#,
+        )
+        .unwrap();
+
+        let mut env = TestEnv::default();
+        script.run(&mut env).unwrap();
+
+        assert_eq!(env.orders, [(OrderSide::Buy, 95.0, 0.5)]);
+        assert_eq!(env.output, ["bought at 95"]);
+    }
+
+    #[test]
+    fn stops_endless_loop() {
+        let script = Script::parse("loop {\n sleep(1)\n}").unwrap();
+
+        let mut env = TestEnv::default();
+        script.run(&mut env).unwrap();
+
+        assert_eq!(env.slept, 11);
+
+        // empty body never reaches statement check
+        Script::parse("loop {\n}").unwrap().run(&mut env).unwrap();
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert!(Script::parse("let = 1").is_err());
+        assert!(Script::parse("loop {").is_err());
+        assert!(Script::parse("buy(1, 2").is_err());
+
+        let mut env = TestEnv::default();
+        assert!(Script::parse("sell(-1, 1)").unwrap().run(&mut env).is_err());
+        assert!(Script::parse("price(\"ETHUSDT\")")
+            .unwrap()
+            .run(&mut env)
+            .is_err());
+        assert!(Script::parse("x + 1").unwrap().run(&mut env).is_err());
+    }
+}
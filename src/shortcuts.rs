@@ -0,0 +1,198 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use iced::keyboard::{Key, Modifiers};
+use serde::{Deserialize, Serialize};
+
+/// Key with modifiers, stored as string like `Ctrl+Shift+Tab`
+///
+/// Ctrl means Cmd on macOS
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) struct Shortcut {
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    key: String,
+}
+
+impl Shortcut {
+    fn key_name(key: &Key) -> Option<String> {
+        match key {
+            Key::Named(named) => Some(format!("{named:?}")),
+            Key::Character(c) => Some(c.to_uppercase()),
+            Key::Unidentified => None,
+        }
+    }
+
+    pub(crate) fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
+        self.ctrl == modifiers.command()
+            && self.alt == modifiers.alt()
+            && self.shift == modifiers.shift()
+            && Self::key_name(key).is_some_and(|name| name == self.key)
+    }
+}
+
+impl Display for Shortcut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (enabled, name) in [
+            (self.ctrl, "Ctrl"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+        ] {
+            if enabled {
+                write!(f, "{name}+")?;
+            }
+        }
+
+        write!(f, "{}", self.key)
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<_> = s.split('+').map(str::trim).collect();
+        let key = match parts.pop() {
+            Some(key) if !key.is_empty() => key,
+            _ => return Err(format!("no key in shortcut: {s}")),
+        };
+
+        let mut shortcut = Self {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            // named keys are case sensitive, characters are uppercase
+            key: if key.chars().count() == 1 {
+                key.to_uppercase()
+            } else {
+                key.to_owned()
+            },
+        };
+
+        for modifier in parts {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "cmd" => shortcut.ctrl = true,
+                "alt" => shortcut.alt = true,
+                "shift" => shortcut.shift = true,
+                other => return Err(format!("unknown modifier: {other}")),
+            }
+        }
+
+        Ok(shortcut)
+    }
+}
+
+impl TryFrom<String> for Shortcut {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Shortcut> for String {
+    fn from(value: Shortcut) -> Self {
+        value.to_string()
+    }
+}
+
+/// Things that can be done with keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub(crate) enum ShortcutAction {
+    Buy,
+    Sell,
+    FocusNext,
+    FocusPrevious,
+    ToggleMaximize,
+    Close,
+    Undo,
+    Redo,
+    Search,
+    Screenshot,
+}
+
+/// Actions missing from saved config get default shortcuts
+///
+/// Buy and sell have none, trading from keyboard has to be bound in config explicitly
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "BTreeMap<ShortcutAction, Shortcut>")]
+pub(crate) struct KeyboardShortcuts(BTreeMap<ShortcutAction, Shortcut>);
+
+impl From<BTreeMap<ShortcutAction, Shortcut>> for KeyboardShortcuts {
+    fn from(mut value: BTreeMap<ShortcutAction, Shortcut>) -> Self {
+        for (action, shortcut) in Self::default().0 {
+            value.entry(action).or_insert(shortcut);
+        }
+
+        Self(value)
+    }
+}
+
+impl Default for KeyboardShortcuts {
+    fn default() -> Self {
+        Self(
+            [
+                (ShortcutAction::FocusNext, "Tab"),
+                (ShortcutAction::FocusPrevious, "Shift+Tab"),
+                (ShortcutAction::ToggleMaximize, "Ctrl+M"),
+                (ShortcutAction::Close, "Ctrl+W"),
+                (ShortcutAction::Undo, "Ctrl+Z"),
+                (ShortcutAction::Redo, "Ctrl+Shift+Z"),
+                (ShortcutAction::Search, "Ctrl+F"),
+                (ShortcutAction::Screenshot, "Ctrl+Shift+S"),
+            ]
+            .into_iter()
+            .map(|(action, shortcut)| (action, shortcut.parse().expect("default is valid")))
+            .collect(),
+        )
+    }
+}
+
+impl KeyboardShortcuts {
+    /// Action bound to pressed key
+    pub(crate) fn action(&self, key: &Key, modifiers: Modifiers) -> Option<ShortcutAction> {
+        self.0
+            .iter()
+            .find(|(_, shortcut)| shortcut.matches(key, modifiers))
+            .map(|(action, _)| *action)
+    }
+
+    pub(crate) fn shortcut(&self, action: ShortcutAction) -> Option<&Shortcut> {
+        self.0.get(&action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortcut_roundtrip() {
+        for s in ["Ctrl+B", "Shift+Tab", "Ctrl+Alt+Shift+ArrowUp", "F5"] {
+            assert_eq!(s.parse::<Shortcut>().unwrap().to_string(), s);
+        }
+
+        assert_eq!("ctrl+b".parse::<Shortcut>().unwrap().to_string(), "Ctrl+B");
+        assert!("Hyper+B".parse::<Shortcut>().is_err());
+        assert!("Ctrl+".parse::<Shortcut>().is_err());
+    }
+
+    #[test]
+    fn missing_actions_get_defaults() {
+        let shortcuts: KeyboardShortcuts = serde_json::from_str(r#"{"Buy": "F1"}"#).unwrap();
+
+        assert_eq!(
+            shortcuts.shortcut(ShortcutAction::Buy).unwrap().to_string(),
+            "F1"
+        );
+        assert_eq!(
+            shortcuts
+                .shortcut(ShortcutAction::Undo)
+                .unwrap()
+                .to_string(),
+            "Ctrl+Z"
+        );
+        assert!(shortcuts.shortcut(ShortcutAction::Sell).is_none());
+    }
+}
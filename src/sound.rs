@@ -0,0 +1,203 @@
+//! Short notification tones played with audio player of OS
+//!
+//! Tones are synthesized, so nothing has to be bundled. Linux players read them from stdin, other
+//! systems get a temporary file per playback
+
+use std::{
+    f32::consts::TAU,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{self, ExitStatus, Stdio},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+const SAMPLE_RATE: u32 = 22_050;
+
+/// Players reading WAV from stdin with their arguments, first installed one is used
+const STDIN_PLAYERS: [(&str, &[&str]); 3] = [
+    ("paplay", &[]),
+    ("pw-play", &["-"]),
+    ("aplay", &["-q", "-"]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SoundEffect {
+    /// Own order was filled
+    Fill,
+    /// Alert for price going above threshold
+    PriceUp,
+    /// Alert for price going below threshold
+    PriceDown,
+}
+
+impl SoundEffect {
+    /// Frequencies in Hz and length in milliseconds of consecutive beeps
+    fn beeps(self) -> &'static [(f32, u32)] {
+        match self {
+            SoundEffect::Fill => &[(880.0, 80), (0.0, 40), (880.0, 80)],
+            SoundEffect::PriceUp => &[(660.0, 100), (990.0, 140)],
+            SoundEffect::PriceDown => &[(990.0, 100), (660.0, 140)],
+        }
+    }
+
+    fn file_name(self) -> &'static str {
+        match self {
+            SoundEffect::Fill => "dynasty-fill.wav",
+            SoundEffect::PriceUp => "dynasty-price-up.wav",
+            SoundEffect::PriceDown => "dynasty-price-down.wav",
+        }
+    }
+}
+
+/// Mono 16 bit WAV of effect, `volume` is between 0 and 1
+fn wav(effect: SoundEffect, volume: f32) -> Vec<u8> {
+    let amplitude = volume.clamp(0.0, 1.0) * f32::from(i16::MAX);
+    let samples: Vec<i16> = effect
+        .beeps()
+        .iter()
+        .flat_map(|&(frequency, ms)| {
+            let len = SAMPLE_RATE * ms / 1000;
+            (0..len).map(move |i| {
+                // short fade at both ends avoids clicks
+                let fade = (i.min(len - i) as f32 / 200.0).min(1.0);
+                let t = i as f32 / SAMPLE_RATE as f32;
+                ((TAU * frequency * t).sin() * amplitude * fade) as i16
+            })
+        })
+        .collect();
+
+    let data_len = samples.len() as u32 * 2;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    // PCM, one channel
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    wav.extend(samples.iter().flat_map(|s| s.to_le_bytes()));
+
+    wav
+}
+
+/// Unique file for every playback, player of previous one could still be reading its file
+fn write_wav(effect: SoundEffect, wav: &[u8]) -> io::Result<PathBuf> {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+
+    let path = std::env::temp_dir().join(format!(
+        "{}-{}-{}",
+        process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed),
+        effect.file_name()
+    ));
+    fs::write(&path, wav)?;
+
+    Ok(path)
+}
+
+/// Pipes WAV into first installed player and waits for it to exit
+fn play_piped(wav: &[u8]) -> io::Result<ExitStatus> {
+    let mut not_found = None;
+
+    for (player, args) in STDIN_PLAYERS {
+        let spawned = process::Command::new(player)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+
+        match spawned {
+            Ok(mut child) => {
+                // player that failed closes pipe early, its exit status explains why
+                if let Some(mut stdin) = child.stdin.take() {
+                    let _ = stdin.write_all(wav);
+                }
+                return child.wait();
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => not_found = Some(err),
+            Err(err) => return Err(err),
+        }
+    }
+
+    Err(not_found.unwrap_or_else(|| io::ErrorKind::NotFound.into()))
+}
+
+/// Plays file with player of OS and waits for it to exit
+fn play_file(path: &Path) -> io::Result<ExitStatus> {
+    if cfg!(target_os = "macos") {
+        process::Command::new("afplay").arg(path).status()
+    } else {
+        process::Command::new("powershell")
+            .arg("-c")
+            .arg(format!(
+                "(New-Object Media.SoundPlayer {:?}).PlaySync()",
+                path.display().to_string()
+            ))
+            .status()
+    }
+}
+
+/// Plays effect on blocking thread, player process is waited for so that it does not linger
+pub(crate) async fn play(effect: SoundEffect, volume: f32) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let wav = wav(effect, volume);
+
+        let status = if cfg!(any(target_os = "macos", target_os = "windows")) {
+            let path = write_wav(effect, &wav)?;
+            let status = play_file(&path);
+            let _ = fs::remove_file(&path);
+            status
+        } else {
+            play_piped(&wav)
+        }?;
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!("player exited with {status}")))
+        }
+    })
+    .await
+    .map_err(|err| err.to_string())?
+    .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wav_header_matches_samples() {
+        let wav = wav(SoundEffect::Fill, 0.5);
+        // 200 ms at 22050 Hz, two bytes each
+        let data_len = SAMPLE_RATE * 200 / 1000 * 2;
+
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[40..44], &data_len.to_le_bytes());
+        assert_eq!(wav.len(), 44 + data_len as usize);
+        // 40 ms of silence after first beep
+        let pause = (SAMPLE_RATE * 80 / 1000) as usize * 2 + 44;
+        let pause_len = (SAMPLE_RATE * 40 / 1000) as usize * 2;
+        assert!(wav[pause..pause + pause_len].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn playbacks_do_not_share_files() {
+        let wav = wav(SoundEffect::Fill, 0.5);
+        let first = write_wav(SoundEffect::Fill, &wav).unwrap();
+        let second = write_wav(SoundEffect::Fill, &wav).unwrap();
+
+        assert_ne!(first, second);
+        for path in [first, second] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+}
@@ -1,6 +1,14 @@
-use std::num::ParseIntError;
+use std::{
+    cell::Cell,
+    num::ParseIntError,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        RwLock,
+    },
+};
 
 use iced::Color;
+use serde::{Deserialize, Serialize};
 
 macro_rules! str {
     ($a: expr) => {
@@ -14,3 +22,292 @@ pub fn h2c(h: &str) -> Result<Color, ParseIntError> {
     let b = str![&h[4..6]];
     Ok(Color { r, g, b, a: 1.0 })
 }
+
+/// What color is used for, resolved by active palette
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Role {
+    Background,
+    /// Panes and bars drawn over background
+    Surface,
+    Foreground,
+    /// Less important text like amounts
+    Secondary,
+    /// Labels and hints
+    Muted,
+    /// Buys, gains and highlights
+    Accent,
+    /// Sells, losses and errors
+    Danger,
+    Warning,
+}
+
+impl Role {
+    const COUNT: usize = 8;
+}
+
+/// Colors of every role as hex strings like `32D993`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Palette {
+    pub(crate) background: String,
+    pub(crate) surface: String,
+    pub(crate) foreground: String,
+    pub(crate) secondary: String,
+    pub(crate) muted: String,
+    pub(crate) accent: String,
+    pub(crate) danger: String,
+    pub(crate) warning: String,
+}
+
+impl Palette {
+    pub(crate) fn dark() -> Self {
+        Self {
+            background: "000000".to_owned(),
+            surface: "121212".to_owned(),
+            foreground: "EEEEEE".to_owned(),
+            secondary: "B7BDB7".to_owned(),
+            muted: "808080".to_owned(),
+            accent: "32D993".to_owned(),
+            danger: "FF707E".to_owned(),
+            warning: "F5C451".to_owned(),
+        }
+    }
+
+    pub(crate) fn light() -> Self {
+        Self {
+            background: "E4E6EB".to_owned(),
+            surface: "FAFAFA".to_owned(),
+            foreground: "1B1B1B".to_owned(),
+            secondary: "4A4F4A".to_owned(),
+            muted: "7D7D7D".to_owned(),
+            accent: "13915E".to_owned(),
+            danger: "D1293D".to_owned(),
+            warning: "B7861B".to_owned(),
+        }
+    }
+
+    /// Reads palette from TOML file with one `role = "hex"` line per role
+    ///
+    /// Only flat string keys are supported, roles missing from file are taken from dark palette
+    pub(crate) fn from_toml(contents: &str) -> Result<Self, String> {
+        let mut palette = Self::dark();
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected key = value", i + 1))?;
+            let value = value
+                .trim()
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .ok_or_else(|| format!("line {}: value should be a quoted string", i + 1))?;
+
+            if value.len() != 6 || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("line {}: {value} is not a hex color", i + 1));
+            }
+
+            let field = match key.trim() {
+                "background" => &mut palette.background,
+                "surface" => &mut palette.surface,
+                "foreground" => &mut palette.foreground,
+                "secondary" => &mut palette.secondary,
+                "muted" => &mut palette.muted,
+                "accent" => &mut palette.accent,
+                "danger" => &mut palette.danger,
+                "warning" => &mut palette.warning,
+                key => return Err(format!("line {}: unknown role {key}", i + 1)),
+            };
+            *field = value.to_uppercase();
+        }
+
+        Ok(palette)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Theme {
+    #[default]
+    Dark,
+    Light,
+    Custom(Palette),
+}
+
+impl Theme {
+    /// Custom palette is read from this file in config directory
+    pub(crate) const PALETTE_FILE_NAME: &'static str = "palette.toml";
+
+    /// Dark, light and custom palette if there is one, in that order
+    pub(crate) fn cycled(&self, custom: Option<&Palette>) -> Self {
+        match (self, custom) {
+            (Theme::Dark, _) => Theme::Light,
+            (Theme::Light, Some(palette)) => Theme::Custom(palette.clone()),
+            (Theme::Light, None) | (Theme::Custom(_), _) => Theme::Dark,
+        }
+    }
+
+    pub(crate) fn palette(&self) -> Palette {
+        match self {
+            Theme::Dark => Palette::dark(),
+            Theme::Light => Palette::light(),
+            Theme::Custom(palette) => palette.clone(),
+        }
+    }
+}
+
+/// Resolves roles to colors of current theme
+pub(crate) struct ThemeProvider {
+    colors: [Color; Role::COUNT],
+    iced: iced::Theme,
+}
+
+static ACTIVE: RwLock<Option<ThemeProvider>> = RwLock::new(None);
+/// Bumped every time theme is set, invalidates colors cached by threads
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// Colors of active theme and generation they were copied at, saves taking lock per color
+    static COLORS: Cell<Option<(u64, [Color; Role::COUNT])>> = const { Cell::new(None) };
+}
+
+impl ThemeProvider {
+    fn new(theme: &Theme) -> Self {
+        let palette = theme.palette();
+        // invalid colors of custom palette are rejected on load
+        let c = |hex: &str| h2c(hex).unwrap_or(Color::WHITE);
+
+        let mut colors = [Color::WHITE; Role::COUNT];
+        for (role, hex) in [
+            (Role::Background, &palette.background),
+            (Role::Surface, &palette.surface),
+            (Role::Foreground, &palette.foreground),
+            (Role::Secondary, &palette.secondary),
+            (Role::Muted, &palette.muted),
+            (Role::Accent, &palette.accent),
+            (Role::Danger, &palette.danger),
+            (Role::Warning, &palette.warning),
+        ] {
+            colors[role as usize] = c(hex);
+        }
+
+        let iced = match theme {
+            Theme::Dark => iced::Theme::Dark,
+            Theme::Light => iced::Theme::Light,
+            Theme::Custom(_) => iced::Theme::custom(
+                "Custom".to_owned(),
+                iced::theme::Palette {
+                    background: colors[Role::Background as usize],
+                    text: colors[Role::Foreground as usize],
+                    primary: colors[Role::Accent as usize],
+                    success: colors[Role::Accent as usize],
+                    danger: colors[Role::Danger as usize],
+                },
+            ),
+        };
+
+        Self { colors, iced }
+    }
+
+    /// Makes theme used by `color` and `iced_theme`
+    pub(crate) fn set(theme: &Theme) {
+        *ACTIVE.write().expect("theme lock is not poisoned") = Some(Self::new(theme));
+        GENERATION.fetch_add(1, Ordering::Release);
+    }
+
+    fn with<T>(f: impl FnOnce(&ThemeProvider) -> T) -> T {
+        let active = ACTIVE.read().expect("theme lock is not poisoned");
+        match &*active {
+            Some(provider) => f(provider),
+            None => f(&Self::new(&Theme::Dark)),
+        }
+    }
+
+    /// Color of role in active theme, dark one until theme is set
+    pub(crate) fn color(role: Role) -> Color {
+        let generation = GENERATION.load(Ordering::Acquire);
+
+        COLORS.with(|cached| {
+            let colors = match cached.get() {
+                Some((cached_generation, colors)) if cached_generation == generation => colors,
+                _ => {
+                    let colors = Self::with(|provider| provider.colors);
+                    cached.set(Some((generation, colors)));
+                    colors
+                }
+            };
+            colors[role as usize]
+        })
+    }
+
+    /// Base iced theme matching active palette
+    pub(crate) fn iced_theme() -> iced::Theme {
+        Self::with(|provider| provider.iced.clone())
+    }
+}
+
+/// Shorthand for `ThemeProvider::color`
+pub(crate) fn color(role: Role) -> Color {
+    ThemeProvider::color(role)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_from_toml() {
+        let palette = Palette::from_toml(
+            r#"
+            # only accent is changed
+            accent = "aabbcc"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            palette,
+            Palette {
+                accent: "AABBCC".to_owned(),
+                ..Palette::dark()
+            }
+        );
+    }
+
+    #[test]
+    fn palette_from_toml_errors() {
+        assert!(Palette::from_toml("accent = aabbcc").is_err());
+        assert!(Palette::from_toml("accent = \"zzzzzz\"").is_err());
+        assert!(Palette::from_toml("unknown = \"aabbcc\"").is_err());
+        assert!(Palette::from_toml("accent").is_err());
+    }
+
+    #[test]
+    fn cycle_includes_custom_palette() {
+        let custom = Palette {
+            accent: "AABBCC".to_owned(),
+            ..Palette::dark()
+        };
+
+        assert_eq!(Theme::Light.cycled(None), Theme::Dark);
+        assert_eq!(
+            Theme::Light.cycled(Some(&custom)),
+            Theme::Custom(custom.clone())
+        );
+        assert_eq!(
+            Theme::Custom(custom.clone()).cycled(Some(&custom)),
+            Theme::Dark
+        );
+    }
+
+    #[test]
+    fn cached_colors_follow_theme() {
+        ThemeProvider::set(&Theme::Light);
+        assert_eq!(color(Role::Accent), h2c(&Palette::light().accent).unwrap());
+
+        ThemeProvider::set(&Theme::Dark);
+        assert_eq!(color(Role::Accent), h2c(&Palette::dark().accent).unwrap());
+    }
+}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use iced::{
+    widget::{container, mouse_area, text, tooltip, Space},
+    Element,
+};
+
+/// "Copied!" stays over copied value this long
+pub(crate) const COPIED_FLASH: Duration = Duration::from_secs(1);
+
+/// Copies `value` on right click, shows "Copied!" over content while `copied` is the same value
+///
+/// Tooltip is always present so that state of content, like focus of input, survives the flash
+pub(crate) fn copyable<'a, Message: Clone + 'a>(
+    content: impl Into<Element<'a, Message>>,
+    value: String,
+    copied: Option<&str>,
+    on_copy: impl Fn(String) -> Message,
+) -> Element<'a, Message> {
+    let flash: Element<_> = if copied == Some(value.as_str()) {
+        container(text("Copied!").size(12))
+            .padding(4)
+            .style(iced::theme::Container::Box)
+            .into()
+    } else {
+        Space::new(0, 0).into()
+    };
+
+    tooltip(
+        mouse_area(content).on_right_press(on_copy(value)),
+        flash,
+        tooltip::Position::Top,
+    )
+    .into()
+}
@@ -0,0 +1,271 @@
+//! Draws element on top of another element without affecting its layout
+
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::widget::{self, tree::Tree, Widget};
+use iced::advanced::{overlay, renderer, Clipboard, Shell};
+use iced::{event, mouse, Element, Event, Length, Point, Rectangle, Renderer, Size, Theme, Vector};
+
+/// Distance between floating element and edges of base element
+const PADDING: f32 = 16.0;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Anchor {
+    TopLeft,
+    BottomRight,
+    /// Stretched over whole base element
+    Cover,
+}
+
+pub(crate) struct Floating<'a, Message> {
+    base: Element<'a, Message>,
+    floating: Option<Element<'a, Message>>,
+    anchor: Anchor,
+}
+
+impl<'a, Message> Floating<'a, Message> {
+    /// Shows `floating` over `base` if it is present
+    pub(crate) fn new(
+        base: impl Into<Element<'a, Message>>,
+        floating: Option<Element<'a, Message>>,
+        anchor: Anchor,
+    ) -> Self {
+        Self {
+            base: base.into(),
+            floating,
+            anchor,
+        }
+    }
+}
+
+impl<'a, Message> Widget<Message, Theme, Renderer> for Floating<'a, Message> {
+    fn size(&self) -> Size<Length> {
+        self.base.as_widget().size()
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.base
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.base.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        std::iter::once(&self.base)
+            .chain(&self.floating)
+            .map(Tree::new)
+            .collect()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        let children: Vec<_> = std::iter::once(&self.base).chain(&self.floating).collect();
+        tree.diff_children(&children);
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn widget::Operation<Message>,
+    ) {
+        self.base
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        self.base.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.base.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        let mut children = tree.children.iter_mut();
+
+        let base = self.base.as_widget_mut().overlay(
+            children.next().expect("base tree exists"),
+            layout,
+            renderer,
+            translation,
+        );
+
+        let floating = self.floating.as_mut().map(|floating| {
+            overlay::Element::new(Box::new(FloatingOverlay {
+                content: floating,
+                tree: children.next().expect("floating tree exists"),
+                bounds: layout.bounds() + translation,
+                anchor: self.anchor,
+            }))
+        });
+
+        match (base, floating) {
+            (Some(base), Some(floating)) => {
+                Some(overlay::Group::with_children(vec![base, floating]).overlay())
+            }
+            (base, floating) => base.or(floating),
+        }
+    }
+}
+
+struct FloatingOverlay<'a, 'b, Message> {
+    content: &'b mut Element<'a, Message>,
+    tree: &'b mut Tree,
+    bounds: Rectangle,
+    anchor: Anchor,
+}
+
+impl<Message> overlay::Overlay<Message, Theme, Renderer> for FloatingOverlay<'_, '_, Message> {
+    fn layout(&mut self, renderer: &Renderer, _bounds: Size) -> layout::Node {
+        let limits = layout::Limits::new(Size::ZERO, self.bounds.size());
+        let node = self
+            .content
+            .as_widget()
+            .layout(self.tree, renderer, &limits);
+        let size = node.size();
+
+        let position = match self.anchor {
+            Anchor::TopLeft => Point::new(self.bounds.x + PADDING, self.bounds.y + PADDING),
+            Anchor::BottomRight => Point::new(
+                self.bounds.x + self.bounds.width - size.width - PADDING,
+                self.bounds.y + self.bounds.height - size.height - PADDING,
+            ),
+            Anchor::Cover => self.bounds.position(),
+        };
+
+        node.move_to(position)
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+    ) {
+        self.content.as_widget().draw(
+            self.tree,
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            &layout.bounds(),
+        );
+    }
+
+    fn operate(
+        &mut self,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn widget::Operation<Message>,
+    ) {
+        self.content
+            .as_widget()
+            .operate(self.tree, layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        self.content.as_widget_mut().on_event(
+            self.tree,
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            &layout.bounds(),
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content
+            .as_widget()
+            .mouse_interaction(self.tree, layout, cursor, viewport, renderer)
+    }
+}
+
+impl<'a, Message: 'a> From<Floating<'a, Message>> for Element<'a, Message> {
+    fn from(value: Floating<'a, Message>) -> Self {
+        Element::new(value)
+    }
+}
@@ -1,5 +1,7 @@
 use iced::{widget::text_input, Background, Color};
 
+use crate::theme::{color, Role};
+
 pub struct Inp;
 
 impl text_input::StyleSheet for Inp {
@@ -72,3 +74,50 @@ impl text_input::StyleSheet for Inp {
         }
     }
 }
+
+/// `Inp` with red border for rejected input
+pub struct InvalidInp;
+
+impl text_input::StyleSheet for InvalidInp {
+    type Style = iced::Theme;
+
+    fn active(&self, style: &Self::Style) -> text_input::Appearance {
+        text_input::Appearance {
+            border: iced::Border {
+                color: color(Role::Danger),
+                ..Inp.active(style).border
+            },
+            ..Inp.active(style)
+        }
+    }
+
+    fn focused(&self, style: &Self::Style) -> text_input::Appearance {
+        text_input::Appearance {
+            border: iced::Border {
+                color: color(Role::Danger),
+                ..Inp.focused(style).border
+            },
+            ..Inp.focused(style)
+        }
+    }
+
+    fn placeholder_color(&self, style: &Self::Style) -> Color {
+        Inp.placeholder_color(style)
+    }
+
+    fn value_color(&self, style: &Self::Style) -> Color {
+        Inp.value_color(style)
+    }
+
+    fn disabled_color(&self, style: &Self::Style) -> Color {
+        Inp.disabled_color(style)
+    }
+
+    fn selection_color(&self, style: &Self::Style) -> Color {
+        Inp.selection_color(style)
+    }
+
+    fn disabled(&self, style: &Self::Style) -> text_input::Appearance {
+        Inp.disabled(style)
+    }
+}
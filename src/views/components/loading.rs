@@ -13,6 +13,7 @@ use iced::{Background, Color, Element, Event, Length, Radians, Rectangle, Render
 use easing::Easing;
 
 use std::f32::consts::PI;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::OnceLock;
 use std::time::Duration;
 use std::time::Instant;
@@ -23,6 +24,14 @@ const BASE_ROTATION_SPEED: u32 = u32::MAX / 80;
 
 static STANDARD_EASING: OnceLock<Easing> = OnceLock::new();
 
+/// Milliseconds between animation frames, shared by all loaders
+static FRAME_INTERVAL: AtomicU64 = AtomicU64::new(1000 / 30);
+
+/// Limit redraws caused by animation instead of redrawing every monitor frame
+pub(crate) fn set_target_fps(fps: u32) {
+    FRAME_INTERVAL.store(1000 / u64::from(fps.max(1)), Ordering::Relaxed);
+}
+
 pub(crate) struct Loader<Theme>
 where
     Theme: StyleSheet,
@@ -47,6 +56,34 @@ macro_rules! loader {
 
 pub(crate) use loader;
 
+/// Shown in place of loader once data arrived, but there is nothing in it
+pub(crate) fn placeholder<'a, Message: 'a>(hint: &'a str) -> Element<'a, Message> {
+    iced::widget::container(
+        iced::widget::text(hint)
+            .size(14)
+            .style(crate::theme::color(crate::theme::Role::Muted)),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .center_x()
+    .center_y()
+    .into()
+}
+
+/// Shown in place of loader when request failed and there is nothing loaded earlier
+pub(crate) fn error_placeholder<'a, Message: 'a>(error: &str) -> Element<'a, Message> {
+    iced::widget::container(
+        iced::widget::text(format!("Failed to load: {error}"))
+            .size(14)
+            .style(crate::theme::color(crate::theme::Role::Danger)),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .center_x()
+    .center_y()
+    .into()
+}
+
 impl<Theme> Loader<Theme>
 where
     Theme: StyleSheet,
@@ -195,9 +232,9 @@ struct State {
     cache: canvas::Cache,
 }
 
-impl<'a, Message, Theme> Widget<Message, Theme, Renderer> for Loader<Theme>
+impl<Message, Theme> Widget<Message, Theme, Renderer> for Loader<Theme>
 where
-    Message: 'a + Clone,
+    Message: Clone,
     Theme: StyleSheet,
 {
     fn tag(&self) -> tree::Tag {
@@ -244,7 +281,8 @@ where
                     .timed_transition(self.cycle_duration, self.rotation_duration, now);
 
             state.cache.clear();
-            shell.request_redraw(RedrawRequest::NextFrame);
+            let interval = Duration::from_millis(FRAME_INTERVAL.load(Ordering::Relaxed));
+            shell.request_redraw(RedrawRequest::At(now + interval));
         }
 
         event::Status::Ignored
@@ -438,7 +476,7 @@ mod easing {
 
         fn point(p: impl Into<Point>) -> lyon_algorithms::geom::Point<f32> {
             let p: Point = p.into();
-            lyon_algorithms::geom::point(p.x.min(1.0).max(0.0), p.y.min(1.0).max(0.0))
+            lyon_algorithms::geom::point(p.x.clamp(0.0, 1.0), p.y.clamp(0.0, 1.0))
         }
     }
 
@@ -1,6 +1,10 @@
 pub mod better_btn;
+pub mod copyable;
+pub mod floating;
 pub mod input;
 // pub mod list;
 pub mod loading;
 pub mod scrollbar;
+pub mod sparkline;
+pub mod touch;
 pub mod unstyled_btn;
@@ -0,0 +1,80 @@
+use iced::{
+    mouse,
+    widget::canvas::{Canvas, Frame, Geometry, Path, Program, Stroke},
+    Element, Length, Point, Rectangle, Renderer, Theme,
+};
+
+use crate::theme::{color, Role};
+
+/// Tiny line chart without axes, green if last value is not below first one
+pub struct Sparkline {
+    values: Vec<f64>,
+}
+
+impl<Message> Program<Message> for Sparkline {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+
+        let (Some(first), Some(last)) = (self.values.first(), self.values.last()) else {
+            return vec![];
+        };
+        if self.values.len() < 2 {
+            return vec![];
+        }
+
+        let (min, max) = self
+            .values
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(min, max), v| {
+                (min.min(*v), max.max(*v))
+            });
+        // flat line goes through the middle
+        let span = if max > min { max - min } else { 1.0 };
+        let offset = if max > min { 0.0 } else { 0.5 };
+
+        let step = bounds.width / (self.values.len() - 1) as f32;
+        let point = |i: usize, v: f64| {
+            let y = 1.0 - ((v - min) / span + offset) as f32;
+            Point::new(i as f32 * step, y * (bounds.height - 2.0) + 1.0)
+        };
+
+        let line = Path::new(|p| {
+            p.move_to(point(0, *first));
+            for (i, v) in self.values.iter().enumerate().skip(1) {
+                p.line_to(point(i, *v));
+            }
+        });
+
+        let line_color = if last >= first {
+            color(Role::Accent)
+        } else {
+            color(Role::Danger)
+        };
+        frame.stroke(
+            &line,
+            Stroke::default().with_color(line_color).with_width(1.0),
+        );
+
+        vec![frame.into_geometry()]
+    }
+}
+
+pub fn sparkline<'a, Message: 'a>(
+    values: Vec<f64>,
+    width: f32,
+    height: f32,
+) -> Element<'a, Message> {
+    Canvas::new(Sparkline { values })
+        .width(Length::Fixed(width))
+        .height(Length::Fixed(height))
+        .into()
+}
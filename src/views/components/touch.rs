@@ -0,0 +1,272 @@
+//! Two finger gestures, single touches are left to wrapped widgets
+
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::widget::{self, tree, tree::Tree, Widget};
+use iced::advanced::{overlay, renderer, Clipboard, Shell};
+use iced::{
+    event, mouse, touch, Element, Event, Length, Point, Rectangle, Renderer, Size, Theme, Vector,
+};
+
+/// Position of both fingers of a gesture after it moved
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Pinch {
+    pub(crate) midpoint: Point,
+    /// Change of distance between fingers since previous event
+    pub(crate) scale: f32,
+}
+
+/// Fingers currently touching, only first two are followed
+#[derive(Debug, Default)]
+pub(crate) struct Fingers(Vec<(touch::Finger, Point)>);
+
+impl Fingers {
+    pub(crate) fn is_pinching(&self) -> bool {
+        self.0.len() == 2
+    }
+
+    /// Tracks finger of `event`, returns pinch if two fingers are down after it
+    pub(crate) fn update(&mut self, event: touch::Event) -> Option<Pinch> {
+        let before = self.distance();
+
+        match event {
+            touch::Event::FingerPressed { id, position } => {
+                if self.0.len() < 2 {
+                    self.0.push((id, position));
+                }
+            }
+            touch::Event::FingerMoved { id, position } => {
+                if let Some((_, tracked)) = self.0.iter_mut().find(|(finger, _)| *finger == id) {
+                    *tracked = position;
+                }
+            }
+            touch::Event::FingerLifted { id, .. } | touch::Event::FingerLost { id, .. } => {
+                self.0.retain(|(finger, _)| *finger != id);
+                return None;
+            }
+        }
+
+        let [(_, a), (_, b)] = self.0[..] else {
+            return None;
+        };
+
+        Some(Pinch {
+            midpoint: Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0),
+            // second finger was just pressed
+            scale: match (before, self.distance()) {
+                (Some(before), Some(after)) if before > 0.0 => after / before,
+                _ => 1.0,
+            },
+        })
+    }
+
+    fn distance(&self) -> Option<f32> {
+        let [(_, a), (_, b)] = self.0[..] else {
+            return None;
+        };
+
+        Some(a.distance(b))
+    }
+}
+
+/// Reports where two fingers are dragged over content
+///
+/// Touches are still passed to content, so pinching widgets inside keep working
+pub(crate) struct TwoFingerDrag<'a, Message> {
+    content: Element<'a, Message>,
+    /// Midpoint relative to widget while dragging, None once a finger is lifted
+    on_drag: Box<dyn Fn(Option<Point>) -> Message + 'a>,
+}
+
+impl<'a, Message> TwoFingerDrag<'a, Message> {
+    pub(crate) fn new(
+        content: impl Into<Element<'a, Message>>,
+        on_drag: impl Fn(Option<Point>) -> Message + 'a,
+    ) -> Self {
+        Self {
+            content: content.into(),
+            on_drag: Box::new(on_drag),
+        }
+    }
+}
+
+impl<'a, Message> Widget<Message, Theme, Renderer> for TwoFingerDrag<'a, Message> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<Fingers>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(Fingers::default())
+    }
+
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.content
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn widget::Operation<Message>,
+    ) {
+        self.content
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        if let Event::Touch(touch) = event {
+            let bounds = layout.bounds();
+            let fingers = tree.state.downcast_mut::<Fingers>();
+            let was_pinching = fingers.is_pinching();
+
+            // fingers are only tracked from inside of widget, but followed when they leave it
+            let pressed_outside = matches!(
+                touch,
+                touch::Event::FingerPressed { position, .. } if !bounds.contains(position)
+            );
+            if !pressed_outside {
+                match fingers.update(touch) {
+                    Some(pinch) => {
+                        let position = pinch.midpoint - Vector::new(bounds.x, bounds.y);
+                        shell.publish((self.on_drag)(Some(position)));
+                    }
+                    None if was_pinching => shell.publish((self.on_drag)(None)),
+                    None => {}
+                }
+            }
+        }
+
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        self.content
+            .as_widget_mut()
+            .overlay(&mut tree.children[0], layout, renderer, translation)
+    }
+}
+
+impl<'a, Message: 'a> From<TwoFingerDrag<'a, Message>> for Element<'a, Message> {
+    fn from(value: TwoFingerDrag<'a, Message>) -> Self {
+        Element::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_fingers_pinch() {
+        let mut fingers = Fingers::default();
+        let press = |id, x| touch::Event::FingerPressed {
+            id: touch::Finger(id),
+            position: Point::new(x, 0.0),
+        };
+        let moved = |id, x| touch::Event::FingerMoved {
+            id: touch::Finger(id),
+            position: Point::new(x, 0.0),
+        };
+
+        assert_eq!(fingers.update(press(1, 0.0)), None);
+        let pinch = fingers.update(press(2, 10.0)).unwrap();
+        assert_eq!((pinch.midpoint, pinch.scale), (Point::new(5.0, 0.0), 1.0));
+
+        // third finger is ignored
+        assert!(fingers.update(press(3, 100.0)).is_some());
+        let pinch = fingers.update(moved(2, 20.0)).unwrap();
+        assert_eq!((pinch.midpoint, pinch.scale), (Point::new(10.0, 0.0), 2.0));
+
+        assert_eq!(
+            fingers.update(touch::Event::FingerLifted {
+                id: touch::Finger(1),
+                position: Point::ORIGIN,
+            }),
+            None
+        );
+        assert!(!fingers.is_pinching());
+    }
+}
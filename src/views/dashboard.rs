@@ -1,43 +1,113 @@
+#![deny(clippy::to_string_trait_impl)]
+
+use std::{
+    cell::Cell,
+    collections::BTreeMap,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use binance::rest_model::OrderSide;
 use iced::{
-    theme,
+    event, keyboard, theme,
     widget::{
-        button,
+        button, checkbox, column, container, mouse_area,
         pane_grid::{self, Configuration},
-        responsive, row, text, PaneGrid,
+        responsive, row, text, text_input, tooltip, Column, PaneGrid,
     },
-    Command, Element, Font, Length,
+    window, Color, Command, Element, Font, Length, Point, Rectangle, Size, Subscription,
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
-    api::Client, config::Config, data::AppData, message::Message, theme::h2c, ws::Websockets,
+    api::TradingClient,
+    config::{Config, LayoutConfig, SaveError},
+    data::{AppData, Vwap},
+    message::{MaybeError, Message},
+    news,
+    notification::{Notification, Severity},
+    pair::PairBuilder,
+    screenshot,
+    shortcuts::{KeyboardShortcuts, ShortcutAction},
+    sound::{self, SoundEffect},
+    theme::{color, Role},
+    ws::{futures::Liquidation, trades::TradesEvent, Websockets},
 };
 
+use super::components::copyable::COPIED_FLASH;
+use super::components::floating::{Anchor, Floating};
+use super::components::touch::TwoFingerDrag;
+#[cfg(feature = "wgpu-panes")]
+use super::panes::market_depth_3d::{self, MarketDepth3DMessage, MarketDepth3DPane};
+#[cfg(feature = "options")]
+use super::panes::options::{OptionsMessage, OptionsPane};
+#[cfg(feature = "perf-pane")]
+use super::panes::performance::PerformancePane;
 use super::panes::{
-    balances::BalancesPane,
-    book::BookPane,
+    algo_trading::{AlgoTradingMessage, AlgoTradingPane},
+    arbitrage::{ArbitrageMessage, ArbitragePane},
+    balances::{BalancesMessage, BalancesPane},
+    book::{BookGrouping, BookMessage, BookPane},
     calculator::{CalculatorPane, CalculatorPaneMessage},
-    chart::ChartPane,
+    chart::{self, ChartMessage, ChartPane, Timeframe},
+    correlation::{CorrelationMessage, CorrelationPane},
+    depth_chart::{DepthChartMessage, DepthChartPane},
+    funding::{FundingMessage, FundingPane},
+    heatmap::{HeatmapMessage, HeatmapPane},
+    history::{HistoryMessage, HistoryPane},
+    liquidations::{LiquidationsMessage, LiquidationsPane},
     market::{Market, MarketPanelMessage},
-    orders::OrdersPane,
+    news::{NewsMessage, NewsPane},
+    order_flow::{OrderFlowMessage, OrderFlowPane},
+    orders::{OrdersMessage, OrdersPane},
+    portfolio::{PortfolioMessage, PortfolioPane},
+    sentiment::{SentimentMessage, SentimentPane},
+    spread_monitor::{SpreadMonitorMessage, SpreadMonitorPane},
     style,
-    trades::TradesPane,
-    watchlist::{WatchlistMessage, WatchlistPane},
+    trade_sizer::{TradeSizerMessage, TradeSizerPane},
+    trades::{TradesPane, TradesPaneMessage},
+    watchlist::{self, WatchlistMessage, WatchlistPane},
+    KeyEvent,
 };
 
-#[derive(PartialEq)]
+/// Integer encoding is fixed per variant, optional panes keep theirs when others are disabled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) enum PaneType {
-    Prices,
-    Book,
-    Trades,
-    Market,
-    Balances,
-    Orders,
-    Calculator,
-    Chart,
+    Prices = 0,
+    Book = 1,
+    Trades = 2,
+    Market = 3,
+    Balances = 4,
+    Orders = 5,
+    Calculator = 6,
+    Chart = 7,
+    DepthChart = 8,
+    Portfolio = 9,
+    Heatmap = 10,
+    News = 11,
+    FundingRate = 12,
+    AlgoTrading = 13,
+    Liquidations = 14,
+    TradeSizer = 15,
+    History = 16,
+    OrderFlow = 17,
+    Arbitrage = 18,
+    Correlation = 19,
+    SpreadMonitor = 20,
+    Sentiment = 21,
+    #[cfg(feature = "options")]
+    Options = 22,
+    #[cfg(feature = "perf-pane")]
+    Performance = 23,
+    #[cfg(feature = "wgpu-panes")]
+    MarketDepth3D = 24,
 }
 
-impl ToString for PaneType {
-    fn to_string(&self) -> String {
+impl Display for PaneType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             PaneType::Prices => "Watchlist",
             PaneType::Book => "Book",
@@ -47,14 +117,150 @@ impl ToString for PaneType {
             PaneType::Orders => "Orders",
             PaneType::Calculator => "Calculator",
             PaneType::Chart => "Chart",
+            PaneType::DepthChart => "Depth",
+            PaneType::Portfolio => "Portfolio",
+            PaneType::Heatmap => "Correlation",
+            PaneType::News => "News",
+            PaneType::FundingRate => "Funding",
+            PaneType::AlgoTrading => "Algo",
+            PaneType::Liquidations => "Liquidations",
+            PaneType::TradeSizer => "Sizing",
+            PaneType::History => "History",
+            PaneType::OrderFlow => "Order flow",
+            PaneType::Arbitrage => "Arbitrage",
+            PaneType::Correlation => "Correlation 24h",
+            PaneType::SpreadMonitor => "Spreads",
+            PaneType::Sentiment => "Sentiment",
+            #[cfg(feature = "options")]
+            PaneType::Options => "Options",
+            #[cfg(feature = "perf-pane")]
+            PaneType::Performance => "Performance",
+            #[cfg(feature = "wgpu-panes")]
+            PaneType::MarketDepth3D => "Depth 3D",
+        }
+        .fmt(f)
+    }
+}
+
+impl PaneType {
+    /// All enabled variants in order of their integer encoding
+    const ALL: &'static [PaneType] = &[
+        PaneType::Prices,
+        PaneType::Book,
+        PaneType::Trades,
+        PaneType::Market,
+        PaneType::Balances,
+        PaneType::Orders,
+        PaneType::Calculator,
+        PaneType::Chart,
+        PaneType::DepthChart,
+        PaneType::Portfolio,
+        PaneType::Heatmap,
+        PaneType::News,
+        PaneType::FundingRate,
+        PaneType::AlgoTrading,
+        PaneType::Liquidations,
+        PaneType::TradeSizer,
+        PaneType::History,
+        PaneType::OrderFlow,
+        PaneType::Arbitrage,
+        PaneType::Correlation,
+        PaneType::SpreadMonitor,
+        PaneType::Sentiment,
+        #[cfg(feature = "options")]
+        PaneType::Options,
+        #[cfg(feature = "perf-pane")]
+        PaneType::Performance,
+        #[cfg(feature = "wgpu-panes")]
+        PaneType::MarketDepth3D,
+    ];
+
+    /// Tint of title bar from active palette, panes showing similar things share it
+    pub(crate) fn accent_color(&self) -> Color {
+        // palette has few roles, halfway colors tell more groups apart
+        let mix = |a: Role, b: Role| {
+            let (a, b) = (color(a), color(b));
+            Color::from_rgb((a.r + b.r) / 2.0, (a.g + b.g) / 2.0, (a.b + b.b) / 2.0)
+        };
+
+        match self {
+            // charts
+            PaneType::Chart | PaneType::DepthChart => color(Role::Accent),
+            #[cfg(feature = "wgpu-panes")]
+            PaneType::MarketDepth3D => color(Role::Accent),
+            PaneType::Book | PaneType::SpreadMonitor => mix(Role::Accent, Role::Foreground),
+            // trade stream
+            PaneType::Trades
+            | PaneType::OrderFlow
+            | PaneType::Liquidations
+            | PaneType::Sentiment => color(Role::Danger),
+            // placing orders
+            PaneType::Market
+            | PaneType::AlgoTrading
+            | PaneType::Calculator
+            | PaneType::TradeSizer => mix(Role::Danger, Role::Warning),
+            // account
+            PaneType::Balances | PaneType::Orders | PaneType::History | PaneType::Portfolio => {
+                mix(Role::Accent, Role::Warning)
+            }
+            // across symbols
+            PaneType::Prices
+            | PaneType::Heatmap
+            | PaneType::Correlation
+            | PaneType::Arbitrage
+            | PaneType::FundingRate => color(Role::Warning),
+            PaneType::News => color(Role::Muted),
+            #[cfg(feature = "options")]
+            PaneType::Options => mix(Role::Danger, Role::Foreground),
+            #[cfg(feature = "perf-pane")]
+            PaneType::Performance => color(Role::Secondary),
         }
-        .to_string()
     }
 }
 
+impl From<PaneType> for usize {
+    fn from(value: PaneType) -> Self {
+        value as usize
+    }
+}
+
+/// Integer that does not encode any pane type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct InvalidPaneIndex(pub(crate) usize);
+
+impl Display for InvalidPaneIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid pane index {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidPaneIndex {}
+
+impl TryFrom<usize> for PaneType {
+    type Error = InvalidPaneIndex;
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Self::ALL
+            .iter()
+            .find(|ty| usize::from((*ty).clone()) == value)
+            .cloned()
+            .ok_or(InvalidPaneIndex(value))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) struct Pane {
     id: PaneType,
     is_pinned: bool,
+    /// Title set by user instead of pane type
+    #[serde(default)]
+    custom_label: Option<String>,
+    /// Chart of this symbol instead of market pair
+    #[serde(default)]
+    symbol: Option<String>,
+    /// Price bucket size of book
+    #[serde(default)]
+    book_grouping: BookGrouping,
 }
 
 impl Pane {
@@ -62,8 +268,176 @@ impl Pane {
         Self {
             id: ty,
             is_pinned: false,
+            custom_label: None,
+            symbol: None,
+            book_grouping: BookGrouping::default(),
         }
     }
+
+    fn title(&self) -> String {
+        self.custom_label
+            .clone()
+            .unwrap_or_else(|| match &self.symbol {
+                Some(symbol) => format!("{} {symbol}", self.id),
+                None => self.id.to_string(),
+            })
+    }
+}
+
+/// How often news feed and 24 hour tickers are fetched
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often futures funding rates are fetched
+const FUNDING_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Layout changes that can be undone
+const UNDO_LIMIT: usize = 20;
+
+/// Frame time of pane animations
+const ANIMATION_INTERVAL: Duration = Duration::from_millis(33);
+
+/// Symbols shown in search results, open panes are always shown
+const SEARCH_SYMBOLS: usize = 8;
+
+/// Open pane to focus or symbol to trade
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SearchResult {
+    Pane(pane_grid::Pane),
+    Symbol(String),
+}
+
+/// Floating search over pane titles and symbols
+#[derive(Default)]
+pub(crate) struct SearchOverlay {
+    visible: bool,
+    query: String,
+    results: Vec<SearchResult>,
+}
+
+impl SearchOverlay {
+    /// Panes with matching title or type, then symbols starting with query, then other matching symbols
+    fn search<'a>(
+        &mut self,
+        panes: impl Iterator<Item = (pane_grid::Pane, &'a Pane)>,
+        symbols: impl Iterator<Item = &'a String>,
+    ) {
+        self.results.clear();
+
+        let query = self.query.trim().to_uppercase();
+        if query.is_empty() {
+            return;
+        }
+
+        self.results.extend(
+            panes
+                .filter(|(_, pane)| {
+                    pane.title().to_uppercase().contains(&query)
+                        || pane.id.to_string().to_uppercase().contains(&query)
+                })
+                .map(|(id, _)| SearchResult::Pane(id)),
+        );
+
+        let mut symbols: Vec<_> = symbols.filter(|s| s.contains(&query)).collect();
+        symbols.sort_by_key(|s| (!s.starts_with(&query), s.len(), s.as_str()));
+        self.results.extend(
+            symbols
+                .into_iter()
+                .take(SEARCH_SYMBOLS)
+                .map(|s| SearchResult::Symbol(s.clone())),
+        );
+    }
+}
+
+/// Max time between clicks on title to start renaming
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Distance from split divider two finger drag can start at
+const TOUCH_SLOP: f32 = 24.0;
+
+/// Split ratio keeping both sides of split of `length` at least `min` long
+fn clamp_ratio(ratio: f32, length: f32, min: f32, spacing: f32) -> f32 {
+    let min_ratio = (min + spacing / 2.0) / length;
+    if min_ratio >= 0.5 {
+        // too small to fit both, split evenly
+        return 0.5;
+    }
+
+    ratio.clamp(min_ratio, 1.0 - min_ratio)
+}
+
+/// Split with divider under `position`, fingers are less precise than mouse
+fn split_at(
+    regions: &BTreeMap<pane_grid::Split, (pane_grid::Axis, Rectangle, f32)>,
+    position: Point,
+) -> Option<pane_grid::Split> {
+    regions
+        .iter()
+        .find(|(_, (axis, region, ratio))| {
+            let distance = match axis {
+                pane_grid::Axis::Horizontal => {
+                    (position.y - (region.y + region.height * ratio)).abs()
+                }
+                pane_grid::Axis::Vertical => (position.x - (region.x + region.width * ratio)).abs(),
+            };
+            distance <= TOUCH_SLOP && region.contains(position)
+        })
+        .map(|(split, _)| *split)
+}
+
+/// Dot showing how long ago pane data arrived, green to red
+fn latency_dot<'a>(latency: Duration) -> Element<'a, DashboardMessage> {
+    let role = if latency < Duration::from_millis(500) {
+        Role::Accent
+    } else if latency < Duration::from_secs(2) {
+        Role::Warning
+    } else {
+        Role::Danger
+    };
+
+    tooltip(
+        text("●").size(12).style(color(role)),
+        container(text(format!("Last update {} ms ago", latency.as_millis())).size(12))
+            .padding(4)
+            .style(theme::Container::Box),
+        tooltip::Position::Bottom,
+    )
+    .into()
+}
+
+/// Placeholder of panes fed by streams that demo client does not have
+fn demo_unavailable<'a>() -> Element<'a, DashboardMessage> {
+    container(
+        text("Not available in demo mode")
+            .size(12)
+            .style(color(Role::Secondary)),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .center_x()
+    .center_y()
+    .into()
+}
+
+/// Adds tooltip with name of action and its shortcut
+fn with_shortcut<'a>(
+    content: impl Into<Element<'a, DashboardMessage>>,
+    name: &str,
+    action: ShortcutAction,
+    shortcuts: &KeyboardShortcuts,
+) -> Element<'a, DashboardMessage> {
+    let label = match shortcuts.shortcut(action) {
+        Some(shortcut) => format!("{name} ({shortcut})"),
+        None => name.to_owned(),
+    };
+
+    tooltip(
+        content,
+        container(text(label).size(12))
+            .padding(4)
+            .style(theme::Container::Box),
+        tooltip::Position::Bottom,
+    )
+    .into()
 }
 
 pub fn view_controls<'a>(
@@ -71,32 +445,69 @@ pub fn view_controls<'a>(
     total_panes: usize,
     is_pinned: bool,
     is_maximized: bool,
+    latency: Option<Duration>,
+    shortcuts: &KeyboardShortcuts,
 ) -> Element<'a, DashboardMessage> {
-    let mut row = row![].spacing(5);
+    let mut row = row![]
+        .spacing(5)
+        .align_items(iced::Alignment::Center)
+        .push_maybe(latency.map(latency_dot));
 
     if total_panes > 1 {
         let toggle = {
-            let (content, message) = if is_maximized {
+            let (content, message, name) = if is_maximized {
                 (
                     text('\u{F3DE}').font(Font::with_name("bootstrap-icons")),
                     DashboardMessage::Restore,
+                    "Restore",
                 )
             } else {
                 (
                     text('\u{F3DF}').font(Font::with_name("bootstrap-icons")),
                     DashboardMessage::Maximize(pane),
+                    "Maximize",
                 )
             };
-            button(content.size(12).style(h2c("FFFFFF").unwrap()))
-                .height(14)
-                .width(14)
-                .style(theme::Button::Secondary)
-                .on_press(message)
+            with_shortcut(
+                button(content.size(12).style(color(Role::Foreground)))
+                    .height(14)
+                    .width(14)
+                    .style(theme::Button::Secondary)
+                    .on_press(message),
+                name,
+                ShortcutAction::ToggleMaximize,
+                shortcuts,
+            )
         };
 
         row = row.push(toggle);
     }
 
+    let pin = {
+        let (icon, name) = if is_pinned {
+            ('\u{F4EC}', "Unpin")
+        } else {
+            ('\u{F4EB}', "Pin")
+        };
+        tooltip(
+            button(
+                text(icon)
+                    .size(12)
+                    .font(Font::with_name("bootstrap-icons"))
+                    .style(color(Role::Foreground)),
+            )
+            .height(14)
+            .width(14)
+            .style(theme::Button::Secondary)
+            .on_press(DashboardMessage::TogglePin(pane)),
+            container(text(name).size(12))
+                .padding(4)
+                .style(theme::Container::Box),
+            tooltip::Position::Bottom,
+        )
+    };
+    row = row.push(pin);
+
     let mut close = button(
         text('\u{F62A}')
             .size(12)
@@ -110,7 +521,44 @@ pub fn view_controls<'a>(
         close = close.on_press(DashboardMessage::Close(pane));
     }
 
-    row.push(close).into()
+    row.push(with_shortcut(
+        close,
+        "Close",
+        ShortcutAction::Close,
+        shortcuts,
+    ))
+    .into()
+}
+
+/// Pane types with buttons to split focused pane with them
+/// Splits `target` if set, focused pane otherwise
+fn view_add_pane_menu<'a>(target: Option<pane_grid::Pane>) -> Element<'a, DashboardMessage> {
+    let split_button = |label, ty, axis| {
+        button(text(label).size(12))
+            .padding([2, 6])
+            .style(theme::Button::Secondary)
+            .on_press(match target {
+                Some(pane) => DashboardMessage::SplitPane(pane, axis, ty),
+                None => DashboardMessage::AddPane(ty, axis),
+            })
+    };
+
+    container(
+        Column::with_children(PaneType::ALL.iter().cloned().map(|ty| {
+            row![
+                text(ty.to_string()).size(14).width(100),
+                split_button("Right", ty.clone(), pane_grid::Axis::Vertical),
+                split_button("Below", ty, pane_grid::Axis::Horizontal),
+            ]
+            .spacing(4)
+            .align_items(iced::Alignment::Center)
+            .into()
+        }))
+        .spacing(4),
+    )
+    .padding(8)
+    .style(theme::Container::Box)
+    .into()
 }
 
 #[derive(Debug, Clone)]
@@ -118,35 +566,170 @@ pub(crate) enum DashboardMessage {
     Clicked(pane_grid::Pane),
     Dragged(pane_grid::DragEvent),
     Resized(pane_grid::ResizeEvent),
+    /// Midpoint of two fingers dragged over pane grid of size, None once lifted
+    TouchDragged(Option<Point>, Size),
+    /// Config file was edited, splits are fitted to new minimal pane size
+    ConfigReloaded,
     Maximize(pane_grid::Pane),
     Restore,
     Close(pane_grid::Pane),
+    /// Pinned panes cannot be closed
+    TogglePin(pane_grid::Pane),
+
+    /// Raw key press, mapped to action using configured shortcuts
+    KeyPressed(keyboard::Key, keyboard::Modifiers),
+    /// Key press without global shortcut, handled by focused pane
+    PaneKeyEvent(pane_grid::Pane, KeyEvent),
+
+    /// Move focus to next or previous pane
+    CycleFocus {
+        forward: bool,
+    },
+
+    /// Revert or reapply last close, resize or drag of panes
+    Undo,
+    Redo,
+
+    /// Show search over panes and symbols
+    SearchOpen,
+    SearchQuery(String),
+    SearchConfirm(SearchResult),
+    SearchClose,
+
+    /// Save window as PNG into pictures directory
+    TakeScreenshot,
+    ScreenshotTaken(window::Screenshot),
+
+    /// Show or hide list of panes that can be added
+    OpenAddPaneMenu,
+    /// Show or hide config export and import controls
+    OpenConfigFileMenu,
+    ConfigFilePathInput(String),
+    /// Export API keys too instead of blanking them
+    ExportSecretsToggled(bool),
+    /// Write config with layout to entered path
+    ExportConfig,
+    ConfigExported(Result<(), String>),
+    /// Check entered file and ask to confirm import
+    ImportConfig,
+    /// Replace config and layout with imported ones and close app, they are used after restart
+    ImportConfigConfirm,
+    ImportConfigAbort,
+    /// Split focused pane, new pane goes right of or below it
+    AddPane(PaneType, pane_grid::Axis),
+    /// Title right clicked, opens pane menu that splits it
+    OpenSplitMenu(pane_grid::Pane),
+    SplitPane(pane_grid::Pane, pane_grid::Axis, PaneType),
+
+    /// Title text clicked, two clicks start renaming
+    TitleClicked(pane_grid::Pane),
+    RenamePaneStart(pane_grid::Pane),
+    RenamePaneInput(String),
+    /// Empty name restores default title
+    RenamePaneCommit(pane_grid::Pane, String),
+
+    /// Symbol dragged from watchlist released over pane, splits it with chart of symbol
+    WatchlistDragEnd(pane_grid::Pane, String),
 
     Watchlist(WatchlistMessage),
+    /// Book of pane is summed into different price buckets
+    SetBookGrouping(pane_grid::Pane, BookGrouping),
+    /// Start daily VWAP of traded pair over
+    VwapReset,
+    /// Switch to reversed pair, or flip order buttons if it does not exist
+    FlipPair,
     Market(MarketPanelMessage),
+    Balances(BalancesMessage),
+    Orders(OrdersMessage),
     Calculator(CalculatorPaneMessage),
+    Chart(ChartMessage),
+    DepthChart(DepthChartMessage),
+    Portfolio(PortfolioMessage),
+    Heatmap(HeatmapMessage),
+    News(NewsMessage),
+    FundingRate(FundingMessage),
+    AlgoTrading(AlgoTradingMessage),
+    Liquidations(LiquidationsMessage),
+    TradeSizer(TradeSizerMessage),
+    Book(BookMessage),
+    Trades(TradesPaneMessage),
+    History(HistoryMessage),
+    OrderFlow(OrderFlowMessage),
+    Arbitrage(ArbitrageMessage),
+    Correlation(CorrelationMessage),
+    SpreadMonitor(SpreadMonitorMessage),
+    Sentiment(SentimentMessage),
+    #[cfg(feature = "options")]
+    Options(OptionsMessage),
+    #[cfg(feature = "perf-pane")]
+    ResetPerformanceStats,
+    #[cfg(feature = "wgpu-panes")]
+    MarketDepth3D(MarketDepth3DMessage),
 
-    CurrencyPairSelected(String),
-
-    // TODO: move to chart
-    TimeframeChanged(String),
+    /// Timer for everything polled over HTTP
+    Refresh,
+    /// Time to fetch news feed again
+    NewsRefresh,
+    /// Time to fetch 24 hour statistics again
+    Refresh24hTickers,
+    /// Render one more page of watchlist rows
+    LoadNextPricePage,
+    /// Time to fetch funding rates again
+    RefreshFundingRates,
+    /// Beep if sounds are enabled
+    PlaySound(SoundEffect),
+    /// Value right clicked in a pane
+    CopyToClipboard(String),
 }
 
-impl From<WatchlistMessage> for DashboardMessage {
-    fn from(value: WatchlistMessage) -> Self {
-        Self::Watchlist(value)
-    }
+macro_rules! from_pane_message {
+    ($($variant: ident($message: ty)),+ $(,)?) => {
+        $(
+            impl From<$message> for DashboardMessage {
+                fn from(value: $message) -> Self {
+                    Self::$variant(value)
+                }
+            }
+        )+
+    };
 }
 
-impl From<CalculatorPaneMessage> for DashboardMessage {
-    fn from(value: CalculatorPaneMessage) -> Self {
-        Self::Calculator(value)
+from_pane_message![
+    Watchlist(WatchlistMessage),
+    Market(MarketPanelMessage),
+    Balances(BalancesMessage),
+    Orders(OrdersMessage),
+    Calculator(CalculatorPaneMessage),
+    Chart(ChartMessage),
+    DepthChart(DepthChartMessage),
+    Portfolio(PortfolioMessage),
+    Heatmap(HeatmapMessage),
+    News(NewsMessage),
+    FundingRate(FundingMessage),
+    AlgoTrading(AlgoTradingMessage),
+    Liquidations(LiquidationsMessage),
+    TradeSizer(TradeSizerMessage),
+    Book(BookMessage),
+    Trades(TradesPaneMessage),
+    History(HistoryMessage),
+    OrderFlow(OrderFlowMessage),
+    Arbitrage(ArbitrageMessage),
+    Correlation(CorrelationMessage),
+    SpreadMonitor(SpreadMonitorMessage),
+    Sentiment(SentimentMessage),
+];
+
+#[cfg(feature = "options")]
+impl From<OptionsMessage> for DashboardMessage {
+    fn from(value: OptionsMessage) -> Self {
+        Self::Options(value)
     }
 }
 
-impl From<MarketPanelMessage> for DashboardMessage {
-    fn from(value: MarketPanelMessage) -> Self {
-        Self::Market(value)
+#[cfg(feature = "wgpu-panes")]
+impl From<MarketDepth3DMessage> for DashboardMessage {
+    fn from(value: MarketDepth3DMessage) -> Self {
+        Self::MarketDepth3D(value)
     }
 }
 
@@ -155,12 +738,67 @@ pub(crate) struct DashboardView {
     panes: pane_grid::State<Pane>,
     watchlist: WatchlistPane,
     chart: ChartPane,
+    depth_chart: DepthChartPane,
     calculator: CalculatorPane,
     market: Market,
     book: BookPane,
     orders: OrdersPane,
     balances: BalancesPane,
+    portfolio: PortfolioPane,
+    heatmap: HeatmapPane,
+    news: NewsPane,
+    funding_rate: FundingPane,
+    algo_trading: AlgoTradingPane,
+    liquidations: LiquidationsPane,
+    trade_sizer: TradeSizerPane,
+    history: HistoryPane,
+    order_flow: OrderFlowPane,
+    arbitrage: ArbitragePane,
+    correlation: CorrelationPane,
+    spread_monitor: SpreadMonitorPane,
+    sentiment: SentimentPane,
+    #[cfg(feature = "options")]
+    options: OptionsPane,
+    #[cfg(feature = "perf-pane")]
+    performance: PerformancePane,
+    #[cfg(feature = "wgpu-panes")]
+    market_depth_3d: MarketDepth3DPane,
     trades: TradesPane,
+    /// Panes waiting for websocket reconnect and when it happens
+    reconnecting: Vec<(PaneType, Instant)>,
+    /// Panes whose websocket went silent, until it connects again
+    stalled: Vec<PaneType>,
+    /// Panes fed by websocket and when it last sent something
+    last_message_at: Vec<(PaneType, Instant)>,
+    last_title_click: Option<(pane_grid::Pane, Instant)>,
+    /// Pane being renamed and entered name
+    renaming: Option<(pane_grid::Pane, String)>,
+    /// Layouts before each change, newest last
+    undo: Vec<pane_grid::State<Pane>>,
+    redo: Vec<pane_grid::State<Pane>>,
+    /// Split being resized, resizing it further is part of the same undo step
+    resizing: Option<pane_grid::Split>,
+    /// Split followed by two finger drag
+    touch_split: Option<pane_grid::Split>,
+    /// Size of pane grid when it was last drawn
+    grid_size: Cell<Size>,
+    add_pane_menu_opened: bool,
+    /// Pane add menu was opened on, focused pane is split if not set
+    split_target: Option<pane_grid::Pane>,
+    config_file_menu_opened: bool,
+    /// File config is exported to and imported from
+    config_file_path: String,
+    /// Outcome of last export or import
+    config_file_status: Option<String>,
+    /// API keys are exported, off by default so that exported file can be shared
+    export_secrets: bool,
+    /// Import file was read and waits for confirmation
+    import_confirming: bool,
+    /// Symbol dragged from watchlist, other panes accept it while set
+    dragged_symbol: Option<String>,
+    /// Value last copied to clipboard and when
+    copied: Option<(String, Instant)>,
+    search: SearchOverlay,
 }
 
 macro_rules! v {
@@ -190,44 +828,474 @@ macro_rules! pane {
 }
 
 impl DashboardView {
-    pub(crate) fn new() -> Self {
-        let panes = pane_grid::State::with_configuration(h![
-            0.65,
-            v![
-                0.15,
-                h![0.6, pane![Prices], pane![Balances]],
-                v![
-                    0.5,
-                    pane![Chart],
-                    v![0.6, h![0.33, pane![Market], pane![Trades]], pane![Book]]
-                ]
-            ],
-            v![0.7, pane![Orders], pane![Calculator]]
-        ]);
+    pub(crate) fn new(config: &Config) -> Self {
+        let panes = Self::load_layout().unwrap_or_else(Self::default_layout);
 
         Self {
             focus: None,
             panes,
             watchlist: WatchlistPane::new(),
-            chart: ChartPane::new(),
+            chart: ChartPane::new(&config.indicators),
+            depth_chart: DepthChartPane::new(),
             calculator: CalculatorPane::new(),
-            market: Market::new(),
+            market: Market::new(config.default_pair.clone()),
             book: BookPane::new(),
             orders: OrdersPane::new(),
             balances: BalancesPane::new(),
+            portfolio: PortfolioPane::new(),
+            heatmap: HeatmapPane::new(),
+            news: NewsPane::new(),
+            funding_rate: FundingPane::new(),
+            algo_trading: AlgoTradingPane::new(),
+            liquidations: LiquidationsPane::new(),
+            trade_sizer: TradeSizerPane::new(),
+            history: HistoryPane::new(),
+            order_flow: OrderFlowPane::new(),
+            arbitrage: ArbitragePane::new(),
+            correlation: CorrelationPane::new(),
+            spread_monitor: SpreadMonitorPane::new(),
+            sentiment: SentimentPane::new(),
+            #[cfg(feature = "options")]
+            options: OptionsPane::new(),
+            #[cfg(feature = "perf-pane")]
+            performance: PerformancePane::new(),
+            #[cfg(feature = "wgpu-panes")]
+            market_depth_3d: MarketDepth3DPane::new(),
             trades: TradesPane::new(),
+            reconnecting: Vec::new(),
+            stalled: Vec::new(),
+            last_message_at: Vec::new(),
+            last_title_click: None,
+            renaming: None,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            resizing: None,
+            touch_split: None,
+            grid_size: Cell::new(Size::ZERO),
+            add_pane_menu_opened: false,
+            split_target: None,
+            config_file_menu_opened: false,
+            config_file_path: Config::dir()
+                .join("dynasty-export.json")
+                .to_string_lossy()
+                .into_owned(),
+            config_file_status: None,
+            export_secrets: false,
+            import_confirming: false,
+            search: SearchOverlay::default(),
+            dragged_symbol: None,
+            copied: None,
+        }
+    }
+
+    pub(crate) fn default_layout() -> pane_grid::State<Pane> {
+        pane_grid::State::with_configuration(h![
+            0.65,
+            v![
+                0.15,
+                h![
+                    0.6,
+                    pane![Prices],
+                    h![0.5, pane![Balances], pane![Portfolio]]
+                ],
+                v![
+                    0.5,
+                    pane![Chart],
+                    v![0.6, h![0.33, pane![Market], pane![Trades]], pane![Book]]
+                ]
+            ],
+            v![
+                0.6,
+                pane![Orders],
+                v![0.5, pane![Calculator], h![0.5, pane![Heatmap], pane![News]]]
+            ]
+        ])
+    }
+
+    /// Serializable copy of current pane layout
+    pub(crate) fn layout(&self) -> LayoutConfig {
+        LayoutConfig::from_state(&self.panes)
+    }
+
+    /// Write current pane layout to disk
+    pub(crate) fn save_layout(&self) -> Result<(), SaveError> {
+        self.layout().save()
+    }
+
+    /// Read pane layout saved by `save_layout`
+    ///
+    /// Returns None if layout was never saved or cannot be read
+    pub(crate) fn load_layout() -> Option<pane_grid::State<Pane>> {
+        let contents = fs::read_to_string(Config::dir().join(LayoutConfig::FILE_NAME)).ok()?;
+
+        match serde_json::from_str::<LayoutConfig>(&contents) {
+            Ok(layout) => Some(pane_grid::State::with_configuration(layout)),
+            Err(err) => {
+                tracing::error!("failed to parse saved layout: {err}");
+                None
+            }
         }
     }
 
+    /// Query input with pane and symbol results under it
+    fn view_search(&self) -> Element<'_, DashboardMessage> {
+        let mut input = text_input("Search panes and symbols", &self.search.query)
+            .id(text_input::Id::new("search"))
+            .on_input(DashboardMessage::SearchQuery)
+            .size(14)
+            .padding(6)
+            .width(320);
+        if let Some(first) = self.search.results.first() {
+            input = input.on_submit(DashboardMessage::SearchConfirm(first.clone()));
+        }
+
+        let results = self.search.results.iter().filter_map(|result| {
+            let label = match result {
+                SearchResult::Pane(pane) => format!("Pane: {}", self.panes.get(*pane)?.title()),
+                SearchResult::Symbol(symbol) => symbol.clone(),
+            };
+
+            Some(
+                button(text(label).size(12))
+                    .padding([2, 6])
+                    .width(Length::Fill)
+                    .style(theme::Button::Text)
+                    .on_press(DashboardMessage::SearchConfirm(result.clone()))
+                    .into(),
+            )
+        });
+
+        container(
+            column![input, Column::with_children(results)]
+                .spacing(4)
+                .width(320),
+        )
+        .padding(8)
+        .style(theme::Container::Box)
+        .into()
+    }
+
+    /// Path input with export and import buttons
+    fn view_config_file_menu(&self) -> Element<'_, DashboardMessage> {
+        let file_button = |label, message| {
+            button(text(label).size(12))
+                .padding([2, 6])
+                .style(theme::Button::Secondary)
+                .on_press(message)
+        };
+
+        // import replaces live state, so it is only done after restart
+        let confirmation = self.import_confirming.then(|| {
+            row![
+                text("Replace config and layout? Dynasty will close, start it again to apply")
+                    .size(12)
+                    .style(color(Role::Warning)),
+                button(text("Confirm").size(12))
+                    .style(theme::Button::Destructive)
+                    .padding([2, 8])
+                    .on_press(DashboardMessage::ImportConfigConfirm),
+                button(text("Abort").size(12))
+                    .style(theme::Button::Text)
+                    .padding([2, 8])
+                    .on_press(DashboardMessage::ImportConfigAbort),
+            ]
+            .spacing(4)
+            .align_items(iced::Alignment::Center)
+        });
+
+        container(
+            column![row![
+                text_input("config file", &self.config_file_path)
+                    .on_input(DashboardMessage::ConfigFilePathInput)
+                    .size(12)
+                    .padding(4)
+                    .width(320),
+                file_button("Export", DashboardMessage::ExportConfig),
+                file_button("Import", DashboardMessage::ImportConfig),
+                checkbox("Include API keys", self.export_secrets)
+                    .on_toggle(DashboardMessage::ExportSecretsToggled)
+                    .text_size(12)
+                    .size(14),
+            ]
+            .spacing(4)
+            .align_items(iced::Alignment::Center)]
+            .push_maybe(confirmation)
+            .push_maybe(
+                self.config_file_status
+                    .as_ref()
+                    .map(|status| text(status).size(12)),
+            )
+            .spacing(4),
+        )
+        .padding(8)
+        .style(theme::Container::Box)
+        .into()
+    }
+
     /// currently entered pair of currencies
     pub(crate) fn pair(&self) -> &str {
         self.market.pair()
     }
 
+    /// Price was added to chart, `scrolled` if chart was full
+    pub(crate) fn price_pushed(&mut self, price: f64, scrolled: bool) {
+        self.chart.push_price(price, scrolled);
+    }
+
+    /// Timeframe of history shown by chart
+    pub(crate) fn chart_timeframe(&self) -> Timeframe {
+        self.chart.timeframe()
+    }
+
+    /// Chart prices were replaced with history
+    pub(crate) fn price_history_changed(&mut self, data: &AppData) {
+        self.chart.history_changed(data);
+    }
+
+    /// Show reconnect countdown in pane title, None once connected
+    pub(crate) fn set_reconnecting(&mut self, pane: PaneType, at: Option<Instant>) {
+        self.reconnecting.retain(|(ty, _)| *ty != pane);
+
+        if let Some(at) = at {
+            self.reconnecting.push((pane, at));
+        }
+    }
+
+    /// Show warning in pane title while its websocket is stalled
+    pub(crate) fn set_stalled(&mut self, pane: PaneType, stalled: bool) {
+        self.stalled.retain(|ty| *ty != pane);
+
+        if stalled {
+            self.stalled.push(pane);
+        }
+    }
+
+    /// Websocket feeding pane connected or sent data
+    pub(crate) fn ws_message_received(&mut self, pane: PaneType) {
+        let now = Instant::now();
+        #[cfg(feature = "perf-pane")]
+        self.performance.message_received(pane.clone());
+
+        match self.last_message_at.iter_mut().find(|(ty, _)| *ty == pane) {
+            Some((_, at)) => *at = now,
+            None => self.last_message_at.push((pane, now)),
+        }
+    }
+
+    /// Liquidation of market pair arrived
+    pub(crate) fn liquidation_received(&mut self, liquidation: &Liquidation) {
+        self.liquidations.received(liquidation);
+        self.ws_message_received(PaneType::Liquidations);
+    }
+
+    /// Trade of market pair arrived
+    pub(crate) fn trade_received(&mut self, trade: &TradesEvent) {
+        self.trades.trade_received(trade);
+        self.order_flow.trade_received(trade);
+        self.ws_message_received(PaneType::Trades);
+        self.ws_message_received(PaneType::OrderFlow);
+    }
+
+    /// New order book of market pair arrived
+    pub(crate) fn book_received(&mut self, data: &AppData) {
+        self.book.book_received(data);
+        self.ws_message_received(PaneType::Book);
+    }
+
+    /// Page of own trades arrived
+    pub(crate) fn trade_history_received(&mut self, len: usize) {
+        self.history.page_received(len);
+    }
+
+    /// Some pane of this type is in layout
+    pub(crate) fn is_pane_open(&self, ty: PaneType) -> bool {
+        self.panes.iter().any(|(_, pane)| pane.id == ty)
+    }
+
+    /// Symbols whose best bid and ask are needed by open panes
+    pub(crate) fn book_ticker_symbols(&self, favorites: &[String]) -> Vec<String> {
+        let mut symbols = Vec::new();
+        if self.is_pane_open(PaneType::SpreadMonitor) {
+            symbols.extend_from_slice(favorites);
+        }
+        if self.is_pane_open(PaneType::Arbitrage) {
+            symbols.extend_from_slice(self.arbitrage.pairs());
+        }
+        symbols.sort_unstable();
+        symbols.dedup();
+
+        symbols
+    }
+
+    /// Symbols charted in their own panes
+    pub(crate) fn chart_symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<_> = self
+            .panes
+            .iter()
+            .filter_map(|(_, pane)| pane.symbol.clone())
+            .collect();
+        symbols.sort();
+        symbols.dedup();
+        symbols
+    }
+
+    /// Remember layout before it is changed
+    fn snapshot(&mut self) {
+        if self.undo.len() == UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(self.panes.clone());
+        self.redo.clear();
+        self.resizing = None;
+    }
+
+    /// Replace layout with one from history, current one goes to the other stack
+    fn restore_snapshot(&mut self, redo: bool) {
+        let (from, to) = if redo {
+            (&mut self.redo, &mut self.undo)
+        } else {
+            (&mut self.undo, &mut self.redo)
+        };
+
+        if let Some(panes) = from.pop() {
+            to.push(std::mem::replace(&mut self.panes, panes));
+            self.resizing = None;
+            self.renaming = None;
+
+            if self
+                .focus
+                .is_some_and(|focus| self.panes.get(focus).is_none())
+            {
+                self.focus = None;
+            }
+        }
+    }
+
+    /// Panes in the same order they appear in layout
+    fn pane_order(&self) -> Vec<pane_grid::Pane> {
+        fn walk(node: &pane_grid::Node, order: &mut Vec<pane_grid::Pane>) {
+            match node {
+                pane_grid::Node::Split { a, b, .. } => {
+                    walk(a, order);
+                    walk(b, order);
+                }
+                pane_grid::Node::Pane(pane) => order.push(*pane),
+            }
+        }
+
+        let mut order = Vec::with_capacity(self.panes.len());
+        walk(self.panes.layout(), &mut order);
+        order
+    }
+
+    /// Shortcut of pane the key was pressed in, only that pane reacts to it
+    fn handle_key_event(
+        &self,
+        pane: pane_grid::Pane,
+        event: &KeyEvent,
+    ) -> Option<DashboardMessage> {
+        let state = self.panes.get(pane)?;
+
+        match state.id {
+            PaneType::Prices => WatchlistPane::key_message(event).map(Into::into),
+            PaneType::Chart => ChartPane::key_message(event).map(Into::into),
+            PaneType::Book if event.character()?.eq_ignore_ascii_case("g") => Some(
+                DashboardMessage::SetBookGrouping(pane, state.book_grouping.next()),
+            ),
+            _ => None,
+        }
+    }
+
+    fn shortcut_message(&self, action: ShortcutAction) -> Option<DashboardMessage> {
+        Some(match action {
+            ShortcutAction::Buy => {
+                DashboardMessage::Market(MarketPanelMessage::Preview(OrderSide::Buy))
+            }
+            ShortcutAction::Sell => {
+                DashboardMessage::Market(MarketPanelMessage::Preview(OrderSide::Sell))
+            }
+            ShortcutAction::FocusNext => DashboardMessage::CycleFocus { forward: true },
+            ShortcutAction::FocusPrevious => DashboardMessage::CycleFocus { forward: false },
+            ShortcutAction::ToggleMaximize => {
+                if self.panes.maximized().is_some() {
+                    DashboardMessage::Restore
+                } else {
+                    DashboardMessage::Maximize(self.focus?)
+                }
+            }
+            ShortcutAction::Close => {
+                let focus = self.focus?;
+                if self.panes.len() < 2 || self.panes.get(focus)?.is_pinned {
+                    return None;
+                }
+                DashboardMessage::Close(focus)
+            }
+            ShortcutAction::Undo => DashboardMessage::Undo,
+            ShortcutAction::Redo => DashboardMessage::Redo,
+            ShortcutAction::Search => DashboardMessage::SearchOpen,
+            ShortcutAction::Screenshot => DashboardMessage::TakeScreenshot,
+        })
+    }
+
+    pub(crate) fn subscription(&self) -> Subscription<DashboardMessage> {
+        Subscription::batch([
+            keyboard::on_key_press(|key, modifiers| {
+                Some(DashboardMessage::KeyPressed(key, modifiers))
+            }),
+            iced::time::every(REFRESH_INTERVAL).map(|_| DashboardMessage::Refresh),
+            iced::time::every(FUNDING_REFRESH_INTERVAL)
+                .map(|_| DashboardMessage::RefreshFundingRates),
+            if self.liquidations.is_animating() {
+                iced::time::every(ANIMATION_INTERVAL)
+                    .map(|now| LiquidationsMessage::Animate(now).into())
+            } else {
+                Subscription::none()
+            },
+            if self.chart.is_animating() {
+                iced::time::every(ANIMATION_INTERVAL).map(|now| ChartMessage::Animate(now).into())
+            } else {
+                Subscription::none()
+            },
+            #[cfg(feature = "wgpu-panes")]
+            if self.is_pane_open(PaneType::MarketDepth3D) {
+                iced::time::every(market_depth_3d::SAMPLE_INTERVAL)
+                    .map(|_| MarketDepth3DMessage::Sample.into())
+            } else {
+                Subscription::none()
+            },
+            // drag ends wherever mouse is released, including outside of watchlist and window
+            if self.dragged_symbol.is_some() {
+                event::listen_with(|event, status| match (event, status) {
+                    (
+                        iced::Event::Mouse(iced::mouse::Event::ButtonReleased(
+                            iced::mouse::Button::Left,
+                        )),
+                        event::Status::Ignored,
+                    ) => Some(WatchlistMessage::DragCancelled.into()),
+                    _ => None,
+                })
+            } else {
+                Subscription::none()
+            },
+            // search input captures Escape, so it is listened for regardless of status
+            if self.search.visible {
+                event::listen_with(|event, _| match event {
+                    iced::Event::Keyboard(keyboard::Event::KeyPressed {
+                        key: keyboard::Key::Named(keyboard::key::Named::Escape),
+                        ..
+                    }) => Some(DashboardMessage::SearchClose),
+                    _ => None,
+                })
+            } else {
+                Subscription::none()
+            },
+        ])
+    }
+
     pub(crate) fn update(
         &mut self,
         message: DashboardMessage,
-        api: &Client,
+        api: &dyn TradingClient,
         data: &mut AppData,
         ws: &Websockets,
         config: &Config,
@@ -235,17 +1303,134 @@ impl DashboardView {
         match message {
             DashboardMessage::Clicked(pane) => {
                 self.focus = Some(pane);
+                self.add_pane_menu_opened = false;
+                // symbol released over title bar or outside of dashboard
+                self.dragged_symbol = None;
+                self.watchlist.cancel_drag();
+
+                // clicking away from title input commits name
+                match self.renaming.take() {
+                    Some((renamed, name)) if renamed != pane => self.update(
+                        DashboardMessage::RenamePaneCommit(renamed, name),
+                        api,
+                        data,
+                        ws,
+                        config,
+                    ),
+                    renaming => {
+                        self.renaming = renaming;
+                        Command::none()
+                    }
+                }
+            }
+            DashboardMessage::TitleClicked(pane) => {
+                let now = Instant::now();
+                let is_double = self.last_title_click.is_some_and(|(clicked, at)| {
+                    clicked == pane && now.duration_since(at) <= DOUBLE_CLICK
+                });
+                self.last_title_click = Some((pane, now));
+
+                if is_double {
+                    self.last_title_click = None;
+                    self.update(
+                        DashboardMessage::RenamePaneStart(pane),
+                        api,
+                        data,
+                        ws,
+                        config,
+                    )
+                } else {
+                    Command::none()
+                }
+            }
+            DashboardMessage::RenamePaneStart(pane) => {
+                if let Some(state) = self.panes.get(pane) {
+                    self.renaming = Some((pane, state.title()));
+                    return text_input::focus(text_input::Id::new("pane-title"));
+                }
+                Command::none()
+            }
+            DashboardMessage::RenamePaneInput(name) => {
+                if let Some((_, current)) = &mut self.renaming {
+                    *current = name;
+                }
+                Command::none()
+            }
+            DashboardMessage::RenamePaneCommit(pane, name) => {
+                self.renaming = None;
+
+                if let Some(state) = self.panes.get_mut(pane) {
+                    let name = name.trim();
+                    state.custom_label =
+                        (!name.is_empty() && name != state.id.to_string()).then(|| name.to_owned());
+                }
                 Command::none()
             }
             DashboardMessage::Resized(pane_grid::ResizeEvent { split, ratio }) => {
+                // resize events arrive continuously while dragging the split
+                if self.resizing != Some(split) {
+                    self.snapshot();
+                    self.resizing = Some(split);
+                }
                 self.panes.resize(split, ratio);
                 Command::none()
             }
+            DashboardMessage::TouchDragged(Some(position), size) => {
+                let regions = self.panes.layout().split_regions(style::spacing(), size);
+                // gesture keeps following split it started on
+                let Some(split) = self.touch_split.or_else(|| split_at(&regions, position)) else {
+                    return Command::none();
+                };
+                let Some((axis, region, _)) = regions.get(&split) else {
+                    return Command::none();
+                };
+                self.touch_split = Some(split);
+
+                let ratio = match axis {
+                    pane_grid::Axis::Horizontal => (position.y - region.y) / region.height,
+                    pane_grid::Axis::Vertical => (position.x - region.x) / region.width,
+                };
+                let event = self.clamp_resize(pane_grid::ResizeEvent { split, ratio }, size);
+                self.update(DashboardMessage::Resized(event), api, data, ws, config)
+            }
+            DashboardMessage::ConfigReloaded => {
+                let size = self.grid_size.get();
+                // grid that was never drawn has no lengths to fit
+                if size.width <= 0.0 || size.height <= 0.0 {
+                    return Command::none();
+                }
+                let splits: Vec<_> = self
+                    .panes
+                    .layout()
+                    .split_regions(style::spacing(), size)
+                    .into_iter()
+                    .map(|(split, (_, _, ratio))| pane_grid::ResizeEvent { split, ratio })
+                    .collect();
+
+                for event in splits {
+                    let clamped = self.clamp_resize(event, size);
+                    if clamped.ratio != event.ratio {
+                        self.panes.resize(clamped.split, clamped.ratio);
+                    }
+                }
+                Command::none()
+            }
+            DashboardMessage::TouchDragged(None, _) => {
+                self.touch_split = None;
+                Command::none()
+            }
             DashboardMessage::Dragged(pane_grid::DragEvent::Dropped { pane, target }) => {
+                self.snapshot();
                 self.panes.drop(pane, target);
                 Command::none()
             }
             DashboardMessage::Dragged(_) => Command::none(),
+            DashboardMessage::TogglePin(pane) => {
+                if let Some(state) = self.panes.get_mut(pane) {
+                    state.is_pinned = !state.is_pinned;
+                }
+                Command::none()
+            }
             DashboardMessage::Maximize(pane) => {
                 self.panes.maximize(pane);
                 Command::none()
@@ -255,71 +1440,838 @@ impl DashboardView {
                 Command::none()
             }
             DashboardMessage::Close(pane) => {
+                // last pane cannot be closed
+                if self.panes.len() > 1 {
+                    self.snapshot();
+                }
                 if let Some((_, sibling)) = self.panes.close(pane) {
                     self.focus = Some(sibling);
                 }
                 Command::none()
             }
-            DashboardMessage::CurrencyPairSelected(pair) => {
+            DashboardMessage::KeyPressed(key, modifiers) => {
+                match config
+                    .keyboard_shortcuts
+                    .action(&key, modifiers)
+                    .and_then(|action| self.shortcut_message(action))
+                    .or_else(|| {
+                        self.focus.map(|pane| {
+                            DashboardMessage::PaneKeyEvent(pane, KeyEvent { key, modifiers })
+                        })
+                    }) {
+                    Some(message) => self.update(message, api, data, ws, config),
+                    None => Command::none(),
+                }
+            }
+            DashboardMessage::PaneKeyEvent(pane, event) => {
+                match self.handle_key_event(pane, &event) {
+                    Some(message) => self.update(message, api, data, ws, config),
+                    None => Command::none(),
+                }
+            }
+            DashboardMessage::Undo => {
+                self.restore_snapshot(false);
+                Command::none()
+            }
+            DashboardMessage::Redo => {
+                self.restore_snapshot(true);
+                Command::none()
+            }
+            DashboardMessage::SearchOpen => {
+                self.search.visible = true;
+                self.add_pane_menu_opened = false;
+                self.config_file_menu_opened = false;
+                text_input::focus(text_input::Id::new("search"))
+            }
+            DashboardMessage::SearchQuery(query) => {
+                self.search.query = query;
+                self.search.search(
+                    self.panes.iter().map(|(id, pane)| (*id, pane)),
+                    data.prices.all().map(|(s, _)| s),
+                );
+                Command::none()
+            }
+            DashboardMessage::SearchConfirm(result) => {
+                self.search = SearchOverlay::default();
+
+                match result {
+                    SearchResult::Pane(pane) => {
+                        if self
+                            .panes
+                            .maximized()
+                            .is_some_and(|maximized| maximized != pane)
+                        {
+                            self.panes.restore();
+                        }
+                        self.focus = Some(pane);
+                        Command::none()
+                    }
+                    SearchResult::Symbol(symbol) => self.update(
+                        DashboardMessage::Watchlist(WatchlistMessage::PairSelected(symbol)),
+                        api,
+                        data,
+                        ws,
+                        config,
+                    ),
+                }
+            }
+            DashboardMessage::SearchClose => {
+                self.search = SearchOverlay::default();
+                Command::none()
+            }
+            DashboardMessage::TakeScreenshot => window::screenshot(window::Id::MAIN, |shot| {
+                DashboardMessage::ScreenshotTaken(shot).into()
+            }),
+            DashboardMessage::ScreenshotTaken(shot) => {
+                Command::perform(screenshot::save(shot), |saved| match saved {
+                    Ok(path) => Message::PushNotification(Notification::new(
+                        Severity::Success,
+                        format!("Screenshot saved to {}", path.display()),
+                    )),
+                    Err(err) => Message::ToastError(format!("failed to save screenshot: {err}")),
+                })
+            }
+            DashboardMessage::OpenAddPaneMenu => {
+                self.add_pane_menu_opened = !self.add_pane_menu_opened;
+                self.split_target = None;
+                self.config_file_menu_opened = false;
+                Command::none()
+            }
+            DashboardMessage::OpenSplitMenu(pane) => {
+                self.add_pane_menu_opened = true;
+                self.split_target = Some(pane);
+                self.config_file_menu_opened = false;
+                self.focus = Some(pane);
+                Command::none()
+            }
+            DashboardMessage::OpenConfigFileMenu => {
+                self.config_file_menu_opened = !self.config_file_menu_opened;
+                self.add_pane_menu_opened = false;
+                self.config_file_status = None;
+                self.import_confirming = false;
+                Command::none()
+            }
+            DashboardMessage::ConfigFilePathInput(path) => {
+                self.config_file_path = path;
+                self.import_confirming = false;
+                Command::none()
+            }
+            DashboardMessage::ExportSecretsToggled(export_secrets) => {
+                self.export_secrets = export_secrets;
+                Command::none()
+            }
+            DashboardMessage::ExportConfig => {
+                let layout = LayoutConfig::from_state(&self.panes);
+                let config = if self.export_secrets {
+                    config.clone()
+                } else {
+                    config.without_secrets()
+                };
+                let path = PathBuf::from(&self.config_file_path);
+                self.config_file_status = Some("Exporting".to_owned());
+
+                Command::perform(
+                    async move {
+                        config
+                            .export_to_file(&layout, &path)
+                            .map_err(|err| err.to_string())
+                    },
+                    |result| DashboardMessage::ConfigExported(result).into(),
+                )
+            }
+            DashboardMessage::ConfigExported(result) => {
+                self.config_file_status = Some(match result {
+                    Ok(()) => "Exported".to_owned(),
+                    Err(err) => format!("Export failed: {err}"),
+                });
+                Command::none()
+            }
+            DashboardMessage::ImportConfig => {
+                match Config::import_from_file(Path::new(&self.config_file_path)) {
+                    Ok(_) => {
+                        self.config_file_status = None;
+                        self.import_confirming = true;
+                    }
+                    Err(err) => self.config_file_status = Some(format!("Import failed: {err}")),
+                }
+                Command::none()
+            }
+            DashboardMessage::ImportConfigConfirm => {
+                self.import_confirming = false;
+
+                let saved = Config::import_from_file(Path::new(&self.config_file_path))
+                    .map_err(|err| err.to_string())
+                    .and_then(|(config, layout)| {
+                        config.save().map_err(|err| err.to_string())?;
+                        match layout {
+                            Some(layout) => layout.save().map_err(|err| err.to_string()),
+                            None => Ok(()),
+                        }
+                    });
+
+                match saved {
+                    // layout and config of running app would be saved over imported ones on close
+                    Ok(()) => window::close(window::Id::MAIN),
+                    Err(err) => {
+                        self.config_file_status = Some(format!("Import failed: {err}"));
+                        Command::none()
+                    }
+                }
+            }
+            DashboardMessage::ImportConfigAbort => {
+                self.import_confirming = false;
+                Command::none()
+            }
+            DashboardMessage::AddPane(ty, axis) => {
+                match self.focus.or_else(|| self.pane_order().first().copied()) {
+                    Some(target) => self.update(
+                        DashboardMessage::SplitPane(target, axis, ty),
+                        api,
+                        data,
+                        ws,
+                        config,
+                    ),
+                    None => {
+                        self.add_pane_menu_opened = false;
+                        Command::none()
+                    }
+                }
+            }
+            DashboardMessage::SplitPane(target, axis, ty) => {
+                self.add_pane_menu_opened = false;
+                self.split_target = None;
+
+                self.snapshot();
+                // new split is halved, it can be resized after
+                if let Some((pane, _)) = self.panes.split(axis, target, Pane::new(ty)) {
+                    self.focus = Some(pane);
+                }
+                Command::none()
+            }
+            DashboardMessage::WatchlistDragEnd(target, symbol) => {
+                self.dragged_symbol = None;
+                self.watchlist.cancel_drag();
+
+                self.snapshot();
+                let chart = Pane {
+                    symbol: Some(symbol),
+                    ..Pane::new(PaneType::Chart)
+                };
+                if let Some((pane, _)) = self.panes.split(pane_grid::Axis::Vertical, target, chart)
+                {
+                    self.focus = Some(pane);
+                }
+                Command::none()
+            }
+            DashboardMessage::CycleFocus { forward } => {
+                let order = self.pane_order();
+                let position = self
+                    .focus
+                    .and_then(|focus| order.iter().position(|pane| *pane == focus));
+
+                let next = match position {
+                    Some(i) if forward => (i + 1) % order.len(),
+                    Some(i) => (i + order.len() - 1) % order.len(),
+                    None => 0,
+                };
+                self.focus = order.get(next).copied();
+
+                Command::none()
+            }
+            DashboardMessage::Watchlist(WatchlistMessage::PairSelected(pair))
+            | DashboardMessage::Balances(BalancesMessage::AssetSelected(pair))
+            | DashboardMessage::Heatmap(HeatmapMessage::PairSelected(pair))
+            | DashboardMessage::Correlation(CorrelationMessage::PairSelected(pair))
+            | DashboardMessage::SpreadMonitor(SpreadMonitorMessage::PairSelected(pair))
+            | DashboardMessage::Sentiment(SentimentMessage::PairSelected(pair))
+            | DashboardMessage::FundingRate(FundingMessage::PairSelected(pair)) => {
+                let pair = PairBuilder::new(&config.preferred_quote_asset, &data.symbol_filters)
+                    .build(&pair);
                 ws.track_new_currency_pair(&pair);
                 self.market.set_currency_pair(pair);
 
                 Command::none()
             }
-            DashboardMessage::Watchlist(msg) => self
-                .watchlist
-                .update(msg, data, config)
-                .map(DashboardMessage::from)
-                .map(Message::from),
+            DashboardMessage::Watchlist(msg) => {
+                // dragged symbol can also be dropped on other panes
+                match &msg {
+                    WatchlistMessage::DragStarted(symbol) => {
+                        self.dragged_symbol = Some(symbol.clone());
+                    }
+                    WatchlistMessage::DragDropped(_) | WatchlistMessage::DragCancelled => {
+                        self.dragged_symbol = None;
+                    }
+                    _ => {}
+                }
+
+                self.watchlist
+                    .update(msg, data, config)
+                    .map(DashboardMessage::from)
+                    .map(Message::from)
+            }
+            DashboardMessage::SetBookGrouping(pane, grouping) => {
+                if let Some(state) = self.panes.get_mut(pane) {
+                    state.book_grouping = grouping;
+                }
+                Command::none()
+            }
+            DashboardMessage::FlipPair => {
+                match self.market.reversed_pair(data) {
+                    Some(reversed) => {
+                        ws.track_new_currency_pair(&reversed);
+                        self.market.set_currency_pair(reversed);
+                    }
+                    None => self.market.toggle_flipped(),
+                }
+                Command::none()
+            }
+            DashboardMessage::CopyToClipboard(value)
+            | DashboardMessage::Balances(BalancesMessage::Copy(value)) => {
+                self.copied = Some((value.clone(), Instant::now()));
+                iced::clipboard::write(value)
+            }
+            DashboardMessage::VwapReset => {
+                data.vwap.remove(self.market.pair());
+                Command::none()
+            }
             DashboardMessage::Calculator(msg) => self
                 .calculator
                 .update(msg)
                 .map(DashboardMessage::from)
                 .map(Message::from),
-            DashboardMessage::TimeframeChanged(tf) => api.klines(self.pair().to_owned(), tf),
-            DashboardMessage::Market(msg) => self.market.update(msg, api, data, ws),
+            DashboardMessage::Chart(ChartMessage::Animate(now)) => {
+                self.chart.animate(now, config.chart_scroll_speed_ms);
+                Command::none()
+            }
+            DashboardMessage::Chart(msg) => self.chart.update(msg, api, data, self.market.pair()),
+            DashboardMessage::Orders(msg) => self.orders.update(msg, data, api, config.paper_trade),
+            DashboardMessage::AlgoTrading(msg) => {
+                self.algo_trading
+                    .update(msg, api, data, self.market.pair(), config.paper_trade)
+            }
+            DashboardMessage::Refresh => Command::batch([
+                self.update(DashboardMessage::NewsRefresh, api, data, ws, config),
+                self.update(DashboardMessage::Refresh24hTickers, api, data, ws, config),
+                #[cfg(feature = "options")]
+                if self.is_pane_open(PaneType::Options) {
+                    self.update(OptionsMessage::Refresh.into(), api, data, ws, config)
+                } else {
+                    Command::none()
+                },
+            ]),
+            DashboardMessage::NewsRefresh => news::fetch(config.news_feed_url.clone()),
+            DashboardMessage::Refresh24hTickers => api.get_24hr_all_tickers(),
+            DashboardMessage::LoadNextPricePage => {
+                if !watchlist::load_next_page(data) {
+                    return Command::none();
+                }
+                Command::perform(tokio::time::sleep(watchlist::PAGE_INTERVAL), |_| {
+                    DashboardMessage::LoadNextPricePage.into()
+                })
+            }
+            DashboardMessage::RefreshFundingRates => api.get_funding_rates(),
+            DashboardMessage::PlaySound(effect) => {
+                if !config.sound_alerts {
+                    return Command::none();
+                }
+                Command::perform(sound::play(effect, config.sound_volume), |result| {
+                    MaybeError::new("sound".to_owned()).maybe(&result).into()
+                })
+            }
+            DashboardMessage::News(msg) => {
+                self.news.update(msg);
+                Command::none()
+            }
+            DashboardMessage::Portfolio(msg) => {
+                self.portfolio.update(msg);
+                Command::none()
+            }
+            DashboardMessage::DepthChart(msg) => {
+                self.depth_chart.update(msg);
+                Command::none()
+            }
+            DashboardMessage::Liquidations(msg) => {
+                self.liquidations.update(msg);
+                Command::none()
+            }
+            DashboardMessage::TradeSizer(TradeSizerMessage::UseSize(units)) => self.market.update(
+                MarketPanelMessage::AmountInput(units.to_string()),
+                api,
+                data,
+                ws,
+                config.paper_trade,
+            ),
+            DashboardMessage::TradeSizer(msg) => {
+                self.trade_sizer.update(msg, data);
+                Command::none()
+            }
+            DashboardMessage::History(msg) => {
+                self.history.update(msg, data, api, self.market.pair())
+            }
+            DashboardMessage::Book(msg) => {
+                self.book.update(msg);
+                Command::none()
+            }
+            DashboardMessage::Trades(msg) => {
+                self.trades.update(msg);
+                Command::none()
+            }
+            DashboardMessage::OrderFlow(msg) => {
+                self.order_flow.update(msg);
+                Command::none()
+            }
+            DashboardMessage::Arbitrage(msg) => {
+                self.arbitrage.update(msg, api, data, config.paper_trade)
+            }
+            #[cfg(feature = "options")]
+            DashboardMessage::Options(msg) => self
+                .options
+                .update(msg)
+                .map(DashboardMessage::from)
+                .map(Message::from),
+            #[cfg(feature = "perf-pane")]
+            DashboardMessage::ResetPerformanceStats => {
+                self.performance.reset();
+                Command::none()
+            }
+            #[cfg(feature = "wgpu-panes")]
+            DashboardMessage::MarketDepth3D(msg) => {
+                self.market_depth_3d.update(msg, data);
+                Command::none()
+            }
+            DashboardMessage::Market(msg) => {
+                self.market.update(msg, api, data, ws, config.paper_trade)
+            }
         }
     }
 
-    pub(crate) fn tick(&mut self, data: &AppData) {
+    /// Limits ratio of resized split so that neither side gets below minimal pane size
+    fn clamp_resize(&self, event: pane_grid::ResizeEvent, size: Size) -> pane_grid::ResizeEvent {
+        let spacing = style::spacing();
+        let regions = self.panes.layout().split_regions(spacing, size);
+        let Some((axis, region, _)) = regions.get(&event.split) else {
+            return event;
+        };
+
+        let length = match axis {
+            pane_grid::Axis::Horizontal => region.height,
+            pane_grid::Axis::Vertical => region.width,
+        };
+
+        pane_grid::ResizeEvent {
+            ratio: clamp_ratio(event.ratio, length, style::min_pane_size(), spacing),
+            ..event
+        }
+    }
+
+    pub(crate) fn tick(&mut self, data: &mut AppData, config: &Config) {
+        data.snapshot_portfolio();
+        self.watchlist.tick(data);
         self.calculator.tick(data);
+        self.portfolio.tick(data);
+        self.heatmap.tick(data, &config.watchlist_favorites);
+        self.correlation.tick(data, &config.watchlist_favorites);
+        self.arbitrage.tick(
+            data,
+            &config.watchlist_favorites,
+            config.arb_threshold_pct,
+            &config.preferred_quote_asset,
+        );
+        self.algo_trading.tick(data);
+        self.market.tick();
+        if self
+            .copied
+            .as_ref()
+            .is_some_and(|(_, at)| at.elapsed() >= COPIED_FLASH)
+        {
+            self.copied = None;
+        }
+        #[cfg(feature = "perf-pane")]
+        self.performance.tick();
     }
 
-    pub(crate) fn view<'a>(&'a self, data: &'a AppData) -> Element<'a, DashboardMessage> {
+    pub(crate) fn view<'a>(
+        &'a self,
+        data: &'a AppData,
+        config: &'a Config,
+    ) -> Element<'a, DashboardMessage> {
         let focus = self.focus;
         let total_panes = self.panes.len();
+        let copied = self.copied.as_ref().map(|(value, _)| value.as_str());
 
-        PaneGrid::new(&self.panes, |id, pane, is_maximized| {
-            let is_focused = focus == Some(id);
-
-            let title = row![text(pane.id.to_string())].spacing(5);
-            let title_bar = pane_grid::TitleBar::new(title)
-                .controls(view_controls(id, total_panes, pane.is_pinned, is_maximized))
-                .padding([8, 12]);
-
-            pane_grid::Content::new(responsive(|_size| match pane.id {
-                PaneType::Prices => self.watchlist.view(data).map(DashboardMessage::from),
-                PaneType::Chart => self.chart.view(data),
-                PaneType::Book => self.book.view(data),
-                PaneType::Trades => self.trades.view(data),
-                PaneType::Market => self.market.view().map(DashboardMessage::from),
-                PaneType::Balances => self.balances.view(data),
-                PaneType::Orders => self.orders.view(data),
-                PaneType::Calculator => self.calculator.view().map(DashboardMessage::from),
-            }))
-            .title_bar(title_bar)
-            .style(if is_focused {
-                style::pane_focused
-            } else {
-                style::pane_active
+        let toolbar_button = |content, message, hint| {
+            tooltip(
+                button(content)
+                    .padding([2, 8])
+                    .style(theme::Button::Secondary)
+                    .on_press(message),
+                container(text(hint).size(12))
+                    .padding(4)
+                    .style(theme::Container::Box),
+                tooltip::Position::Right,
+            )
+        };
+
+        let toolbar = row![
+            toolbar_button(
+                text('\u{F64D}')
+                    .size(14)
+                    .font(Font::with_name("bootstrap-icons")),
+                DashboardMessage::OpenAddPaneMenu,
+                "Add pane"
+            ),
+            toolbar_button(
+                text('\u{F362}')
+                    .size(14)
+                    .font(Font::with_name("bootstrap-icons")),
+                DashboardMessage::OpenConfigFileMenu,
+                "Export or import config"
+            ),
+        ]
+        .spacing(4);
+
+        // size of grid is needed to keep resized panes above minimal size
+        let grid = responsive(move |size| {
+            self.grid_size.set(size);
+            let grid = PaneGrid::new(&self.panes, |id, pane, is_maximized| {
+                let is_focused = focus == Some(id);
+
+                let reconnect_badge =
+                    self.reconnecting
+                        .iter()
+                        .find(|(ty, _)| *ty == pane.id)
+                        .map(|(_, at)| {
+                            let secs = at.saturating_duration_since(Instant::now()).as_secs_f32();
+                            text(format!("Reconnecting in {:.0} s…", secs.ceil()))
+                                .size(12)
+                                .style(color(Role::Danger))
+                        });
+
+                let stalled_badge = self
+                    .stalled
+                    .contains(&pane.id)
+                    .then(|| text("Stalled").size(12).style(color(Role::Warning)));
+
+                let title: Element<_> = match &self.renaming {
+                    Some((renamed, name)) if *renamed == id => text_input("pane name", name)
+                        .id(text_input::Id::new("pane-title"))
+                        .on_input(DashboardMessage::RenamePaneInput)
+                        .on_submit(DashboardMessage::RenamePaneCommit(id, name.clone()))
+                        .size(14)
+                        .padding(2)
+                        .width(150)
+                        .into(),
+                    _ => mouse_area(text(pane.title()))
+                        .on_press(DashboardMessage::TitleClicked(id))
+                        .on_right_press(DashboardMessage::OpenSplitMenu(id))
+                        .into(),
+                };
+
+                let symbol_count =
+                    (pane.id == PaneType::Prices && !data.prices.is_empty()).then(|| {
+                        let (shown, total) = watchlist::shown_count(data);
+                        text(format!("showing {shown} of {total}"))
+                            .size(12)
+                            .style(color(Role::Secondary))
+                    });
+
+                let title = row![title]
+                    .push_maybe(stalled_badge)
+                    .push_maybe(reconnect_badge)
+                    .push_maybe(symbol_count)
+                    .spacing(5)
+                    .align_items(iced::Alignment::Center);
+                let latency = self
+                    .last_message_at
+                    .iter()
+                    .find(|(ty, _)| *ty == pane.id)
+                    .map(|(_, at)| at.elapsed());
+
+                let title_bar = pane_grid::TitleBar::new(title)
+                    .controls(view_controls(
+                        id,
+                        total_panes,
+                        pane.is_pinned,
+                        is_maximized,
+                        latency,
+                        &config.keyboard_shortcuts,
+                    ))
+                    .padding(style::title_padding());
+                let title_bar = if pane.is_pinned {
+                    title_bar.style(style::pane_pinned)
+                } else {
+                    title_bar.style(theme::Container::Custom(Box::new(style::PaneTitle {
+                        accent: pane.id.accent_color(),
+                        focused: is_focused,
+                    })))
+                };
+
+                let body = responsive(move |_size| {
+                    #[cfg(feature = "perf-pane")]
+                    let started = Instant::now();
+
+                    let body = match pane.id {
+                        // demo client only streams prices
+                        PaneType::Book
+                        | PaneType::DepthChart
+                        | PaneType::Trades
+                        | PaneType::OrderFlow
+                        | PaneType::Liquidations
+                        | PaneType::SpreadMonitor
+                        | PaneType::Sentiment
+                            if config.demo_mode =>
+                        {
+                            demo_unavailable()
+                        }
+                        PaneType::Prices => self
+                            .watchlist
+                            .view(data, &config.watchlist_favorites, copied)
+                            .map(|msg| match msg {
+                                WatchlistMessage::Copy(value) => {
+                                    DashboardMessage::CopyToClipboard(value)
+                                }
+                                msg => msg.into(),
+                            }),
+                        PaneType::Chart => match &pane.symbol {
+                            Some(symbol) => chart::symbol_view(
+                                data.symbol_candles.get(symbol),
+                                &config.chart_colors,
+                            )
+                            .map(DashboardMessage::from),
+                            None => self
+                                .chart
+                                .view(
+                                    data,
+                                    config,
+                                    data.symbol_filter(self.market.pair()),
+                                    data.vwap.get(self.market.pair()).and_then(Vwap::value),
+                                )
+                                .map(DashboardMessage::from),
+                        },
+                        PaneType::Book => self
+                            .book
+                            .view(data, data.symbol_filter(&data.book.0), pane.book_grouping)
+                            .map(move |msg| match msg {
+                                BookMessage::GroupingSelected(grouping) => {
+                                    DashboardMessage::SetBookGrouping(id, grouping)
+                                }
+                                msg => msg.into(),
+                            }),
+                        PaneType::DepthChart => {
+                            self.depth_chart.view(data).map(DashboardMessage::from)
+                        }
+                        PaneType::Trades => self
+                            .trades
+                            .view(data, data.symbol_filter(&data.book.0))
+                            .map(|msg| match msg {
+                                TradesPaneMessage::VwapReset => DashboardMessage::VwapReset,
+                                msg => msg.into(),
+                            }),
+                        PaneType::Market => {
+                            self.market
+                                .view(&config.alerts, data, copied)
+                                .map(|msg| match msg {
+                                    MarketPanelMessage::FlipPair => DashboardMessage::FlipPair,
+                                    MarketPanelMessage::Copy(value) => {
+                                        DashboardMessage::CopyToClipboard(value)
+                                    }
+                                    msg => msg.into(),
+                                })
+                        }
+                        PaneType::Balances => {
+                            self.balances.view(data, copied).map(|msg| match msg {
+                                BalancesMessage::Copy(value) => {
+                                    DashboardMessage::CopyToClipboard(value)
+                                }
+                                msg => msg.into(),
+                            })
+                        }
+                        PaneType::Portfolio => self.portfolio.view().map(DashboardMessage::from),
+                        PaneType::Heatmap => self.heatmap.view().map(DashboardMessage::from),
+                        PaneType::News => self.news.view(data).map(DashboardMessage::from),
+                        PaneType::FundingRate => self
+                            .funding_rate
+                            .view(data, &config.watchlist_favorites)
+                            .map(DashboardMessage::from),
+                        PaneType::Orders => self.orders.view(data).map(DashboardMessage::from),
+                        PaneType::Calculator => self
+                            .calculator
+                            .view(config.fee_maker, config.fee_taker)
+                            .map(DashboardMessage::from),
+                        PaneType::AlgoTrading => {
+                            self.algo_trading.view().map(DashboardMessage::from)
+                        }
+                        PaneType::Liquidations => self.liquidations.view(&data.liquidations),
+                        PaneType::TradeSizer => self
+                            .trade_sizer
+                            .view(
+                                data,
+                                data.symbol_filter(self.market.pair()),
+                                data.prices.get(self.market.pair()).map(f64::from),
+                            )
+                            .map(DashboardMessage::from),
+                        PaneType::History => self.history.view(data).map(DashboardMessage::from),
+                        PaneType::OrderFlow => self
+                            .order_flow
+                            .view(data.symbol_filter(&data.book.0))
+                            .map(DashboardMessage::from),
+                        PaneType::Arbitrage => self
+                            .arbitrage
+                            .view(config.arb_threshold_pct)
+                            .map(DashboardMessage::from),
+                        PaneType::Correlation => {
+                            self.correlation.view().map(DashboardMessage::from)
+                        }
+                        PaneType::SpreadMonitor => self
+                            .spread_monitor
+                            .view(data, &config.watchlist_favorites)
+                            .map(DashboardMessage::from),
+                        PaneType::Sentiment => self
+                            .sentiment
+                            .view(data, &config.watchlist_favorites)
+                            .map(DashboardMessage::from),
+                        #[cfg(feature = "options")]
+                        PaneType::Options => self.options.view().map(DashboardMessage::from),
+                        #[cfg(feature = "perf-pane")]
+                        PaneType::Performance => self
+                            .performance
+                            .view(DashboardMessage::ResetPerformanceStats),
+                        #[cfg(feature = "wgpu-panes")]
+                        PaneType::MarketDepth3D => {
+                            self.market_depth_3d.view().map(DashboardMessage::from)
+                        }
+                    };
+
+                    // layout and drawing happen after pane widgets are built
+                    #[cfg(feature = "perf-pane")]
+                    self.performance.record_view(started.elapsed());
+
+                    body
+                });
+
+                let body: Element<_> = match &self.dragged_symbol {
+                    Some(symbol) if pane.id != PaneType::Prices => mouse_area(body)
+                        .on_release(DashboardMessage::WatchlistDragEnd(id, symbol.clone()))
+                        .into(),
+                    _ => body.into(),
+                };
+
+                pane_grid::Content::new(body)
+                    .title_bar(title_bar)
+                    .style(if is_focused {
+                        style::pane_focused
+                    } else {
+                        style::pane_active
+                    })
             })
-        })
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .spacing(10)
-        .on_click(DashboardMessage::Clicked)
-        .on_drag(DashboardMessage::Dragged)
-        .on_resize(10, DashboardMessage::Resized)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .spacing(style::spacing())
+            .on_click(DashboardMessage::Clicked)
+            .on_drag(DashboardMessage::Dragged)
+            .on_resize(10, move |event| {
+                DashboardMessage::Resized(self.clamp_resize(event, size))
+            });
+
+            TwoFingerDrag::new(grid, move |position| {
+                DashboardMessage::TouchDragged(position, size)
+            })
+            .into()
+        });
+
+        column![
+            toolbar,
+            Floating::new(
+                grid,
+                self.add_pane_menu_opened
+                    .then(|| view_add_pane_menu(self.split_target))
+                    .or_else(|| self
+                        .config_file_menu_opened
+                        .then(|| self.view_config_file_menu()))
+                    .or_else(|| self.search.visible.then(|| self.view_search())),
+                Anchor::TopLeft
+            ),
+        ]
+        .spacing(8)
         .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_roundtrip() {
+        let mut panes = DashboardView::default_layout();
+        let default = LayoutConfig::from_state(&panes);
+        let split = *panes.layout().splits().next().unwrap();
+        panes.resize(split, 0.123_456_79);
+        if let Some((_, pane)) = panes.iter_mut().next() {
+            pane.custom_label = Some("renamed".to_owned());
+        }
+        // changes are detected to save layout
+        assert_ne!(LayoutConfig::from_state(&panes), default);
+
+        let json = serde_json::to_string(&LayoutConfig::from_state(&panes)).unwrap();
+        let restored = pane_grid::State::with_configuration(
+            serde_json::from_str::<LayoutConfig>(&json).unwrap(),
+        );
+
+        assert_eq!(
+            serde_json::to_string(&LayoutConfig::from_state(&restored)).unwrap(),
+            json,
+            "Layout should not change after being saved and loaded"
+        );
+    }
+
+    #[test]
+    fn pane_type_index_roundtrip() {
+        for ty in PaneType::ALL.iter().cloned() {
+            assert_eq!(PaneType::try_from(usize::from(ty.clone())), Ok(ty));
+        }
+
+        // same numbers whichever optional panes are enabled
+        assert_eq!(usize::from(PaneType::Sentiment), 21);
+        #[cfg(feature = "perf-pane")]
+        assert_eq!(usize::from(PaneType::Performance), 23);
+        #[cfg(feature = "wgpu-panes")]
+        assert_eq!(usize::from(PaneType::MarketDepth3D), 24);
+
+        assert_eq!(PaneType::try_from(25), Err(InvalidPaneIndex(25)));
+    }
+
+    #[test]
+    fn search_panes_and_symbols() {
+        let panes = DashboardView::default_layout();
+        let symbols = ["ETHBTC", "BTCUSDT", "WBTCUSDT", "BTCFDUSD"].map(str::to_owned);
+        let mut search = SearchOverlay {
+            query: "btc".to_owned(),
+            ..Default::default()
+        };
+
+        search.search(panes.iter().map(|(id, pane)| (*id, pane)), symbols.iter());
+        let found: Vec<_> = search
+            .results
+            .iter()
+            .filter_map(|result| match result {
+                SearchResult::Symbol(symbol) => Some(symbol.as_str()),
+                SearchResult::Pane(_) => None,
+            })
+            .collect();
+        assert_eq!(found, ["BTCUSDT", "BTCFDUSD", "ETHBTC", "WBTCUSDT"]);
+
+        search.query = "book".to_owned();
+        search.search(panes.iter().map(|(id, pane)| (*id, pane)), symbols.iter());
+        assert!(matches!(search.results.as_slice(), [SearchResult::Pane(_)]));
+    }
+
+    #[test]
+    fn resize_keeps_min_size() {
+        // 10 spacing leaves 495 on each side of even split
+        assert_eq!(clamp_ratio(0.01, 1000.0, 95.0, 10.0), 0.1);
+        assert_eq!(clamp_ratio(0.99, 1000.0, 95.0, 10.0), 0.9);
+        assert_eq!(clamp_ratio(0.3, 1000.0, 95.0, 10.0), 0.3);
+        assert_eq!(clamp_ratio(0.3, 100.0, 95.0, 10.0), 0.5);
+    }
+}
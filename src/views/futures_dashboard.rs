@@ -0,0 +1,150 @@
+use std::fmt::Display;
+
+use iced::{
+    widget::{
+        pane_grid::{self, Configuration},
+        responsive, text, PaneGrid,
+    },
+    Command, Element, Length,
+};
+
+use crate::{api::TradingClient, config::Config, data::AppData, message::Message};
+
+use super::panes::{
+    funding::{FundingMessage, FundingPane},
+    futures_market::{FuturesMarket, FuturesMarketMessage},
+    liquidations::LiquidationsPane,
+    style,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum FuturesPaneType {
+    Funding,
+    Liquidations,
+    Market,
+}
+
+impl Display for FuturesPaneType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FuturesPaneType::Funding => "Funding rates",
+            FuturesPaneType::Liquidations => "Liquidations",
+            FuturesPaneType::Market => "Futures market",
+        }
+        .fmt(f)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum FuturesDashboardMessage {
+    Clicked(pane_grid::Pane),
+    Dragged(pane_grid::DragEvent),
+    Resized(pane_grid::ResizeEvent),
+
+    Funding(FundingMessage),
+    Market(FuturesMarketMessage),
+}
+
+impl From<FundingMessage> for FuturesDashboardMessage {
+    fn from(value: FundingMessage) -> Self {
+        Self::Funding(value)
+    }
+}
+
+impl From<FuturesMarketMessage> for FuturesDashboardMessage {
+    fn from(value: FuturesMarketMessage) -> Self {
+        Self::Market(value)
+    }
+}
+
+/// Derivatives trading layout, lives in its own tab next to spot dashboard
+pub(crate) struct FuturesDashboardView {
+    focus: Option<pane_grid::Pane>,
+    panes: pane_grid::State<FuturesPaneType>,
+    funding: FundingPane,
+    liquidations: LiquidationsPane,
+    market: FuturesMarket,
+}
+
+impl FuturesDashboardView {
+    pub(crate) fn new(config: &Config) -> Self {
+        let panes = pane_grid::State::with_configuration(Configuration::Split {
+            axis: pane_grid::Axis::Vertical,
+            ratio: 0.35,
+            a: Box::new(Configuration::Pane(FuturesPaneType::Funding)),
+            b: Box::new(Configuration::Split {
+                axis: pane_grid::Axis::Vertical,
+                ratio: 0.5,
+                a: Box::new(Configuration::Pane(FuturesPaneType::Liquidations)),
+                b: Box::new(Configuration::Pane(FuturesPaneType::Market)),
+            }),
+        });
+
+        Self {
+            focus: None,
+            panes,
+            funding: FundingPane::new(),
+            liquidations: LiquidationsPane::new(),
+            market: FuturesMarket::new(config.default_pair.clone()),
+        }
+    }
+
+    pub(crate) fn update(
+        &mut self,
+        message: FuturesDashboardMessage,
+        api: &dyn TradingClient,
+        data: &AppData,
+        paper: bool,
+    ) -> Command<Message> {
+        match message {
+            FuturesDashboardMessage::Clicked(pane) => {
+                self.focus = Some(pane);
+                Command::none()
+            }
+            FuturesDashboardMessage::Resized(pane_grid::ResizeEvent { split, ratio }) => {
+                self.panes.resize(split, ratio);
+                Command::none()
+            }
+            FuturesDashboardMessage::Dragged(pane_grid::DragEvent::Dropped { pane, target }) => {
+                self.panes.drop(pane, target);
+                Command::none()
+            }
+            FuturesDashboardMessage::Dragged(_) => Command::none(),
+            FuturesDashboardMessage::Funding(FundingMessage::PairSelected(pair)) => self
+                .market
+                .update(FuturesMarketMessage::PairInput(pair), api, data, paper),
+            FuturesDashboardMessage::Market(msg) => self.market.update(msg, api, data, paper),
+        }
+    }
+
+    pub(crate) fn view<'a>(&'a self, data: &'a AppData) -> Element<'a, FuturesDashboardMessage> {
+        let focus = self.focus;
+
+        PaneGrid::new(&self.panes, |id, pane, _is_maximized| {
+            let title_bar =
+                pane_grid::TitleBar::new(text(pane.to_string())).padding(style::title_padding());
+
+            pane_grid::Content::new(responsive(|_size| match pane {
+                FuturesPaneType::Funding => self
+                    .funding
+                    .view(data, &[])
+                    .map(FuturesDashboardMessage::from),
+                FuturesPaneType::Liquidations => self.liquidations.view(&data.futures.liquidations),
+                FuturesPaneType::Market => self.market.view().map(FuturesDashboardMessage::from),
+            }))
+            .title_bar(title_bar)
+            .style(if focus == Some(id) {
+                style::pane_focused
+            } else {
+                style::pane_active
+            })
+        })
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .spacing(style::spacing())
+        .on_click(FuturesDashboardMessage::Clicked)
+        .on_drag(FuturesDashboardMessage::Dragged)
+        .on_resize(10, FuturesDashboardMessage::Resized)
+        .into()
+    }
+}
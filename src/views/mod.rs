@@ -1,4 +1,13 @@
 pub mod components;
 pub mod dashboard;
+pub mod futures_dashboard;
 pub mod panes;
 pub mod settings;
+pub mod splash;
+
+/// Dashboards shown in tab bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Tab {
+    Spot,
+    Futures,
+}
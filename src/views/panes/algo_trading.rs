@@ -0,0 +1,350 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+use ahash::AHashMap;
+use binance::rest_model::OrderSide;
+use iced::{
+    widget::{
+        button, column, container, row, scrollable, text,
+        text_editor::{self, Content},
+        Column, Space,
+    },
+    Command, Element, Font, Length,
+};
+use iced_futures::futures::channel::mpsc;
+
+use super::orders::t;
+use crate::{
+    api::{OrderType, TradingClient},
+    data::AppData,
+    message::Message,
+    script::{Script, ScriptEnv},
+    theme::{color, Role},
+    views::{components::better_btn::GreenBtn, dashboard::DashboardMessage},
+};
+
+/// Older console lines are dropped
+const CONSOLE_LEN: usize = 200;
+
+/// Longest time stop can go unnoticed while script sleeps
+const STOP_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Script is stopped when it tries to place more orders within window
+const ORDER_RATE_LIMIT: usize = 10;
+const ORDER_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+const EXAMPLE_SCRIPT: &str = r#"# sell 10% above current price
+let target = price("BTCUSDT") * 1.1
+print("waiting for", target)
+loop {
+    if price("BTCUSDT") >= target {
+        sell(target, 0.001)
+        break
+    }
+    sleep(1000)
+}
+"#;
+
+/// Something running script did
+#[derive(Debug, Clone)]
+pub(crate) enum ScriptEvent {
+    Print(String),
+    Order {
+        pair: String,
+        side: OrderSide,
+        price: f64,
+        qty: f64,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum AlgoTradingMessage {
+    Action(text_editor::Action),
+    Run,
+    Stop,
+    Output(ScriptEvent),
+    Finished(Result<(), String>),
+}
+
+/// Records order at `now`, false if limit of orders within window is already reached
+fn allow_order(recent: &mut VecDeque<Instant>, now: Instant) -> bool {
+    while recent
+        .front()
+        .is_some_and(|at| now.duration_since(*at) >= ORDER_RATE_WINDOW)
+    {
+        recent.pop_front();
+    }
+    if recent.len() >= ORDER_RATE_LIMIT {
+        return false;
+    }
+
+    recent.push_back(now);
+    true
+}
+
+/// Environment of script running in blocking task
+struct TaskEnv {
+    prices: Arc<RwLock<AHashMap<String, f64>>>,
+    events: mpsc::UnboundedSender<ScriptEvent>,
+    stop: Arc<AtomicBool>,
+    /// Market selected when script started, switching market does not move its orders
+    pair: String,
+    /// Times of orders within rate window
+    recent_orders: VecDeque<Instant>,
+}
+
+impl ScriptEnv for TaskEnv {
+    fn price(&self, symbol: &str) -> Option<f64> {
+        self.prices
+            .read()
+            .expect("prices lock is not poisoned")
+            .get(symbol)
+            .copied()
+    }
+
+    fn order(&mut self, side: OrderSide, price: f64, qty: f64) {
+        if !allow_order(&mut self.recent_orders, Instant::now()) {
+            self.print(format!(
+                "more than {ORDER_RATE_LIMIT} orders in {}s, stopping",
+                ORDER_RATE_WINDOW.as_secs()
+            ));
+            self.stop.store(true, Ordering::Relaxed);
+            return;
+        }
+
+        let _ = self.events.unbounded_send(ScriptEvent::Order {
+            pair: self.pair.clone(),
+            side,
+            price,
+            qty,
+        });
+    }
+
+    fn print(&mut self, line: String) {
+        let _ = self.events.unbounded_send(ScriptEvent::Print(line));
+    }
+
+    fn sleep(&mut self, duration: Duration) -> bool {
+        let mut left = duration;
+        while !left.is_zero() {
+            if self.is_stopped() {
+                return false;
+            }
+            let step = left.min(STOP_CHECK_INTERVAL);
+            std::thread::sleep(step);
+            left -= step;
+        }
+        true
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+}
+
+fn to_message(msg: AlgoTradingMessage) -> Message {
+    DashboardMessage::from(msg).into()
+}
+
+pub(crate) struct AlgoTradingPane {
+    content: Content,
+    console: Vec<String>,
+    /// Set to stop running script
+    stop: Option<Arc<AtomicBool>>,
+    /// Prices visible to script, refreshed every tick while it runs
+    prices: Arc<RwLock<AHashMap<String, f64>>>,
+}
+
+impl AlgoTradingPane {
+    pub(crate) fn new() -> Self {
+        Self {
+            content: Content::with_text(EXAMPLE_SCRIPT),
+            console: Vec::new(),
+            stop: None,
+            prices: Arc::default(),
+        }
+    }
+
+    fn log(&mut self, line: String) {
+        self.console.push(line);
+        if self.console.len() > CONSOLE_LEN {
+            self.console.remove(0);
+        }
+    }
+
+    pub(crate) fn tick(&mut self, data: &AppData) {
+        if self.stop.is_none() {
+            return;
+        }
+
+        *self.prices.write().expect("prices lock is not poisoned") = data
+            .prices
+            .all()
+            .map(|(name, price)| (name.clone(), f64::from(*price)))
+            .collect();
+    }
+
+    /// Script started by Run places orders on `pair`, with paper broker if `paper` is set
+    pub(crate) fn update(
+        &mut self,
+        msg: AlgoTradingMessage,
+        api: &dyn TradingClient,
+        data: &AppData,
+        pair: &str,
+        paper: bool,
+    ) -> Command<Message> {
+        match msg {
+            AlgoTradingMessage::Action(action) => {
+                self.content.perform(action);
+                Command::none()
+            }
+            AlgoTradingMessage::Run => {
+                let script = match Script::parse(&self.content.text()) {
+                    Ok(script) => script,
+                    Err(err) => {
+                        self.log(format!("error: {err}"));
+                        return Command::none();
+                    }
+                };
+
+                let stop = Arc::new(AtomicBool::new(false));
+                let (events, receiver) = mpsc::unbounded();
+                let mut env = TaskEnv {
+                    prices: Arc::clone(&self.prices),
+                    events,
+                    stop: Arc::clone(&stop),
+                    pair: pair.to_owned(),
+                    recent_orders: VecDeque::new(),
+                };
+                self.stop = Some(stop);
+                // prices are only refreshed while running
+                self.tick(data);
+                self.log("started".to_owned());
+
+                Command::batch([
+                    Command::run(receiver, |event| {
+                        to_message(AlgoTradingMessage::Output(event))
+                    }),
+                    Command::perform(
+                        async move {
+                            tokio::task::spawn_blocking(move || script.run(&mut env))
+                                .await
+                                .map_err(|err| err.to_string())?
+                        },
+                        |result| to_message(AlgoTradingMessage::Finished(result)),
+                    ),
+                ])
+            }
+            AlgoTradingMessage::Stop => {
+                if let Some(stop) = &self.stop {
+                    stop.store(true, Ordering::Relaxed);
+                    self.log("stopping".to_owned());
+                }
+                Command::none()
+            }
+            AlgoTradingMessage::Finished(result) => {
+                self.stop = None;
+                self.log(match result {
+                    Ok(()) => "finished".to_owned(),
+                    Err(err) => format!("error: {err}"),
+                });
+                Command::none()
+            }
+            AlgoTradingMessage::Output(ScriptEvent::Print(line)) => {
+                self.log(line);
+                Command::none()
+            }
+            AlgoTradingMessage::Output(ScriptEvent::Order {
+                pair,
+                side,
+                price,
+                qty,
+            }) => {
+                self.log(format!("{side:?} {qty} {pair} at {price}"));
+
+                if paper {
+                    return Command::perform(async {}, move |_| Message::PaperOrder {
+                        pair,
+                        price,
+                        amt: qty,
+                        side,
+                        order_type: OrderType::Limit,
+                    });
+                }
+
+                api.trade_spot(pair, price, qty, side, OrderType::Limit)
+            }
+        }
+    }
+
+    pub(crate) fn view(&self) -> Element<'_, AlgoTradingMessage> {
+        let is_running = self.stop.is_some();
+
+        let toolbar = row![
+            button(
+                text(if is_running { '\u{F592}' } else { '\u{F4F4}' })
+                    .font(Font::with_name("bootstrap-icons"))
+            )
+            .style(iced::theme::Button::Custom(Box::new(GreenBtn {})))
+            .on_press(if is_running {
+                AlgoTradingMessage::Stop
+            } else {
+                AlgoTradingMessage::Run
+            }),
+            t(if is_running { "running" } else { "stopped" }).style(if is_running {
+                color(Role::Accent)
+            } else {
+                color(Role::Muted)
+            }),
+            Space::new(Length::Fill, 0),
+        ]
+        .spacing(8)
+        .align_items(iced::Alignment::Center);
+
+        let console = scrollable(
+            Column::with_children(
+                self.console
+                    .iter()
+                    .map(|line| t(line).style(color(Role::Secondary)).into()),
+            )
+            .width(Length::Fill),
+        )
+        .height(Length::FillPortion(2));
+
+        container(
+            column![
+                toolbar,
+                text_editor::TextEditor::new(&self.content)
+                    .height(Length::FillPortion(3))
+                    .on_action(AlgoTradingMessage::Action),
+                console,
+            ]
+            .spacing(8),
+        )
+        .padding(10)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order_rate_is_capped() {
+        let mut recent = VecDeque::new();
+        let start = Instant::now();
+
+        for _ in 0..ORDER_RATE_LIMIT {
+            assert!(allow_order(&mut recent, start));
+        }
+        assert!(!allow_order(&mut recent, start + Duration::from_secs(1)));
+        assert!(allow_order(&mut recent, start + ORDER_RATE_WINDOW));
+    }
+}
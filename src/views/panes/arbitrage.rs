@@ -0,0 +1,489 @@
+use std::collections::VecDeque;
+
+use ahash::{AHashMap, AHashSet};
+use binance::rest_model::OrderSide;
+
+use super::orders::{t, tb};
+use crate::{
+    api::{split_symbol, MarketFill, TradingClient},
+    data::{AppData, Prices},
+    message::Message,
+    notification::{Notification, Severity},
+    theme::{color, Role},
+    views::{
+        components::{better_btn::GreenBtn, input::Inp},
+        dashboard::DashboardMessage,
+    },
+    ws::book_ticker::BookTick,
+};
+
+use iced::{
+    widget::{button, column, container, row, scrollable, text, text_input, Column, Space},
+    Alignment, Command, Element, Length,
+};
+
+/// Decimals of quote amount spent by market buys, precision of most quote assets
+const QUOTE_DECIMALS: i32 = 8;
+
+#[derive(Debug, Clone)]
+pub(crate) enum ArbitrageMessage {
+    /// Amount of starting asset traded around route
+    AmountInput(String),
+    /// Place orders of route through assets, list could change before press is handled
+    Take([String; 3]),
+    /// Order of current leg was executed, next one trades what it returned
+    LegFilled(Result<MarketFill, String>),
+}
+
+/// Pair converting `from` into `to` and whether it is bought, None if neither direction is listed
+fn listed_pair(prices: &Prices, from: &str, to: &str) -> Option<(String, bool)> {
+    let bought = format!("{to}{from}");
+    if prices.get(&bought).is_some() {
+        return Some((bought, true));
+    }
+
+    let sold = format!("{from}{to}");
+    prices.get(&sold).is_some().then_some((sold, false))
+}
+
+/// Conversion of one asset into another through a pair
+#[derive(Debug, Clone, PartialEq)]
+struct Hop {
+    pair: String,
+    /// Pair is bought, its base asset is received
+    buy: bool,
+    /// Ask when bought, bid when sold
+    price: f64,
+}
+
+impl Hop {
+    /// None if pair is not listed or its book ticker has not arrived
+    fn new(
+        prices: &Prices,
+        book_tickers: &AHashMap<String, BookTick>,
+        from: &str,
+        to: &str,
+    ) -> Option<Self> {
+        let (pair, buy) = listed_pair(prices, from, to)?;
+        let tick = book_tickers.get(&pair)?;
+
+        Some(Self {
+            price: if buy { tick.ask } else { tick.bid },
+            pair,
+            buy,
+        })
+        .filter(|hop| hop.price > 0.0)
+    }
+
+    fn side(&self) -> OrderSide {
+        if self.buy {
+            OrderSide::Buy
+        } else {
+            OrderSide::Sell
+        }
+    }
+
+    /// Amount received for `amount` sent, after fee
+    fn convert(&self, amount: f64, fee: f64) -> f64 {
+        let received = if self.buy {
+            amount / self.price
+        } else {
+            amount * self.price
+        };
+
+        received * (1.0 - fee)
+    }
+
+    /// Amount received by executed order, after fee
+    fn received(&self, fill: MarketFill, fee: f64) -> f64 {
+        let received = if self.buy { fill.qty } else { fill.quote_qty };
+
+        received * (1.0 - fee)
+    }
+}
+
+/// Round trip through three assets ending where it started
+#[derive(Debug, Clone)]
+struct Opportunity {
+    /// Assets in order of conversion, first one is also the last
+    assets: [String; 3],
+    hops: [Hop; 3],
+    /// Gain of one round in percent, fees included
+    profit_pct: f64,
+}
+
+impl Opportunity {
+    fn new(
+        prices: &Prices,
+        book_tickers: &AHashMap<String, BookTick>,
+        assets: [&str; 3],
+        fee: f64,
+    ) -> Option<Self> {
+        let hops = [
+            Hop::new(prices, book_tickers, assets[0], assets[1])?,
+            Hop::new(prices, book_tickers, assets[1], assets[2])?,
+            Hop::new(prices, book_tickers, assets[2], assets[0])?,
+        ];
+        let returned = hops
+            .iter()
+            .fold(1.0, |amount, hop| hop.convert(amount, fee));
+
+        Some(Self {
+            assets: assets.map(ToOwned::to_owned),
+            hops,
+            profit_pct: (returned - 1.0) * 100.0,
+        })
+    }
+}
+
+/// Both directions around every triangle of assets of `favorites`
+///
+/// Every two favorites sharing an asset make one triangle, so this is quadratic over favorites
+fn routes<'a>(favorites: &'a [String], preferred_start: &str) -> Vec<[&'a str; 3]> {
+    let assets: Vec<_> = favorites.iter().filter_map(|s| split_symbol(s)).collect();
+    let mut seen = AHashSet::new();
+    let mut found = Vec::new();
+
+    for (i, &[a_base, a_quote]) in assets.iter().enumerate() {
+        for &[b_base, b_quote] in &assets[i + 1..] {
+            let (shared, a, b) = if a_base == b_base {
+                (a_base, a_quote, b_quote)
+            } else if a_base == b_quote {
+                (a_base, a_quote, b_base)
+            } else if a_quote == b_base {
+                (a_quote, a_base, b_quote)
+            } else if a_quote == b_quote {
+                (a_quote, a_base, b_base)
+            } else {
+                continue;
+            };
+            if a == b {
+                continue;
+            }
+
+            let mut key = [shared, a, b];
+            key.sort_unstable();
+            if !seen.insert(key) {
+                continue;
+            }
+
+            // routes start from quote asset when possible so that profit is in it
+            let [start, x, y] = if a == preferred_start {
+                [a, shared, b]
+            } else if b == preferred_start {
+                [b, shared, a]
+            } else {
+                [shared, a, b]
+            };
+
+            found.extend([[start, x, y], [start, y, x]]);
+        }
+    }
+
+    found
+}
+
+/// Pairs traded by routes through favorites, their book tickers price routes
+fn route_pairs(favorites: &[String], prices: &Prices, preferred_start: &str) -> Vec<String> {
+    let mut pairs: Vec<_> = routes(favorites, preferred_start)
+        .into_iter()
+        .flat_map(|[a, b, c]| [(a, b), (b, c), (c, a)])
+        .filter_map(|(from, to)| listed_pair(prices, from, to))
+        .map(|(pair, _)| pair)
+        .collect();
+    pairs.sort_unstable();
+    pairs.dedup();
+
+    pairs
+}
+
+/// Routes more profitable than `threshold_pct` at current best bid and ask, best first
+fn find_opportunities(
+    favorites: &[String],
+    prices: &Prices,
+    book_tickers: &AHashMap<String, BookTick>,
+    fee: f64,
+    threshold_pct: f64,
+    preferred_start: &str,
+) -> Vec<Opportunity> {
+    let mut found: Vec<_> = routes(favorites, preferred_start)
+        .into_iter()
+        .filter_map(|route| Opportunity::new(prices, book_tickers, route, fee))
+        .filter(|o| o.profit_pct > threshold_pct)
+        .collect();
+
+    found.sort_by(|a, b| b.profit_pct.total_cmp(&a.profit_pct));
+    found
+}
+
+pub(crate) struct ArbitragePane {
+    opportunities: Vec<Opportunity>,
+    /// Pairs of routes through favorites, book tickers are streamed for them
+    pairs: Vec<String>,
+    amount: String,
+    /// Hops of taken route not traded yet
+    legs: VecDeque<Hop>,
+    /// Hop whose order is being executed
+    sending: Option<Hop>,
+}
+
+impl ArbitragePane {
+    pub(crate) fn new() -> Self {
+        Self {
+            opportunities: Vec::new(),
+            pairs: Vec::new(),
+            amount: String::new(),
+            legs: VecDeque::new(),
+            sending: None,
+        }
+    }
+
+    pub(crate) fn pairs(&self) -> &[String] {
+        &self.pairs
+    }
+
+    /// Re-evaluates triangles of favorites with latest book tickers
+    pub(crate) fn tick(
+        &mut self,
+        data: &AppData,
+        favorites: &[String],
+        threshold_pct: f64,
+        preferred_start: &str,
+    ) {
+        self.pairs = route_pairs(favorites, &data.prices, preferred_start);
+        self.opportunities = find_opportunities(
+            favorites,
+            &data.prices,
+            &data.book_tickers,
+            data.fee_tier,
+            threshold_pct,
+            preferred_start,
+        );
+    }
+
+    pub(crate) fn update(
+        &mut self,
+        msg: ArbitrageMessage,
+        api: &dyn TradingClient,
+        data: &AppData,
+        paper: bool,
+    ) -> Command<Message> {
+        match msg {
+            ArbitrageMessage::AmountInput(amount) => {
+                self.amount = amount;
+                Command::none()
+            }
+            ArbitrageMessage::Take(assets) => {
+                let opportunity = self.opportunities.iter().find(|o| o.assets == assets);
+                let (Some(opportunity), Ok(amount)) = (opportunity, self.amount.parse::<f64>())
+                else {
+                    return Command::none();
+                };
+
+                self.legs = opportunity.hops.clone().into();
+                self.send_leg(amount, api, data, paper)
+            }
+            ArbitrageMessage::LegFilled(Ok(fill)) => {
+                let Some(hop) = self.sending.take() else {
+                    return Command::none();
+                };
+                let received = hop.received(fill, data.fee_tier);
+
+                if self.legs.is_empty() {
+                    let asset = split_symbol(&hop.pair)
+                        .map(|[base, quote]| if hop.buy { base } else { quote })
+                        .unwrap_or_default()
+                        .to_owned();
+                    return Command::perform(async {}, move |_| {
+                        Message::PushNotification(Notification::new(
+                            Severity::Info,
+                            format!("route finished with {received} {asset}"),
+                        ))
+                    });
+                }
+
+                self.send_leg(received, api, data, paper)
+            }
+            ArbitrageMessage::LegFilled(Err(err)) => {
+                let pair = self.sending.take().map(|hop| hop.pair).unwrap_or_default();
+                self.legs.clear();
+
+                Command::perform(async {}, move |_| {
+                    Message::ToastError(format!("route aborted at {pair}: {err}"))
+                })
+            }
+        }
+    }
+
+    /// Trades `amount` received by previous leg, route is aborted if it rounds to nothing
+    fn send_leg(
+        &mut self,
+        amount: f64,
+        api: &dyn TradingClient,
+        data: &AppData,
+        paper: bool,
+    ) -> Command<Message> {
+        let Some(hop) = self.legs.pop_front() else {
+            return Command::none();
+        };
+
+        // sells can not exceed what was received, buys spend quote amount as is
+        let amount = if hop.buy {
+            let scale = 10f64.powi(QUOTE_DECIMALS);
+            (amount * scale).floor() / scale
+        } else {
+            data.symbol_filter(&hop.pair).floor_qty(amount)
+        };
+        if amount <= 0.0 {
+            self.legs.clear();
+            let pair = hop.pair;
+            return Command::perform(async {}, move |_| {
+                Message::ToastError(format!(
+                    "route aborted at {pair}: amount is below step size"
+                ))
+            });
+        }
+
+        let (pair, side) = (hop.pair.clone(), hop.side());
+        self.sending = Some(hop);
+        let reply = |fill| DashboardMessage::from(ArbitrageMessage::LegFilled(fill)).into();
+
+        if paper {
+            Command::perform(async {}, move |_| Message::PaperMarketOrder {
+                pair,
+                side,
+                amount,
+                reply,
+            })
+        } else {
+            api.market_order(pair, side, amount, reply)
+        }
+    }
+
+    pub(crate) fn view(&self, threshold_pct: f64) -> Element<'_, ArbitrageMessage> {
+        let warning = container(
+            text(
+                "Routes are three market orders, each trading what previous one returned. Prices \
+                 move between them and slippage can turn expected profit into a loss.",
+            )
+            .size(12)
+            .style(iced::Color::BLACK),
+        )
+        .padding([4, 8])
+        .width(Length::Fill)
+        .style(container::Appearance {
+            background: Some(iced::Background::Color(color(Role::Warning))),
+            ..Default::default()
+        });
+
+        let amount_valid = self
+            .amount
+            .parse::<f64>()
+            .is_ok_and(|a| a.is_finite() && a > 0.0);
+        let taking = self.sending.is_some();
+
+        let toolbar = row![
+            tb(format!("Above {threshold_pct}%")),
+            Space::new(Length::Fill, 0),
+            t(if taking {
+                format!("{} orders left", self.legs.len() + 1)
+            } else {
+                String::new()
+            })
+            .style(color(Role::Secondary)),
+            text_input("amount", &self.amount)
+                .on_input(ArbitrageMessage::AmountInput)
+                .style(iced::theme::TextInput::Custom(Box::new(Inp {})))
+                .size(12)
+                .width(120.0),
+        ]
+        .spacing(8)
+        .align_items(Alignment::Center);
+
+        let rows = self.opportunities.iter().map(|o| {
+            row![
+                t(format!(
+                    "{} → {} → {} → {}",
+                    o.assets[0], o.assets[1], o.assets[2], o.assets[0]
+                ))
+                .width(Length::Fill),
+                t(format!("{:.3}%", o.profit_pct)).style(color(Role::Accent)),
+                button(text("Take").size(12).style(iced::Color::WHITE))
+                    .style(iced::theme::Button::Custom(Box::new(GreenBtn {})))
+                    .padding([2, 8])
+                    .on_press_maybe(
+                        (amount_valid && !taking).then(|| ArbitrageMessage::Take(o.assets.clone()))
+                    ),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center)
+            .into()
+        });
+
+        let list: Element<_> = if self.opportunities.is_empty() {
+            t("No routes above threshold")
+                .style(color(Role::Secondary))
+                .into()
+        } else {
+            scrollable(Column::with_children(rows).spacing(4)).into()
+        };
+
+        column![warning, toolbar, list]
+            .padding([2, 12])
+            .spacing(4)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws::prices::AssetDetails;
+
+    #[test]
+    fn triangle_profit() {
+        let mut prices = Prices::default();
+        prices.add_many(
+            [
+                ("ETHBTC", 0.05),
+                ("BTCUSDT", 40_000.0),
+                ("ETHUSDT", 2_100.0),
+            ]
+            .map(|(name, price)| AssetDetails {
+                name: name.to_owned(),
+                price,
+            })
+            .into(),
+        );
+
+        let book_tickers: AHashMap<_, _> = [
+            ("ETHBTC", 0.0499, 0.05),
+            ("BTCUSDT", 39_990.0, 40_000.0),
+            ("ETHUSDT", 2_100.0, 2_101.0),
+        ]
+        .into_iter()
+        .map(|(name, bid, ask)| (name.to_owned(), BookTick { bid, ask }))
+        .collect();
+
+        let favorites = ["BTCUSDT".to_owned(), "ETHUSDT".to_owned()];
+        assert_eq!(
+            route_pairs(&favorites, &prices, "USDT"),
+            ["BTCUSDT", "ETHBTC", "ETHUSDT"]
+        );
+
+        // USDT → BTC → ETH → USDT is (1 / ask_BTCUSDT) * (1 / ask_ETHBTC) * bid_ETHUSDT - 1
+        let found = find_opportunities(&favorites, &prices, &book_tickers, 0.0, 0.0, "USDT");
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].assets,
+            ["USDT", "BTC", "ETH"].map(ToOwned::to_owned)
+        );
+        let expected = (1.0 / 40_000.0) * (1.0 / 0.05) * 2_100.0 - 1.0;
+        assert!((found[0].profit_pct - expected * 100.0).abs() < 1e-3);
+
+        // fees of three orders eat the 5%
+        assert!(
+            find_opportunities(&favorites, &prices, &book_tickers, 0.02, 0.0, "USDT").is_empty()
+        );
+    }
+}
@@ -1,19 +1,32 @@
 use crate::{
     data::AppData,
     svg_logos,
-    theme::h2c,
-    views::{
-        components::{loading::loader, unstyled_btn::UnstyledBtn},
-        dashboard::DashboardMessage,
+    theme::{color, Role},
+    views::components::{
+        copyable::copyable,
+        loading::{error_placeholder, loader, placeholder},
+        sparkline::sparkline,
+        unstyled_btn::UnstyledBtn,
     },
 };
 
 use iced::{
-    widget::{button, container, row, svg, text, Column, Space},
+    widget::{button, column, container, row, svg, text, Column, Space},
     Element, Length,
 };
 
-use super::orders::tb;
+use super::orders::{t, tb};
+
+const SPARKLINE_WIDTH: f32 = 60.0;
+const SPARKLINE_HEIGHT: f32 = 16.0;
+
+#[derive(Debug, Clone)]
+pub(crate) enum BalancesMessage {
+    /// Asset clicked, sets market pair
+    AssetSelected(String),
+    /// Amount right clicked
+    Copy(String),
+}
 
 pub(crate) struct BalancesPane {}
 
@@ -22,14 +35,50 @@ impl BalancesPane {
         Self {}
     }
 
-    pub(crate) fn view<'a>(&'a self, data: &'a AppData) -> Element<'a, DashboardMessage> {
+    pub(crate) fn view<'a>(
+        &'a self,
+        data: &'a AppData,
+        copied: Option<&str>,
+    ) -> Element<'a, BalancesMessage> {
         let bs = &data.balances;
 
-        if bs.is_empty() {
+        if !data.loader.balances {
             return loader!().into();
         }
+        if let (true, Some(error)) = (bs.is_empty(), &data.loader.balances_error) {
+            return error_placeholder(error);
+        }
+        if bs.is_empty() {
+            return placeholder("No balances");
+        }
+
+        let total = data.portfolio_total();
+        let change = total
+            .zip(data.portfolio_value_24h_ago())
+            .filter(|(_, then)| *then > 0.0)
+            .map(|(now, then)| (now - then) / then * 100.0);
 
-        Column::with_children(
+        let header = row![
+            tb("Total").size(14),
+            Space::new(Length::Fill, 1.0),
+            sparkline(
+                data.portfolio_values(None),
+                SPARKLINE_WIDTH,
+                SPARKLINE_HEIGHT
+            ),
+            t(total.map_or_else(|| "-".to_owned(), |total| format!("{total:.2} USDT"))),
+            t(change.map_or_else(String::new, |change| format!("{change:+.2}%"))).style(
+                if change.unwrap_or(0.0) >= 0.0 {
+                    color(Role::Accent)
+                } else {
+                    color(Role::Danger)
+                }
+            ),
+        ]
+        .spacing(8)
+        .align_items(iced::Alignment::Center);
+
+        let rows = Column::with_children(
             bs.iter()
                 .map(|b| {
                     let asset = &b.asset;
@@ -43,29 +92,42 @@ impl BalancesPane {
                     let svg = svg(handle)
                         .width(Length::Fixed(16.0))
                         .height(Length::Fixed(16.0));
-                    container(row![
+                    container(
                         row![
-                            svg,
-                            button(tb(&b.asset).size(14).style(h2c("B7BDB7").unwrap()))
+                            row![
+                                svg,
+                                button(tb(&b.asset).size(14).style(color(Role::Secondary)))
+                                    .style(iced::theme::Button::Custom(Box::new(UnstyledBtn {})))
+                                    .on_press(BalancesMessage::AssetSelected(b.asset.clone())),
+                            ]
+                            .spacing(4)
+                            .align_items(iced::Alignment::Center),
+                            Space::new(Length::Fill, 1.0),
+                            sparkline(
+                                data.portfolio_values(Some(asset)),
+                                SPARKLINE_WIDTH,
+                                SPARKLINE_HEIGHT
+                            ),
+                            copyable(
+                                button(
+                                    text(format!("{}", (b.free * 10.0).round() / 10.0))
+                                        .size(14)
+                                        .style(color(Role::Secondary))
+                                )
                                 .style(iced::theme::Button::Custom(Box::new(UnstyledBtn {})))
-                                .on_press(DashboardMessage::CurrencyPairSelected(b.asset.clone())),
+                                .on_press(BalancesMessage::AssetSelected(b.asset.clone())),
+                                b.free.to_string(),
+                                copied,
+                                BalancesMessage::Copy,
+                            ),
                         ]
-                        .spacing(4)
                         .align_items(iced::Alignment::Center),
-                        Space::new(Length::Fill, 1.0),
-                        button(
-                            text(format!("{}", (b.free * 10.0).round() / 10.0))
-                                .size(14)
-                                .style(h2c("B7BDB7").unwrap())
-                        )
-                        .style(iced::theme::Button::Custom(Box::new(UnstyledBtn {})))
-                        .on_press(DashboardMessage::CurrencyPairSelected(b.asset.clone())),
-                    ])
+                    )
                     .width(Length::Fill)
                 })
                 .map(Element::from),
-        )
-        .padding(8)
-        .into()
+        );
+
+        column![header, rows].spacing(8).padding(8).into()
     }
 }
@@ -1,30 +1,228 @@
-use super::orders::{t, tb};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Display,
+};
+
+use super::{
+    depth_chart::parse_levels,
+    orders::{t, tb},
+};
 
 use crate::{
-    data::AppData,
-    theme::h2c,
-    views::{components::loading::loader, dashboard::DashboardMessage},
+    data::{AppData, SymbolFilter},
+    theme::{color, Role},
+    views::components::{better_btn::BetterBtn, input::Inp, loading::loader, sparkline::sparkline},
 };
 
 use iced::{
-    widget::{column, row, Column, Container},
-    Element, Length,
+    widget::{button, column, row, text, text_input, Column, Container, Row},
+    Alignment, Element, Length,
 };
+use serde::{Deserialize, Serialize};
+
+/// Size of price buckets levels are summed into
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) enum BookGrouping {
+    #[default]
+    Raw,
+    Agg0_01,
+    Agg0_1,
+    Agg1_0,
+    Agg10_0,
+}
+
+impl BookGrouping {
+    const ALL: [BookGrouping; 5] = [
+        BookGrouping::Raw,
+        BookGrouping::Agg0_01,
+        BookGrouping::Agg0_1,
+        BookGrouping::Agg1_0,
+        BookGrouping::Agg10_0,
+    ];
+
+    fn step(&self) -> Option<f64> {
+        match self {
+            BookGrouping::Raw => None,
+            BookGrouping::Agg0_01 => Some(0.01),
+            BookGrouping::Agg0_1 => Some(0.1),
+            BookGrouping::Agg1_0 => Some(1.0),
+            BookGrouping::Agg10_0 => Some(10.0),
+        }
+    }
+
+    /// Following grouping, wraps around to raw
+    pub(crate) fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|g| *g == self).unwrap_or(0);
+
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+impl Display for BookGrouping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BookGrouping::Raw => "Raw",
+            BookGrouping::Agg0_01 => "0.01",
+            BookGrouping::Agg0_1 => "0.1",
+            BookGrouping::Agg1_0 => "1",
+            BookGrouping::Agg10_0 => "10",
+        }
+        .fmt(f)
+    }
+}
 
-pub(crate) struct BookPane {}
+/// Older spread samples are dropped
+const SPREAD_HISTORY_LEN: usize = 500;
+
+#[derive(Debug, Clone)]
+pub(crate) enum BookMessage {
+    GroupingSelected(BookGrouping),
+    /// Spread percent above which pane warns
+    SpreadAlertInput(String),
+}
+
+/// Best ask minus best bid and its percent of mid price
+fn spread(bids: &BTreeMap<String, f64>, asks: &BTreeMap<String, f64>) -> Option<(f64, f64)> {
+    let best_bid = parse_levels(bids.iter()).last()?.0;
+    let best_ask = parse_levels(asks.iter()).first()?.0;
+    let mid = (best_bid + best_ask) / 2.0;
+    if mid <= 0.0 {
+        return None;
+    }
+
+    let spread = best_ask - best_bid;
+
+    Some((spread, spread / mid * 100.0))
+}
+
+/// Levels in ascending order, summed into buckets of grouping
+///
+/// Asks are rounded up and bids down like on exchange so best bid and ask never share a bucket
+fn group_levels(
+    side: &BTreeMap<String, f64>,
+    grouping: BookGrouping,
+    round_up: bool,
+) -> Vec<(f64, f64)> {
+    let levels = parse_levels(side.iter());
+    let Some(step) = grouping.step() else {
+        return levels;
+    };
+
+    let mut buckets = BTreeMap::<i64, f64>::new();
+    for (price, qty) in levels {
+        let bucket = if round_up {
+            (price / step).ceil()
+        } else {
+            (price / step).floor()
+        };
+        *buckets.entry(bucket as i64).or_default() += qty;
+    }
+
+    buckets
+        .into_iter()
+        .map(|(bucket, qty)| (bucket as f64 * step, qty))
+        .collect()
+}
+
+pub(crate) struct BookPane {
+    /// Symbol spread history belongs to
+    symbol: String,
+    /// Spread percent of every book update
+    spread_history: VecDeque<f64>,
+    spread_alert: String,
+}
 
 impl BookPane {
     pub(crate) fn new() -> Self {
-        Self {}
+        Self {
+            symbol: String::new(),
+            spread_history: VecDeque::with_capacity(SPREAD_HISTORY_LEN),
+            spread_alert: String::new(),
+        }
     }
 
-    pub(crate) fn view<'a>(&'a self, data: &'a AppData) -> Element<'a, DashboardMessage> {
+    /// Records spread of new book, history starts over when pair changes
+    pub(crate) fn book_received(&mut self, data: &AppData) {
+        let (symbol, bids, asks) = &data.book;
+        if *symbol != self.symbol {
+            self.symbol.clone_from(symbol);
+            self.spread_history.clear();
+        }
+
+        if let Some((_, pct)) = spread(bids, asks) {
+            if self.spread_history.len() == SPREAD_HISTORY_LEN {
+                self.spread_history.pop_front();
+            }
+            self.spread_history.push_back(pct);
+        }
+    }
+
+    pub(crate) fn update(&mut self, msg: BookMessage) {
+        match msg {
+            // handled by dashboard
+            BookMessage::GroupingSelected(_) => (),
+            BookMessage::SpreadAlertInput(s) => self.spread_alert = s,
+        }
+    }
+
+    pub(crate) fn view<'a>(
+        &'a self,
+        data: &'a AppData,
+        filter: SymbolFilter,
+        grouping: BookGrouping,
+    ) -> Element<'a, BookMessage> {
         let book = &data.book;
 
         if book.1.is_empty() {
             return loader!().into();
         }
 
+        let grouping_buttons = Row::with_children(
+            BookGrouping::ALL
+                .map(|g| {
+                    button(text(g.to_string()).size(12))
+                        .padding([2, 6])
+                        .style(if g == grouping {
+                            iced::theme::Button::Custom(Box::new(BetterBtn {}))
+                        } else {
+                            iced::theme::Button::Text
+                        })
+                        .on_press(BookMessage::GroupingSelected(g))
+                })
+                .map(Element::from),
+        )
+        .spacing(2);
+
+        let spread_row = match spread(&book.1, &book.2) {
+            Some((spread, pct)) => {
+                let alert = self
+                    .spread_alert
+                    .parse::<f64>()
+                    .is_ok_and(|threshold| pct > threshold);
+                let spread_color = if alert {
+                    color(Role::Warning)
+                } else {
+                    color(Role::Foreground)
+                };
+
+                row![
+                    tb(format!("Spread {} ({pct:.3}%)", filter.price(spread))).style(spread_color),
+                    sparkline(self.spread_history.iter().copied().collect(), 80.0, 16.0),
+                ]
+                .push_maybe(alert.then(|| t("wide spread").style(color(Role::Warning))))
+            }
+            None => row![tb("Spread -")],
+        }
+        .push(
+            text_input("alert %", &self.spread_alert)
+                .on_input(BookMessage::SpreadAlertInput)
+                .style(iced::theme::TextInput::Custom(Box::new(Inp {})))
+                .size(12)
+                .width(70),
+        )
+        .spacing(8)
+        .align_items(Alignment::Center);
+
         let header = row![
             tb("Price").width(Length::Fill),
             tb("Amount").width(Length::Fill),
@@ -32,62 +230,51 @@ impl BookPane {
         ]
         .spacing(10);
 
+        let level_row = |(price, quantity): (f64, f64), side_color| {
+            row![
+                t(filter.price(price))
+                    .width(Length::FillPortion(1))
+                    .style(side_color),
+                t(filter.qty(quantity))
+                    .width(Length::FillPortion(1))
+                    .style(color(Role::Secondary)),
+                t(format!("{:.2}", price * quantity))
+                    .style(color(Role::Secondary))
+                    .width(Length::FillPortion(1)),
+            ]
+            .spacing(10)
+            .into()
+        };
+
+        let bids = group_levels(&book.1, grouping, false);
+        let asks = group_levels(&book.2, grouping, true);
+
+        // best ask is at the bottom, next to best bid
+        let mut best_asks: Vec<_> = asks.into_iter().take(12).collect();
+        best_asks.reverse();
         let ask_rows = Column::with_children(
-            book.2
-                .iter()
-                .rev()
-                .take(12)
-                .map(|(price, quantity)| {
-                    row![
-                        t(format!("{:.2}", price.parse::<f64>().unwrap()))
-                            .width(Length::FillPortion(1))
-                            .style(iced::Color::from_rgb(1.0, 0.0, 0.0)),
-                        t(format!("{quantity:.4}"))
-                            .width(Length::FillPortion(1))
-                            .style(h2c("B7BDB7").unwrap()),
-                        t(format!("{:.2}", price.parse::<f64>().unwrap() * quantity))
-                            .style(h2c("B7BDB7").unwrap())
-                            .width(Length::FillPortion(1)),
-                    ]
-                    .spacing(10)
-                })
-                .map(Element::from),
+            best_asks
+                .into_iter()
+                .map(|level| level_row(level, color(Role::Danger))),
         );
 
         let bid_rows = Column::with_children(
-            book.1
-                .iter()
+            bids.iter()
                 .rev()
                 .take(9)
-                .map(|(price, quantity)| {
-                    row![
-                        t(format!("{:.2}", price.parse::<f64>().unwrap()))
-                            .width(Length::FillPortion(1))
-                            .style(iced::Color::from_rgb(0.0, 1.0, 0.0)),
-                        t(format!("{quantity:.2}"))
-                            .width(Length::FillPortion(1))
-                            .style(h2c("B7BDB7").unwrap()),
-                        t(format!("{:.2}", price.parse::<f64>().unwrap() * quantity))
-                            .width(Length::FillPortion(1))
-                            .style(h2c("B7BDB7").unwrap())
-                    ]
-                    .spacing(10)
-                })
-                .map(Element::from),
+                .map(|level| level_row(*level, color(Role::Accent))),
         );
 
         let content = column![
+            spread_row,
+            grouping_buttons,
             header,
             ask_rows,
-            tb(format!(
-                "${}",
-                book.1
-                    .iter()
-                    .next_back()
-                    .unwrap_or((&String::new(), &0.0))
-                    .0
-            ))
-            .style(iced::Color::WHITE),
+            tb(bids
+                .last()
+                .map(|(price, _)| format!("${}", filter.price(*price)))
+                .unwrap_or_else(|| "$".to_owned()))
+            .style(color(Role::Foreground)),
             bid_rows
         ]
         .padding([2, 12])
@@ -97,3 +284,44 @@ impl BookPane {
         Container::new(content).into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_are_grouped() {
+        let side: BTreeMap<String, f64> = [("99.95", 1.0), ("100.04", 2.0), ("100.06", 3.0)]
+            .map(|(price, qty)| (price.to_owned(), qty))
+            .into();
+
+        let bids = group_levels(&side, BookGrouping::Agg0_1, false);
+        assert_eq!(bids.len(), 2);
+        assert!((bids[0].0 - 99.9).abs() < 1e-9 && bids[0].1 == 1.0);
+        assert!((bids[1].0 - 100.0).abs() < 1e-9 && bids[1].1 == 5.0);
+
+        let asks = group_levels(&side, BookGrouping::Agg1_0, true);
+        assert_eq!(asks, vec![(100.0, 1.0), (101.0, 5.0)]);
+
+        assert_eq!(group_levels(&side, BookGrouping::Raw, false).len(), 3);
+    }
+
+    #[test]
+    fn spread_of_book() {
+        let side = |levels: &[(&str, f64)]| -> BTreeMap<String, f64> {
+            levels
+                .iter()
+                .map(|(price, qty)| ((*price).to_owned(), *qty))
+                .collect()
+        };
+
+        // keys are sorted as strings, 99 would be after 100
+        let bids = side(&[("99", 1.0), ("100", 1.0)]);
+        let asks = side(&[("101", 1.0), ("102", 1.0)]);
+        let (spread, pct) = super::spread(&bids, &asks).unwrap();
+        assert_eq!(spread, 1.0);
+        assert!((pct - 1.0 / 100.5 * 100.0).abs() < 1e-9);
+
+        assert_eq!(super::spread(&bids, &BTreeMap::new()), None);
+    }
+}
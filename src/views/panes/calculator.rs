@@ -1,14 +1,21 @@
 #[cfg(not(any(feature = "calculator_meval", feature = "calculator_k")))]
 compile_error!("no calculator backend selected");
 
-use crate::{data::AppData, theme::h2c, views::components::better_btn::GreenBtn};
+use crate::{
+    data::AppData,
+    theme::{color, Role},
+    views::components::{
+        better_btn::{BetterBtn, GreenBtn},
+        input::Inp,
+    },
+};
 
 use binance::rest_model::Order;
 use iced::{
     widget::{
-        button, column, container, text,
+        button, checkbox, column, container, row, text,
         text_editor::{self, Content},
-        Column, Space,
+        text_input, Column, Space,
     },
     Alignment, Command, Element, Font, Length,
 };
@@ -26,12 +33,115 @@ pub(crate) struct CalculatorPane {
     content: iced::widget::text_editor::Content,
     is_editing: bool,
     eval_results: Vec<String>,
+    risk: Option<RiskCalculator>,
+    fees: Option<FeeCalculator>,
+    /// Free USDT balance
+    account_size: Option<f64>,
+    /// Account holds BNB which fees can be paid in
+    has_bnb: bool,
+}
+
+/// Inputs of position size calculator
+#[derive(Default)]
+struct RiskCalculator {
+    risk_pct: String,
+    entry: String,
+    stop_loss: String,
+    is_short: bool,
+}
+
+/// Fee rate an order pays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FeeType {
+    /// Order rested in book
+    Maker,
+    /// Order was filled right away
+    Taker,
+}
+
+/// Part of fees taken off when they are paid in BNB
+const BNB_DISCOUNT: f64 = 0.25;
+
+/// Inputs of fee calculator, rates themselves are kept in config
+#[derive(Default)]
+struct FeeCalculator {
+    entry: String,
+    exit: String,
+    qty: String,
+    is_short: bool,
+    /// Rates in percent as typed, config values are shown until they are edited
+    maker: Option<String>,
+    taker: Option<String>,
+    pay_in_bnb: bool,
+}
+
+/// Exit price at which fees of both orders are covered
+fn break_even(entry: f64, fee: f64, is_short: bool) -> f64 {
+    if is_short {
+        entry * (1.0 - fee) / (1.0 + fee)
+    } else {
+        entry * (1.0 + fee) / (1.0 - fee)
+    }
+}
+
+/// Profit in quote asset after fees of both orders
+fn net_profit(entry: f64, exit: f64, qty: f64, fee: f64, is_short: bool) -> f64 {
+    let (bought, sold) = if is_short {
+        (exit, entry)
+    } else {
+        (entry, exit)
+    };
+
+    qty * (sold * (1.0 - fee) - bought * (1.0 + fee))
+}
+
+/// Position size in base asset units and in USDT
+///
+/// Errors if stop loss is not below entry for long or above it for short
+fn position_size(
+    account_size: f64,
+    risk_pct: f64,
+    entry: f64,
+    stop_loss: f64,
+    is_short: bool,
+) -> Result<(f64, f64), &'static str> {
+    if entry <= 0.0 || stop_loss <= 0.0 {
+        return Err("prices must be positive");
+    }
+    if !(0.0..=100.0).contains(&risk_pct) {
+        return Err("risk must be between 0 and 100%");
+    }
+    if is_short && stop_loss <= entry {
+        return Err("stop loss must be above entry for short");
+    }
+    if !is_short && stop_loss >= entry {
+        return Err("stop loss must be below entry for long");
+    }
+
+    let units = account_size * risk_pct / 100.0 / (entry - stop_loss).abs();
+
+    Ok((units, units * entry))
 }
 
 #[derive(Debug, Clone)]
 pub(crate) enum CalculatorPaneMessage {
     Toggle,
     Action(text_editor::Action),
+
+    /// Switch between expressions and position size calculator
+    RiskMode,
+    /// Switch between expressions and fee calculator
+    FeesMode,
+    RiskPctChanged(String),
+    EntryChanged(String),
+    StopLossChanged(String),
+    /// Long or short in risk or fee calculator
+    SideToggled,
+    ExitChanged(String),
+    QtyChanged(String),
+    /// Rate in percent, saved to config by app once it is valid
+    FeeChanged(FeeType, String),
+    PayInBnbToggled(bool),
 }
 
 impl CalculatorPane {
@@ -41,6 +151,10 @@ impl CalculatorPane {
             content: Content::new(),
             is_editing: true,
             eval_results: Vec::new(),
+            risk: None,
+            fees: None,
+            account_size: None,
+            has_bnb: false,
         }
     }
 
@@ -68,19 +182,211 @@ impl CalculatorPane {
                 self.content.perform(action);
                 Command::none()
             }
+            CalculatorPaneMessage::RiskMode => {
+                self.fees = None;
+                self.risk = match self.risk {
+                    Some(_) => None,
+                    None => Some(RiskCalculator::default()),
+                };
+                Command::none()
+            }
+            CalculatorPaneMessage::FeesMode => {
+                self.risk = None;
+                self.fees = match self.fees {
+                    Some(_) => None,
+                    None => Some(FeeCalculator::default()),
+                };
+                Command::none()
+            }
+            message => {
+                if let Some(risk) = &mut self.risk {
+                    match message {
+                        CalculatorPaneMessage::RiskPctChanged(s) => risk.risk_pct = s,
+                        CalculatorPaneMessage::EntryChanged(s) => risk.entry = s,
+                        CalculatorPaneMessage::StopLossChanged(s) => risk.stop_loss = s,
+                        CalculatorPaneMessage::SideToggled => risk.is_short = !risk.is_short,
+                        _ => (),
+                    }
+                } else if let Some(fees) = &mut self.fees {
+                    match message {
+                        CalculatorPaneMessage::EntryChanged(s) => fees.entry = s,
+                        CalculatorPaneMessage::ExitChanged(s) => fees.exit = s,
+                        CalculatorPaneMessage::QtyChanged(s) => fees.qty = s,
+                        CalculatorPaneMessage::SideToggled => fees.is_short = !fees.is_short,
+                        CalculatorPaneMessage::FeeChanged(FeeType::Maker, s) => {
+                            fees.maker = Some(s)
+                        }
+                        CalculatorPaneMessage::FeeChanged(FeeType::Taker, s) => {
+                            fees.taker = Some(s)
+                        }
+                        CalculatorPaneMessage::PayInBnbToggled(value) => fees.pay_in_bnb = value,
+                        _ => (),
+                    }
+                }
+                Command::none()
+            }
         }
     }
 
     pub(crate) fn tick(&mut self, data: &AppData) {
         self.calculator.update_context(data);
+        self.account_size = data
+            .balances
+            .iter()
+            .find(|b| b.asset == "USDT")
+            .map(|b| b.free);
+        self.has_bnb = data
+            .balances
+            .iter()
+            .any(|b| b.asset == "BNB" && b.free > 0.0);
 
         if !self.is_editing {
             self.run();
         }
     }
 
-    pub(crate) fn view(&self) -> Element<'_, CalculatorPaneMessage> {
-        if self.is_editing {
+    fn view_risk<'a>(&'a self, risk: &'a RiskCalculator) -> Element<'a, CalculatorPaneMessage> {
+        let input = |placeholder, value, on_input: fn(String) -> CalculatorPaneMessage| {
+            text_input(placeholder, value)
+                .on_input(on_input)
+                .style(iced::theme::TextInput::Custom(Box::new(Inp {})))
+                .width(200.0)
+        };
+
+        let result = match self.account_size {
+            None => "USDT balance is not loaded".to_owned(),
+            Some(account_size) => match (
+                risk.risk_pct.parse(),
+                risk.entry.parse(),
+                risk.stop_loss.parse(),
+            ) {
+                (Ok(pct), Ok(entry), Ok(stop_loss)) => {
+                    match position_size(account_size, pct, entry, stop_loss, risk.is_short) {
+                        Ok((units, notional)) => format!("{units:.6} units\n{notional:.2} USDT"),
+                        Err(err) => err.to_owned(),
+                    }
+                }
+                _ => "enter risk, entry and stop loss".to_owned(),
+            },
+        };
+
+        column![
+            text(format!(
+                "Account: {:.2} USDT",
+                self.account_size.unwrap_or_default()
+            ))
+            .size(14),
+            button(text(if risk.is_short { "Short" } else { "Long" }).size(12))
+                .style(iced::theme::Button::Custom(Box::new(BetterBtn {})))
+                .padding(8)
+                .on_press(CalculatorPaneMessage::SideToggled),
+            input(
+                "risk %",
+                &risk.risk_pct,
+                CalculatorPaneMessage::RiskPctChanged
+            ),
+            input("entry", &risk.entry, CalculatorPaneMessage::EntryChanged),
+            input(
+                "stop loss",
+                &risk.stop_loss,
+                CalculatorPaneMessage::StopLossChanged
+            ),
+            tb(result).size(18).style(color(Role::Foreground)),
+        ]
+        .spacing(6)
+        .align_items(Alignment::Center)
+        .into()
+    }
+
+    fn view_fees<'a>(
+        &'a self,
+        fees: &'a FeeCalculator,
+        maker: f64,
+        taker: f64,
+    ) -> Element<'a, CalculatorPaneMessage> {
+        let input = |placeholder, value: &str, on_input: fn(String) -> CalculatorPaneMessage| {
+            text_input(placeholder, value)
+                .on_input(on_input)
+                .style(iced::theme::TextInput::Custom(Box::new(Inp {})))
+                .width(200.0)
+        };
+        let rate_input = |fee_type, edited: &Option<String>, rate: f64| {
+            let value = edited
+                .clone()
+                // rounded so that float error does not show up in percent
+                .unwrap_or_else(|| format!("{}", (rate * 1e8).round() / 1e6));
+            text_input("%", &value)
+                .on_input(move |s| CalculatorPaneMessage::FeeChanged(fee_type, s))
+                .style(iced::theme::TextInput::Custom(Box::new(Inp {})))
+                .width(96.0)
+        };
+
+        let discount = if fees.pay_in_bnb && self.has_bnb {
+            1.0 - BNB_DISCOUNT
+        } else {
+            1.0
+        };
+
+        let results = match (fees.entry.parse(), fees.exit.parse(), fees.qty.parse()) {
+            (Ok(entry), Ok(exit), Ok(qty)) if entry > 0.0 => [("Maker", maker), ("Taker", taker)]
+                .map(|(label, rate)| {
+                    let rate = rate * discount;
+                    format!(
+                        "{label}: break-even {:.6}, net {:.2}",
+                        break_even(entry, rate, fees.is_short),
+                        net_profit(entry, exit, qty, rate, fees.is_short)
+                    )
+                })
+                .join("\n"),
+            _ => "enter entry, exit and quantity".to_owned(),
+        };
+
+        column![
+            button(text(if fees.is_short { "Short" } else { "Long" }).size(12))
+                .style(iced::theme::Button::Custom(Box::new(BetterBtn {})))
+                .padding(8)
+                .on_press(CalculatorPaneMessage::SideToggled),
+            input("entry", &fees.entry, CalculatorPaneMessage::EntryChanged),
+            input("exit", &fees.exit, CalculatorPaneMessage::ExitChanged),
+            input("quantity", &fees.qty, CalculatorPaneMessage::QtyChanged),
+            row![
+                text("Maker %").size(12),
+                rate_input(FeeType::Maker, &fees.maker, maker),
+                text("Taker %").size(12),
+                rate_input(FeeType::Taker, &fees.taker, taker),
+            ]
+            .spacing(4)
+            .align_items(Alignment::Center),
+        ]
+        .push_maybe(self.has_bnb.then(|| {
+            checkbox("Pay fees in BNB (-25%)", fees.pay_in_bnb)
+                .on_toggle(CalculatorPaneMessage::PayInBnbToggled)
+                .size(14)
+        }))
+        .push(tb(results).size(16).style(color(Role::Foreground)))
+        .spacing(6)
+        .align_items(Alignment::Center)
+        .into()
+    }
+
+    /// Fee rates are used by fee calculator
+    pub(crate) fn view(&self, maker: f64, taker: f64) -> Element<'_, CalculatorPaneMessage> {
+        let mode_button = |active, label, message| {
+            button(text(if active { "Expressions" } else { label }).size(12))
+                .style(iced::theme::Button::Text)
+                .on_press(message)
+        };
+        let mode_toggles = row![
+            Space::new(Length::Fill, 1.0),
+            mode_button(self.fees.is_some(), "Fees", CalculatorPaneMessage::FeesMode),
+            mode_button(self.risk.is_some(), "Risk", CalculatorPaneMessage::RiskMode),
+        ];
+
+        let content = if let Some(risk) = &self.risk {
+            container(self.view_risk(risk))
+        } else if let Some(fees) = &self.fees {
+            container(self.view_fees(fees, maker, taker))
+        } else if self.is_editing {
             container(
                 column![
                     text_editor::TextEditor::new(&self.content)
@@ -105,8 +411,8 @@ impl CalculatorPane {
                             .lines()
                             .zip(&self.eval_results)
                             .map(|(s, e)| column![
-                                tb(s).size(18).style(h2c("EFE1D1").unwrap()),
-                                text(e).size(18).style(h2c("EEEEEE").unwrap()),
+                                tb(s).size(18).style(color(Role::Foreground)),
+                                text(e).size(18).style(color(Role::Foreground)),
                             ])
                             .map(Element::from)
                     ),
@@ -117,9 +423,9 @@ impl CalculatorPane {
                 ]
                 .align_items(Alignment::Center),
             )
-        }
-        .padding(10)
-        .into()
+        };
+
+        column![mode_toggles, content.padding(10)].into()
     }
 }
 
@@ -135,7 +441,7 @@ pub(crate) fn order_value(order: &Order, price_now: f64) -> f64 {
 
 #[cfg(feature = "calculator_k")]
 mod calc_k {
-    use crate::{api::Client, data::AppData, views::panes::calculator::order_value};
+    use crate::{api::split_symbol, data::AppData, views::panes::calculator::order_value};
 
     use ngnk::{kinit, CK, K0};
 
@@ -152,7 +458,7 @@ mod calc_k {
             let mut keys = String::new();
             let mut values = String::new();
             for (key, val) in data.prices.all().take(250) {
-                if let Some([base, _]) = Client::split_symbol(key) {
+                if let Some([base, _]) = split_symbol(key) {
                     let filtered: String = base.chars().filter(|c| c.is_alphabetic()).collect();
                     if !filtered.is_empty() {
                         keys.push_str(&format!("`\"{filtered}\""));
@@ -226,3 +532,34 @@ mod calc_meval {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn risk_position_size() {
+        // risking 10 USDT with 2 USDT stop distance
+        let (units, notional) = position_size(1000.0, 1.0, 20.0, 18.0, false).unwrap();
+        assert!((units - 5.0).abs() < 1e-9);
+        assert!((notional - 100.0).abs() < 1e-9);
+
+        assert!(position_size(1000.0, 1.0, 20.0, 22.0, false).is_err());
+        assert!(position_size(1000.0, 1.0, 20.0, 22.0, true).is_ok());
+        assert!(position_size(1000.0, 1.0, 20.0, 18.0, true).is_err());
+    }
+
+    #[test]
+    fn fees_move_break_even() {
+        let long = break_even(100.0, 0.001, false);
+        assert!((net_profit(100.0, long, 2.0, 0.001, false)).abs() < 1e-9);
+        assert!(long > 100.0);
+
+        let short = break_even(100.0, 0.001, true);
+        assert!((net_profit(100.0, short, 2.0, 0.001, true)).abs() < 1e-9);
+        assert!(short < 100.0);
+
+        // 0.1 * 100 + 0.1 * 110 paid in fees
+        assert!((net_profit(100.0, 110.0, 1.0, 0.001, false) - (10.0 - 0.21)).abs() < 1e-9);
+    }
+}
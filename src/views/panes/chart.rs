@@ -1,11 +1,22 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use iced::event;
+use iced::mouse;
 use iced::widget::button;
-use iced::widget::column;
+use iced::widget::canvas::Event;
 use iced::widget::container;
 use iced::widget::row;
+use iced::widget::text;
+use iced::widget::Column;
 use iced::widget::Row;
 use iced::widget::Space;
+use iced::Command;
 use iced::Element;
 use iced::Length;
+use iced::Point;
+use plotters::coord::types::RangedCoordf64;
+use plotters::coord::ReverseCoordTranslate;
 use plotters::prelude::*;
 use plotters::style::colors;
 use plotters::style::IntoFont;
@@ -14,35 +25,419 @@ use ringbuf::ring_buffer::RbBase;
 use ringbuf::Rb;
 
 use super::orders::tb;
-use crate::data::AppData;
+use super::KeyEvent;
+use crate::api::TradingClient;
+use crate::config::{ChartColors, Config};
+use crate::data::{AppData, CandleData, SymbolFilter};
+use crate::indicators::{IndicatorConfig, Indicators, Series};
+use crate::message::Message;
+use crate::theme::{color, h2c, Role};
+use crate::views::components::better_btn::GreenBtn;
 use crate::views::components::loading::loader;
-use crate::views::{components::better_btn::GreenBtn, dashboard::DashboardMessage};
+use crate::views::components::touch::Fingers;
 
-pub(crate) struct ChartPane {}
+/// Share of chart height taken by volume bars
+const VOLUME_HEIGHT: f64 = 0.2;
 
-struct PriceChart<'a>(&'a AppData);
+/// Bars this many times above average volume are highlighted
+const HIGH_VOLUME_FACTOR: f64 = 2.0;
 
-impl Chart<DashboardMessage> for PriceChart<'_> {
-    type State = ();
+const VWAP_COLOR: RGBColor = RGBColor(245, 196, 81);
+
+/// Approximate size of monospace tooltip text
+const TOOLTIP_CHAR_WIDTH: i32 = 7;
+const TOOLTIP_LINE_HEIGHT: i32 = 15;
+
+/// Pinching in stops once this share of chart is shown
+const MAX_ZOOM: f32 = 8.0;
+
+/// Zoom factor of one `+` or `-` press
+const ZOOM_STEP: f32 = 1.25;
+
+/// How price is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ChartMode {
+    Line,
+    Candle,
+}
+
+/// Interval of historical klines
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) enum Timeframe {
+    T1m,
+    #[default]
+    T5m,
+    T15m,
+    T1h,
+    T4h,
+    T1d,
+}
+
+impl Timeframe {
+    const ALL: [Timeframe; 6] = [
+        Timeframe::T1m,
+        Timeframe::T5m,
+        Timeframe::T15m,
+        Timeframe::T1h,
+        Timeframe::T4h,
+        Timeframe::T1d,
+    ];
+
+    /// Length of one kline
+    pub(crate) fn duration(&self) -> Duration {
+        const MINUTE: u64 = 60;
+
+        Duration::from_secs(match self {
+            Timeframe::T1m => MINUTE,
+            Timeframe::T5m => 5 * MINUTE,
+            Timeframe::T15m => 15 * MINUTE,
+            Timeframe::T1h => 60 * MINUTE,
+            Timeframe::T4h => 4 * 60 * MINUTE,
+            Timeframe::T1d => 24 * 60 * MINUTE,
+        })
+    }
+
+    /// Interval as used by Binance API
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Timeframe::T1m => "1m",
+            Timeframe::T5m => "5m",
+            Timeframe::T15m => "15m",
+            Timeframe::T1h => "1h",
+            Timeframe::T4h => "4h",
+            Timeframe::T1d => "1d",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum ChartMessage {
+    /// Reload history with klines of timeframe
+    TimeframeChanged(Timeframe),
+
+    /// Switch between line and candles
+    ModeToggled,
+
+    /// Cursor moved over price chart, relative to its top left corner
+    MouseMoved(Point),
+
+    /// Cursor left price chart
+    MouseLeft,
+
+    /// Advance scroll towards latest price
+    Animate(Instant),
+
+    /// Pinch changed distance between fingers by factor
+    Zoom(f32),
+}
+
+pub(crate) struct ChartPane {
+    mode: ChartMode,
+    timeframe: Timeframe,
+    indicators: Indicators,
+    /// Position of crosshair
+    cursor: Option<Point>,
+    /// Prices pushed out of full line chart since scroll started
+    target_data_offset: f64,
+    /// Samples scrolled so far, lags behind target while animating
+    current_data_offset: f64,
+    last_frame: Option<Instant>,
+    /// Only newest part of chart is shown when zoomed in
+    zoom: f32,
+}
+
+struct PriceChart<'a> {
+    data: &'a AppData,
+    mode: ChartMode,
+    colors: &'a ChartColors,
+    /// Computed from line prices so only drawn in line mode
+    indicators: &'a Indicators,
+    cursor: Option<Point>,
+    filter: SymbolFilter,
+    /// Daily VWAP of pair, drawn as horizontal line
+    vwap: Option<f64>,
+    /// Samples line chart is still to scroll by
+    scroll: f64,
+    /// First shown sample
+    first: usize,
+}
+
+/// Candles of symbol charted in its own pane, independent of market pair
+struct SymbolChart<'a> {
+    candles: &'a VecDeque<CandleData>,
+    colors: &'a ChartColors,
+}
+
+struct RsiChart<'a> {
+    values: &'a Series,
+    /// Length of price chart, RSI is aligned to its end
+    len: usize,
+    /// First shown sample of price chart
+    first: usize,
+}
+
+/// First of `len` samples shown at `zoom`
+fn first_visible(len: usize, zoom: f32) -> usize {
+    len.saturating_sub((len as f32 / zoom).ceil() as usize)
+}
+
+/// Wicks and bodies of candles centered on their index, starting from `first`
+fn draw_candles<'a, DB: DrawingBackend>(
+    chart: &mut ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+    candles: impl Iterator<Item = &'a CandleData> + Clone,
+    first: usize,
+    colors: &ChartColors,
+) {
+    let wick = rgb(&colors.wick);
+    let bull = rgb(&colors.bull_body);
+    let bear = rgb(&colors.bear_body);
+
+    chart
+        .draw_series(
+            candles
+                .clone()
+                .enumerate()
+                .skip(first)
+                .map(|(x, c)| PathElement::new([(x as f64, c.low), (x as f64, c.high)], wick)),
+        )
+        .expect("failed to draw candle wicks");
+
+    chart
+        .draw_series(candles.enumerate().skip(first).map(|(x, c)| {
+            let color = if c.close >= c.open { bull } else { bear };
+            Rectangle::new(
+                [(x as f64 - 0.35, c.open), (x as f64 + 0.35, c.close)],
+                color.filled(),
+            )
+        }))
+        .expect("failed to draw candle bodies");
+}
+
+/// Value between two neighbouring points, None outside of line
+fn interpolate<'a>(mut values: impl Iterator<Item = &'a f64>, x: f64) -> Option<f64> {
+    if x < 0.0 {
+        return None;
+    }
+
+    let left = *values.nth(x as usize)?;
+    let Some(right) = values.next() else {
+        // last point
+        return (x.fract() == 0.0).then_some(left);
+    };
+
+    Some(left + (right - left) * x.fract())
+}
+
+/// Converts hex color for plotters, gray if it cannot be parsed
+fn rgb(hex: &str) -> RGBColor {
+    h2c(hex).map_or(colors::full_palette::GREY_500, |c| {
+        RGBColor(
+            (c.r * 255.0) as u8,
+            (c.g * 255.0) as u8,
+            (c.b * 255.0) as u8,
+        )
+    })
+}
+
+impl PriceChart<'_> {
+    /// Tooltip lines describing price at chart x coordinate
+    fn hover_lines(&self, x: f64) -> Option<Vec<String>> {
+        match self.mode {
+            ChartMode::Line => interpolate(self.data.price_chart.iter(), x)
+                .map(|price| vec![format!("P {}", self.filter.price(price))]),
+            ChartMode::Candle => {
+                let candle = self.data.candles.iter().nth(x.round().max(0.0) as usize)?;
+
+                Some(vec![
+                    format!("O {}", self.filter.price(candle.open)),
+                    format!("H {}", self.filter.price(candle.high)),
+                    format!("L {}", self.filter.price(candle.low)),
+                    format!("C {}", self.filter.price(candle.close)),
+                    format!("V {:.2}", candle.volume),
+                ])
+            }
+        }
+    }
+
+    /// Crosshair through cursor and tooltip next to it, flipped to stay inside of chart
+    fn draw_crosshair<DB: DrawingBackend>(
+        &self,
+        chart: &mut ChartContext<'_, DB, Cartesian2d<RangedCoordf64, RangedCoordf64>>,
+        cursor: Point,
+    ) {
+        let pixel = (cursor.x as i32, cursor.y as i32);
+        let (x_pixels, y_pixels) = chart.plotting_area().get_pixel_range();
+        if !x_pixels.contains(&pixel.0) || !y_pixels.contains(&pixel.1) {
+            return;
+        }
+
+        let coords = chart.as_coord_spec();
+        let Some((x, y)) = coords.reverse_translate(pixel) else {
+            return;
+        };
+        let (x_range, y_range) = (coords.get_x_range(), coords.get_y_range());
+
+        let style = colors::full_palette::GREY_500.stroke_width(1);
+        chart
+            .draw_series([
+                PathElement::new([(x, y_range.start), (x, y_range.end)], style),
+                PathElement::new([(x_range.start, y), (x_range.end, y)], style),
+            ])
+            .expect("failed to draw crosshair");
+
+        let Some(lines) = self.hover_lines(x) else {
+            return;
+        };
+
+        let width =
+            lines.iter().map(String::len).max().unwrap_or(0) as i32 * TOOLTIP_CHAR_WIDTH + 8;
+        let height = lines.len() as i32 * TOOLTIP_LINE_HEIGHT + 8;
+        let offset_x = if pixel.0 + 10 + width > x_pixels.end {
+            -10 - width
+        } else {
+            10
+        };
+        let offset_y = if pixel.1 + 10 + height > y_pixels.end {
+            -10 - height
+        } else {
+            10
+        };
+
+        let mut text = MultiLineText::<_, String>::new(
+            (offset_x + 4, offset_y + 4),
+            ("monospace", 12).into_font().color(&colors::WHITE),
+        );
+        text.set_line_height(1.25);
+        for line in lines {
+            text.push_line(line);
+        }
+
+        chart
+            .plotting_area()
+            .draw(
+                &(EmptyElement::at((x, y))
+                    + Rectangle::new(
+                        [(offset_x, offset_y), (offset_x + width, offset_y + height)],
+                        RGBColor(30, 30, 30).filled(),
+                    )
+                    + text),
+            )
+            .expect("failed to draw chart tooltip");
+    }
+}
+
+impl Chart<ChartMessage> for PriceChart<'_> {
+    type State = Fingers;
+
+    fn update(
+        &self,
+        fingers: &mut Self::State,
+        event: Event,
+        bounds: iced::Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<ChartMessage>) {
+        let message = match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => match cursor.position_in(bounds) {
+                Some(position) => Some(ChartMessage::MouseMoved(position)),
+                None => self.cursor.map(|_| ChartMessage::MouseLeft),
+            },
+            Event::Mouse(mouse::Event::CursorLeft) => self.cursor.map(|_| ChartMessage::MouseLeft),
+            Event::Touch(touch) => fingers
+                .update(touch)
+                .filter(|pinch| pinch.scale != 1.0)
+                .map(|pinch| ChartMessage::Zoom(pinch.scale)),
+            _ => None,
+        };
+
+        (event::Status::Ignored, message)
+    }
 
     fn build_chart<DB: DrawingBackend>(&self, _state: &Self::State, mut builder: ChartBuilder<DB>) {
         const LINE_COLOR: RGBColor = colors::GREEN;
 
-        let (min, max) = self
-            .0
-            .price_chart
-            .iter()
-            .fold((f32::MAX, f32::MIN), |acc, &x| {
-                (acc.0.min(x as f32), acc.1.max(x as f32))
-            });
+        let (x_range, (min, max)) = match self.mode {
+            ChartMode::Line => (
+                self.first as f64 - self.scroll..self.data.price_chart.len() as f64 - self.scroll,
+                self.data
+                    .price_chart
+                    .iter()
+                    .skip(self.first)
+                    .fold((f64::MAX, f64::MIN), |acc, &x| (acc.0.min(x), acc.1.max(x))),
+            ),
+            // candles are centered on their index
+            ChartMode::Candle => (
+                self.first as f64 - 0.5..self.data.candles.len() as f64 - 0.5,
+                self.data
+                    .candles
+                    .iter()
+                    .skip(self.first)
+                    .fold((f64::MAX, f64::MIN), |acc, c| {
+                        (acc.0.min(c.low), acc.1.max(c.high))
+                    }),
+            ),
+        };
+
+        // x position of every bar, same as price points they belong to
+        let volumes: Vec<(f64, f64)> = match self.mode {
+            ChartMode::Line => {
+                let offset = self
+                    .data
+                    .price_chart
+                    .len()
+                    .saturating_sub(self.data.volume.len());
+                self.data
+                    .volume
+                    .iter()
+                    .enumerate()
+                    .map(|(x, v)| ((x + offset) as f64, *v))
+                    .filter(|(x, _)| *x >= self.first as f64)
+                    .collect()
+            }
+            ChartMode::Candle => self
+                .data
+                .candles
+                .iter()
+                .enumerate()
+                .skip(self.first)
+                .map(|(x, c)| (x as f64, c.volume))
+                .collect(),
+        };
+
+        // reserve bottom of chart for volume so it does not cover price
+        let span = max - min;
+        let volume_base = min - span * VOLUME_HEIGHT / (1.0 - VOLUME_HEIGHT);
 
         let mut chart = builder
             .x_label_area_size(0_i32)
             .y_label_area_size(70_i32)
             .margin(0_i32)
-            .build_cartesian_2d(0..self.0.price_chart.len(), min..max)
+            .build_cartesian_2d(x_range, volume_base..max)
             .expect("Failed to build chart");
 
+        let max_volume = volumes.iter().fold(0.0_f64, |acc, (_, v)| acc.max(*v));
+        if max_volume > 0.0 {
+            let average = volumes.iter().map(|(_, v)| v).sum::<f64>() / volumes.len() as f64;
+            let normal = rgb(&self.colors.volume);
+            let high = rgb(&self.colors.high_volume);
+
+            chart
+                .draw_series(volumes.iter().map(|(x, v)| {
+                    let color = if *v > average * HIGH_VOLUME_FACTOR {
+                        high
+                    } else {
+                        normal
+                    };
+                    Rectangle::new(
+                        [
+                            (x - 0.35, volume_base),
+                            (x + 0.35, volume_base + v / max_volume * (min - volume_base)),
+                        ],
+                        color.filled(),
+                    )
+                }))
+                .expect("failed to draw volume");
+        }
+
         chart
             .configure_mesh()
             .disable_mesh()
@@ -64,49 +459,366 @@ impl Chart<DashboardMessage> for PriceChart<'_> {
             .draw()
             .unwrap();
 
+        match self.mode {
+            ChartMode::Line => {
+                chart
+                    .draw_series(LineSeries::new(
+                        self.data
+                            .price_chart
+                            .iter()
+                            .enumerate()
+                            .skip(self.first)
+                            .map(|(x, y)| (x as f64, *y)),
+                        LINE_COLOR,
+                    ))
+                    .expect("failed to draw chart data");
+
+                let len = self.data.price_chart.len();
+                for (_, color, values) in self.indicators.lines() {
+                    let offset = len.saturating_sub(values.len());
+                    chart
+                        .draw_series(LineSeries::new(
+                            values
+                                .iter()
+                                .enumerate()
+                                .map(|(x, y)| (x + offset, *y))
+                                .filter(|(x, _)| *x >= self.first)
+                                .map(|(x, y)| (x as f64, y)),
+                            rgb(color),
+                        ))
+                        .expect("failed to draw indicator");
+                }
+            }
+            ChartMode::Candle => {
+                draw_candles(
+                    &mut chart,
+                    self.data.candles.iter(),
+                    self.first,
+                    self.colors,
+                );
+            }
+        }
+
+        if let Some(vwap) = self.vwap.filter(|vwap| (min..=max).contains(vwap)) {
+            let x_range = chart.x_range();
+            chart
+                .draw_series(LineSeries::new(
+                    [(x_range.start, vwap), (x_range.end, vwap)],
+                    VWAP_COLOR.stroke_width(1),
+                ))
+                .expect("failed to draw vwap");
+        }
+
+        if let Some(cursor) = self.cursor {
+            self.draw_crosshair(&mut chart, cursor);
+        }
+    }
+}
+
+impl Chart<ChartMessage> for SymbolChart<'_> {
+    type State = ();
+
+    fn build_chart<DB: DrawingBackend>(&self, _state: &Self::State, mut builder: ChartBuilder<DB>) {
+        let (min, max) = self.candles.iter().fold((f64::MAX, f64::MIN), |acc, c| {
+            (acc.0.min(c.low), acc.1.max(c.high))
+        });
+
+        let mut chart = builder
+            .x_label_area_size(0_i32)
+            .y_label_area_size(70_i32)
+            .margin(0_i32)
+            .build_cartesian_2d(-0.5..self.candles.len() as f64 - 0.5, min..max)
+            .expect("Failed to build chart");
+
+        chart
+            .configure_mesh()
+            .disable_mesh()
+            .axis_style(
+                ShapeStyle::from(plotters::style::colors::full_palette::GREY_500).stroke_width(0),
+            )
+            .y_labels(10)
+            .y_label_style(
+                ("monospace", 12)
+                    .into_font()
+                    .color(&plotters::style::colors::WHITE),
+            )
+            .draw()
+            .unwrap();
+
+        draw_candles(&mut chart, self.candles.iter(), 0, self.colors);
+    }
+}
+
+impl Chart<ChartMessage> for RsiChart<'_> {
+    type State = ();
+
+    fn build_chart<DB: DrawingBackend>(&self, _state: &Self::State, mut builder: ChartBuilder<DB>) {
+        let mut chart = builder
+            .x_label_area_size(0_i32)
+            .y_label_area_size(70_i32)
+            .margin(0_i32)
+            .build_cartesian_2d(self.first as f64..self.len as f64, 0.0..100.0)
+            .expect("Failed to build chart");
+
+        chart
+            .configure_mesh()
+            .disable_mesh()
+            .axis_style(
+                ShapeStyle::from(plotters::style::colors::full_palette::GREY_500).stroke_width(0),
+            )
+            .y_labels(3)
+            .y_label_style(
+                ("monospace", 12)
+                    .into_font()
+                    .color(&plotters::style::colors::WHITE),
+            )
+            .draw()
+            .unwrap();
+
+        // overbought and oversold levels
+        for level in [30.0, 70.0] {
+            chart
+                .draw_series(LineSeries::new(
+                    [(self.first as f64, level), (self.len as f64, level)],
+                    colors::full_palette::GREY_600,
+                ))
+                .expect("failed to draw RSI level");
+        }
+
+        let offset = self.len.saturating_sub(self.values.len());
         chart
             .draw_series(LineSeries::new(
-                self.0
-                    .price_chart
+                self.values
                     .iter()
                     .enumerate()
-                    .map(|(x, y)| (x, (*y as f32))),
-                LINE_COLOR,
+                    .map(|(x, y)| (x + offset, *y))
+                    .filter(|(x, _)| *x >= self.first)
+                    .map(|(x, y)| (x as f64, y)),
+                colors::full_palette::PURPLE_300,
             ))
-            .expect("failed to draw chart data");
+            .expect("failed to draw RSI");
     }
 }
 
 impl ChartPane {
-    pub(crate) fn new() -> Self {
-        Self {}
+    pub(crate) fn new(indicators: &IndicatorConfig) -> Self {
+        Self {
+            mode: ChartMode::Line,
+            timeframe: Timeframe::default(),
+            indicators: Indicators::new(indicators),
+            cursor: None,
+            target_data_offset: 0.0,
+            current_data_offset: 0.0,
+            last_frame: None,
+            zoom: 1.0,
+        }
+    }
+
+    /// Update indicators with price added to chart, `scrolled` if oldest price was dropped
+    pub(crate) fn push_price(&mut self, price: f64, scrolled: bool) {
+        self.indicators.push(price);
+
+        if scrolled {
+            if !self.is_animating() {
+                self.last_frame = Some(Instant::now());
+            }
+            self.target_data_offset += 1.0;
+        }
+    }
+
+    /// Line chart has not caught up with pushed prices
+    pub(crate) fn is_animating(&self) -> bool {
+        self.current_data_offset < self.target_data_offset
+    }
+
+    /// Scrolls by one sample per `scroll_speed_ms`, offsets start over once caught up
+    pub(crate) fn animate(&mut self, now: Instant, scroll_speed_ms: u32) {
+        let elapsed = self
+            .last_frame
+            .map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+        let step = if scroll_speed_ms == 0 {
+            f64::INFINITY
+        } else {
+            elapsed.as_secs_f64() * 1000.0 / f64::from(scroll_speed_ms)
+        };
+
+        self.current_data_offset = (self.current_data_offset + step).min(self.target_data_offset);
+        if self.is_animating() {
+            self.last_frame = Some(now);
+        } else {
+            self.target_data_offset = 0.0;
+            self.current_data_offset = 0.0;
+            self.last_frame = None;
+        }
+    }
+
+    /// Recompute indicators after chart history was loaded
+    pub(crate) fn history_changed(&mut self, data: &AppData) {
+        self.indicators.rebuild(data.price_chart.iter());
+    }
+
+    pub(crate) fn timeframe(&self) -> Timeframe {
+        self.timeframe
+    }
+
+    /// Timeframe of history requested from now on, does not reload chart
+    pub(crate) fn set_timeframe(&mut self, timeframe: Timeframe) {
+        self.timeframe = timeframe;
+    }
+
+    /// `+` and `-` zoom in and out
+    pub(crate) fn key_message(event: &KeyEvent) -> Option<ChartMessage> {
+        match event.character()? {
+            "+" | "=" => Some(ChartMessage::Zoom(ZOOM_STEP)),
+            "-" => Some(ChartMessage::Zoom(1.0 / ZOOM_STEP)),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn update(
+        &mut self,
+        msg: ChartMessage,
+        api: &dyn TradingClient,
+        data: &mut AppData,
+        pair: &str,
+    ) -> Command<Message> {
+        match msg {
+            ChartMessage::TimeframeChanged(timeframe) => {
+                self.set_timeframe(timeframe);
+                // history of previous timeframe would be mixed with new one
+                data.clear_chart();
+                self.history_changed(data);
+
+                api.klines(pair.to_owned(), timeframe)
+            }
+            ChartMessage::ModeToggled => {
+                self.mode = match self.mode {
+                    ChartMode::Line => ChartMode::Candle,
+                    ChartMode::Candle => ChartMode::Line,
+                };
+                Command::none()
+            }
+            ChartMessage::MouseMoved(position) => {
+                self.cursor = Some(position);
+                Command::none()
+            }
+            ChartMessage::MouseLeft => {
+                self.cursor = None;
+                Command::none()
+            }
+            // handled by dashboard, speed is in config
+            ChartMessage::Animate(_) => Command::none(),
+            ChartMessage::Zoom(factor) => {
+                self.zoom = (self.zoom * factor).clamp(1.0, MAX_ZOOM);
+                Command::none()
+            }
+        }
     }
 
-    pub(crate) fn view<'a>(&'a self, data: &'a AppData) -> Element<'a, DashboardMessage> {
-        if data.price_chart.is_empty() {
+    /// Prices in tooltip are formatted with `filter` of market pair
+    pub(crate) fn view<'a>(
+        &'a self,
+        data: &'a AppData,
+        config: &'a Config,
+        filter: SymbolFilter,
+        vwap: Option<f64>,
+    ) -> Element<'a, ChartMessage> {
+        let is_empty = match self.mode {
+            ChartMode::Line => data.price_chart.is_empty(),
+            ChartMode::Candle => data.candles.is_empty(),
+        };
+
+        if is_empty {
             return loader!().into();
         }
 
         let btns = Row::with_children(
-            ["1m", "5m", "30m", "1h", "1d"]
-                .map(|t| {
-                    button(tb(t).style(iced::Color::WHITE).size(12))
-                        .on_press(DashboardMessage::TimeframeChanged(t.into()))
-                        .padding(8)
-                        .style(iced::theme::Button::Custom(Box::new(GreenBtn {})))
+            Timeframe::ALL
+                .map(|tf| {
+                    button(
+                        tb(tf.as_str())
+                            .style(if tf == self.timeframe {
+                                color(Role::Accent)
+                            } else {
+                                iced::Color::WHITE
+                            })
+                            .size(12),
+                    )
+                    .on_press(ChartMessage::TimeframeChanged(tf))
+                    .padding(8)
+                    .style(iced::theme::Button::Custom(Box::new(GreenBtn {})))
                 })
-                .map(Element::from),
+                .map(Element::from)
+                .into_iter()
+                .chain([button(
+                    tb(match self.mode {
+                        ChartMode::Line => "Candles",
+                        ChartMode::Candle => "Line",
+                    })
+                    .style(iced::Color::WHITE)
+                    .size(12),
+                )
+                .on_press(ChartMessage::ModeToggled)
+                .padding(8)
+                .style(iced::theme::Button::Custom(Box::new(GreenBtn {})))
+                .into()]),
         )
         .spacing(4);
 
-        container(column![
-            ChartWidget::new(PriceChart(data)),
-            row![
-                Space::new(Length::Fill, 0),
-                btns,
-                Space::new(Length::Fill, 0)
-            ]
-        ])
+        let rsi = self
+            .indicators
+            .rsi()
+            .filter(|_| self.mode == ChartMode::Line)
+            .map(|values| {
+                ChartWidget::new(RsiChart {
+                    values,
+                    len: data.price_chart.len(),
+                    first: first_visible(data.price_chart.len(), self.zoom),
+                })
+                .height(Length::FillPortion(1))
+            });
+
+        let legend = (self.mode == ChartMode::Line).then(|| {
+            Row::with_children(self.indicators.lines().map(|(label, color, _)| {
+                text(label)
+                    .size(12)
+                    .style(h2c(color).unwrap_or(iced::Color::WHITE))
+                    .into()
+            }))
+            .spacing(8)
+            .padding([4, 8])
+        });
+
+        container(
+            Column::new()
+                .push_maybe(legend)
+                .push(
+                    ChartWidget::new(PriceChart {
+                        data,
+                        mode: self.mode,
+                        colors: &config.chart_colors,
+                        indicators: &self.indicators,
+                        cursor: self.cursor,
+                        filter,
+                        vwap,
+                        scroll: self.target_data_offset - self.current_data_offset,
+                        first: first_visible(
+                            match self.mode {
+                                ChartMode::Line => data.price_chart.len(),
+                                ChartMode::Candle => data.candles.len(),
+                            },
+                            self.zoom,
+                        ),
+                    })
+                    .height(Length::FillPortion(3)),
+                )
+                .push_maybe(rsi)
+                .push(row![
+                    Space::new(Length::Fill, 0),
+                    btns,
+                    Space::new(Length::Fill, 0)
+                ]),
+        )
         .style(container::Appearance {
             background: Some(iced::Background::Color(iced::Color::from_rgb(
                 0.07, 0.07, 0.07,
@@ -123,3 +835,70 @@ impl ChartPane {
         .into()
     }
 }
+
+/// Chart of symbol dropped from watchlist, loading until first candle arrives
+pub(crate) fn symbol_view<'a>(
+    candles: Option<&'a VecDeque<CandleData>>,
+    colors: &'a ChartColors,
+) -> Element<'a, ChartMessage> {
+    let Some(candles) = candles.filter(|candles| !candles.is_empty()) else {
+        return loader!().into();
+    };
+
+    container(ChartWidget::new(SymbolChart { candles, colors }).height(Length::Fill))
+        .padding(2)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scroll_catches_up() {
+        let mut chart = ChartPane::new(&IndicatorConfig::default());
+        chart.push_price(1.0, false);
+        assert!(!chart.is_animating());
+
+        chart.push_price(2.0, true);
+        chart.push_price(3.0, true);
+        let start = chart.last_frame.unwrap();
+        chart.animate(start + Duration::from_millis(150), 100);
+        assert!((chart.target_data_offset - chart.current_data_offset - 0.5).abs() < 1e-9);
+
+        chart.animate(start + Duration::from_millis(300), 100);
+        assert!(!chart.is_animating());
+        assert_eq!(chart.target_data_offset, 0.0);
+    }
+
+    #[test]
+    fn interpolates_between_points() {
+        let values = [1.0, 3.0, 2.0];
+
+        assert_eq!(interpolate(values.iter(), 0.5), Some(2.0));
+        assert_eq!(interpolate(values.iter(), 1.25), Some(2.75));
+        assert_eq!(interpolate(values.iter(), 2.0), Some(2.0));
+        assert_eq!(interpolate(values.iter(), 2.5), None);
+        assert_eq!(interpolate(values.iter(), -0.5), None);
+    }
+
+    #[test]
+    fn plus_and_minus_zoom() {
+        use iced::keyboard::{Key, Modifiers};
+
+        let press = |c: &str, modifiers| KeyEvent {
+            key: Key::Character(c.into()),
+            modifiers,
+        };
+
+        assert!(matches!(
+            ChartPane::key_message(&press("+", Modifiers::SHIFT)),
+            Some(ChartMessage::Zoom(f)) if f > 1.0
+        ));
+        assert!(matches!(
+            ChartPane::key_message(&press("-", Modifiers::empty())),
+            Some(ChartMessage::Zoom(f)) if f < 1.0
+        ));
+        assert!(ChartPane::key_message(&press("-", Modifiers::CTRL)).is_none());
+    }
+}
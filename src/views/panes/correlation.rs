@@ -0,0 +1,294 @@
+use std::{collections::VecDeque, time::Instant};
+
+use ahash::AHashMap;
+
+use super::{
+    heatmap::cell_color,
+    orders::{t, tb},
+};
+use crate::{
+    data::{AppData, PRICE_SERIES_LEN},
+    theme::{color, Role},
+};
+
+use iced::{
+    alignment,
+    widget::{container, mouse_area, text, Column, Row},
+    Color, Element, Length,
+};
+
+/// Minute returns correlated, price series also keeps one close before them and current price
+const WINDOW: usize = PRICE_SERIES_LEN - 2;
+
+#[derive(Debug, Clone)]
+pub(crate) enum CorrelationMessage {
+    /// Cell clicked, sets market pair to symbol of row
+    PairSelected(String),
+}
+
+/// Pearson correlation over sliding window, values are added and removed one pair at a time
+///
+/// Means and co-moments are updated with Welford's method instead of keeping raw sums, which
+/// lose precision over long windows
+#[derive(Debug, Default, Clone, Copy)]
+struct RollingCorrelation {
+    n: usize,
+    mean_a: f64,
+    mean_b: f64,
+    m2_a: f64,
+    m2_b: f64,
+    co: f64,
+}
+
+impl RollingCorrelation {
+    fn push(&mut self, a: f64, b: f64) {
+        self.n += 1;
+        let n = self.n as f64;
+
+        let da = a - self.mean_a;
+        self.mean_a += da / n;
+        let db = b - self.mean_b;
+        self.mean_b += db / n;
+
+        self.m2_a += da * (a - self.mean_a);
+        self.m2_b += db * (b - self.mean_b);
+        self.co += da * (b - self.mean_b);
+    }
+
+    /// Removes values pushed earlier, inverse of push
+    fn pop(&mut self, a: f64, b: f64) {
+        if self.n <= 1 {
+            *self = Self::default();
+            return;
+        }
+
+        // means without removed values
+        let n = (self.n - 1) as f64;
+        let mean_a = self.mean_a + (self.mean_a - a) / n;
+        let mean_b = self.mean_b + (self.mean_b - b) / n;
+
+        self.m2_a -= (a - mean_a) * (a - self.mean_a);
+        self.m2_b -= (b - mean_b) * (b - self.mean_b);
+        self.co -= (a - mean_a) * (b - self.mean_b);
+
+        self.n -= 1;
+        self.mean_a = mean_a;
+        self.mean_b = mean_b;
+    }
+
+    /// None until there are two values, or if either series is flat
+    fn value(&self) -> Option<f64> {
+        if self.n < 2 || self.m2_a <= 0.0 || self.m2_b <= 0.0 {
+            return None;
+        }
+
+        Some((self.co / (self.m2_a * self.m2_b).sqrt()).clamp(-1.0, 1.0))
+    }
+}
+
+/// Returns between closed minutes of series, current price is left out
+fn returns(series: &VecDeque<f64>) -> impl Iterator<Item = f64> + '_ {
+    let closed = series.len().saturating_sub(1);
+
+    series
+        .iter()
+        .take(closed)
+        .zip(series.iter().skip(1).take(closed.saturating_sub(1)))
+        .map(|(prev, next)| next / prev - 1.0)
+}
+
+pub(crate) struct CorrelationPane {
+    symbols: Vec<String>,
+    /// Returns in window by symbol, oldest first
+    returns: AHashMap<String, VecDeque<f64>>,
+    /// Row major matrix, only cells above diagonal are used
+    matrix: Vec<RollingCorrelation>,
+    /// Start of minute of price series when returns were last added
+    minute: Option<Instant>,
+}
+
+impl CorrelationPane {
+    pub(crate) fn new() -> Self {
+        Self {
+            symbols: Vec::new(),
+            returns: AHashMap::new(),
+            matrix: Vec::new(),
+            minute: None,
+        }
+    }
+
+    /// Adds return of minute that closed since last call, rebuilds everything if symbols changed
+    pub(crate) fn tick(&mut self, data: &AppData, symbols: &[String]) {
+        if self.symbols != symbols {
+            self.rebuild(data, symbols);
+            return;
+        }
+        if self.minute == data.price_series_minute {
+            return;
+        }
+        self.minute = data.price_series_minute;
+
+        let latest: Vec<_> = symbols
+            .iter()
+            .map(|symbol| returns(data.price_series.get(symbol)?).last())
+            .collect();
+        let n = symbols.len();
+
+        for i in 0..n {
+            for j in i + 1..n {
+                let (Some(a), Some(b)) = (latest[i], latest[j]) else {
+                    continue;
+                };
+                let cell = &mut self.matrix[i * n + j];
+
+                if cell.n == WINDOW {
+                    let (ra, rb) = (&self.returns[&symbols[i]], &self.returns[&symbols[j]]);
+                    cell.pop(ra[ra.len() - WINDOW], rb[rb.len() - WINDOW]);
+                }
+                cell.push(a, b);
+            }
+        }
+
+        for (symbol, latest) in symbols.iter().zip(latest) {
+            let Some(latest) = latest else {
+                continue;
+            };
+            let returns = self.returns.entry(symbol.clone()).or_default();
+            returns.push_back(latest);
+            if returns.len() > WINDOW {
+                returns.pop_front();
+            }
+        }
+    }
+
+    fn rebuild(&mut self, data: &AppData, symbols: &[String]) {
+        let n = symbols.len();
+
+        self.symbols = symbols.to_vec();
+        self.minute = data.price_series_minute;
+        self.returns = symbols
+            .iter()
+            .filter_map(|symbol| {
+                let series = data.price_series.get(symbol)?;
+                Some((symbol.clone(), returns(series).collect()))
+            })
+            .collect();
+        self.matrix = vec![RollingCorrelation::default(); n * n];
+
+        for i in 0..n {
+            for j in i + 1..n {
+                let (Some(a), Some(b)) =
+                    (self.returns.get(&symbols[i]), self.returns.get(&symbols[j]))
+                else {
+                    continue;
+                };
+
+                // series end at the same minute
+                let len = a.len().min(b.len());
+                let cell = &mut self.matrix[i * n + j];
+                for (a, b) in a
+                    .iter()
+                    .skip(a.len() - len)
+                    .zip(b.iter().skip(b.len() - len))
+                {
+                    cell.push(*a, *b);
+                }
+            }
+        }
+    }
+
+    fn correlation(&self, row: usize, col: usize) -> Option<f64> {
+        if row == col {
+            return Some(1.0);
+        }
+
+        self.matrix[row.min(col) * self.symbols.len() + row.max(col)].value()
+    }
+
+    pub(crate) fn view(&self) -> Element<'_, CorrelationMessage> {
+        let n = self.symbols.len();
+        if !(0..n).any(|i| (i + 1..n).any(|j| self.correlation(i, j).is_some())) {
+            return t("Correlations appear after a few minutes of prices")
+                .style(color(Role::Secondary))
+                .into();
+        }
+
+        let label = |symbol: &str| {
+            tb(symbol.strip_suffix("USDT").unwrap_or(symbol))
+                .size(12)
+                .width(Length::FillPortion(1))
+                .horizontal_alignment(alignment::Horizontal::Center)
+        };
+
+        let header = Row::with_children(
+            std::iter::once(label("").into())
+                .chain(self.symbols.iter().map(|symbol| label(symbol).into())),
+        )
+        .spacing(1);
+
+        let rows = self.symbols.iter().enumerate().map(|(row, symbol)| {
+            let cells = (0..n).map(|col| {
+                let value = self.correlation(row, col);
+                let background = value.map_or(color(Role::Surface), cell_color);
+
+                mouse_area(
+                    container(
+                        text(value.map_or("-".to_owned(), |v| format!("{v:.2}")))
+                            .size(12)
+                            .style(Color::BLACK),
+                    )
+                    .width(Length::Fill)
+                    .padding(4)
+                    .center_x()
+                    .style(container::Appearance {
+                        background: Some(iced::Background::Color(background)),
+                        ..Default::default()
+                    }),
+                )
+                .on_press(CorrelationMessage::PairSelected(symbol.clone()))
+                .into()
+            });
+
+            Row::with_children(std::iter::once(label(symbol).into()).chain(cells))
+                .spacing(1)
+                .into()
+        });
+
+        Column::with_children(std::iter::once(header.into()).chain(rows))
+            .spacing(1)
+            .padding([2, 12])
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolling_correlation_matches_window() {
+        let a = [0.01, -0.02, 0.005, 0.03, -0.01, 0.02];
+        let b = [0.02, -0.01, 0.0, 0.025, -0.02, 0.01];
+
+        let mut rolling = RollingCorrelation::default();
+        for (a, b) in a.iter().zip(&b) {
+            rolling.push(*a, *b);
+        }
+        for (a, b) in a.iter().zip(&b).take(2) {
+            rolling.pop(*a, *b);
+        }
+
+        let mut fresh = RollingCorrelation::default();
+        for (a, b) in a.iter().zip(&b).skip(2) {
+            fresh.push(*a, *b);
+        }
+
+        assert_eq!(rolling.n, 4);
+        assert!((rolling.value().unwrap() - fresh.value().unwrap()).abs() < 1e-12);
+
+        let mut flat = RollingCorrelation::default();
+        flat.push(0.01, 0.0);
+        flat.push(0.02, 0.0);
+        assert_eq!(flat.value(), None);
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+
+use iced::{
+    mouse,
+    widget::canvas::{event, Canvas, Event, Frame, Geometry, Path, Program, Stroke},
+    Color, Element, Length, Point, Rectangle, Renderer, Theme,
+};
+
+use crate::{
+    data::AppData,
+    theme::{color, Role},
+    views::components::loading::loader,
+};
+
+const MIN_ZOOM: f32 = 1.0;
+const MAX_ZOOM: f32 = 100.0;
+
+#[derive(Debug, Clone)]
+pub(crate) enum DepthChartMessage {
+    /// Multiply zoom
+    Zoom(f32),
+}
+
+pub(crate) struct DepthChartPane {
+    zoom: f32,
+}
+
+/// Parsed price levels in ascending order
+pub(crate) fn parse_levels<'a>(
+    levels: impl Iterator<Item = (&'a String, &'a f64)>,
+) -> Vec<(f64, f64)> {
+    let mut levels: Vec<_> = levels
+        .filter_map(|(price, qty)| Some((price.parse::<f64>().ok()?, *qty)))
+        .collect();
+
+    // keys are strings, they are not sorted numerically
+    levels.sort_by(|(p1, _), (p2, _)| p1.total_cmp(p2));
+
+    levels
+}
+
+struct DepthChart {
+    bids: Vec<(f64, f64)>,
+    asks: Vec<(f64, f64)>,
+    zoom: f32,
+}
+
+impl DepthChart {
+    fn new(bids: &BTreeMap<String, f64>, asks: &BTreeMap<String, f64>, zoom: f32) -> Self {
+        let mut bids = parse_levels(bids.iter());
+        bids.reverse();
+        let mut asks = parse_levels(asks.iter());
+
+        // running sum of quantity, starting from best price
+        for side in [&mut bids, &mut asks] {
+            let mut total = 0.0;
+            for (_, qty) in side.iter_mut() {
+                total += *qty;
+                *qty = total;
+            }
+        }
+
+        Self { bids, asks, zoom }
+    }
+
+    /// Center and half width of shown prices, centered on best price if book has one side only
+    fn price_range(&self) -> Option<(f64, f64)> {
+        let best_bid = self.bids.first().map(|(price, _)| *price);
+        let best_ask = self.asks.first().map(|(price, _)| *price);
+        let mid = match (best_bid, best_ask) {
+            (Some(bid), Some(ask)) => (bid + ask) / 2.0,
+            (Some(best), None) | (None, Some(best)) => best,
+            (None, None) => return None,
+        };
+
+        let lowest = self.bids.last().map_or(mid, |(p, _)| *p);
+        let highest = self.asks.last().map_or(mid, |(p, _)| *p);
+        let range = (mid - lowest).max(highest - mid).max(f64::EPSILON) / f64::from(self.zoom);
+
+        Some((mid, range))
+    }
+
+    fn draw_side(
+        frame: &mut Frame,
+        levels: &[(f64, f64)],
+        to_point: impl Fn(f64, f64) -> Point,
+        color: Color,
+    ) {
+        let Some((best, _)) = levels.first() else {
+            return;
+        };
+
+        let path = Path::new(|p| {
+            p.move_to(to_point(*best, 0.0));
+
+            let mut previous = 0.0;
+            for (price, total) in levels {
+                p.line_to(to_point(*price, previous));
+                p.line_to(to_point(*price, *total));
+                previous = *total;
+            }
+
+            if let Some((worst, _)) = levels.last() {
+                p.line_to(to_point(*worst, 0.0));
+            }
+            p.close();
+        });
+
+        frame.fill(&path, Color { a: 0.3, ..color });
+        frame.stroke(&path, Stroke::default().with_color(color).with_width(1.0));
+    }
+}
+
+impl Program<DepthChartMessage> for DepthChart {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<DepthChartMessage>) {
+        if !cursor.is_over(bounds) {
+            return (event::Status::Ignored, None);
+        }
+
+        match event {
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                let y = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / 20.0,
+                };
+
+                (
+                    event::Status::Captured,
+                    Some(DepthChartMessage::Zoom(1.0 + y * 0.1)),
+                )
+            }
+            _ => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+
+        let Some((mid, range)) = self.price_range() else {
+            return vec![frame.into_geometry()];
+        };
+
+        let max_total = self
+            .bids
+            .iter()
+            .chain(self.asks.iter())
+            .filter(|(p, _)| (mid - p).abs() <= range)
+            .map(|(_, total)| *total)
+            .fold(f64::EPSILON, f64::max);
+
+        let (width, height) = (frame.width(), frame.height());
+        let to_point = |price: f64, total: f64| {
+            let x = (price - (mid - range)) / (2.0 * range) * f64::from(width);
+            let y = f64::from(height) - total.min(max_total) / max_total * f64::from(height);
+            Point::new(x.clamp(0.0, f64::from(width)) as f32, y as f32)
+        };
+
+        Self::draw_side(&mut frame, &self.bids, to_point, color(Role::Accent));
+        Self::draw_side(&mut frame, &self.asks, to_point, color(Role::Danger));
+
+        vec![frame.into_geometry()]
+    }
+}
+
+impl DepthChartPane {
+    pub(crate) fn new() -> Self {
+        Self { zoom: MIN_ZOOM }
+    }
+
+    pub(crate) fn update(&mut self, msg: DepthChartMessage) {
+        match msg {
+            // narrows visible price range around mid price
+            DepthChartMessage::Zoom(factor) => {
+                self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+            }
+        }
+    }
+
+    pub(crate) fn view<'a>(&'a self, data: &'a AppData) -> Element<'a, DepthChartMessage> {
+        let (_, bids, asks) = &data.book;
+
+        if bids.is_empty() && asks.is_empty() {
+            return loader!().into();
+        }
+
+        Canvas::new(DepthChart::new(bids, asks, self.zoom))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_sided_book_is_centered_on_best_price() {
+        let levels = |levels: &[(&str, f64)]| -> BTreeMap<String, f64> {
+            levels.iter().map(|(p, q)| ((*p).to_owned(), *q)).collect()
+        };
+        let bids = levels(&[("99", 1.0), ("100", 2.0)]);
+        let asks = levels(&[("101", 1.0), ("103", 1.0)]);
+        let empty = BTreeMap::new();
+
+        assert_eq!(
+            DepthChart::new(&bids, &asks, 1.0).price_range(),
+            Some((100.5, 2.5))
+        );
+        assert_eq!(
+            DepthChart::new(&bids, &empty, 1.0).price_range(),
+            Some((100.0, 1.0))
+        );
+        assert_eq!(
+            DepthChart::new(&empty, &asks, 2.0).price_range(),
+            Some((101.0, 1.0))
+        );
+        assert_eq!(DepthChart::new(&empty, &empty, 1.0).price_range(), None);
+    }
+}
@@ -0,0 +1,114 @@
+use super::orders::{t, tb};
+use crate::{
+    data::{AppData, EXTREME_FUNDING_RATE},
+    theme::{color, Role},
+    views::components::{loading::loader, unstyled_btn::UnstyledBtn},
+};
+
+use iced::{
+    widget::{button, column, container, row, scrollable, Column},
+    Color, Element, Length,
+};
+
+/// Only most extreme rates are shown
+const MAX_ROWS: usize = 50;
+
+#[derive(Debug, Clone)]
+pub(crate) enum FundingMessage {
+    /// Symbol clicked, sets market pair
+    PairSelected(String),
+}
+
+pub(crate) struct FundingPane {}
+
+/// Sign picks color, rates closer to extreme are more opaque
+fn rate_color(rate: f64) -> Color {
+    // positive rate means longs pay shorts
+    let base = if rate >= 0.0 {
+        color(Role::Accent)
+    } else {
+        color(Role::Danger)
+    };
+
+    Color {
+        a: (0.4 + 0.6 * rate.abs() / EXTREME_FUNDING_RATE).min(1.0) as f32,
+        ..base
+    }
+}
+
+impl FundingPane {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+
+    /// Rates of `symbols` or of every symbol if it is empty, most extreme first
+    pub(crate) fn view<'a>(
+        &'a self,
+        data: &'a AppData,
+        symbols: &'a [String],
+    ) -> Element<'a, FundingMessage> {
+        if data.futures.funding.is_empty() {
+            return loader!().into();
+        }
+
+        let mut rates: Vec<_> = if symbols.is_empty() {
+            data.futures.funding.values().collect()
+        } else {
+            symbols
+                .iter()
+                .filter_map(|s| data.futures.funding.get(s))
+                .collect()
+        };
+        rates.sort_by(|a, b| b.rate.abs().total_cmp(&a.rate.abs()));
+
+        let now = chrono::Utc::now().timestamp_millis() as u64;
+
+        column![
+            row![
+                tb("Symbol").width(Length::Fill),
+                tb("Rate").width(Length::Fill),
+                tb("Mark").width(Length::Fill),
+                tb("Next in").width(Length::Fill)
+            ],
+            scrollable(Column::with_children(
+                rates
+                    .into_iter()
+                    .take(MAX_ROWS)
+                    .map(|r| {
+                        let is_extreme = r.rate.abs() > EXTREME_FUNDING_RATE;
+                        let left = r.next_funding_time.saturating_sub(now) / 1000;
+
+                        container(row![
+                            button(t(&r.symbol).style(if is_extreme {
+                                color(Role::Warning)
+                            } else {
+                                color(Role::Secondary)
+                            }))
+                            .padding(0)
+                            .style(iced::theme::Button::Custom(Box::new(UnstyledBtn {})))
+                            .on_press(FundingMessage::PairSelected(r.symbol.clone()))
+                            .width(Length::Fill),
+                            t(format!("{:+.4}%", r.rate * 100.0))
+                                .style(rate_color(r.rate))
+                                .width(Length::Fill),
+                            t(r.mark_price)
+                                .style(color(Role::Secondary))
+                                .width(Length::Fill),
+                            t(format!(
+                                "{:02}:{:02}:{:02}",
+                                left / 3600,
+                                left / 60 % 60,
+                                left % 60
+                            ))
+                            .style(color(Role::Secondary))
+                            .width(Length::Fill),
+                        ])
+                        .width(Length::Fill)
+                    })
+                    .map(Element::from),
+            ))
+        ]
+        .padding([2, 12])
+        .into()
+    }
+}
@@ -0,0 +1,238 @@
+use super::orders::tb;
+
+use crate::{
+    api::TradingClient,
+    data::AppData,
+    message::Message,
+    views::components::{
+        better_btn::{GreenBtn, RedBtn},
+        input::Inp,
+    },
+};
+
+use binance::rest_model::OrderSide;
+use iced::{
+    widget::{button, column, container, row, slider, text, text_input, Space},
+    Alignment, Command, Element, Length,
+};
+
+/// Highest leverage allowed by exchange for most liquid symbols
+const MAX_LEVERAGE: u8 = 125;
+
+/// Percent range of trailing stop callback accepted by futures API
+const CALLBACK_RATE: std::ops::RangeInclusive<f64> = 0.1..=10.0;
+
+macro_rules! tin {
+    ($e: expr, $b: expr) => {
+        text_input($e, $b).style(iced::theme::TextInput::Custom(Box::new(Inp {})))
+    };
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum FuturesMarketMessage {
+    PairInput(String),
+    PriceInput(String),
+    AmountInput(String),
+    LeverageChanged(u8),
+    /// Percent trailing stop follows price by
+    TrailingRateInput(String),
+    BuyPressed,
+    SellPressed,
+    /// Reduce only trailing stop, sells to close long or buys to close short
+    TrailPressed(OrderSide),
+}
+
+pub(crate) struct FuturesMarket {
+    pair: String,
+    price: String,
+    amount: String,
+    leverage: u8,
+    trailing_rate: String,
+}
+
+impl FuturesMarket {
+    pub(crate) fn new(pair: String) -> Self {
+        Self {
+            pair,
+            price: String::default(),
+            amount: String::default(),
+            leverage: 1,
+            trailing_rate: String::default(),
+        }
+    }
+
+    pub(crate) fn update(
+        &mut self,
+        msg: FuturesMarketMessage,
+        api: &dyn TradingClient,
+        data: &AppData,
+        paper: bool,
+    ) -> Command<Message> {
+        match msg {
+            FuturesMarketMessage::PairInput(new) => {
+                self.pair = new.to_uppercase();
+                Command::none()
+            }
+            FuturesMarketMessage::PriceInput(new) => {
+                self.price = new;
+                Command::none()
+            }
+            FuturesMarketMessage::AmountInput(new) => {
+                self.amount = new;
+                Command::none()
+            }
+            FuturesMarketMessage::LeverageChanged(leverage) => {
+                self.leverage = leverage;
+                Command::none()
+            }
+            FuturesMarketMessage::TrailingRateInput(new) => {
+                self.trailing_rate = new;
+                Command::none()
+            }
+            // paper broker only simulates spot, orders would be real
+            FuturesMarketMessage::BuyPressed
+            | FuturesMarketMessage::SellPressed
+            | FuturesMarketMessage::TrailPressed(_)
+                if paper =>
+            {
+                Command::perform(async {}, |_| {
+                    Message::ToastError("futures are not simulated in paper trading".to_owned())
+                })
+            }
+            FuturesMarketMessage::BuyPressed => self.place_order(api, data, OrderSide::Buy),
+            FuturesMarketMessage::SellPressed => self.place_order(api, data, OrderSide::Sell),
+            FuturesMarketMessage::TrailPressed(side) => self.place_trailing_stop(api, data, side),
+        }
+    }
+
+    fn place_order(
+        &self,
+        api: &dyn TradingClient,
+        data: &AppData,
+        side: OrderSide,
+    ) -> Command<Message> {
+        let (Ok(price), Ok(amount)) = (self.price.parse::<f64>(), self.amount.parse::<f64>())
+        else {
+            return Command::perform(async {}, |_| {
+                Message::ToastError("price and amount must be numbers".to_owned())
+            });
+        };
+        let amount = data.futures.symbol_filter(&self.pair).round_qty(amount);
+
+        api.trade_futures(self.pair.clone(), price, amount, side, self.leverage)
+    }
+
+    /// Callback rate if it is within range accepted by exchange
+    fn callback_rate(&self) -> Result<f64, String> {
+        self.trailing_rate
+            .parse()
+            .ok()
+            .filter(|rate| CALLBACK_RATE.contains(rate))
+            .ok_or_else(|| {
+                format!(
+                    "callback rate must be between {}% and {}%",
+                    CALLBACK_RATE.start(),
+                    CALLBACK_RATE.end()
+                )
+            })
+    }
+
+    fn place_trailing_stop(
+        &self,
+        api: &dyn TradingClient,
+        data: &AppData,
+        side: OrderSide,
+    ) -> Command<Message> {
+        let callback_rate = match self.callback_rate() {
+            Ok(rate) => rate,
+            Err(err) => return Command::perform(async {}, |_| Message::ToastError(err)),
+        };
+        let Some(amount) = self.amount.parse::<f64>().ok().filter(|a| *a > 0.0) else {
+            return Command::perform(async {}, |_| {
+                Message::ToastError("amount must be a positive number".to_owned())
+            });
+        };
+        let amount = data.futures.symbol_filter(&self.pair).round_qty(amount);
+
+        api.place_trailing_stop(self.pair.clone(), amount, side, callback_rate)
+    }
+
+    pub(crate) fn view(&self) -> Element<'_, FuturesMarketMessage> {
+        container(
+            column![
+                Space::new(Length::Fill, 1.0),
+                tin!("type a pair", &self.pair)
+                    .on_input(FuturesMarketMessage::PairInput)
+                    .width(300.0),
+                row![
+                    tin!("price", &self.price)
+                        .on_input(FuturesMarketMessage::PriceInput)
+                        .width(150.0),
+                    tin!("amount", &self.amount)
+                        .on_input(FuturesMarketMessage::AmountInput)
+                        .width(150.0),
+                ]
+                .spacing(4.0)
+                .width(300.0),
+                row![
+                    text(format!("{}x", self.leverage)).size(14).width(40.0),
+                    slider(
+                        1..=MAX_LEVERAGE,
+                        self.leverage,
+                        FuturesMarketMessage::LeverageChanged
+                    ),
+                ]
+                .spacing(4.0)
+                .align_items(Alignment::Center)
+                .width(300.0),
+                row![
+                    button(tb("Long").style(iced::Color::WHITE).size(12))
+                        .style(iced::theme::Button::Custom(Box::new(GreenBtn {})))
+                        .padding(8)
+                        .on_press(FuturesMarketMessage::BuyPressed),
+                    Space::new(5.0, 0.0),
+                    button(tb("Short").style(iced::Color::WHITE).size(12))
+                        .style(iced::theme::Button::Custom(Box::new(RedBtn {})))
+                        .padding(8)
+                        .on_press(FuturesMarketMessage::SellPressed)
+                ],
+                row![
+                    tin!("callback rate %", &self.trailing_rate)
+                        .on_input(FuturesMarketMessage::TrailingRateInput)
+                        .width(120.0),
+                    button(tb("Trail long").size(12))
+                        .padding(8)
+                        .on_press(FuturesMarketMessage::TrailPressed(OrderSide::Sell)),
+                    button(tb("Trail short").size(12))
+                        .padding(8)
+                        .on_press(FuturesMarketMessage::TrailPressed(OrderSide::Buy)),
+                ]
+                .spacing(4.0)
+                .align_items(Alignment::Center)
+                .width(300.0),
+                Space::new(Length::Fill, 1.0)
+            ]
+            .spacing(4.0)
+            .align_items(Alignment::Center),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn callback_rate_is_validated() {
+        let mut market = FuturesMarket::new("BTCUSDT".to_owned());
+        for rate in ["", "0.05", "11", "nan"] {
+            market.trailing_rate = rate.to_owned();
+            assert!(market.callback_rate().is_err(), "{rate}");
+        }
+        market.trailing_rate = "1.5".to_owned();
+        assert_eq!(market.callback_rate(), Ok(1.5));
+    }
+}
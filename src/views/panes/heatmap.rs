@@ -0,0 +1,295 @@
+use ahash::AHashMap;
+use iced::{
+    alignment, mouse,
+    widget::canvas::{event, Canvas, Event, Frame, Geometry, Program, Text},
+    Color, Element, Length, Point, Rectangle, Renderer, Size, Theme,
+};
+use ringbuf::Rb;
+
+use crate::{
+    data::{AppData, StaticLocalRb},
+    theme::{color, Role},
+    views::components::loading::loader,
+};
+
+/// Number of ticks used for correlation
+const SAMPLES: usize = 120;
+
+/// Space for symbol names left of and above the grid
+const LABEL_SIZE: f32 = 48.0;
+
+type Samples = StaticLocalRb<f64, SAMPLES>;
+
+/// Relative change between consecutive prices
+fn returns<'a>(prices: impl Iterator<Item = &'a f64>) -> Vec<f64> {
+    let prices: Vec<_> = prices.collect();
+    prices
+        .windows(2)
+        .map(|pair| pair[1] / pair[0] - 1.0)
+        .collect()
+}
+
+/// Pearson correlation of returns of two price series, None if either is flat
+fn correlation(a: &Samples, b: &Samples) -> Option<f64> {
+    let len = a.len().min(b.len());
+    // at least two returns are needed
+    if len < 3 || a.iter().chain(b.iter()).any(|price| *price == 0.0) {
+        return None;
+    }
+
+    // align to the newest samples, prices trending together are not correlated on their own
+    let a = returns(a.iter().skip(a.len() - len));
+    let b = returns(b.iter().skip(b.len() - len));
+    let len = a.len();
+
+    let mean_a = a.iter().sum::<f64>() / len as f64;
+    let mean_b = b.iter().sum::<f64>() / len as f64;
+
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.into_iter().zip(b) {
+        let (dx, dy) = (x - mean_a, y - mean_b);
+        cov += dx * dy;
+        var_a += dx * dx;
+        var_b += dy * dy;
+    }
+
+    if var_a == 0.0 || var_b == 0.0 {
+        return None;
+    }
+
+    Some(cov / (var_a * var_b).sqrt())
+}
+
+/// Red at -1, white at 0, green at 1
+pub(crate) fn cell_color(value: f64) -> Color {
+    let white = Color::WHITE;
+    let target = if value < 0.0 {
+        color(Role::Danger)
+    } else {
+        color(Role::Accent)
+    };
+    let t = value.abs().min(1.0) as f32;
+
+    Color::from_rgb(
+        white.r + (target.r - white.r) * t,
+        white.g + (target.g - white.g) * t,
+        white.b + (target.b - white.b) * t,
+    )
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum HeatmapMessage {
+    /// Cell clicked, sets market pair
+    PairSelected(String),
+}
+
+pub(crate) struct HeatmapPane {
+    symbols: Vec<String>,
+    history: AHashMap<String, Samples>,
+    /// Row major matrix, same order as symbols
+    matrix: Vec<Option<f64>>,
+}
+
+struct Heatmap<'a> {
+    symbols: &'a [String],
+    matrix: &'a [Option<f64>],
+}
+
+impl Heatmap<'_> {
+    fn cell_size(&self, bounds: Size) -> Size {
+        let n = self.symbols.len().max(1) as f32;
+        Size::new(
+            ((bounds.width - LABEL_SIZE) / n).max(0.0),
+            ((bounds.height - LABEL_SIZE) / n).max(0.0),
+        )
+    }
+
+    fn cell_at(&self, bounds: Size, position: Point) -> Option<(usize, usize)> {
+        let cell = self.cell_size(bounds);
+        if position.x < LABEL_SIZE || position.y < LABEL_SIZE || cell.width == 0.0 {
+            return None;
+        }
+
+        let col = ((position.x - LABEL_SIZE) / cell.width) as usize;
+        let row = ((position.y - LABEL_SIZE) / cell.height) as usize;
+
+        (row < self.symbols.len() && col < self.symbols.len()).then_some((row, col))
+    }
+}
+
+impl Program<HeatmapMessage> for Heatmap<'_> {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<HeatmapMessage>) {
+        let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event else {
+            return (event::Status::Ignored, None);
+        };
+
+        match cursor
+            .position_in(bounds)
+            .and_then(|position| self.cell_at(bounds.size(), position))
+        {
+            Some((row, _)) => (
+                event::Status::Captured,
+                Some(HeatmapMessage::PairSelected(self.symbols[row].clone())),
+            ),
+            None => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let cell = self.cell_size(bounds.size());
+        let label_color = color(Role::Secondary);
+
+        for (i, symbol) in self.symbols.iter().enumerate() {
+            let name = symbol.strip_suffix("USDT").unwrap_or(symbol).to_owned();
+            let offset = LABEL_SIZE + (i as f32 + 0.5) * cell.width;
+
+            frame.fill_text(Text {
+                content: name.clone(),
+                position: Point::new(offset, LABEL_SIZE / 2.0),
+                color: label_color,
+                size: 12.0.into(),
+                horizontal_alignment: alignment::Horizontal::Center,
+                vertical_alignment: alignment::Vertical::Center,
+                ..Text::default()
+            });
+            frame.fill_text(Text {
+                content: name,
+                position: Point::new(
+                    LABEL_SIZE - 4.0,
+                    LABEL_SIZE + (i as f32 + 0.5) * cell.height,
+                ),
+                color: label_color,
+                size: 12.0.into(),
+                horizontal_alignment: alignment::Horizontal::Right,
+                vertical_alignment: alignment::Vertical::Center,
+                ..Text::default()
+            });
+        }
+
+        let n = self.symbols.len();
+        for (i, value) in self.matrix.iter().enumerate() {
+            let (row, col) = (i / n, i % n);
+            let top_left = Point::new(
+                LABEL_SIZE + col as f32 * cell.width,
+                LABEL_SIZE + row as f32 * cell.height,
+            );
+            let inner = Size::new((cell.width - 1.0).max(0.0), (cell.height - 1.0).max(0.0));
+
+            frame.fill_rectangle(
+                top_left,
+                inner,
+                value.map_or(color(Role::Surface), cell_color),
+            );
+
+            if let Some(value) = value {
+                frame.fill_text(Text {
+                    content: format!("{value:.2}"),
+                    position: Point::new(
+                        top_left.x + inner.width / 2.0,
+                        top_left.y + inner.height / 2.0,
+                    ),
+                    color: Color::BLACK,
+                    size: 11.0.into(),
+                    horizontal_alignment: alignment::Horizontal::Center,
+                    vertical_alignment: alignment::Vertical::Center,
+                    ..Text::default()
+                });
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+impl HeatmapPane {
+    pub(crate) fn new() -> Self {
+        Self {
+            symbols: Vec::new(),
+            history: AHashMap::new(),
+            matrix: Vec::new(),
+        }
+    }
+
+    /// Samples current prices of symbols and recomputes matrix
+    pub(crate) fn tick(&mut self, data: &AppData, symbols: &[String]) {
+        self.history.retain(|symbol, _| symbols.contains(symbol));
+
+        for symbol in symbols {
+            if let Some(price) = data.prices.get(symbol) {
+                self.history
+                    .entry(symbol.clone())
+                    .or_default()
+                    .push_overwrite(f64::from(price));
+            }
+        }
+
+        self.symbols = symbols.to_vec();
+        self.matrix = self
+            .symbols
+            .iter()
+            .flat_map(|a| self.symbols.iter().map(move |b| (a, b)))
+            .map(|(a, b)| correlation(self.history.get(a)?, self.history.get(b)?))
+            .collect();
+    }
+
+    pub(crate) fn view(&self) -> Element<'_, HeatmapMessage> {
+        if self.matrix.iter().all(Option::is_none) {
+            return loader!().into();
+        }
+
+        Canvas::new(Heatmap {
+            symbols: &self.symbols,
+            matrix: &self.matrix,
+        })
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pearson_correlation() {
+        let mut a = Samples::default();
+        let mut b = Samples::default();
+        let mut c = Samples::default();
+        for (x, y) in [(100.0, 100.0), (110.0, 90.0), (99.0, 99.0), (108.9, 89.1)] {
+            a.push_overwrite(x);
+            b.push_overwrite(x * 3.0);
+            c.push_overwrite(y);
+        }
+
+        assert!((correlation(&a, &b).unwrap() - 1.0).abs() < 1e-9);
+        assert!((correlation(&a, &c).unwrap() + 1.0).abs() < 1e-9);
+
+        // both rise, but their returns move oppositely
+        let mut d = Samples::default();
+        let mut e = Samples::default();
+        d.push_iter_overwrite([100.0, 102.0, 103.0, 106.0].into_iter());
+        e.push_iter_overwrite([100.0, 101.0, 103.0, 104.0].into_iter());
+        assert!(correlation(&d, &e).unwrap() < 0.0);
+
+        let mut flat = Samples::default();
+        flat.push_iter_overwrite([5.0; 4].into_iter());
+        assert_eq!(correlation(&a, &flat), None);
+    }
+}
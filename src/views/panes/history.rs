@@ -0,0 +1,303 @@
+use std::{collections::VecDeque, fmt::Write};
+
+use super::orders::{csv_field, export_csv, t, tb};
+use crate::{
+    api::{split_symbol, TradingClient},
+    data::{AppData, Prices, Trade},
+    message::Message,
+    theme::{color, Role},
+};
+
+use iced::{
+    widget::{button, column, row, scrollable, text, Column, Space},
+    Command, Element, Length,
+};
+
+/// Trades requested at once
+const PAGE_LEN: u32 = 50;
+
+#[derive(Debug, Clone)]
+pub(crate) enum HistoryMessage {
+    /// Fetch newest trades of market pair
+    Refresh,
+    /// Fetch trades older than oldest loaded one
+    LoadMore,
+    /// Save loaded trades to CSV file
+    Export,
+}
+
+/// Fee converted to quote asset, None if there is no price to convert it with
+fn fee_in_quote(trade: &Trade, prices: &Prices) -> Option<f64> {
+    let [base, quote] = split_symbol(&trade.symbol)?;
+
+    if trade.commission == 0.0 || trade.commission_asset == quote {
+        Some(trade.commission)
+    } else if trade.commission_asset == base {
+        Some(trade.commission * trade.price)
+    } else {
+        prices
+            .get(&format!("{}{quote}", trade.commission_asset))
+            .map(|price| trade.commission * f64::from(price))
+    }
+}
+
+/// Realized profit of every sell against earliest bought lots still held, None for buys
+///
+/// `trades` are oldest first, `fee` is in quote asset
+fn fifo_pnl(trades: &[&Trade], fee: impl Fn(&Trade) -> f64) -> Vec<Option<f64>> {
+    // quantity and cost per unit of bought lots
+    let mut lots = VecDeque::<(f64, f64)>::new();
+
+    trades
+        .iter()
+        .map(|trade| {
+            if trade.is_buyer {
+                lots.push_back((trade.qty, trade.price + fee(trade) / trade.qty));
+                return None;
+            }
+
+            let mut remaining = trade.qty;
+            let mut cost = 0.0;
+            while let Some((qty, unit_cost)) = lots.front_mut() {
+                if remaining <= 0.0 {
+                    break;
+                }
+                let matched = remaining.min(*qty);
+                cost += matched * *unit_cost;
+                remaining -= matched;
+                *qty -= matched;
+                if *qty <= 0.0 {
+                    lots.pop_front();
+                }
+            }
+
+            // sold amount bought before loaded history has unknown cost
+            let matched = trade.qty - remaining;
+            (matched > 0.0)
+                .then(|| (trade.price * trade.qty - fee(trade)) * matched / trade.qty - cost)
+        })
+        .collect()
+}
+
+/// Loaded trades as CSV table, oldest first
+fn trades_csv(trades: &[Trade], pnl: &[Option<f64>]) -> String {
+    let mut csv = String::from("timestamp,symbol,side,price,quantity,fee,fee_asset,pnl\n");
+
+    for (trade, pnl) in trades.iter().rev().zip(pnl) {
+        let timestamp = chrono::DateTime::from_timestamp_millis(trade.time as i64)
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default();
+
+        writeln!(
+            csv,
+            "{timestamp},{},{},{},{},{},{},{}",
+            csv_field(&trade.symbol),
+            if trade.is_buyer { "BUY" } else { "SELL" },
+            trade.price,
+            trade.qty,
+            trade.commission,
+            csv_field(&trade.commission_asset),
+            pnl.map(|pnl| pnl.to_string()).unwrap_or_default()
+        )
+        .expect("writing to string does not fail");
+    }
+
+    csv
+}
+
+/// Profit of loaded trades, oldest first
+fn history_pnl(data: &AppData) -> Vec<Option<f64>> {
+    let oldest_first: Vec<_> = data.trade_history.iter().rev().collect();
+
+    fifo_pnl(&oldest_first, |trade| {
+        fee_in_quote(trade, &data.prices).unwrap_or_default()
+    })
+}
+
+pub(crate) struct HistoryPane {
+    /// Trade id last page was requested from, newest trades were requested if not set
+    from_id: Option<u64>,
+    /// Nothing older than loaded trades is left
+    exhausted: bool,
+}
+
+impl HistoryPane {
+    pub(crate) fn new() -> Self {
+        Self {
+            from_id: None,
+            exhausted: false,
+        }
+    }
+
+    pub(crate) fn update(
+        &mut self,
+        msg: HistoryMessage,
+        data: &AppData,
+        api: &dyn TradingClient,
+        pair: &str,
+    ) -> Command<Message> {
+        match msg {
+            HistoryMessage::Refresh => {
+                self.from_id = None;
+                self.exhausted = false;
+                api.get_trade_history(pair, PAGE_LEN, None)
+            }
+            HistoryMessage::LoadMore => match data.trade_history.last() {
+                Some(oldest) => {
+                    let from_id = oldest.id.saturating_sub(PAGE_LEN as u64);
+                    self.from_id = Some(from_id);
+                    api.get_trade_history(&oldest.symbol, PAGE_LEN, Some(from_id))
+                }
+                None => {
+                    self.from_id = None;
+                    api.get_trade_history(pair, PAGE_LEN, None)
+                }
+            },
+            HistoryMessage::Export => export_csv(
+                trades_csv(&data.trade_history, &history_pnl(data)),
+                "trades",
+            ),
+        }
+    }
+
+    /// Remembers if older trades are left, called with length of received page
+    pub(crate) fn page_received(&mut self, len: usize) {
+        self.exhausted = match self.from_id {
+            // short page from id only means that newer trades ran out
+            Some(from_id) => from_id == 0,
+            None => len < PAGE_LEN as usize,
+        };
+    }
+
+    pub(crate) fn view<'a>(&'a self, data: &'a AppData) -> Element<'a, HistoryMessage> {
+        let trades = &data.trade_history;
+        let pnl = history_pnl(data);
+
+        let toolbar = row![
+            tb(trades
+                .first()
+                .map_or_else(|| "No trades loaded".to_owned(), |t| t.symbol.clone())),
+            Space::new(Length::Fill, 0),
+            button(text("Refresh").size(12))
+                .style(iced::theme::Button::Text)
+                .padding(8)
+                .on_press(HistoryMessage::Refresh),
+            button(text("Export CSV").size(12))
+                .style(iced::theme::Button::Text)
+                .padding(8)
+                .on_press_maybe((!trades.is_empty()).then_some(HistoryMessage::Export)),
+        ];
+
+        let header = row![
+            tb("Time").width(Length::Fill),
+            tb("Pair").width(Length::Fill),
+            tb("Side").width(Length::Fill),
+            tb("Price").width(Length::Fill),
+            tb("Qty").width(Length::Fill),
+            tb("Fee").width(Length::Fill),
+            tb("PNL").width(Length::Fill),
+        ]
+        .spacing(8);
+
+        let rows = trades.iter().zip(pnl.iter().rev()).map(|(trade, pnl)| {
+            let filter = data.symbol_filter(&trade.symbol);
+            let time = chrono::DateTime::from_timestamp_millis(trade.time as i64)
+                .map(|dt| dt.format("%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            let (side, side_color) = if trade.is_buyer {
+                ("Buy", color(Role::Accent))
+            } else {
+                ("Sell", color(Role::Danger))
+            };
+
+            row![
+                t(time).width(Length::Fill),
+                t(&trade.symbol).width(Length::Fill),
+                t(side).style(side_color).width(Length::Fill),
+                t(filter.price(trade.price)).width(Length::Fill),
+                t(filter.qty(trade.qty)).width(Length::Fill),
+                t(format!("{} {}", trade.commission, trade.commission_asset))
+                    .style(color(Role::Secondary))
+                    .width(Length::Fill),
+                match pnl {
+                    Some(pnl) => t(format!("{pnl:.2}")).style(if *pnl >= 0.0 {
+                        color(Role::Accent)
+                    } else {
+                        color(Role::Danger)
+                    }),
+                    None => t("-").style(color(Role::Secondary)),
+                }
+                .width(Length::Fill),
+            ]
+            .spacing(8)
+            .into()
+        });
+
+        let load_more = (!trades.is_empty() && !self.exhausted).then(|| {
+            button(text("Load more").size(12))
+                .style(iced::theme::Button::Text)
+                .padding(8)
+                .on_press(HistoryMessage::LoadMore)
+        });
+
+        column![
+            toolbar,
+            header,
+            scrollable(Column::with_children(rows).push_maybe(load_more).spacing(4))
+        ]
+        .padding([2, 12])
+        .spacing(4)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_until_first_trade_id() {
+        let mut pane = HistoryPane::new();
+        pane.page_received(PAGE_LEN as usize);
+        assert!(!pane.exhausted);
+
+        pane.from_id = Some(10);
+        pane.page_received(3);
+        assert!(!pane.exhausted);
+
+        pane.from_id = Some(0);
+        pane.page_received(PAGE_LEN as usize);
+        assert!(pane.exhausted);
+    }
+
+    #[test]
+    fn fifo_profit() {
+        let trade = |is_buyer, price, qty| Trade {
+            id: 0,
+            order_id: 0,
+            symbol: "BTCUSDT".to_owned(),
+            time: 0,
+            is_buyer,
+            price,
+            qty,
+            commission: 0.0,
+            commission_asset: "USDT".to_owned(),
+        };
+        let trades = [
+            trade(false, 90.0, 1.0),
+            trade(true, 100.0, 1.0),
+            trade(true, 200.0, 1.0),
+            trade(false, 300.0, 1.5),
+            trade(false, 300.0, 1.0),
+        ];
+        let trades: Vec<_> = trades.iter().collect();
+
+        let pnl = fifo_pnl(&trades, |_| 1.0);
+        assert_eq!(pnl[0], None);
+        assert_eq!(pnl[1], None);
+        // 300 * 1.5 - 1 fee against 101 and half of 201
+        assert!((pnl[3].unwrap() - (449.0 - 101.0 - 100.5)).abs() < 1e-9);
+        // only half of amount sold was bought
+        assert!((pnl[4].unwrap() - (299.0 * 0.5 - 100.5)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,127 @@
+use std::time::{Duration, Instant};
+
+use super::orders::{t, tb};
+use crate::{
+    data::{StaticLocalRb, LIQUIDATIONS_LEN},
+    theme::{color, Role},
+    views::components::loading::loader,
+    ws::futures::Liquidation,
+};
+
+use iced::{
+    widget::{column, container, row, scrollable, Column},
+    Background, Color, Element, Length,
+};
+use ringbuf::{ring_buffer::RbBase, Rb};
+
+/// Liquidations worth more than this in quote asset are flashed
+const LARGE_LIQUIDATION: f64 = 100_000.0;
+
+const FLASH_DURATION: Duration = Duration::from_millis(1500);
+
+#[derive(Debug, Clone)]
+pub(crate) enum LiquidationsMessage {
+    /// Redraw flashing rows
+    Animate(Instant),
+}
+
+pub(crate) struct LiquidationsPane {
+    /// Exchange time of large liquidations being flashed and when they arrived
+    flashes: Vec<(u64, Instant)>,
+}
+
+impl LiquidationsPane {
+    pub(crate) fn new() -> Self {
+        Self {
+            flashes: Vec::new(),
+        }
+    }
+
+    /// Starts flashing liquidation if it is large
+    pub(crate) fn received(&mut self, liquidation: &Liquidation) {
+        if liquidation.price * liquidation.qty > LARGE_LIQUIDATION {
+            self.flashes.push((liquidation.time, Instant::now()));
+        }
+    }
+
+    /// Pane needs to be redrawn until flashes fade out
+    pub(crate) fn is_animating(&self) -> bool {
+        !self.flashes.is_empty()
+    }
+
+    pub(crate) fn update(&mut self, msg: LiquidationsMessage) {
+        match msg {
+            LiquidationsMessage::Animate(now) => self
+                .flashes
+                .retain(|(_, at)| now.duration_since(*at) < FLASH_DURATION),
+        }
+    }
+
+    /// Opacity of flash over liquidation row, fades to 0
+    fn flash(&self, liquidation: &Liquidation) -> f32 {
+        self.flashes
+            .iter()
+            .find(|(time, _)| *time == liquidation.time)
+            .map_or(0.0, |(_, at)| {
+                1.0 - at.elapsed().as_secs_f32() / FLASH_DURATION.as_secs_f32()
+            })
+            .max(0.0)
+    }
+
+    pub(crate) fn view<'a, M: Clone + 'a>(
+        &'a self,
+        liquidations: &'a StaticLocalRb<Liquidation, LIQUIDATIONS_LEN>,
+    ) -> Element<'a, M> {
+        if liquidations.is_empty() {
+            return loader!().into();
+        }
+
+        column![
+            row![
+                tb("Symbol").width(Length::Fill),
+                tb("Price").width(Length::Fill),
+                tb("Value").width(Length::Fill),
+                tb("Time").width(Length::Fill)
+            ],
+            scrollable(Column::with_children(
+                liquidations
+                    .iter()
+                    .rev()
+                    .map(|l| {
+                        // sell order closes long position
+                        let c = if l.side == "SELL" {
+                            color(Role::Danger)
+                        } else {
+                            color(Role::Accent)
+                        };
+                        let time = chrono::DateTime::from_timestamp_millis(l.time as i64)
+                            .map(|dt| dt.format("%H:%M:%S").to_string())
+                            .unwrap_or_default();
+                        let flash = self.flash(l);
+
+                        container(row![
+                            t(&l.symbol).style(c).width(Length::Fill),
+                            t(l.price).width(Length::Fill),
+                            t(format!("{:.2}", l.price * l.qty))
+                                .style(color(Role::Secondary))
+                                .width(Length::Fill),
+                            t(time).style(color(Role::Secondary)).width(Length::Fill),
+                        ])
+                        .style(container::Appearance {
+                            background: (flash > 0.0).then(|| {
+                                Background::Color(Color {
+                                    a: flash * 0.4,
+                                    ..color(Role::Warning)
+                                })
+                            }),
+                            ..Default::default()
+                        })
+                        .width(Length::Fill)
+                    })
+                    .map(Element::from),
+            ))
+        ]
+        .padding([2, 12])
+        .into()
+    }
+}
@@ -1,19 +1,25 @@
 use super::orders::tb;
 
 use crate::{
-    api::Client,
-    data::AppData,
+    alerts::{Alert, AlertCondition},
+    api::{split_symbol, OrderType, TradingClient},
+    data::{AppData, SymbolFilter},
     message::Message,
+    theme::{color, Role},
     views::components::{
-        better_btn::{GreenBtn, RedBtn},
-        input::Inp,
+        better_btn::{BetterBtn, GreenBtn, RedBtn},
+        copyable::copyable,
+        input::{Inp, InvalidInp},
     },
     ws::Websockets,
 };
 
+use binance::rest_model::OrderSide;
+use std::time::{Duration, Instant};
+
 use iced::{
-    widget::{button, column, container, row, text, text_input, Space},
-    Alignment, Command, Element, Length,
+    widget::{button, column, container, row, text, text_input, tooltip, Column, Space},
+    Alignment, Command, Element, Font, Length,
 };
 
 macro_rules! bbtn {
@@ -28,30 +34,123 @@ macro_rules! tin {
     };
 }
 
+/// Unconfirmed order preview is dismissed after this long
+const PREVIEW_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Price or amount that can be sent to exchange
+fn is_valid_number(value: &str) -> bool {
+    value.parse::<f64>().is_ok_and(|v| v.is_finite() && v > 0.0)
+}
+
+/// Expected outcome of order, fee is taken from received asset like on exchange
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FillEstimate {
+    price: f64,
+    /// Value in quote asset before fee
+    total: f64,
+    /// In received asset
+    fee: f64,
+    /// Received amount after fee, base asset for buys and quote for sells
+    net: f64,
+}
+
+impl FillEstimate {
+    fn new(side: &OrderSide, price: f64, amount: f64, fee_rate: f64) -> Self {
+        let total = price * amount;
+        let received = match side {
+            OrderSide::Buy => amount,
+            OrderSide::Sell => total,
+        };
+        let fee = received * fee_rate;
+
+        Self {
+            price,
+            total,
+            fee,
+            net: received - fee,
+        }
+    }
+}
+
+/// Order waiting for confirmation, inputs are copied so that later edits do not change it
+#[derive(Debug, Clone)]
+struct OrderPreview {
+    side: OrderSide,
+    shown_at: Instant,
+    pair: String,
+    order_type: OrderType,
+    /// Zero for market orders
+    price: f64,
+    /// In base asset, rounded to step size
+    amount: f64,
+}
+
 #[derive(Debug, Clone)]
 pub(crate) enum MarketPanelMessage {
-    BuyPressed,
-    SellPressed,
+    /// Show estimated fill before placing order
+    Preview(OrderSide),
+    PreviewConfirm,
+    PreviewCancel,
     PriceMultiplied(f64),
     PriceInput(String),
     AmountMultiplied(f64),
     AmountInput(String),
     PairSet,
     PairInput(String),
+    /// Swap base and quote of pair
+    FlipPair,
+    /// Input right clicked
+    Copy(String),
+    OrderTypeChanged(OrderType),
+    StopPriceInput(String),
+
+    /// Switch between order form and alerts
+    AlertsToggled,
+    AlertConditionChanged(AlertCondition),
+    AlertPriceInput(String),
+
+    /// Price alerts, stored in config
+    AddAlert(Alert),
+    RemoveAlert(usize),
 }
 
 pub(crate) struct Market {
     price: String,
+    /// Price is a positive number
+    price_valid: bool,
     amount: String,
+    /// Amount is a positive number
+    amount_valid: bool,
+    /// Amount after last change was rounded to step size
+    rounded_amount: Option<String>,
+    stop_price: String,
     pair: String,
+    /// Buttons buy and sell quote asset instead of base, for pairs without reversed market,
+    /// amount is entered in quote asset then
+    flipped: bool,
+    order_type: OrderType,
+    alerts_opened: bool,
+    alert_condition: AlertCondition,
+    alert_price: String,
+    preview: Option<OrderPreview>,
 }
 
 impl Market {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(pair: String) -> Self {
         Self {
             price: String::default(),
+            price_valid: false,
             amount: String::default(),
-            pair: "BTCUSDT".to_owned(),
+            amount_valid: false,
+            rounded_amount: None,
+            stop_price: String::default(),
+            pair,
+            flipped: false,
+            order_type: OrderType::Limit,
+            alerts_opened: false,
+            alert_condition: AlertCondition::Above,
+            alert_price: String::default(),
+            preview: None,
         }
     }
 
@@ -60,19 +159,469 @@ impl Market {
         &self.pair
     }
 
-    pub(crate) fn view(&self) -> Element<'_, MarketPanelMessage> {
+    fn order_type_button(
+        &self,
+        label: &str,
+        order_type: OrderType,
+    ) -> Element<'_, MarketPanelMessage> {
+        let is_selected =
+            std::mem::discriminant(&self.order_type) == std::mem::discriminant(&order_type);
+
+        button(text(label).size(12))
+            .padding(8)
+            .style(if is_selected {
+                iced::theme::Button::Custom(Box::new(BetterBtn {}))
+            } else {
+                iced::theme::Button::Text
+            })
+            .on_press(MarketPanelMessage::OrderTypeChanged(order_type))
+            .into()
+    }
+
+    fn alert_condition_button(&self, condition: AlertCondition) -> Element<'_, MarketPanelMessage> {
+        button(text(condition.to_string()).size(12))
+            .padding(8)
+            .style(if self.alert_condition == condition {
+                iced::theme::Button::Custom(Box::new(BetterBtn {}))
+            } else {
+                iced::theme::Button::Text
+            })
+            .on_press(MarketPanelMessage::AlertConditionChanged(condition))
+            .into()
+    }
+
+    fn view_alerts<'a>(&'a self, alerts: &'a [Alert]) -> Element<'a, MarketPanelMessage> {
+        let list = alerts.iter().enumerate().map(|(i, alert)| {
+            row![
+                text(alert.to_string()).size(14).style(if alert.triggered {
+                    color(Role::Muted)
+                } else {
+                    color(Role::Foreground)
+                }),
+                Space::new(Length::Fill, 1.0),
+                button(text("Remove").size(12))
+                    .style(iced::theme::Button::Custom(Box::new(RedBtn {})))
+                    .padding(4)
+                    .on_press(MarketPanelMessage::RemoveAlert(i)),
+            ]
+            .align_items(Alignment::Center)
+            .into()
+        });
+
+        let price = self.alert_price.parse::<f64>().ok().filter(|p| *p > 0.0);
+        let mut add = button(tb("Add").style(iced::Color::WHITE).size(12))
+            .style(iced::theme::Button::Custom(Box::new(GreenBtn {})))
+            .padding(8);
+        if let Some(price) = price {
+            add = add.on_press(MarketPanelMessage::AddAlert(Alert::new(
+                self.pair.clone(),
+                self.alert_condition,
+                price,
+            )));
+        }
+
+        column![
+            Column::with_children(list).spacing(4),
+            row![
+                text(&self.pair).size(14),
+                self.alert_condition_button(AlertCondition::Above),
+                self.alert_condition_button(AlertCondition::Below),
+                tin!("price", &self.alert_price)
+                    .on_input(MarketPanelMessage::AlertPriceInput)
+                    .width(150.0),
+                add,
+            ]
+            .spacing(4)
+            .align_items(Alignment::Center),
+        ]
+        .spacing(8)
+        .padding(8)
+        .into()
+    }
+
+    /// Amount rounded to step size of pair, remembered to be shown in tooltip if it changed
+    fn set_amount(&mut self, amount: f64, rounded: f64, filter: SymbolFilter) {
+        self.amount = filter.qty(rounded);
+        self.amount_valid = is_valid_number(&self.amount);
+        self.rounded_amount =
+            ((rounded - amount).abs() > f64::EPSILON).then(|| self.amount.clone());
+    }
+
+    /// Asset amount is entered in
+    fn amount_asset(&self) -> Option<&str> {
+        let [base, quote] = split_symbol(&self.pair)?;
+
+        Some(if self.flipped { quote } else { base })
+    }
+
+    /// Entered amount in base asset, converted from quote asset at `price` when flipped
+    fn base_amount(&self, price: f64) -> Option<f64> {
+        let amount = self.amount.parse::<f64>().ok()?;
+        if !self.flipped {
+            return Some(amount);
+        }
+
+        (price > 0.0).then(|| amount / price)
+    }
+
+    /// Price order is expected to fill at, current one for market orders
+    fn order_price(&self, price_now: Option<f64>) -> Option<f64> {
+        if self.order_type.is_market() {
+            price_now
+        } else {
+            self.price.parse().ok()
+        }
+    }
+
+    /// Order value in quote asset if it is below minimal notional of pair
+    fn notional_below_min(&self, filter: SymbolFilter, price_now: Option<f64>) -> Option<f64> {
+        let price = self.order_price(price_now)?;
+        let notional = price * self.base_amount(price)?;
+
+        (notional < filter.min_notional).then_some(notional)
+    }
+
+    /// Why order cannot be placed
+    fn order_error(&self, below_min: Option<f64>) -> Option<&'static str> {
+        if !self.order_type.is_market() && !self.price_valid {
+            Some("price must be a positive number")
+        } else if !self.amount_valid {
+            Some("amount must be a positive number")
+        } else if below_min.is_some() {
+            Some("order total is below minimum")
+        } else {
+            None
+        }
+    }
+
+    /// Red border for text that is not a number, empty input is not highlighted
+    fn input_style(value: &str, valid: bool) -> iced::theme::TextInput {
+        if valid || value.is_empty() {
+            iced::theme::TextInput::Custom(Box::new(Inp {}))
+        } else {
+            iced::theme::TextInput::Custom(Box::new(InvalidInp {}))
+        }
+    }
+
+    pub(crate) fn view<'a>(
+        &'a self,
+        alerts: &'a [Alert],
+        data: &AppData,
+        copied: Option<&str>,
+    ) -> Element<'a, MarketPanelMessage> {
+        let filter = data.symbol_filter(&self.pair);
+        let price_now = data.prices.get(&self.pair).map(f64::from);
+        // book is streamed for one pair which could be another one while switching
+        let best_prices = data.best_prices().filter(|_| data.book.0 == self.pair);
+
+        let paper_badge = data.paper_pnl.map(|pnl| {
+            container(
+                tb(format!("PAPER  P&L {pnl:.2}"))
+                    .size(12)
+                    .style(iced::Color::BLACK),
+            )
+            .padding([4, 8])
+            .style(container::Appearance {
+                background: Some(iced::Background::Color(color(Role::Warning))),
+                border: iced::Border {
+                    radius: 8.0.into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+        });
+
+        let toolbar = row![]
+            .push_maybe(paper_badge)
+            .push(Space::new(Length::Fill, 1.0))
+            .push(
+                bbtn!(text(if self.alerts_opened {
+                    "Order"
+                } else {
+                    "Alerts"
+                })
+                .size(12))
+                .on_press(MarketPanelMessage::AlertsToggled),
+            )
+            .align_items(Alignment::Center);
+
+        column![
+            toolbar,
+            if self.alerts_opened {
+                self.view_alerts(alerts)
+            } else {
+                self.view_order_form(filter, price_now, best_prices, data.fee_tier, copied)
+            }
+        ]
+        .into()
+    }
+
+    /// Fill of previewed order at its price, or at top of book for market orders
+    fn fill_estimate(
+        preview: &OrderPreview,
+        price_now: Option<f64>,
+        best_prices: Option<(f64, f64)>,
+        fee_rate: f64,
+    ) -> Option<FillEstimate> {
+        let price = if preview.order_type.is_market() {
+            // buys take lowest ask and sells take highest bid
+            best_prices
+                .map(|(bid, ask)| match preview.side {
+                    OrderSide::Buy => ask,
+                    OrderSide::Sell => bid,
+                })
+                .or(price_now)?
+        } else {
+            preview.price
+        };
+
+        Some(FillEstimate::new(
+            &preview.side,
+            price,
+            preview.amount,
+            fee_rate,
+        ))
+    }
+
+    fn view_preview<'a>(
+        &'a self,
+        preview: &'a OrderPreview,
+        filter: SymbolFilter,
+        estimate: Option<FillEstimate>,
+        fee_rate: f64,
+    ) -> Element<'a, MarketPanelMessage> {
+        let side = &preview.side;
+        let [base, quote] = split_symbol(&preview.pair).unwrap_or(["", ""]);
+        let received = match side {
+            OrderSide::Buy => base,
+            OrderSide::Sell => quote,
+        };
+        let amount = |value| match side {
+            OrderSide::Buy => filter.qty(value),
+            OrderSide::Sell => filter.price(value),
+        };
+
+        let details: Element<_> = match estimate {
+            Some(estimate) => column![
+                text(format!(
+                    "fill price ~{} {quote}",
+                    filter.price(estimate.price)
+                ))
+                .size(14),
+                text(format!("total {} {quote}", filter.price(estimate.total))).size(14),
+                // account commission is not fetched, rate comes from calculator settings
+                text(format!(
+                    "fee {} {received} at assumed {:.3}% rate",
+                    amount(estimate.fee),
+                    fee_rate * 100.0
+                ))
+                .size(14)
+                .style(color(Role::Secondary)),
+                tb(format!("receive {} {received}", amount(estimate.net))).size(14),
+            ]
+            .spacing(2)
+            .into(),
+            None => text("no price to estimate fill with")
+                .size(14)
+                .style(color(Role::Danger))
+                .into(),
+        };
+
+        let remaining = PREVIEW_TIMEOUT.saturating_sub(preview.shown_at.elapsed());
+        let (label, style) = match side {
+            OrderSide::Buy => (
+                "Confirm buy",
+                iced::theme::Button::Custom(Box::new(GreenBtn {})),
+            ),
+            OrderSide::Sell => (
+                "Confirm sell",
+                iced::theme::Button::Custom(Box::new(RedBtn {})),
+            ),
+        };
+
+        let title = format!("{side:?} {} {base}", filter.qty(preview.amount));
+
+        column![
+            tb(title).size(14),
+            details,
+            row![
+                button(tb(label).style(iced::Color::WHITE).size(12))
+                    .style(style)
+                    .padding(8)
+                    .on_press(MarketPanelMessage::PreviewConfirm),
+                bbtn!(text(format!("Cancel ({}s)", remaining.as_secs_f32().ceil())).size(12))
+                    .on_press(MarketPanelMessage::PreviewCancel),
+            ]
+            .spacing(5.0)
+            .align_items(Alignment::Center),
+        ]
+        .spacing(4.0)
+        .width(300.0)
+        .into()
+    }
+
+    fn view_order_form(
+        &self,
+        filter: SymbolFilter,
+        price_now: Option<f64>,
+        best_prices: Option<(f64, f64)>,
+        fee_rate: f64,
+        copied: Option<&str>,
+    ) -> Element<'_, MarketPanelMessage> {
+        let mut price_input = tin!("price", &self.price).width(150.0);
+        // market orders are filled at any price
+        if !self.order_type.is_market() {
+            price_input = price_input
+                .on_input(MarketPanelMessage::PriceInput)
+                .style(Self::input_style(&self.price, self.price_valid));
+        }
+
+        let stop_price_input = matches!(self.order_type, OrderType::StopLimit { .. }).then(|| {
+            tin!("stop price", &self.stop_price)
+                .on_input(MarketPanelMessage::StopPriceInput)
+                .width(300.0)
+        });
+
+        let amount_placeholder = match self.amount_asset() {
+            Some(asset) => format!("amount {asset}"),
+            None => "amount".to_owned(),
+        };
+        let amount_input = tin!(&amount_placeholder, &self.amount)
+            .on_input(MarketPanelMessage::AmountInput)
+            .style(Self::input_style(&self.amount, self.amount_valid))
+            .width(150.0);
+        let amount_input: Element<_> = match &self.rounded_amount {
+            Some(rounded) => tooltip(
+                amount_input,
+                container(text(format!("rounded to {rounded}")).size(12))
+                    .padding(4)
+                    .style(iced::theme::Container::Box),
+                tooltip::Position::Bottom,
+            )
+            .into(),
+            None => amount_input.into(),
+        };
+        let amount_input = copyable(
+            amount_input,
+            self.amount.clone(),
+            copied,
+            MarketPanelMessage::Copy,
+        );
+        let price_input = copyable(
+            price_input,
+            self.price.clone(),
+            copied,
+            MarketPanelMessage::Copy,
+        );
+
+        // exchange rejects orders below minimal value
+        let below_min = self.notional_below_min(filter, price_now);
+        let notional_error = below_min.map(|notional| {
+            text(format!(
+                "total {} is below minimum {}",
+                filter.price(notional),
+                filter.price(filter.min_notional)
+            ))
+            .size(12)
+            .style(color(Role::Danger))
+        });
+
+        let assets = split_symbol(&self.pair).map(|[base, quote]| {
+            let (from, to) = if self.flipped {
+                (quote, base)
+            } else {
+                (base, quote)
+            };
+
+            row![
+                tb(from).size(14),
+                bbtn!(text('\u{F12B}')
+                    .size(14)
+                    .font(Font::with_name("bootstrap-icons")))
+                .on_press(MarketPanelMessage::FlipPair),
+                tb(to).size(14),
+            ]
+            .align_items(Alignment::Center)
+        });
+
+        let order_error = self.order_error(below_min);
+        let order_button = |label, style, msg| -> Element<'_, MarketPanelMessage> {
+            let btn = button(tb(label).style(iced::Color::WHITE).size(12))
+                .style(style)
+                .padding(8)
+                .on_press_maybe(order_error.is_none().then_some(msg));
+
+            match order_error {
+                Some(err) => tooltip(
+                    btn,
+                    container(text(err).size(12))
+                        .padding(4)
+                        .style(iced::theme::Container::Box),
+                    tooltip::Position::Top,
+                )
+                .into(),
+                None => btn.into(),
+            }
+        };
+
+        // buying quote asset is selling base
+        let (buy_side, sell_side) = if self.flipped {
+            (OrderSide::Sell, OrderSide::Buy)
+        } else {
+            (OrderSide::Buy, OrderSide::Sell)
+        };
+
+        let order_buttons: Element<_> = match &self.preview {
+            Some(preview) => self.view_preview(
+                preview,
+                filter,
+                Self::fill_estimate(preview, price_now, best_prices, fee_rate),
+                fee_rate,
+            ),
+            None => row![
+                order_button(
+                    "Buy",
+                    iced::theme::Button::Custom(Box::new(GreenBtn {})),
+                    MarketPanelMessage::Preview(buy_side)
+                ),
+                Space::new(5.0, 0.0),
+                order_button(
+                    "Sell",
+                    iced::theme::Button::Custom(Box::new(RedBtn {})),
+                    MarketPanelMessage::Preview(sell_side)
+                ),
+            ]
+            .into(),
+        };
+
         container(
             column![
                 Space::new(Length::Fill, 1.0),
-                tin!("type a pair", &self.pair)
-                    .on_input(MarketPanelMessage::PairInput)
-                    .width(300.0)
-                    .on_submit(MarketPanelMessage::PairSet),
+                copyable(
+                    tin!("type a pair", &self.pair)
+                        .on_input(MarketPanelMessage::PairInput)
+                        .width(300.0)
+                        .on_submit(MarketPanelMessage::PairSet),
+                    self.pair.clone(),
+                    copied,
+                    MarketPanelMessage::Copy,
+                ),
+                row![
+                    self.order_type_button("Limit", OrderType::Limit),
+                    self.order_type_button("Market", OrderType::Market),
+                    self.order_type_button(
+                        "Stop Limit",
+                        OrderType::StopLimit {
+                            stop_price: self.stop_price.parse().unwrap_or_default()
+                        }
+                    ),
+                ]
+                .spacing(2.0),
+                Column::with_children(stop_price_input.map(Element::from)),
+                Column::with_children(assets.map(Element::from)),
                 row![
                     column![
-                        tin!("price", &self.price)
-                            .on_input(MarketPanelMessage::PriceInput)
-                            .width(150.0),
+                        price_input,
                         row![
                             bbtn!(text("-0.1%").size(12))
                                 .on_press(MarketPanelMessage::PriceMultiplied(-0.1)),
@@ -83,9 +632,7 @@ impl Market {
                         .width(150.0),
                     ],
                     column![
-                        tin!("amount", &self.amount)
-                            .on_input(MarketPanelMessage::AmountInput)
-                            .width(150.0),
+                        amount_input,
                         row![
                             bbtn!(text("10%").size(12))
                                 .on_press(MarketPanelMessage::AmountMultiplied(0.1)),
@@ -104,17 +651,8 @@ impl Market {
                 ]
                 .spacing(4.0)
                 .width(300.0),
-                row![
-                    button(tb("Buy").style(iced::Color::WHITE).size(12))
-                        .style(iced::theme::Button::Custom(Box::new(GreenBtn {})))
-                        .padding(8)
-                        .on_press(MarketPanelMessage::BuyPressed),
-                    Space::new(5.0, 0.0),
-                    button(tb("Sell").style(iced::Color::WHITE).size(12))
-                        .style(iced::theme::Button::Custom(Box::new(RedBtn {})))
-                        .padding(8)
-                        .on_press(MarketPanelMessage::SellPressed)
-                ],
+                Column::with_children(notional_error.map(Element::from)),
+                order_buttons,
                 Space::new(Length::Fill, 1.0)
             ]
             .spacing(4.0)
@@ -128,35 +666,75 @@ impl Market {
     pub(crate) fn update(
         &mut self,
         msg: MarketPanelMessage,
-        api: &Client,
+        api: &dyn TradingClient,
         data: &AppData,
         ws: &Websockets,
+        paper: bool,
     ) -> Command<Message> {
+        let filter = data.symbol_filter(&self.pair);
+
         match msg {
-            MarketPanelMessage::BuyPressed => self.buy(api),
-            MarketPanelMessage::SellPressed => self.sell(api),
+            MarketPanelMessage::Preview(side) => {
+                let price_now = data.prices.get(&self.pair).map(f64::from);
+                match self.order_preview(side, filter, price_now) {
+                    Ok(preview) => {
+                        self.preview = Some(preview);
+                        Command::none()
+                    }
+                    Err(err) => Command::perform(async {}, move |_| Message::ToastError(err)),
+                }
+            }
+            // preview could have expired right before confirm was pressed
+            MarketPanelMessage::PreviewConfirm => match self.preview.take() {
+                Some(preview) => Self::place_order(api, preview, paper),
+                None => Command::none(),
+            },
+            MarketPanelMessage::PreviewCancel => {
+                self.preview = None;
+                Command::none()
+            }
             MarketPanelMessage::AmountMultiplied(f) => {
-                let usdt_b = data
-                    .balances
-                    .iter()
-                    .find(|b| b.asset == "USDT")
-                    .unwrap()
-                    .free;
-                self.amount = (usdt_b * f).to_string();
+                let Some(usdt_b) = data.balances.iter().find(|b| b.asset == "USDT") else {
+                    return Command::perform(async {}, |_| {
+                        Message::ToastError("USDT balance is not loaded".to_owned())
+                    });
+                };
+                // rounding up would ask for more than balance has
+                let amount = usdt_b.free * f;
+                self.set_amount(amount, filter.floor_qty(amount), filter);
                 Command::none()
             }
             MarketPanelMessage::PriceInput(new) => {
+                self.price_valid = is_valid_number(&new);
                 self.price = new;
                 Command::none()
             }
             MarketPanelMessage::AmountInput(new) => {
-                self.amount = new;
+                // amounts are only rounded once they have too many decimals so that typing works
+                let decimals = new
+                    .split_once('.')
+                    .map_or(0, |(_, fraction)| fraction.len());
+                match new.parse::<f64>() {
+                    Ok(amount) if decimals > usize::from(filter.qty_decimals) => {
+                        self.set_amount(amount, filter.round_qty(amount), filter);
+                    }
+                    _ => {
+                        self.amount_valid = is_valid_number(&new);
+                        self.amount = new;
+                        self.rounded_amount = None;
+                    }
+                }
                 Command::none()
             }
             MarketPanelMessage::PriceMultiplied(inc) => {
-                let price = data.prices.price(&self.pair);
-                self.price =
-                    (((price as f64 * (1.0 + (inc / 100.0))) * 100.0).round() / 100.0).to_string();
+                let Some(price) = data.prices.get(&self.pair) else {
+                    let pair = self.pair.clone();
+                    return Command::perform(async {}, move |_| {
+                        Message::ToastError(format!("no price for {pair}"))
+                    });
+                };
+                self.price = filter.price(price as f64 * (1.0 + (inc / 100.0)));
+                self.price_valid = is_valid_number(&self.price);
                 Command::none()
             }
             MarketPanelMessage::PairSet => {
@@ -165,36 +743,191 @@ impl Market {
             }
             MarketPanelMessage::PairInput(new) => {
                 self.pair = new.to_uppercase();
+                self.flipped = false;
+                self.preview = None;
+                Command::none()
+            }
+            // handled by dashboard
+            MarketPanelMessage::FlipPair | MarketPanelMessage::Copy(_) => Command::none(),
+            MarketPanelMessage::OrderTypeChanged(order_type) => {
+                self.order_type = order_type;
+                Command::none()
+            }
+            MarketPanelMessage::StopPriceInput(new) => {
+                if let OrderType::StopLimit { stop_price } = &mut self.order_type {
+                    *stop_price = new.parse().unwrap_or_default();
+                }
+                self.stop_price = new;
+                Command::none()
+            }
+            MarketPanelMessage::AlertsToggled => {
+                self.alerts_opened = !self.alerts_opened;
+                Command::none()
+            }
+            MarketPanelMessage::AlertConditionChanged(condition) => {
+                self.alert_condition = condition;
+                Command::none()
+            }
+            MarketPanelMessage::AlertPriceInput(new) => {
+                self.alert_price = new;
+                Command::none()
+            }
+            // alert is stored in config by app, only form is cleared
+            MarketPanelMessage::AddAlert(_) => {
+                self.alert_price.clear();
                 Command::none()
             }
+            // config is updated by app
+            MarketPanelMessage::RemoveAlert(_) => Command::none(),
         }
     }
 
-    fn buy(&mut self, api: &Client) -> Command<Message> {
-        api.trade_spot(
-            self.pair.clone(),
-            self.price.parse().unwrap(),
-            self.amount.parse().unwrap(),
-            binance::rest_model::OrderSide::Buy,
-        )
+    /// Copies entered order for confirmation
+    fn order_preview(
+        &self,
+        side: OrderSide,
+        filter: SymbolFilter,
+        price_now: Option<f64>,
+    ) -> Result<OrderPreview, String> {
+        if let OrderType::StopLimit { stop_price } = self.order_type {
+            if stop_price <= 0.0 {
+                return Err("stop price must be a positive number".to_owned());
+            }
+        }
+
+        let price = if self.order_type.is_market() {
+            Some(0.0)
+        } else {
+            self.price.parse().ok().filter(|_| self.price_valid)
+        };
+        let amt = self
+            .order_price(price_now)
+            .and_then(|price| self.base_amount(price))
+            .filter(|_| self.amount_valid);
+
+        // buttons are disabled for invalid input, this only guards against stale messages
+        let (Some(price), Some(amt)) = (price, amt) else {
+            return Err("price and amount must be positive numbers".to_owned());
+        };
+
+        Ok(OrderPreview {
+            side,
+            shown_at: Instant::now(),
+            pair: self.pair.clone(),
+            order_type: self.order_type,
+            price,
+            // typed amount can still be between steps
+            amount: filter.round_qty(amt),
+        })
     }
 
-    fn sell(&mut self, api: &Client) -> Command<Message> {
-        api.trade_spot(
-            self.pair.clone(),
-            self.price.parse().unwrap(),
-            self.amount.parse().unwrap(),
-            binance::rest_model::OrderSide::Sell,
-        )
+    /// Sends order to exchange or to paper broker
+    fn place_order(
+        api: &dyn TradingClient,
+        preview: OrderPreview,
+        paper: bool,
+    ) -> Command<Message> {
+        let OrderPreview {
+            side,
+            pair,
+            order_type,
+            price,
+            amount: amt,
+            ..
+        } = preview;
+
+        if paper {
+            return Command::perform(async {}, move |_| Message::PaperOrder {
+                pair,
+                price,
+                amt,
+                side,
+                order_type,
+            });
+        }
+
+        api.trade_spot(pair, price, amt, side, order_type)
     }
 
-    // FIXME: this is totally wrong and broken
-    /// Set new pair from selected currency
-    pub(crate) fn set_currency_pair(&mut self, mut new: String) {
-        // FIXME: this breaks with any other currency
-        if !(new.ends_with("USDT") || new.ends_with("BTC") || new.ends_with("ETH")) {
-            new = format!("{new}USDT");
+    /// Dismisses preview that was not confirmed in time
+    pub(crate) fn tick(&mut self) {
+        if self
+            .preview
+            .as_ref()
+            .is_some_and(|preview| preview.shown_at.elapsed() >= PREVIEW_TIMEOUT)
+        {
+            self.preview = None;
         }
-        self.pair = new;
+    }
+
+    /// Set new pair built from selected asset or pair
+    pub(crate) fn set_currency_pair(&mut self, pair: String) {
+        self.pair = pair;
+        self.flipped = false;
+        self.preview = None;
+    }
+
+    /// Pair with base and quote swapped if it is known to exchange
+    pub(crate) fn reversed_pair(&self, data: &AppData) -> Option<String> {
+        let [base, quote] = split_symbol(&self.pair)?;
+        let reversed = format!("{quote}{base}");
+
+        data.symbol_filters
+            .contains_key(&reversed)
+            .then_some(reversed)
+    }
+
+    /// Buy and sell buttons trade quote asset instead of base
+    pub(crate) fn toggle_flipped(&mut self) {
+        self.flipped = !self.flipped;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_estimate_deducts_fee() {
+        let buy = FillEstimate::new(&OrderSide::Buy, 100.0, 2.0, 0.001);
+        assert_eq!(buy.total, 200.0);
+        assert!((buy.fee - 0.002).abs() < 1e-12);
+        assert!((buy.net - 1.998).abs() < 1e-12);
+
+        let sell = FillEstimate::new(&OrderSide::Sell, 100.0, 2.0, 0.001);
+        assert!((sell.fee - 0.2).abs() < 1e-12);
+        assert!((sell.net - 199.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn preview_keeps_inputs_it_was_made_with() {
+        let mut market = Market::new("BTCUSDT".to_owned());
+        market.price = "100".to_owned();
+        market.price_valid = true;
+        market.amount = "2".to_owned();
+        market.amount_valid = true;
+        let preview = market
+            .order_preview(OrderSide::Buy, SymbolFilter::default(), None)
+            .unwrap();
+
+        market.amount = "5".to_owned();
+        market.set_currency_pair("ETHUSDT".to_owned());
+        assert_eq!(preview.pair, "BTCUSDT");
+        assert_eq!(preview.price, 100.0);
+        assert_eq!(preview.amount, 2.0);
+        assert!(market.preview.is_none());
+    }
+
+    #[test]
+    fn flipped_amount_is_in_quote_asset() {
+        let mut market = Market::new("BTCUSDT".to_owned());
+        market.amount = "100".to_owned();
+        assert_eq!(market.amount_asset(), Some("BTC"));
+        assert_eq!(market.base_amount(50_000.0), Some(100.0));
+
+        market.toggle_flipped();
+        assert_eq!(market.amount_asset(), Some("USDT"));
+        assert_eq!(market.base_amount(50_000.0), Some(0.002));
+        assert_eq!(market.base_amount(0.0), None);
     }
 }
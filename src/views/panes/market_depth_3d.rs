@@ -0,0 +1,484 @@
+//! Order book history drawn as 3D surface of price, time and quantity
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+use glam::{Mat4, Vec3};
+use iced::{
+    event, mouse,
+    widget::shader::{self, wgpu, Event},
+    window, Element, Length, Point, Rectangle, Size,
+};
+
+use super::depth_chart::parse_levels;
+use crate::{
+    data::AppData,
+    theme::{color, Role},
+    views::components::loading::loader,
+};
+
+/// Book is sampled into new row this often
+pub(crate) const SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Rows kept, oldest is dropped when new one is sampled
+const ROWS: usize = 60;
+
+/// Price buckets of row, bids in lower half and asks in upper
+const BUCKETS: usize = 64;
+
+/// Price range on each side of mid price, percent
+const RANGE_PCT: f64 = 1.0;
+
+/// Opacity of oldest row, newest one is opaque
+const MIN_ALPHA: f32 = 0.1;
+
+/// Radians view turns per dragged pixel
+const DRAG_SPEED: f32 = 0.01;
+
+const CAMERA_DISTANCE: f32 = 3.0;
+
+#[derive(Debug, Clone)]
+pub(crate) enum MarketDepth3DMessage {
+    /// Append row from current book
+    Sample,
+}
+
+/// Quantity in buckets around mid price, None without both sides of book
+fn sample(bids: &BTreeMap<String, f64>, asks: &BTreeMap<String, f64>) -> Option<[f32; BUCKETS]> {
+    let bids = parse_levels(bids.iter());
+    let asks = parse_levels(asks.iter());
+    let mid = (bids.last()?.0 + asks.first()?.0) / 2.0;
+    let step = mid * RANGE_PCT / 100.0 / (BUCKETS / 2) as f64;
+
+    let mut row = [0.0; BUCKETS];
+    for (price, qty) in bids.iter().chain(&asks) {
+        let bucket = ((price - mid) / step).floor() + (BUCKETS / 2) as f64;
+        if (0.0..BUCKETS as f64).contains(&bucket) {
+            row[bucket as usize] += *qty as f32;
+        }
+    }
+
+    Some(row)
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct Vertex {
+    position: [f32; 3],
+    color: [f32; 4],
+}
+
+// SAFETY: only f32 fields without padding between them, any bits are valid
+unsafe impl bytemuck::Zeroable for Vertex {}
+unsafe impl bytemuck::Pod for Vertex {}
+
+#[derive(Debug, Default)]
+struct Mesh {
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+}
+
+impl Mesh {
+    /// Grid over unit cube, oldest row at back so it is blended first
+    fn new(rows: &VecDeque<[f32; BUCKETS]>) -> Self {
+        let max_qty = rows.iter().flatten().copied().fold(f32::EPSILON, f32::max);
+        let [bid, ask] = [Role::Accent, Role::Danger].map(|role| color(role).into_linear());
+
+        let mut vertices = Vec::with_capacity(rows.len() * BUCKETS);
+        for (i, row) in rows.iter().enumerate() {
+            let age = (rows.len() - 1 - i) as f32 / (ROWS - 1) as f32;
+            let alpha = 1.0 - age * (1.0 - MIN_ALPHA);
+
+            for (bucket, qty) in row.iter().enumerate() {
+                let [r, g, b, _] = if bucket < BUCKETS / 2 { bid } else { ask };
+                vertices.push(Vertex {
+                    position: [
+                        bucket as f32 / (BUCKETS - 1) as f32 * 2.0 - 1.0,
+                        qty / max_qty - 0.5,
+                        1.0 - age * 2.0,
+                    ],
+                    color: [r, g, b, alpha],
+                });
+            }
+        }
+
+        let mut indices = Vec::with_capacity(rows.len().saturating_sub(1) * (BUCKETS - 1) * 6);
+        for row in 1..rows.len() as u32 {
+            for bucket in 1..BUCKETS as u32 {
+                let [back, front] = [row - 1, row].map(|r| r * BUCKETS as u32 + bucket);
+                indices.extend([back - 1, back, front - 1, back, front, front - 1]);
+            }
+        }
+
+        Self { vertices, indices }
+    }
+}
+
+pub(crate) struct MarketDepth3DPane {
+    /// Symbol rows belong to
+    symbol: String,
+    /// Oldest first
+    rows: VecDeque<[f32; BUCKETS]>,
+    mesh: Arc<Mesh>,
+}
+
+impl MarketDepth3DPane {
+    pub(crate) fn new() -> Self {
+        Self {
+            symbol: String::new(),
+            rows: VecDeque::with_capacity(ROWS),
+            mesh: Arc::default(),
+        }
+    }
+
+    pub(crate) fn update(&mut self, msg: MarketDepth3DMessage, data: &AppData) {
+        match msg {
+            MarketDepth3DMessage::Sample => {
+                let (symbol, bids, asks) = &data.book;
+                if *symbol != self.symbol {
+                    self.symbol.clone_from(symbol);
+                    self.rows.clear();
+                }
+
+                let Some(row) = sample(bids, asks) else {
+                    return;
+                };
+                if self.rows.len() == ROWS {
+                    self.rows.pop_front();
+                }
+                self.rows.push_back(row);
+                self.mesh = Arc::new(Mesh::new(&self.rows));
+            }
+        }
+    }
+
+    pub(crate) fn view(&self) -> Element<'_, MarketDepth3DMessage> {
+        // surface needs two rows
+        if self.rows.len() < 2 {
+            return loader!().into();
+        }
+
+        shader::Shader::new(Surface {
+            mesh: self.mesh.clone(),
+        })
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+}
+
+struct Surface {
+    mesh: Arc<Mesh>,
+}
+
+/// Orbits around center of surface, dragged with left mouse button
+struct Camera {
+    yaw: f32,
+    pitch: f32,
+    drag_from: Option<Point>,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            yaw: 0.5,
+            pitch: 0.45,
+            drag_from: None,
+        }
+    }
+}
+
+impl Camera {
+    fn view_projection(&self, aspect: f32) -> Mat4 {
+        let eye = Vec3::new(
+            self.yaw.sin() * self.pitch.cos(),
+            self.pitch.sin(),
+            self.yaw.cos() * self.pitch.cos(),
+        ) * CAMERA_DISTANCE;
+
+        Mat4::perspective_rh(std::f32::consts::FRAC_PI_4, aspect, 0.1, 10.0)
+            * Mat4::look_at_rh(eye, Vec3::ZERO, Vec3::Y)
+    }
+}
+
+impl<Message> shader::Program<Message> for Surface {
+    type State = Camera;
+    type Primitive = SurfacePrimitive;
+
+    fn update(
+        &self,
+        state: &mut Camera,
+        event: Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+        shell: &mut iced::advanced::Shell<'_, Message>,
+    ) -> (event::Status, Option<Message>) {
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                state.drag_from = cursor.position_over(bounds);
+                if state.drag_from.is_none() {
+                    return (event::Status::Ignored, None);
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                let Some(from) = state.drag_from.replace(position) else {
+                    return (event::Status::Ignored, None);
+                };
+                state.yaw -= (position.x - from.x) * DRAG_SPEED;
+                // stop short of poles, view flips over them
+                state.pitch = (state.pitch + (position.y - from.y) * DRAG_SPEED).clamp(-1.5, 1.5);
+                shell.request_redraw(window::RedrawRequest::NextFrame);
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+                if state.drag_from.is_some() =>
+            {
+                state.drag_from = None;
+            }
+            _ => return (event::Status::Ignored, None),
+        }
+
+        (event::Status::Captured, None)
+    }
+
+    fn draw(&self, state: &Camera, _cursor: mouse::Cursor, bounds: Rectangle) -> SurfacePrimitive {
+        SurfacePrimitive {
+            mesh: self.mesh.clone(),
+            view_projection: state.view_projection(bounds.width / bounds.height.max(1.0)),
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Camera,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if state.drag_from.is_some() {
+            mouse::Interaction::Grabbing
+        } else if cursor.is_over(bounds) {
+            mouse::Interaction::Grab
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SurfacePrimitive {
+    mesh: Arc<Mesh>,
+    view_projection: Mat4,
+}
+
+impl shader::Primitive for SurfacePrimitive {
+    fn prepare(
+        &self,
+        format: wgpu::TextureFormat,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        _bounds: Rectangle,
+        _target_size: Size<u32>,
+        _scale_factor: f32,
+        storage: &mut shader::Storage,
+    ) {
+        if !storage.has::<Pipeline>() {
+            storage.store(Pipeline::new(device, format));
+        }
+
+        if let Some(pipeline) = storage.get::<Pipeline>() {
+            pipeline.write(queue, &self.mesh, self.view_projection);
+        }
+    }
+
+    fn render(
+        &self,
+        storage: &shader::Storage,
+        target: &wgpu::TextureView,
+        _target_size: Size<u32>,
+        viewport: Rectangle<u32>,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        if let Some(pipeline) = storage.get::<Pipeline>() {
+            pipeline.render(target, encoder, viewport, self.mesh.indices.len() as u32);
+        }
+    }
+}
+
+/// GPU side of surface, shared by all 3D panes so last drawn camera wins when several are open
+struct Pipeline {
+    pipeline: wgpu::RenderPipeline,
+    vertices: wgpu::Buffer,
+    indices: wgpu::Buffer,
+    uniforms: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl Pipeline {
+    fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let buffer = |label, size: usize, usage| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size: size as u64,
+                usage: usage | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        };
+        let vertices = buffer(
+            "market depth 3d vertices",
+            ROWS * BUCKETS * std::mem::size_of::<Vertex>(),
+            wgpu::BufferUsages::VERTEX,
+        );
+        let indices = buffer(
+            "market depth 3d indices",
+            (ROWS - 1) * (BUCKETS - 1) * 6 * std::mem::size_of::<u32>(),
+            wgpu::BufferUsages::INDEX,
+        );
+        let uniforms = buffer(
+            "market depth 3d uniforms",
+            std::mem::size_of::<Mat4>(),
+            wgpu::BufferUsages::UNIFORM,
+        );
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("market depth 3d bind group layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("market depth 3d bind group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniforms.as_entire_binding(),
+            }],
+        });
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("market depth 3d shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("market_depth_3d.wgsl").into()),
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("market depth 3d pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("market depth 3d pipeline"),
+            layout: Some(&layout),
+            vertex: wgpu::VertexState {
+                module: &module,
+                entry_point: "vs_main",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<Vertex>() as u64,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x4],
+                }],
+            },
+            primitive: wgpu::PrimitiveState::default(),
+            // rows are drawn back to front instead, depth test would hide faded ones
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+        });
+
+        Self {
+            pipeline,
+            vertices,
+            indices,
+            uniforms,
+            bind_group,
+        }
+    }
+
+    fn write(&self, queue: &wgpu::Queue, mesh: &Mesh, view_projection: Mat4) {
+        queue.write_buffer(&self.vertices, 0, bytemuck::cast_slice(&mesh.vertices));
+        queue.write_buffer(&self.indices, 0, bytemuck::cast_slice(&mesh.indices));
+        queue.write_buffer(
+            &self.uniforms,
+            0,
+            bytemuck::cast_slice(&view_projection.to_cols_array()),
+        );
+    }
+
+    fn render(
+        &self,
+        target: &wgpu::TextureView,
+        encoder: &mut wgpu::CommandEncoder,
+        viewport: Rectangle<u32>,
+        index_count: u32,
+    ) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("market depth 3d pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        pass.set_scissor_rect(viewport.x, viewport.y, viewport.width, viewport.height);
+        pass.set_viewport(
+            viewport.x as f32,
+            viewport.y as f32,
+            viewport.width as f32,
+            viewport.height as f32,
+            0.0,
+            1.0,
+        );
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        pass.set_vertex_buffer(0, self.vertices.slice(..));
+        pass.set_index_buffer(self.indices.slice(..), wgpu::IndexFormat::Uint32);
+        pass.draw_indexed(0..index_count, 0, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_splits_book_at_mid() {
+        let levels = |levels: &[(&str, f64)]| -> BTreeMap<String, f64> {
+            levels.iter().map(|(p, q)| (p.to_string(), *q)).collect()
+        };
+        let bids = levels(&[("99.99", 1.0), ("99.98", 2.0), ("90", 5.0)]);
+        let asks = levels(&[("100.01", 3.0)]);
+
+        let row = sample(&bids, &asks).unwrap();
+        assert_eq!(row[BUCKETS / 2 - 1], 3.0);
+        assert_eq!(row[BUCKETS / 2], 3.0);
+        // far from mid
+        assert_eq!(row.iter().sum::<f32>(), 6.0);
+
+        assert_eq!(sample(&bids, &BTreeMap::new()), None);
+
+        let rows = VecDeque::from([row, row, row]);
+        assert_eq!(Mesh::new(&rows).indices.len(), 2 * (BUCKETS - 1) * 6);
+    }
+}
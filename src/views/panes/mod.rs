@@ -1,12 +1,56 @@
+pub(crate) mod algo_trading;
+pub(crate) mod arbitrage;
 pub(crate) mod balances;
 pub(crate) mod book;
 pub(crate) mod calculator;
 pub(crate) mod chart;
+pub(crate) mod correlation;
+pub(crate) mod depth_chart;
+pub(crate) mod funding;
+pub(crate) mod futures_market;
+pub(crate) mod heatmap;
+pub(crate) mod history;
+pub(crate) mod liquidations;
 pub(crate) mod market;
+#[cfg(feature = "wgpu-panes")]
+pub(crate) mod market_depth_3d;
+pub(crate) mod news;
+#[cfg(feature = "options")]
+pub(crate) mod options;
+pub(crate) mod order_flow;
 pub(crate) mod orders;
+#[cfg(feature = "perf-pane")]
+pub(crate) mod performance;
+pub(crate) mod portfolio;
+pub(crate) mod sentiment;
+pub(crate) mod spread_monitor;
+pub(crate) mod trade_sizer;
 pub(crate) mod trades;
 pub(crate) mod watchlist;
 
+use iced::keyboard;
+
+/// Key pressed while pane is focused and no global shortcut matched it
+#[derive(Debug, Clone)]
+pub(crate) struct KeyEvent {
+    pub(crate) key: keyboard::Key,
+    pub(crate) modifiers: keyboard::Modifiers,
+}
+
+impl KeyEvent {
+    /// Typed character ignoring shift, which some layouts need for `+`
+    pub(crate) fn character(&self) -> Option<&str> {
+        if self.modifiers.control() || self.modifiers.alt() || self.modifiers.logo() {
+            return None;
+        }
+
+        match &self.key {
+            keyboard::Key::Character(c) => Some(c.as_str()),
+            _ => None,
+        }
+    }
+}
+
 /* pub fn handle_hotkey(key_code: keyboard::KeyCode) -> Option<Message> {
     use keyboard::KeyCode;
     use pane_grid::{Axis, Direction};
@@ -28,32 +72,147 @@ pub(crate) mod watchlist;
 } */
 
 pub mod style {
+    use std::sync::RwLock;
+
     use iced::widget::container;
     use iced::{Color, Theme};
 
-    pub fn pane_active(_: &Theme) -> container::Appearance {
+    use crate::{
+        config::PaneStyleConfig,
+        theme::{color, h2c, Role},
+    };
+
+    /// Pane look from config, default one until it is set
+    pub(crate) static STYLE_CONFIG: RwLock<Option<PaneStyleConfig>> = RwLock::new(None);
+
+    pub(crate) fn set_config(config: &PaneStyleConfig) {
+        *STYLE_CONFIG.write().expect("style lock is not poisoned") = Some(config.clone());
+    }
+
+    fn with_config<T>(f: impl FnOnce(&PaneStyleConfig) -> T) -> T {
+        let config = STYLE_CONFIG.read().expect("style lock is not poisoned");
+        match &*config {
+            Some(config) => f(config),
+            None => f(&PaneStyleConfig::default()),
+        }
+    }
+
+    /// Configured color, empty or invalid one is replaced with theme color
+    fn color_or(hex: &str, fallback: Color) -> Color {
+        if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            h2c(hex).unwrap_or(fallback)
+        } else {
+            fallback
+        }
+    }
+
+    /// Gap between panes
+    pub fn spacing() -> f32 {
+        with_config(|config| config.spacing)
+    }
+
+    pub fn title_padding() -> [u16; 2] {
+        with_config(|config| config.title_padding)
+    }
+
+    pub fn min_pane_size() -> f32 {
+        with_config(|config| f32::from(config.min_pane_size))
+    }
+
+    fn pane(config: &PaneStyleConfig, border_color: Color) -> container::Appearance {
         container::Appearance {
-            background: Some(iced::Background::Color(Color::from_rgb(0.07, 0.07, 0.07))),
+            background: Some(iced::Background::Color(color_or(
+                &config.background,
+                color(Role::Surface),
+            ))),
             border: iced::Border {
-                width: 0.0,
-                radius: 16.0.into(),
-                ..Default::default()
+                width: config.border_width,
+                radius: config.corner_radius.into(),
+                color: border_color,
             },
             ..Default::default()
         }
     }
 
+    pub fn pane_active(_: &Theme) -> container::Appearance {
+        with_config(|config| {
+            pane(
+                config,
+                color_or(&config.border_color_active, Color::TRANSPARENT),
+            )
+        })
+    }
+
     pub fn pane_focused(theme: &Theme) -> container::Appearance {
         let palette = theme.extended_palette();
 
-        container::Appearance {
-            background: Some(iced::Background::Color(Color::from_rgb(0.07, 0.07, 0.07))),
-            border: iced::Border {
-                width: 0.0,
-                radius: 16.0.into(),
-                color: palette.primary.strong.color,
-            },
-            ..Default::default()
+        with_config(|config| {
+            pane(
+                config,
+                color_or(&config.border_color_focused, palette.primary.strong.color),
+            )
+        })
+    }
+
+    /// Title bar tinted with color of pane type, stronger while focused
+    pub struct PaneTitle {
+        pub accent: Color,
+        pub focused: bool,
+    }
+
+    impl container::StyleSheet for PaneTitle {
+        type Style = Theme;
+
+        fn appearance(&self, _: &Self::Style) -> container::Appearance {
+            with_config(|config| {
+                let radius = config.corner_radius;
+
+                container::Appearance {
+                    background: Some(iced::Background::Color(Color {
+                        a: if self.focused { 0.3 } else { 0.12 },
+                        ..self.accent
+                    })),
+                    border: iced::Border {
+                        width: 0.0,
+                        radius: [radius, radius, 0.0, 0.0].into(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }
+            })
+        }
+    }
+
+    /// Title bar of pane that cannot be closed
+    pub fn pane_pinned(_: &Theme) -> container::Appearance {
+        with_config(|config| {
+            let radius = config.corner_radius;
+
+            container::Appearance {
+                background: Some(iced::Background::Color(Color {
+                    a: 0.15,
+                    ..color_or(&config.border_color_pinned, color(Role::Accent))
+                })),
+                border: iced::Border {
+                    width: 0.0,
+                    radius: [radius, radius, 0.0, 0.0].into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn invalid_colors_fall_back() {
+            assert_eq!(color_or("", Color::BLACK), Color::BLACK);
+            assert_eq!(color_or("12345", Color::BLACK), Color::BLACK);
+            assert_eq!(color_or("zz0000", Color::BLACK), Color::BLACK);
+            assert_eq!(color_or("FFFFFF", Color::BLACK), Color::WHITE);
         }
     }
 }
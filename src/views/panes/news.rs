@@ -0,0 +1,75 @@
+use crate::{
+    data::AppData,
+    news,
+    theme::{color, Role},
+    views::components::{
+        loading::{error_placeholder, loader, placeholder},
+        unstyled_btn::UnstyledBtn,
+    },
+};
+
+use iced::{
+    widget::{button, column, scrollable, text, Column},
+    Element, Length,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) enum NewsMessage {
+    /// Headline clicked, contains its link
+    Opened(String),
+}
+
+pub(crate) struct NewsPane {}
+
+impl NewsPane {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+
+    pub(crate) fn view<'a>(&'a self, data: &'a AppData) -> Element<'a, NewsMessage> {
+        if !data.loader.news {
+            return loader!().into();
+        }
+        if let (true, Some(error)) = (data.news.is_empty(), &data.loader.news_error) {
+            return error_placeholder(error);
+        }
+        if data.news.is_empty() {
+            return placeholder("Feed has no headlines");
+        }
+
+        scrollable(
+            Column::with_children(
+                data.news
+                    .iter()
+                    .map(|item| {
+                        let published = item
+                            .published_at
+                            .map(|at| {
+                                at.with_timezone(&chrono::Local)
+                                    .format("%d %b %H:%M")
+                                    .to_string()
+                            })
+                            .unwrap_or_default();
+
+                        button(column![
+                            text(&item.title).size(14).style(color(Role::Foreground)),
+                            text(published).size(12).style(color(Role::Muted)),
+                        ])
+                        .width(Length::Fill)
+                        .style(iced::theme::Button::Custom(Box::new(UnstyledBtn {})))
+                        .on_press(NewsMessage::Opened(item.url.clone()))
+                    })
+                    .map(Element::from),
+            )
+            .spacing(8)
+            .padding([2, 12]),
+        )
+        .into()
+    }
+
+    pub(crate) fn update(&mut self, msg: NewsMessage) {
+        match msg {
+            NewsMessage::Opened(url) => news::open_url(&url),
+        }
+    }
+}
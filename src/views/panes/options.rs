@@ -0,0 +1,204 @@
+use super::orders::{t, tb};
+use crate::{
+    options::{self, BlackScholes, OptionKind, OptionQuote},
+    theme::{color, Role},
+    views::components::{better_btn::BetterBtn, input::Inp, loading::loader},
+};
+
+use iced::{
+    widget::{button, column, row, scrollable, text, text_input, Column, Row},
+    Alignment, Command, Element, Length,
+};
+
+/// Underlyings Binance lists options for
+const UNDERLYINGS: [&str; 6] = [
+    "BTCUSDT", "ETHUSDT", "BNBUSDT", "SOLUSDT", "XRPUSDT", "DOGEUSDT",
+];
+
+#[derive(Debug, Clone)]
+pub(crate) enum OptionsMessage {
+    UnderlyingSelected(String),
+    /// Only strikes starting with typed digits are shown
+    StrikeFilterInput(String),
+    /// Fetch chain of selected underlying again
+    Refresh,
+    /// Chain of underlying, ignored if other one was selected meanwhile
+    ChainReceived(String, Result<Vec<OptionQuote>, String>),
+}
+
+/// Row of chain with values derived from mid price
+struct ChainRow {
+    quote: OptionQuote,
+    iv: Option<f64>,
+    greeks: Option<options::Greeks>,
+}
+
+impl ChainRow {
+    fn new(quote: OptionQuote) -> Self {
+        let model = BlackScholes {
+            kind: quote.kind,
+            spot: quote.underlying_price,
+            strike: quote.strike,
+            time: quote.years_to_expiry(),
+        };
+        let iv = quote.mid().and_then(|mid| model.implied_vol(mid));
+
+        Self {
+            greeks: iv.map(|iv| model.greeks(iv)),
+            iv,
+            quote,
+        }
+    }
+}
+
+pub(crate) struct OptionsPane {
+    underlying: String,
+    strike_filter: String,
+    rows: Vec<ChainRow>,
+    /// Last fetch failed
+    error: Option<String>,
+    /// Fetch in flight, refresh is ignored meanwhile
+    loading: bool,
+}
+
+impl OptionsPane {
+    pub(crate) fn new() -> Self {
+        Self {
+            underlying: UNDERLYINGS[0].to_owned(),
+            strike_filter: String::new(),
+            rows: Vec::new(),
+            error: None,
+            loading: false,
+        }
+    }
+
+    pub(crate) fn update(&mut self, msg: OptionsMessage) -> Command<OptionsMessage> {
+        match msg {
+            OptionsMessage::UnderlyingSelected(underlying) => {
+                self.underlying = underlying;
+                self.rows.clear();
+                self.loading = false;
+                self.update(OptionsMessage::Refresh)
+            }
+            OptionsMessage::StrikeFilterInput(filter) => {
+                self.strike_filter = filter.chars().filter(char::is_ascii_digit).collect();
+                Command::none()
+            }
+            OptionsMessage::Refresh => {
+                if self.loading {
+                    return Command::none();
+                }
+                self.loading = true;
+
+                let underlying = self.underlying.clone();
+                Command::perform(options::fetch_chain(underlying.clone()), move |result| {
+                    OptionsMessage::ChainReceived(underlying, result)
+                })
+            }
+            OptionsMessage::ChainReceived(underlying, _) if underlying != self.underlying => {
+                Command::none()
+            }
+            OptionsMessage::ChainReceived(_, result) => {
+                self.loading = false;
+                match result {
+                    Ok(chain) => {
+                        self.error = None;
+                        self.rows = chain.into_iter().map(ChainRow::new).collect();
+                    }
+                    Err(err) => {
+                        tracing::error!("options chain: {err}");
+                        self.error = Some(err);
+                    }
+                }
+                Command::none()
+            }
+        }
+    }
+
+    pub(crate) fn view(&self) -> Element<'_, OptionsMessage> {
+        let underlyings = Row::with_children(UNDERLYINGS.iter().map(|underlying| {
+            button(text(underlying.strip_suffix("USDT").unwrap_or(underlying)).size(12))
+                .padding([4, 8])
+                .style(if self.underlying == *underlying {
+                    iced::theme::Button::Custom(Box::new(BetterBtn {}))
+                } else {
+                    iced::theme::Button::Text
+                })
+                .on_press(OptionsMessage::UnderlyingSelected((*underlying).to_owned()))
+                .into()
+        }));
+
+        let controls = row![
+            underlyings,
+            text_input("strike", &self.strike_filter)
+                .on_input(OptionsMessage::StrikeFilterInput)
+                .style(iced::theme::TextInput::Custom(Box::new(Inp {})))
+                .size(12)
+                .width(Length::Fixed(80.0)),
+            button(text("Refresh").size(12))
+                .padding([4, 8])
+                .style(iced::theme::Button::Text)
+                .on_press(OptionsMessage::Refresh),
+        ]
+        .spacing(4)
+        .align_items(Alignment::Center);
+
+        let body: Element<_> = if self.rows.is_empty() {
+            match &self.error {
+                Some(err) => t(format!("Failed to load options: {err}"))
+                    .style(color(Role::Danger))
+                    .into(),
+                None if self.loading => loader!().into(),
+                None => t("Press refresh to load options chain")
+                    .style(color(Role::Secondary))
+                    .into(),
+            }
+        } else {
+            let cell = |s: String| t(s).size(12).width(Length::Fill);
+            let header = [
+                "Expiry", "Strike", "Type", "Bid", "Ask", "IV", "Delta", "Gamma", "Theta", "Vega",
+            ]
+            .map(|title| tb(title).size(12).width(Length::Fill).into());
+
+            let rows = self
+                .rows
+                .iter()
+                .filter(|row| {
+                    self.strike_filter.is_empty()
+                        || format!("{}", row.quote.strike).starts_with(&self.strike_filter)
+                })
+                .map(|row| {
+                    let quote = &row.quote;
+                    let (kind, kind_color) = match quote.kind {
+                        OptionKind::Call => ("Call", color(Role::Accent)),
+                        OptionKind::Put => ("Put", color(Role::Danger)),
+                    };
+                    let greek = |value: Option<f64>, precision: usize| {
+                        cell(value.map_or("-".to_owned(), |v| format!("{v:.precision$}")))
+                    };
+
+                    Row::with_children([
+                        cell(quote.expiry.format("%d %b %y").to_string()).into(),
+                        cell(quote.strike.to_string()).into(),
+                        cell(kind.to_owned()).style(kind_color).into(),
+                        cell(quote.bid.to_string()).into(),
+                        cell(quote.ask.to_string()).into(),
+                        greek(row.iv.map(|iv| iv * 100.0), 1).into(),
+                        greek(row.greeks.map(|g| g.delta), 3).into(),
+                        greek(row.greeks.map(|g| g.gamma), 6).into(),
+                        greek(row.greeks.map(|g| g.theta), 2).into(),
+                        greek(row.greeks.map(|g| g.vega), 2).into(),
+                    ])
+                    .into()
+                });
+
+            column![
+                Row::with_children(header),
+                scrollable(Column::with_children(rows))
+            ]
+            .into()
+        };
+
+        column![controls, body].spacing(4).padding([2, 12]).into()
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::VecDeque;
+
+use ahash::AHashMap;
+
+use super::orders::{t, tb};
+use crate::{
+    data::SymbolFilter,
+    theme::{color, Role},
+    views::components::loading::loader,
+    ws::trades::TradesEvent,
+};
+
+use iced::{
+    alignment, mouse,
+    widget::{
+        button,
+        canvas::{Canvas, Frame, Geometry, Program, Text},
+        column, row, text, Space,
+    },
+    Alignment, Element, Length, Point, Rectangle, Renderer, Size, Theme,
+};
+
+/// Length of one footprint bar
+const BAR_DURATION_MS: u64 = 60_000;
+
+/// Older bars are dropped
+const BARS_LEN: usize = 5;
+
+/// Width of price labels on the left
+const LABEL_WIDTH: f32 = 64.0;
+
+/// Rows lower than this have no price labels
+const MIN_LABELED_ROW: f32 = 12.0;
+
+#[derive(Debug, Clone)]
+pub(crate) enum OrderFlowMessage {
+    /// Forget collected bars
+    Reset,
+}
+
+/// Price step rows are grouped by, about a thousandth of `price`
+fn price_tick(price: f64) -> f64 {
+    10f64.powf((price.log10() - 3.0).floor())
+}
+
+/// Buy and sell volume by price level within one bar
+#[derive(Debug)]
+struct Footprint {
+    /// Start time of bar in milliseconds
+    start: u64,
+    /// Price divided by tick, and bought and sold amount at it
+    levels: AHashMap<i64, (f64, f64)>,
+}
+
+/// Bars drawn as columns of two-sided rows, sells to the left and buys to the right
+struct FootprintChart<'a> {
+    bars: &'a VecDeque<Footprint>,
+    tick: f64,
+    filter: SymbolFilter,
+}
+
+impl<Message> Program<Message> for FootprintChart<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+
+        let levels = self.bars.iter().flat_map(|bar| bar.levels.iter());
+        let prices = levels.clone().map(|(level, _)| *level);
+        let (Some(low), Some(high)) = (prices.clone().min(), prices.max()) else {
+            return vec![];
+        };
+        let max_volume = levels
+            .map(|(_, (buy, sell))| buy.max(*sell))
+            .fold(f64::EPSILON, f64::max);
+
+        let rows = (high - low + 1) as f32;
+        let row_height = bounds.height / rows;
+        let column_width = (bounds.width - LABEL_WIDTH) / BARS_LEN as f32;
+        // highest price is at the top
+        let row_top = |level: i64| (high - level) as f32 * row_height;
+
+        if row_height >= MIN_LABELED_ROW {
+            for level in low..=high {
+                frame.fill_text(Text {
+                    content: self.filter.price(level as f64 * self.tick),
+                    position: Point::new(LABEL_WIDTH - 4.0, row_top(level) + row_height / 2.0),
+                    color: color(Role::Secondary),
+                    size: 11.0.into(),
+                    horizontal_alignment: alignment::Horizontal::Right,
+                    vertical_alignment: alignment::Vertical::Center,
+                    ..Text::default()
+                });
+            }
+        }
+
+        // newest bar is in the rightmost column
+        let first_column = BARS_LEN - self.bars.len();
+        for (i, bar) in self.bars.iter().enumerate() {
+            let center = LABEL_WIDTH + ((first_column + i) as f32 + 0.5) * column_width;
+            let half = column_width / 2.0 - 2.0;
+
+            for (level, (buy, sell)) in &bar.levels {
+                let top = row_top(*level) + row_height * 0.1;
+                let height = (row_height * 0.8).max(1.0);
+
+                let sell_width = (sell / max_volume) as f32 * half;
+                frame.fill_rectangle(
+                    Point::new(center - sell_width, top),
+                    Size::new(sell_width, height),
+                    color(Role::Danger),
+                );
+                let buy_width = (buy / max_volume) as f32 * half;
+                frame.fill_rectangle(
+                    Point::new(center, top),
+                    Size::new(buy_width, height),
+                    color(Role::Accent),
+                );
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+pub(crate) struct OrderFlowPane {
+    /// Symbol of collected trades
+    symbol: String,
+    tick: f64,
+    /// Oldest first
+    bars: VecDeque<Footprint>,
+}
+
+impl OrderFlowPane {
+    pub(crate) fn new() -> Self {
+        Self {
+            symbol: String::new(),
+            tick: 1.0,
+            bars: VecDeque::with_capacity(BARS_LEN),
+        }
+    }
+
+    /// Adds trade of market pair to its bar, starting over when pair changes
+    pub(crate) fn trade_received(&mut self, trade: &TradesEvent) {
+        if trade.symbol != self.symbol {
+            self.symbol.clone_from(&trade.symbol);
+            self.bars.clear();
+        }
+        // rows of all bars have to line up, so tick is only picked for first trade
+        if self.bars.is_empty() {
+            self.tick = price_tick(trade.price);
+        }
+
+        let start = trade.time - trade.time % BAR_DURATION_MS;
+        if self.bars.back().is_none_or(|bar| bar.start < start) {
+            if self.bars.len() == BARS_LEN {
+                self.bars.pop_front();
+            }
+            self.bars.push_back(Footprint {
+                start,
+                levels: AHashMap::new(),
+            });
+        }
+        let Some(bar) = self.bars.back_mut() else {
+            return;
+        };
+
+        let level = bar
+            .levels
+            .entry((trade.price / self.tick).round() as i64)
+            .or_default();
+        // buyer being maker means aggressive side sold
+        if trade.is_buyer_maker {
+            level.1 += trade.qty;
+        } else {
+            level.0 += trade.qty;
+        }
+    }
+
+    pub(crate) fn update(&mut self, msg: OrderFlowMessage) {
+        match msg {
+            OrderFlowMessage::Reset => self.bars.clear(),
+        }
+    }
+
+    pub(crate) fn view(&self, filter: SymbolFilter) -> Element<'_, OrderFlowMessage> {
+        if self.bars.is_empty() {
+            return loader!().into();
+        }
+
+        column![
+            row![
+                tb("1m footprint"),
+                t(format!("tick {}", filter.price(self.tick))).style(color(Role::Secondary)),
+                Space::new(Length::Fill, 0),
+                button(text("Reset").size(12))
+                    .padding([2, 8])
+                    .style(iced::theme::Button::Text)
+                    .on_press(OrderFlowMessage::Reset),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center),
+            Canvas::new(FootprintChart {
+                bars: &self.bars,
+                tick: self.tick,
+                filter,
+            })
+            .width(Length::Fill)
+            .height(Length::Fill),
+        ]
+        .padding([2, 12])
+        .spacing(4)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trades_fill_minute_bars() {
+        let trade = |time, price, is_buyer_maker| TradesEvent {
+            symbol: "BTCUSDT".to_owned(),
+            id: 0,
+            time,
+            price,
+            qty: 1.0,
+            trade_order_time_formatted: String::new(),
+            is_buyer_maker,
+        };
+        assert_eq!(price_tick(60_000.0), 10.0);
+
+        let mut pane = OrderFlowPane::new();
+        pane.trade_received(&trade(60_000, 60_004.0, false));
+        pane.trade_received(&trade(61_000, 59_996.0, true));
+        pane.trade_received(&trade(119_999, 60_020.0, false));
+        assert_eq!(pane.bars.len(), 1);
+        assert_eq!(pane.bars[0].levels[&6000], (1.0, 1.0));
+        assert_eq!(pane.bars[0].levels[&6002], (1.0, 0.0));
+
+        pane.trade_received(&trade(120_000, 60_000.0, false));
+        assert_eq!(pane.bars.len(), 2);
+        assert_eq!(pane.bars[1].start, 120_000);
+    }
+}
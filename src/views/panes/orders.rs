@@ -1,15 +1,29 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, VecDeque},
+    fmt::Write,
+    fs,
+    path::PathBuf,
+};
+
 use crate::{
-    api::Client,
-    data::AppData,
-    theme::h2c,
-    views::{components::loading::loader, dashboard::DashboardMessage},
+    api::{split_symbol, TradingClient},
+    config::Config,
+    data::{AppData, Trade},
+    message::Message,
+    notification::{Notification, Severity},
+    theme::{color, Role},
+    views::components::loading::{error_placeholder, loader, placeholder},
 };
 
-use binance::rest_model::{OrderSide, OrderType};
+use binance::rest_model::{Order, OrderSide, OrderStatus, OrderType};
 use iced::{
-    widget::{column, container, row, text, Column, Space},
-    Element, Font, Length,
+    widget::{button, checkbox, column, container, row, text, Column, Space},
+    Command, Element, Font, Length,
 };
+use plotters::prelude::*;
+use plotters::style::colors;
+use plotters_iced::{Chart, ChartBuilder, ChartWidget, DrawingBackend};
 
 macro_rules! fill {
     () => {
@@ -19,7 +33,7 @@ macro_rules! fill {
 macro_rules! filled { ($($rs:expr),+) => { row![$($rs, fill![]),+] }; }
 
 pub fn t<'a>(s: impl ToString) -> iced::widget::Text<'a> {
-    text(s).size(14).style(h2c("EEEEEE").unwrap())
+    text(s).size(14).style(color(Role::Foreground))
 }
 pub fn tb<'a>(s: impl ToString) -> iced::widget::Text<'a> {
     t(s).font(Font {
@@ -28,38 +42,399 @@ pub fn tb<'a>(s: impl ToString) -> iced::widget::Text<'a> {
         ..Default::default()
     })
     .size(14)
-    .style(h2c("808080").unwrap())
+    .style(color(Role::Muted))
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum OrdersMessage {
+    /// Save filled orders to CSV file
+    Export,
+    /// Show or hide profit column
+    PnlToggled(bool),
+    /// Cancel pressed, asks for confirmation
+    CancelOrderRequest(u64),
+    /// Cancellation confirmed, order is canceled on exchange or by paper broker
+    CancelOrderConfirm(u64),
+    /// Cancellation aborted, hides confirmation
+    CancelOrderAbort,
+    /// Start realized profit chart from zero
+    PnlReset,
+    /// Column header clicked, sorts by it or reverses direction
+    Sort(OrdersSortColumn),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum OrdersSortColumn {
+    Symbol,
+    Side,
+    Price,
+    Time,
+    Status,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SortDir {
+    Ascending,
+    Descending,
+}
+
+pub(crate) struct OrdersPane {
+    show_pnl: bool,
+    /// Order waiting for cancel confirmation
+    confirming_cancel: Option<u64>,
+    sort_column: OrdersSortColumn,
+    sort_dir: SortDir,
+}
+
+/// Orders sorted by `column`, ties are broken by time and then id
+fn sorted_orders(orders: &[Order], column: OrdersSortColumn, dir: SortDir) -> Vec<&Order> {
+    let mut sorted: Vec<_> = orders.iter().collect();
+
+    sorted.sort_by(|a, b| {
+        let ordering = match column {
+            OrdersSortColumn::Symbol => a.symbol.cmp(&b.symbol),
+            OrdersSortColumn::Side => (a.side == OrderSide::Sell).cmp(&(b.side == OrderSide::Sell)),
+            OrdersSortColumn::Price => fill_price(a).total_cmp(&fill_price(b)),
+            OrdersSortColumn::Time => a.time.cmp(&b.time),
+            OrdersSortColumn::Status => format!("{:?}", a.status).cmp(&format!("{:?}", b.status)),
+        }
+        .then(a.time.cmp(&b.time))
+        .then(a.order_id.cmp(&b.order_id));
+
+        match dir {
+            SortDir::Ascending => ordering,
+            SortDir::Descending => ordering.reverse(),
+        }
+    });
+
+    sorted
+}
+
+/// Price order was filled at, limit price is meaningless for market orders
+fn fill_price(order: &Order) -> f64 {
+    if order.order_type == OrderType::Market && order.executed_qty > 0.0 {
+        order.cummulative_quote_qty / order.executed_qty
+    } else {
+        order.price
+    }
+}
+
+/// Profit in quote asset of filled part of order if it was closed at current price, None for
+/// orders that are not filled or were canceled, expired or rejected
+fn unrealized_pnl(order: &Order, price_now: f64) -> Option<f64> {
+    let holds = matches!(
+        order.status,
+        OrderStatus::Filled | OrderStatus::PartiallyFilled
+    );
+    if !holds || order.executed_qty <= 0.0 {
+        return None;
+    }
+
+    // remaining quantity of partially filled order is not bought or sold yet
+    let price = fill_price(order);
+    Some(match order.side {
+        OrderSide::Buy => order.executed_qty * (price_now - price),
+        OrderSide::Sell => order.executed_qty * (price - price_now),
+    })
+}
+
+/// Unrealized profit summed per quote asset, profits in different assets do not add up
+fn unrealized_totals(orders: &[Order], price_now: impl Fn(&str) -> f64) -> BTreeMap<&str, f64> {
+    let mut totals = BTreeMap::new();
+    for order in orders {
+        let Some([_, quote]) = split_symbol(&order.symbol) else {
+            continue;
+        };
+        if let Some(pnl) = unrealized_pnl(order, price_now(&order.symbol)) {
+            *totals.entry(quote).or_insert(0.0) += pnl;
+        }
+    }
+
+    totals
+}
+
+fn pnl_color(pnl: f64) -> iced::Color {
+    if pnl >= 0.0 {
+        color(Role::Accent)
+    } else {
+        color(Role::Danger)
+    }
+}
+
+fn pnl_text<'a>(pnl: f64) -> iced::widget::Text<'a> {
+    t(format!("{pnl:.0}$"))
+        .width(Length::Fixed(100.0))
+        .style(pnl_color(pnl))
+}
+
+/// Profit in quote asset of a pair, None is shown as dash
+fn asset_pnl_text<'a>(pnl: Option<f64>, asset: &str) -> iced::widget::Text<'a> {
+    let Some(pnl) = pnl else {
+        return t("-")
+            .width(Length::Fixed(100.0))
+            .style(color(Role::Secondary));
+    };
+    // cents are noise for dollars, but not for BTC
+    let precision = if pnl.abs() >= 1.0 { 2 } else { 6 };
+
+    t(format!("{pnl:.precision$} {asset}"))
+        .width(Length::Fixed(100.0))
+        .style(pnl_color(pnl))
+}
+
+/// Cumulative realized profit over time
+struct PnlChart<'a> {
+    history: &'a VecDeque<(u64, f64)>,
+}
+
+impl Chart<OrdersMessage> for PnlChart<'_> {
+    type State = ();
+
+    fn build_chart<DB: DrawingBackend>(&self, _state: &Self::State, mut builder: ChartBuilder<DB>) {
+        // curve starts from zero before first fill
+        let points: Vec<_> = self
+            .history
+            .front()
+            .map(|(ts, _)| (*ts as f64, 0.0))
+            .into_iter()
+            .chain(self.history.iter().map(|(ts, pnl)| (*ts as f64, *pnl)))
+            .collect();
+
+        let (first, last) = (points[0].0, points[points.len() - 1].0);
+        let (min, max) = points.iter().fold((0.0_f64, 0.0_f64), |acc, (_, y)| {
+            (acc.0.min(*y), acc.1.max(*y))
+        });
+        // flat chart would have empty range
+        let max = if max > min { max } else { min + 1.0 };
+
+        let mut chart = builder
+            .x_label_area_size(0_i32)
+            .y_label_area_size(70_i32)
+            .margin(0_i32)
+            .build_cartesian_2d(first..last.max(first + 1.0), min..max)
+            .expect("Failed to build chart");
+
+        chart
+            .configure_mesh()
+            .disable_mesh()
+            .axis_style(
+                ShapeStyle::from(plotters::style::colors::full_palette::GREY_500).stroke_width(0),
+            )
+            .y_labels(3)
+            .y_label_style(
+                ("monospace", 12)
+                    .into_font()
+                    .color(&plotters::style::colors::WHITE),
+            )
+            .draw()
+            .unwrap();
+
+        chart
+            .draw_series(LineSeries::new(
+                [(first, 0.0), (last, 0.0)],
+                colors::full_palette::GREY_600,
+            ))
+            .expect("failed to draw zero level");
+
+        let line_color = if points[points.len() - 1].1 >= 0.0 {
+            colors::GREEN
+        } else {
+            colors::RED
+        };
+        chart
+            .draw_series(LineSeries::new(points, line_color))
+            .expect("failed to draw realized profit");
+    }
+}
+
+/// Quotes field if it has separators, quotes or line breaks in it
+pub(crate) fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Filled orders as CSV table
+///
+/// Fee is not returned with orders, it is summed from loaded trades and empty for orders without
+/// them
+fn orders_csv(orders: &[Order], trades: &[Trade]) -> String {
+    let mut csv = String::from("timestamp,symbol,side,quantity,price,fee,fee_asset,total\n");
+
+    for o in orders.iter().filter(|o| o.executed_qty > 0.0) {
+        let timestamp = chrono::DateTime::from_timestamp_millis(o.time as i64)
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default();
+
+        let fills: Vec<_> = trades
+            .iter()
+            .filter(|t| t.order_id == o.order_id && t.symbol == o.symbol)
+            .collect();
+        let (fee, fee_asset) = match fills.first() {
+            Some(first) => (
+                fills.iter().map(|t| t.commission).sum::<f64>().to_string(),
+                first.commission_asset.as_str(),
+            ),
+            None => (String::new(), ""),
+        };
+
+        writeln!(
+            csv,
+            "{timestamp},{},{:?},{},{},{fee},{},{}",
+            csv_field(&o.symbol),
+            o.side,
+            o.executed_qty,
+            fill_price(o),
+            csv_field(fee_asset),
+            o.cummulative_quote_qty
+        )
+        .expect("writing to string does not fail");
+    }
+
+    csv
+}
+
+/// Downloads directory, config directory if it is unknown
+fn export_dir() -> PathBuf {
+    directories_next::UserDirs::new()
+        .and_then(|dirs| dirs.download_dir().map(Into::into))
+        .unwrap_or_else(Config::dir)
 }
 
-pub(crate) struct OrdersPane {}
+/// Writes CSV table to timestamped file named after `what` in downloads
+pub(crate) fn export_csv(csv: String, what: &'static str) -> Command<Message> {
+    let path = export_dir().join(format!(
+        "dynasty-{what}-{}.csv",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+
+    Command::perform(
+        async move {
+            fs::write(&path, csv)?;
+            Ok::<_, std::io::Error>(path)
+        },
+        move |result| match result {
+            Ok(path) => Message::PushNotification(Notification::new(
+                Severity::Success,
+                format!("exported {what} to {}", path.display()),
+            )),
+            Err(err) => Message::ToastError(format!("failed to export {what}: {err}")),
+        },
+    )
+}
 
 impl OrdersPane {
     pub(crate) fn new() -> Self {
-        Self {}
+        Self {
+            show_pnl: true,
+            confirming_cancel: None,
+            sort_column: OrdersSortColumn::Time,
+            sort_dir: SortDir::Descending,
+        }
     }
 
-    pub(crate) fn view<'a>(&'a self, data: &'a AppData) -> Element<'a, DashboardMessage> {
+    pub(crate) fn update(
+        &mut self,
+        msg: OrdersMessage,
+        data: &mut AppData,
+        api: &dyn TradingClient,
+        paper: bool,
+    ) -> Command<Message> {
+        let orders = &data.orders;
+
+        match msg {
+            OrdersMessage::Export => export_csv(orders_csv(orders, &data.trade_history), "orders"),
+            OrdersMessage::PnlToggled(show) => {
+                self.show_pnl = show;
+                Command::none()
+            }
+            OrdersMessage::CancelOrderRequest(order_id) => {
+                self.confirming_cancel = Some(order_id);
+                Command::none()
+            }
+            OrdersMessage::CancelOrderConfirm(order_id) => {
+                self.confirming_cancel = None;
+
+                match orders.iter().find(|o| o.order_id == order_id) {
+                    Some(_) if paper => {
+                        Command::perform(async {}, move |_| Message::PaperCancel(order_id))
+                    }
+                    Some(order) => api.cancel_order(&order.symbol, order_id),
+                    None => Command::none(),
+                }
+            }
+            OrdersMessage::CancelOrderAbort => {
+                self.confirming_cancel = None;
+                Command::none()
+            }
+            OrdersMessage::PnlReset => {
+                data.reset_realized_pnl();
+                Command::none()
+            }
+            OrdersMessage::Sort(column) => {
+                if self.sort_column == column {
+                    self.sort_dir = match self.sort_dir {
+                        SortDir::Ascending => SortDir::Descending,
+                        SortDir::Descending => SortDir::Ascending,
+                    };
+                } else {
+                    self.sort_column = column;
+                    self.sort_dir = SortDir::Ascending;
+                }
+                Command::none()
+            }
+        }
+    }
+
+    pub(crate) fn view<'a>(&'a self, data: &'a AppData) -> Element<'a, OrdersMessage> {
         let ps = &data.prices;
         let os = &data.orders;
 
-        if os.is_empty() || ps.is_empty() {
+        if let (true, Some(error)) = (os.is_empty(), &data.loader.orders_error) {
+            return error_placeholder(error);
+        }
+        if !data.loader.orders || ps.is_empty() {
             return loader!().into();
         }
+        if os.is_empty() {
+            return placeholder("No open orders");
+        }
+
+        let show_pnl = self.show_pnl;
+
+        let sort_header = |label: &str, column, width| {
+            let arrow = match self.sort_dir {
+                _ if self.sort_column != column => "",
+                SortDir::Ascending => " \u{25B2}",
+                SortDir::Descending => " \u{25BC}",
+            };
+
+            button(tb(format!("{label}{arrow}")))
+                .style(iced::theme::Button::Text)
+                .padding(0)
+                .width(Length::Fixed(width))
+                .on_press(OrdersMessage::Sort(column))
+        };
 
         let header = filled![
-            tb("Symbol").width(Length::Fixed(100.0)),
-            tb("Price").width(Length::Fixed(100.0)),
+            sort_header("Symbol", OrdersSortColumn::Symbol, 100.0),
+            sort_header("Price", OrdersSortColumn::Price, 100.0),
             tb("Size").width(Length::Fixed(100.0)),
             tb("X-Size").width(Length::Fixed(100.0)),
-            tb("Side").width(Length::Fixed(100.0)),
-            tb("Status").width(Length::Fixed(100.0)),
-            tb("PNL").width(Length::Fixed(100.0)),
-            tb("Time").width(Length::Fixed(150.0))
+            sort_header("Side", OrdersSortColumn::Side, 100.0),
+            sort_header("Status", OrdersSortColumn::Status, 100.0)
         ]
+        .push_maybe(
+            show_pnl.then(|| filled![tb("PNL").width(Length::Fixed(100.0))].width(Length::Fill)),
+        )
+        .push(filled![sort_header("Time", OrdersSortColumn::Time, 150.0)].width(Length::Fill))
         .padding([0, 12])
         .width(Length::Fill);
 
-        let rows = os.iter().map(|b| {
+        let sorted = sorted_orders(os, self.sort_column, self.sort_dir);
+        let rows = sorted.into_iter().map(|b| {
             let time_t = {
                 let dt: chrono::DateTime<chrono::Utc> =
                     chrono::TimeZone::timestamp_opt(&chrono::Utc, (b.time / 1000) as i64, 0)
@@ -70,70 +445,220 @@ impl OrdersPane {
 
             let symbol_t = {
                 let s = &b.symbol;
-                tb(s).style(h2c("11EE11").unwrap())
+                tb(s).style(color(Role::Accent))
             }
             .width(Length::Fixed(100.0));
-            let [base, quote] = Client::split_symbol(&b.symbol).unwrap();
-            let norm_price = if b.order_type == OrderType::Market {
-                b.cummulative_quote_qty / b.executed_qty
-            } else {
-                b.price
-            };
-            let price_t = t(format!("{norm_price:.3}")).width(Length::Fixed(100.0));
-            let executed_t = t(format!("{} {base}", b.executed_qty)).width(Length::Fixed(100.0));
+            let [base, quote] = split_symbol(&b.symbol).unwrap();
+            let norm_price = fill_price(b);
+            let filter = data.symbol_filter(&b.symbol);
+            let price_t = t(filter.price(norm_price)).width(Length::Fixed(100.0));
+            let executed_t =
+                t(format!("{} {base}", filter.qty(b.executed_qty))).width(Length::Fixed(100.0));
             let executed_base = t(format!("{:.0} {quote}", b.executed_qty * norm_price))
                 .width(Length::Fixed(100.0));
             let side_t = t(format!("{:?}", &b.side))
                 .width(Length::Fixed(100.0))
-                .style(
-                    if b.side == OrderSide::Buy {
-                        h2c("11EE11")
-                    } else {
-                        h2c("EE1111")
-                    }
-                    .unwrap(),
-                );
+                .style(if b.side == OrderSide::Buy {
+                    color(Role::Accent)
+                } else {
+                    color(Role::Danger)
+                });
             let status_t = t(format!("{:?}", &b.status)).width(Length::Fixed(100.0));
-            let price_now = ps.price(&b.symbol);
+            let pnl = asset_pnl_text(unrealized_pnl(b, ps.price(&b.symbol) as f64), quote);
+            let is_open = matches!(b.status, OrderStatus::New | OrderStatus::PartiallyFilled);
+            let cancel_b = is_open.then(|| {
+                button(text("Cancel").size(12))
+                    .style(iced::theme::Button::Text)
+                    .padding([0, 8])
+                    .on_press(OrdersMessage::CancelOrderRequest(b.order_id))
+            });
 
-            let pnl = {
-                let pnl_value = match b.side {
-                    binance::rest_model::OrderSide::Buy => {
-                        b.executed_qty * (price_now as f64 - norm_price)
-                    }
-                    binance::rest_model::OrderSide::Sell => {
-                        b.executed_qty * (norm_price - price_now as f64)
-                    }
-                };
-                t(format!("{pnl_value:.0}$"))
-                    .width(Length::Fixed(100.0))
-                    .style(
-                        if pnl_value >= 0.0 {
-                            h2c("11EE11")
-                        } else {
-                            h2c("EE1111")
-                        }
-                        .unwrap(),
-                    )
-            };
+            let order_row = filled![
+                symbol_t,
+                price_t,
+                executed_t,
+                executed_base,
+                side_t,
+                status_t
+            ]
+            .push_maybe(show_pnl.then(|| filled![pnl].width(Length::Fill)))
+            .push(filled![time_t].width(Length::Fill))
+            .push_maybe(cancel_b)
+            .width(Length::Fill);
 
-            container(
-                filled![
-                    symbol_t,
-                    price_t,
-                    executed_t,
-                    executed_base,
-                    side_t,
-                    status_t,
-                    pnl,
-                    time_t
+            // shown under order instead of modal so that other panes stay usable
+            let confirmation = (self.confirming_cancel == Some(b.order_id)).then(|| {
+                row![
+                    t(format!("Cancel {} order {}?", b.symbol, b.order_id))
+                        .style(color(Role::Warning)),
+                    fill![],
+                    button(text("Confirm").size(12))
+                        .style(iced::theme::Button::Destructive)
+                        .padding([2, 8])
+                        .on_press(OrdersMessage::CancelOrderConfirm(b.order_id)),
+                    button(text("Abort").size(12))
+                        .style(iced::theme::Button::Text)
+                        .padding([2, 8])
+                        .on_press(OrdersMessage::CancelOrderAbort),
                 ]
-                .width(Length::Fill),
-            )
-            .padding([2, 4])
-            .into()
+                .spacing(4)
+                .padding([2, 8])
+            });
+
+            container(column![order_row].push_maybe(confirmation))
+                .padding([2, 4])
+                .into()
         });
 
-        column![header, Column::with_children(rows).padding(8)].into()
+        let total = show_pnl.then(|| {
+            let totals = unrealized_totals(os, |symbol| ps.price(symbol) as f64);
+
+            container(row![
+                tb("Total").width(Length::Fixed(100.0)),
+                fill![],
+                Column::with_children(
+                    totals
+                        .into_iter()
+                        .map(|(quote, pnl)| asset_pnl_text(Some(pnl), quote).into())
+                ),
+            ])
+            .padding([2, 12])
+        });
+
+        let pnl_chart = (!data.realized_pnl_history.is_empty()).then(|| {
+            ChartWidget::new(PnlChart {
+                history: &data.realized_pnl_history,
+            })
+            .height(Length::Fixed(80.0))
+        });
+
+        let realized = data.realized_pnl_history.back().map(|(_, pnl)| *pnl);
+        let toolbar = row![
+            tb("Realized"),
+            pnl_text(realized.unwrap_or(0.0)),
+            button(text("Reset").size(12))
+                .style(iced::theme::Button::Text)
+                .padding(8)
+                .on_press_maybe(realized.map(|_| OrdersMessage::PnlReset)),
+            fill![],
+            checkbox("PNL", show_pnl)
+                .size(14)
+                .text_size(12)
+                .on_toggle(OrdersMessage::PnlToggled),
+            button(text("Export CSV").size(12))
+                .style(iced::theme::Button::Text)
+                .padding(8)
+                .on_press(OrdersMessage::Export),
+        ];
+
+        column![toolbar]
+            .push_maybe(pnl_chart)
+            .push(header)
+            .push(Column::with_children(rows).padding(8))
+            .push_maybe(total)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(order_id: u64, symbol: &str, price: f64, time: u64) -> Order {
+        Order {
+            symbol: symbol.to_owned(),
+            order_id,
+            order_list_id: -1,
+            client_order_id: String::new(),
+            price,
+            orig_qty: 1.0,
+            executed_qty: 1.0,
+            cummulative_quote_qty: price,
+            status: OrderStatus::Filled,
+            time_in_force: binance::rest_model::TimeInForce::GTC,
+            order_type: OrderType::Limit,
+            side: OrderSide::Buy,
+            stop_price: 0.0,
+            iceberg_qty: 0.0,
+            time,
+            update_time: time,
+            is_working: false,
+            orig_quote_order_qty: 0.0,
+        }
+    }
+
+    #[test]
+    fn csv_fields_are_escaped_and_fees_summed() {
+        assert_eq!(csv_field("BTCUSDT"), "BTCUSDT");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+
+        let trade = |id, commission| Trade {
+            id,
+            order_id: 1,
+            symbol: "ETHUSDT".to_owned(),
+            time: 0,
+            is_buyer: true,
+            price: 2_000.0,
+            qty: 0.5,
+            commission,
+            commission_asset: "ETH".to_owned(),
+        };
+        let csv = orders_csv(
+            &[
+                order(1, "ETHUSDT", 2_000.0, 0),
+                order(2, "ETHUSDT", 2_000.0, 0),
+            ],
+            &[trade(10, 0.25), trade(11, 0.5)],
+        );
+        let rows: Vec<_> = csv.lines().skip(1).collect();
+
+        assert!(rows[0].ends_with(",0.75,ETH,2000"));
+        assert!(rows[1].ends_with(",,,2000"));
+    }
+
+    #[test]
+    fn sort_with_tie_breaker() {
+        let orders = [
+            order(1, "ETHUSDT", 2_000.0, 10),
+            order(2, "BTCUSDT", 60_000.0, 30),
+            order(3, "ETHUSDT", 1_900.0, 20),
+        ];
+        let ids = |column, dir| -> Vec<u64> {
+            sorted_orders(&orders, column, dir)
+                .iter()
+                .map(|o| o.order_id)
+                .collect()
+        };
+
+        assert_eq!(ids(OrdersSortColumn::Time, SortDir::Descending), [2, 3, 1]);
+        assert_eq!(ids(OrdersSortColumn::Price, SortDir::Ascending), [3, 1, 2]);
+        // equal symbols keep time order
+        assert_eq!(ids(OrdersSortColumn::Symbol, SortDir::Ascending), [2, 1, 3]);
+        assert_eq!(
+            ids(OrdersSortColumn::Status, SortDir::Descending),
+            [2, 3, 1]
+        );
+    }
+
+    #[test]
+    fn unrealized_pnl_per_quote() {
+        let mut canceled = order(3, "ETHUSDT", 1_000.0, 0);
+        canceled.status = OrderStatus::Canceled;
+        let mut open = order(4, "ETHUSDT", 1_000.0, 0);
+        (open.status, open.executed_qty, open.order_type) =
+            (OrderStatus::New, 0.0, OrderType::Market);
+        let orders = [
+            order(1, "ETHUSDT", 2_000.0, 0),
+            order(2, "ETHBTC", 0.04, 0),
+            canceled,
+            open,
+        ];
+        let price_now = |symbol: &str| if symbol == "ETHBTC" { 0.05 } else { 2_100.0 };
+
+        assert_eq!(unrealized_pnl(&orders[3], 2_100.0), None);
+        let totals = unrealized_totals(&orders, price_now);
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals["USDT"], 100.0);
+        assert!((totals["BTC"] - 0.01).abs() < 1e-12);
     }
 }
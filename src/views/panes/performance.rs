@@ -0,0 +1,188 @@
+//! Rendering and websocket statistics, only built with `perf-pane` feature
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use super::orders::{t, tb};
+use crate::{
+    theme::{color, Role},
+    views::{components::better_btn::GreenBtn, dashboard::PaneType},
+};
+
+use iced::{
+    widget::{button, column, container, row, Space},
+    Element, Length,
+};
+
+/// Older view timings are dropped
+const SAMPLES_LEN: usize = 300;
+
+/// Mean, 95th percentile and max of durations
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Stats {
+    mean: Duration,
+    p95: Duration,
+    max: Duration,
+}
+
+impl Stats {
+    fn new(samples: &VecDeque<Duration>) -> Option<Self> {
+        let mut sorted: Vec<_> = samples.iter().copied().collect();
+        sorted.sort();
+        let max = *sorted.last()?;
+
+        // nearest rank
+        let p95 = sorted[(sorted.len() * 95).div_ceil(100) - 1];
+        let mean = sorted.iter().sum::<Duration>() / sorted.len() as u32;
+
+        Some(Self { mean, p95, max })
+    }
+}
+
+/// Resident memory of process in bytes, only known on Linux
+#[cfg(target_os = "linux")]
+fn resident_memory() -> Option<u64> {
+    // SAFETY: plain syscall without pointers
+    let page_size = u64::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) }).ok()?;
+
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+
+    Some(pages * page_size)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn resident_memory() -> Option<u64> {
+    None
+}
+
+fn ms(duration: Duration) -> String {
+    format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
+}
+
+pub(crate) struct PerformancePane {
+    /// Time taken to build widgets of pane, recorded from dashboard view. Layout and drawing happen
+    /// later and are not included
+    view_times: RefCell<VecDeque<Duration>>,
+    book_messages: u32,
+    trade_messages: u32,
+    /// Messages per second since previous tick
+    book_rate: f64,
+    trade_rate: f64,
+    last_tick: Instant,
+    memory: Option<u64>,
+}
+
+impl PerformancePane {
+    pub(crate) fn new() -> Self {
+        Self {
+            view_times: RefCell::new(VecDeque::with_capacity(SAMPLES_LEN)),
+            book_messages: 0,
+            trade_messages: 0,
+            book_rate: 0.0,
+            trade_rate: 0.0,
+            last_tick: Instant::now(),
+            memory: resident_memory(),
+        }
+    }
+
+    pub(crate) fn record_view(&self, duration: Duration) {
+        let mut view_times = self.view_times.borrow_mut();
+        if view_times.len() == SAMPLES_LEN {
+            view_times.pop_front();
+        }
+        view_times.push_back(duration);
+    }
+
+    /// Counts websocket messages of book and trades
+    pub(crate) fn message_received(&mut self, pane: PaneType) {
+        match pane {
+            PaneType::Book => self.book_messages += 1,
+            PaneType::Trades => self.trade_messages += 1,
+            _ => (),
+        }
+    }
+
+    /// Turns message counts into rates
+    pub(crate) fn tick(&mut self) {
+        let elapsed = self.last_tick.elapsed().as_secs_f64().max(f64::EPSILON);
+
+        self.book_rate = f64::from(self.book_messages) / elapsed;
+        self.trade_rate = f64::from(self.trade_messages) / elapsed;
+        self.book_messages = 0;
+        self.trade_messages = 0;
+        self.last_tick = Instant::now();
+        self.memory = resident_memory();
+    }
+
+    pub(crate) fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    pub(crate) fn view<'a, M: Clone + 'a>(&'a self, on_reset: M) -> Element<'a, M> {
+        let stat_row = |label, value: String| {
+            row![
+                tb(label).width(Length::Fill),
+                t(value).style(color(Role::Secondary)).width(Length::Fill),
+            ]
+        };
+
+        let view_stats = Stats::new(&self.view_times.borrow()).map_or_else(
+            || "-".to_owned(),
+            |s| format!("{} / {} / {}", ms(s.mean), ms(s.p95), ms(s.max)),
+        );
+
+        container(
+            column![
+                stat_row("Widget build mean / p95 / max", view_stats),
+                stat_row("Book messages", format!("{:.1}/s", self.book_rate)),
+                stat_row("Trade messages", format!("{:.1}/s", self.trade_rate)),
+                stat_row(
+                    "Memory",
+                    self.memory.map_or_else(
+                        || "unknown".to_owned(),
+                        |bytes| format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+                    ),
+                ),
+                Space::new(0, Length::Fill),
+                button(tb("Reset").style(iced::Color::WHITE).size(12))
+                    .style(iced::theme::Button::Custom(Box::new(GreenBtn {})))
+                    .padding(8)
+                    .on_press(on_reset),
+            ]
+            .spacing(8),
+        )
+        .padding([4, 12])
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_of_samples() {
+        let samples: VecDeque<_> = (1..=20).map(Duration::from_millis).collect();
+        let stats = Stats::new(&samples).unwrap();
+
+        assert_eq!(stats.max, Duration::from_millis(20));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+        assert_eq!(stats.mean, Duration::from_micros(10_500));
+        assert_eq!(Stats::new(&VecDeque::new()), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn resident_memory_is_page_aligned() {
+        let memory = resident_memory().unwrap();
+        // SAFETY: plain syscall without pointers
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as u64;
+
+        assert!(memory > 0);
+        assert_eq!(memory % page_size, 0);
+    }
+}
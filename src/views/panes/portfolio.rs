@@ -0,0 +1,239 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use iced::{
+    alignment, mouse,
+    widget::canvas::{
+        event, path::Arc, Canvas, Event, Frame, Geometry, Path, Program, Stroke, Text,
+    },
+    Color, Element, Length, Point, Radians, Rectangle, Renderer, Size, Theme,
+};
+
+use crate::{
+    data::AppData,
+    theme::{color, h2c, Role},
+    views::components::loading::loader,
+};
+
+/// Slices below this share of total are merged into "Other"
+const MIN_SHARE: f64 = 0.01;
+
+const OTHER: &str = "Other";
+
+const PALETTE: [&str; 8] = [
+    "32D993", "FF707E", "5B8DEF", "F5C451", "B57BFF", "4DD0E1", "FF9F5A", "B7BDB7",
+];
+
+#[derive(Debug, Clone)]
+pub(crate) enum PortfolioMessage {
+    /// Asset under cursor
+    Hover(Option<String>),
+}
+
+pub(crate) struct PortfolioPane {
+    /// Asset and its value in USDT, largest first
+    slices: Vec<(String, f64)>,
+    hovered: Option<String>,
+}
+
+/// Value of every balance in USDT, merging small ones
+fn allocation(data: &AppData) -> Vec<(String, f64)> {
+    let values: Vec<_> = data
+        .balances
+        .iter()
+        .filter_map(|b| {
+            Some((
+                b.asset.clone(),
+                data.usdt_value(&b.asset, b.free + b.locked)?,
+            ))
+        })
+        .filter(|(_, value)| *value > 0.0)
+        .collect();
+
+    let total: f64 = values.iter().map(|(_, value)| value).sum();
+    let (mut slices, small): (Vec<_>, Vec<_>) = values
+        .into_iter()
+        .partition(|(_, value)| value / total >= MIN_SHARE);
+
+    slices.sort_by(|(_, v1), (_, v2)| v2.total_cmp(v1));
+
+    let other: f64 = small.iter().map(|(_, value)| value).sum();
+    if other > 0.0 {
+        slices.push((OTHER.to_owned(), other));
+    }
+
+    slices
+}
+
+struct PieChart<'a> {
+    slices: &'a [(String, f64)],
+    hovered: Option<&'a str>,
+}
+
+impl PieChart<'_> {
+    fn geometry(bounds: Size) -> (Point, f32) {
+        let center = Point::new(bounds.width / 2.0, bounds.height / 2.0);
+        let radius = (bounds.width.min(bounds.height) / 2.0 - 8.0).max(0.0);
+
+        (center, radius)
+    }
+
+    fn total(&self) -> f64 {
+        self.slices.iter().map(|(_, value)| value).sum()
+    }
+
+    /// Start and end angle of every slice, clockwise from the top
+    fn angles(&self) -> impl Iterator<Item = (&(String, f64), f32, f32)> {
+        let total = self.total();
+        let mut start = -FRAC_PI_2;
+
+        self.slices.iter().map(move |slice| {
+            let end = start + (slice.1 / total) as f32 * TAU;
+            let angles = (slice, start, end);
+            start = end;
+            angles
+        })
+    }
+
+    fn slice_at(&self, bounds: Size, position: Point) -> Option<&str> {
+        let (center, radius) = Self::geometry(bounds);
+        let (dx, dy) = (position.x - center.x, position.y - center.y);
+
+        if dx.hypot(dy) > radius {
+            return None;
+        }
+
+        // shift angle into the same range as slices
+        let mut angle = dy.atan2(dx);
+        if angle < -FRAC_PI_2 {
+            angle += TAU;
+        }
+
+        self.angles()
+            .find(|(_, start, end)| (*start..*end).contains(&angle))
+            .map(|((name, _), _, _)| name.as_str())
+    }
+}
+
+impl Program<PortfolioMessage> for PieChart<'_> {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<PortfolioMessage>) {
+        let Event::Mouse(mouse::Event::CursorMoved { .. }) = event else {
+            return (event::Status::Ignored, None);
+        };
+
+        let hovered = cursor
+            .position_in(bounds)
+            .and_then(|position| self.slice_at(bounds.size(), position));
+
+        if hovered == self.hovered {
+            return (event::Status::Ignored, None);
+        }
+
+        (
+            event::Status::Ignored,
+            Some(PortfolioMessage::Hover(hovered.map(str::to_owned))),
+        )
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let (center, radius) = Self::geometry(bounds.size());
+
+        for (i, ((name, _), start, end)) in self.angles().enumerate() {
+            let is_hovered = self.hovered == Some(name.as_str());
+            let slice = Path::new(|p| {
+                p.move_to(center);
+                p.arc(Arc {
+                    center,
+                    radius: if is_hovered { radius + 4.0 } else { radius },
+                    start_angle: Radians(start),
+                    end_angle: Radians(end),
+                });
+                p.close();
+            });
+
+            let slice_color = h2c(PALETTE[i % PALETTE.len()]).unwrap();
+            frame.fill(&slice, slice_color);
+            frame.stroke(
+                &slice,
+                Stroke::default()
+                    .with_color(color(Role::Surface))
+                    .with_width(1.0),
+            );
+        }
+
+        if let (Some(position), Some(hovered)) = (cursor.position_in(bounds), self.hovered) {
+            let total = self.total();
+            if let Some((name, value)) = self.slices.iter().find(|(name, _)| name == hovered) {
+                let label = format!("{name}: {value:.2} USDT ({:.1}%)", value / total * 100.0);
+                let size = Size::new(label.len() as f32 * 7.0 + 12.0, 22.0);
+                // keep tooltip inside of canvas
+                let top_left = Point::new(
+                    (position.x + 12.0).min(bounds.width - size.width).max(0.0),
+                    (position.y + 12.0)
+                        .min(bounds.height - size.height)
+                        .max(0.0),
+                );
+
+                frame.fill_rectangle(top_left, size, Color::from_rgba(0.0, 0.0, 0.0, 0.8));
+                frame.fill_text(Text {
+                    content: label,
+                    position: Point::new(top_left.x + 6.0, top_left.y + size.height / 2.0),
+                    color: color(Role::Foreground),
+                    size: 12.0.into(),
+                    vertical_alignment: alignment::Vertical::Center,
+                    ..Text::default()
+                });
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+impl PortfolioPane {
+    pub(crate) fn new() -> Self {
+        Self {
+            slices: Vec::new(),
+            hovered: None,
+        }
+    }
+
+    pub(crate) fn tick(&mut self, data: &AppData) {
+        self.slices = allocation(data);
+    }
+
+    pub(crate) fn update(&mut self, msg: PortfolioMessage) {
+        match msg {
+            PortfolioMessage::Hover(asset) => self.hovered = asset,
+        }
+    }
+
+    pub(crate) fn view(&self) -> Element<'_, PortfolioMessage> {
+        if self.slices.is_empty() {
+            return loader!().into();
+        }
+
+        Canvas::new(PieChart {
+            slices: &self.slices,
+            hovered: self.hovered.as_deref(),
+        })
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+}
@@ -0,0 +1,104 @@
+use super::orders::{t, tb};
+use crate::{
+    data::AppData,
+    theme::{color, Role},
+    views::components::{loading::loader, unstyled_btn::UnstyledBtn},
+};
+
+use iced::{
+    widget::{button, column, container, row, scrollable, Column, Space},
+    Element, Length,
+};
+
+/// Buy or sell share above this in percent is highlighted
+const EXTREME_PCT: f64 = 70.0;
+
+#[derive(Debug, Clone)]
+pub(crate) enum SentimentMessage {
+    /// Symbol clicked, sets market pair
+    PairSelected(String),
+}
+
+fn is_extreme(buy_pct: f64) -> bool {
+    !(100.0 - EXTREME_PCT..=EXTREME_PCT).contains(&buy_pct)
+}
+
+/// Bar split into buy and sell parts by share of buy volume
+fn bar<'a>(buy_pct: f64) -> Element<'a, SentimentMessage> {
+    let part = |pct: f64, role| {
+        // portions are integers, tenths of percent keep bar smooth
+        container(Space::new(Length::Fill, 12))
+            .width(Length::FillPortion((pct * 10.0).round() as u16))
+            .style(container::Appearance {
+                background: Some(iced::Background::Color(color(role))),
+                ..Default::default()
+            })
+    };
+
+    row![
+        part(buy_pct, Role::Accent),
+        part(100.0 - buy_pct, Role::Danger)
+    ]
+    .width(Length::FillPortion(3))
+    .into()
+}
+
+pub(crate) struct SentimentPane {}
+
+impl SentimentPane {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+
+    /// Taker buy against sell volume of favorites over last 5 minutes
+    pub(crate) fn view<'a>(
+        &'a self,
+        data: &'a AppData,
+        symbols: &'a [String],
+    ) -> Element<'a, SentimentMessage> {
+        let rows: Vec<_> = symbols
+            .iter()
+            .filter_map(|symbol| Some((symbol, data.sentiment.get(symbol)?.buy_pct()?)))
+            .collect();
+        if rows.is_empty() {
+            return loader!().into();
+        }
+
+        column![
+            row![
+                tb("Symbol").width(Length::FillPortion(1)),
+                tb("Buy / sell 5m").width(Length::FillPortion(3)),
+                Space::with_width(Length::FillPortion(1)),
+            ]
+            .spacing(8),
+            scrollable(
+                Column::with_children(rows.into_iter().map(|(symbol, buy_pct)| {
+                    let label_color = if is_extreme(buy_pct) {
+                        color(Role::Warning)
+                    } else {
+                        color(Role::Secondary)
+                    };
+
+                    row![
+                        button(t(symbol).style(label_color))
+                            .padding(0)
+                            .style(iced::theme::Button::Custom(Box::new(UnstyledBtn {})))
+                            .on_press(SentimentMessage::PairSelected(symbol.clone()))
+                            .width(Length::FillPortion(1)),
+                        bar(buy_pct),
+                        t(format!("{buy_pct:.0}% / {:.0}%", 100.0 - buy_pct))
+                            .style(label_color)
+                            .width(Length::FillPortion(1)),
+                    ]
+                    .spacing(8)
+                    .align_items(iced::Alignment::Center)
+                    .into()
+                }))
+                .spacing(4)
+            )
+        ]
+        .spacing(4)
+        .padding([2, 12])
+        .into()
+    }
+}
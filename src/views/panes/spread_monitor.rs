@@ -0,0 +1,130 @@
+use ahash::AHashMap;
+
+use super::orders::{t, tb};
+use crate::{
+    data::AppData,
+    theme::{color, Role},
+    views::components::{loading::loader, unstyled_btn::UnstyledBtn},
+    ws::book_ticker::BookTick,
+};
+
+use iced::{
+    widget::{button, column, container, row, scrollable, Column},
+    Element, Length,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) enum SpreadMonitorMessage {
+    /// Symbol clicked, sets market pair
+    PairSelected(String),
+}
+
+/// Spreads of symbols with received top of book, widest first, and their average
+fn spreads<'a>(
+    book_tickers: &AHashMap<String, BookTick>,
+    symbols: &'a [String],
+) -> (Vec<(&'a str, BookTick, f64)>, f64) {
+    let mut spreads: Vec<_> = symbols
+        .iter()
+        .filter_map(|s| {
+            let tick = *book_tickers.get(s)?;
+            Some((s.as_str(), tick, tick.spread_pct()))
+        })
+        .collect();
+    spreads.sort_by(|(_, _, a), (_, _, b)| b.total_cmp(a));
+
+    let average = if spreads.is_empty() {
+        0.0
+    } else {
+        spreads.iter().map(|(_, _, spread)| spread).sum::<f64>() / spreads.len() as f64
+    };
+
+    (spreads, average)
+}
+
+pub(crate) struct SpreadMonitorPane {}
+
+impl SpreadMonitorPane {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+
+    /// Spreads of favorites, ones wider than average are highlighted
+    pub(crate) fn view<'a>(
+        &'a self,
+        data: &'a AppData,
+        symbols: &'a [String],
+    ) -> Element<'a, SpreadMonitorMessage> {
+        let (spreads, average) = spreads(&data.book_tickers, symbols);
+        if spreads.is_empty() {
+            return loader!().into();
+        }
+
+        column![
+            row![
+                tb("Symbol").width(Length::Fill),
+                tb("Bid").width(Length::Fill),
+                tb("Ask").width(Length::Fill),
+                tb("Spread").width(Length::Fill),
+            ],
+            scrollable(Column::with_children(
+                spreads
+                    .into_iter()
+                    .map(|(symbol, tick, spread)| {
+                        let filter = data.symbol_filter(symbol);
+                        let spread_color = if spread > average {
+                            color(Role::Warning)
+                        } else {
+                            color(Role::Secondary)
+                        };
+
+                        container(row![
+                            button(t(symbol).style(spread_color))
+                                .padding(0)
+                                .style(iced::theme::Button::Custom(Box::new(UnstyledBtn {})))
+                                .on_press(SpreadMonitorMessage::PairSelected(symbol.to_owned()))
+                                .width(Length::Fill),
+                            t(filter.price(tick.bid))
+                                .style(color(Role::Accent))
+                                .width(Length::Fill),
+                            t(filter.price(tick.ask))
+                                .style(color(Role::Danger))
+                                .width(Length::Fill),
+                            t(format!("{spread:.3}%"))
+                                .style(spread_color)
+                                .width(Length::Fill),
+                        ])
+                        .width(Length::Fill)
+                    })
+                    .map(Element::from),
+            ))
+        ]
+        .padding([2, 12])
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widest_spread_first() {
+        let tickers: AHashMap<_, _> = [
+            ("BTCUSDT", 100.0, 100.1),
+            ("ETHUSDT", 10.0, 10.1),
+            ("BNBUSDT", 1.0, 1.0),
+        ]
+        .map(|(symbol, bid, ask)| (symbol.to_owned(), BookTick { bid, ask }))
+        .into();
+        let symbols = ["BTCUSDT", "ETHUSDT", "BNBUSDT", "XRPUSDT"].map(ToOwned::to_owned);
+
+        let (spreads, average) = spreads(&tickers, &symbols);
+        let order: Vec<_> = spreads.iter().map(|(symbol, _, _)| *symbol).collect();
+        assert_eq!(order, ["ETHUSDT", "BTCUSDT", "BNBUSDT"]);
+
+        // only the widest one is above average
+        let above: Vec<_> = spreads.iter().filter(|(_, _, s)| *s > average).collect();
+        assert_eq!(above.len(), 1);
+    }
+}
@@ -0,0 +1,236 @@
+use super::orders::{t, tb};
+use crate::{
+    data::{AppData, SymbolFilter},
+    theme::{color, Role},
+    views::components::{better_btn::BetterBtn, input::Inp},
+};
+
+use iced::{
+    widget::{button, column, row, text, text_input, Column},
+    Alignment, Element, Length,
+};
+
+/// Multiples of Kelly fraction offered as position sizes, full Kelly is rarely used
+const KELLY_MULTIPLES: [f64; 3] = [0.25, 0.5, 1.0];
+
+#[derive(Debug, Clone)]
+pub(crate) enum TradeSizerMessage {
+    WinRateInput(String),
+    RatioInput(String),
+    AccountInput(String),
+    FixedPctInput(String),
+    /// Fill win rate and ratio from realized profit history
+    FromHistory,
+    /// Size in base asset is entered as market order amount
+    UseSize(f64),
+}
+
+/// Share of account to bet, zero if there is no edge
+///
+/// `win_rate` is between 0 and 1, `ratio` is average win divided by average loss
+fn kelly_fraction(win_rate: f64, ratio: f64) -> f64 {
+    if ratio <= 0.0 {
+        return 0.0;
+    }
+
+    (win_rate - (1.0 - win_rate) / ratio).clamp(0.0, 1.0)
+}
+
+/// Win rate and average win to loss ratio of trades in cumulative profit history
+fn history_stats(cumulative_pnl: impl Iterator<Item = f64>) -> Option<(f64, f64)> {
+    let mut previous = 0.0;
+    let (mut wins, mut losses) = (Vec::new(), Vec::new());
+    for total in cumulative_pnl {
+        let pnl = total - previous;
+        previous = total;
+        if pnl > 0.0 {
+            wins.push(pnl);
+        } else if pnl < 0.0 {
+            losses.push(-pnl);
+        }
+    }
+
+    if wins.is_empty() || losses.is_empty() {
+        return None;
+    }
+
+    let avg = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+    let win_rate = wins.len() as f64 / (wins.len() + losses.len()) as f64;
+
+    Some((win_rate, avg(&wins) / avg(&losses)))
+}
+
+pub(crate) struct TradeSizerPane {
+    /// Percent of winning trades
+    win_rate: String,
+    ratio: String,
+    /// Overrides free USDT balance if set
+    account: String,
+    /// Percent of account for fixed-fractional sizing
+    fixed_pct: String,
+    history_error: Option<&'static str>,
+}
+
+impl TradeSizerPane {
+    pub(crate) fn new() -> Self {
+        Self {
+            win_rate: String::new(),
+            ratio: String::new(),
+            account: String::new(),
+            fixed_pct: String::new(),
+            history_error: None,
+        }
+    }
+
+    pub(crate) fn update(&mut self, msg: TradeSizerMessage, data: &AppData) {
+        match msg {
+            TradeSizerMessage::WinRateInput(s) => self.win_rate = s,
+            TradeSizerMessage::RatioInput(s) => self.ratio = s,
+            TradeSizerMessage::AccountInput(s) => self.account = s,
+            TradeSizerMessage::FixedPctInput(s) => self.fixed_pct = s,
+            TradeSizerMessage::FromHistory => {
+                match history_stats(data.realized_pnl_history.iter().map(|(_, pnl)| *pnl)) {
+                    Some((win_rate, ratio)) => {
+                        self.win_rate = format!("{:.1}", win_rate * 100.0);
+                        self.ratio = format!("{ratio:.2}");
+                        self.history_error = None;
+                    }
+                    None => self.history_error = Some("need both winning and losing trades"),
+                }
+            }
+            // handled by dashboard
+            TradeSizerMessage::UseSize(_) => (),
+        }
+    }
+
+    pub(crate) fn view<'a>(
+        &'a self,
+        data: &'a AppData,
+        filter: SymbolFilter,
+        price: Option<f64>,
+    ) -> Element<'a, TradeSizerMessage> {
+        let input = |placeholder, value, on_input: fn(String) -> TradeSizerMessage| {
+            text_input(placeholder, value)
+                .on_input(on_input)
+                .style(iced::theme::TextInput::Custom(Box::new(Inp {})))
+                .size(14)
+                .width(Length::Fill)
+        };
+
+        // follows balance unless account size is entered
+        let balance = data
+            .balances
+            .iter()
+            .find(|b| b.asset == "USDT")
+            .map(|b| b.free);
+        let account = self.account.parse::<f64>().ok().or(balance);
+
+        let size_row = |label: String, fraction: f64| {
+            let notional = account.map(|account| account * fraction);
+            let units = notional
+                .zip(price)
+                .map(|(notional, price)| notional / price);
+
+            let mut use_button = button(text("Use").size(12))
+                .padding([2, 8])
+                .style(iced::theme::Button::Custom(Box::new(BetterBtn {})));
+            if let Some(units) = units.filter(|units| *units > 0.0) {
+                use_button = use_button.on_press(TradeSizerMessage::UseSize(units));
+            }
+
+            row![
+                tb(label).width(Length::Fill),
+                t(notional.map_or_else(|| "-".to_owned(), |n| format!("{n:.2} USDT")))
+                    .style(color(Role::Secondary))
+                    .width(Length::Fill),
+                t(units.map_or_else(|| "-".to_owned(), |u| filter.qty(u)))
+                    .style(color(Role::Secondary))
+                    .width(Length::Fill),
+                use_button,
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center)
+            .into()
+        };
+
+        let kelly = match (self.win_rate.parse::<f64>(), self.ratio.parse::<f64>()) {
+            (Ok(win_rate), Ok(ratio)) if (0.0..=100.0).contains(&win_rate) => {
+                Ok(kelly_fraction(win_rate / 100.0, ratio))
+            }
+            _ => Err("enter win rate between 0 and 100% and win/loss ratio"),
+        };
+
+        let mut sizes: Vec<Element<_>> = match kelly {
+            Ok(0.0) => vec![t("no edge, Kelly suggests not trading")
+                .style(color(Role::Warning))
+                .into()],
+            Ok(kelly) => KELLY_MULTIPLES
+                .iter()
+                .map(|multiple| size_row(format!("{multiple}x Kelly"), kelly * multiple))
+                .collect(),
+            Err(err) => vec![t(err).style(color(Role::Secondary)).into()],
+        };
+        if let Ok(pct) = self.fixed_pct.parse::<f64>() {
+            if (0.0..=100.0).contains(&pct) {
+                sizes.push(size_row(format!("Fixed {pct}%"), pct / 100.0));
+            }
+        }
+
+        column![
+            row![
+                input(
+                    "win rate %",
+                    &self.win_rate,
+                    TradeSizerMessage::WinRateInput
+                ),
+                input("win/loss ratio", &self.ratio, TradeSizerMessage::RatioInput),
+                button(text("From history").size(12))
+                    .padding([4, 8])
+                    .style(iced::theme::Button::Text)
+                    .on_press(TradeSizerMessage::FromHistory),
+            ]
+            .spacing(6)
+            .align_items(Alignment::Center),
+            row![
+                text_input(
+                    &balance.map_or_else(
+                        || "account USDT".to_owned(),
+                        |balance| format!("{balance:.2} USDT")
+                    ),
+                    &self.account
+                )
+                .on_input(TradeSizerMessage::AccountInput)
+                .style(iced::theme::TextInput::Custom(Box::new(Inp {})))
+                .size(14)
+                .width(Length::Fill),
+                input("fixed %", &self.fixed_pct, TradeSizerMessage::FixedPctInput),
+            ]
+            .spacing(6),
+        ]
+        .push_maybe(
+            self.history_error
+                .map(|err| t(err).style(color(Role::Danger))),
+        )
+        .push(Column::with_children(sizes).spacing(6))
+        .padding([4, 12])
+        .spacing(8)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kelly_and_history() {
+        // 60% wins paying as much as losses cost
+        assert!((kelly_fraction(0.6, 1.0) - 0.2).abs() < 1e-9);
+        assert_eq!(kelly_fraction(0.3, 1.0), 0.0);
+
+        let (win_rate, ratio) = history_stats([10.0, 5.0, 25.0, 20.0].into_iter()).unwrap();
+        assert!((win_rate - 0.5).abs() < 1e-9);
+        assert!((ratio - 3.0).abs() < 1e-9);
+        assert_eq!(history_stats([10.0, 20.0].into_iter()), None);
+    }
+}
@@ -1,28 +1,217 @@
+use std::collections::VecDeque;
+
 use super::orders::{t, tb};
 use crate::{
-    data::AppData,
-    theme::h2c,
-    views::{components::loading::loader, dashboard::DashboardMessage},
+    data::{AppData, SymbolFilter},
+    theme::{color, Role},
+    views::components::{better_btn::BetterBtn, loading::loader},
+    ws::trades::TradesEvent,
 };
 
 use iced::{
-    widget::{column, container, row, scrollable, Column},
-    Color, Element, Length,
+    mouse,
+    widget::{
+        button,
+        canvas::{Canvas, Frame, Geometry, Path, Program, Stroke},
+        column, container, row, scrollable, text, Column, Row, Space,
+    },
+    Alignment, Element, Length, Point, Rectangle, Renderer, Theme,
 };
 use ringbuf::{ring_buffer::RbBase, Rb};
 
-pub(crate) struct TradesPane {}
+/// Number of trades delta can be summed over
+const DELTA_WINDOWS: [usize; 3] = [100, 500, 1000];
+
+const DELTA_CHART_HEIGHT: f32 = 60.0;
+
+#[derive(Debug, Clone)]
+pub(crate) enum TradesPaneMessage {
+    /// Handled by dashboard, VWAP lives in app data
+    VwapReset,
+    /// Start cumulative delta from zero
+    DeltaReset,
+    /// Sum delta over this many last trades
+    DeltaWindowSelected(usize),
+}
+
+/// Bought minus sold amount over last `window` trades
+#[derive(Debug)]
+struct CumulativeDelta {
+    window: usize,
+    /// Signed amounts of trades in window, buys are positive
+    trades: VecDeque<f64>,
+    value: f64,
+    /// Delta after each trade, oldest first
+    history: VecDeque<f64>,
+}
+
+impl CumulativeDelta {
+    fn new(window: usize) -> Self {
+        Self {
+            window,
+            trades: VecDeque::with_capacity(window),
+            value: 0.0,
+            history: VecDeque::with_capacity(window),
+        }
+    }
+
+    fn push(&mut self, signed_qty: f64) {
+        if self.trades.len() == self.window {
+            self.value -= self.trades.pop_front().unwrap_or_default();
+        }
+        self.trades.push_back(signed_qty);
+        self.value += signed_qty;
+
+        if self.history.len() == self.window {
+            self.history.pop_front();
+        }
+        self.history.push_back(self.value);
+    }
+}
+
+/// Delta history drawn as steps around zero line
+struct DeltaChart<'a> {
+    history: &'a VecDeque<f64>,
+}
+
+impl<Message> Program<Message> for DeltaChart<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        if self.history.len() < 2 {
+            return vec![];
+        }
+
+        // zero stays in the middle so sign is visible at a glance
+        let extent = self
+            .history
+            .iter()
+            .fold(f64::EPSILON, |extent, v| extent.max(v.abs()));
+        let y = |v: f64| ((1.0 - v / extent) / 2.0) as f32 * (bounds.height - 2.0) + 1.0;
+        let step = bounds.width / (self.history.len() - 1) as f32;
+
+        let zero = Path::line(Point::new(0.0, y(0.0)), Point::new(bounds.width, y(0.0)));
+        frame.stroke(
+            &zero,
+            Stroke::default()
+                .with_color(color(Role::Muted))
+                .with_width(1.0),
+        );
+
+        let steps = Path::new(|p| {
+            p.move_to(Point::new(0.0, y(self.history[0])));
+            for (i, v) in self.history.iter().enumerate().skip(1) {
+                let x = i as f32 * step;
+                p.line_to(Point::new(x, y(self.history[i - 1])));
+                p.line_to(Point::new(x, y(*v)));
+            }
+        });
+        let line_color = if self.history.back().is_some_and(|v| *v >= 0.0) {
+            color(Role::Accent)
+        } else {
+            color(Role::Danger)
+        };
+        frame.stroke(
+            &steps,
+            Stroke::default().with_color(line_color).with_width(1.0),
+        );
+
+        vec![frame.into_geometry()]
+    }
+}
+
+pub(crate) struct TradesPane {
+    /// Symbol of trades summed into delta
+    symbol: String,
+    delta: CumulativeDelta,
+}
 
 impl TradesPane {
     pub(crate) fn new() -> Self {
-        Self {}
+        Self {
+            symbol: String::new(),
+            delta: CumulativeDelta::new(DELTA_WINDOWS[0]),
+        }
+    }
+
+    /// Adds trade of market pair to cumulative delta, starting over when pair changes
+    pub(crate) fn trade_received(&mut self, trade: &TradesEvent) {
+        if trade.symbol != self.symbol {
+            self.symbol.clone_from(&trade.symbol);
+            self.delta = CumulativeDelta::new(self.delta.window);
+        }
+
+        // buyer being maker means aggressive side sold
+        self.delta.push(if trade.is_buyer_maker {
+            -trade.qty
+        } else {
+            trade.qty
+        });
+    }
+
+    pub(crate) fn update(&mut self, msg: TradesPaneMessage) {
+        match msg {
+            // handled by dashboard
+            TradesPaneMessage::VwapReset => (),
+            TradesPaneMessage::DeltaReset => self.delta = CumulativeDelta::new(self.delta.window),
+            TradesPaneMessage::DeltaWindowSelected(window) => {
+                self.delta = CumulativeDelta::new(window);
+            }
+        }
     }
-    pub fn view<'a>(&'a self, data: &'a AppData) -> Element<'a, DashboardMessage> {
+
+    pub(crate) fn view<'a>(
+        &'a self,
+        data: &'a AppData,
+        filter: SymbolFilter,
+    ) -> Element<'a, TradesPaneMessage> {
         if data.trades.is_empty() {
             return loader!().into();
         }
 
+        let vwap = data
+            .trades
+            .iter()
+            .last()
+            .and_then(|trade| data.vwap.get(&trade.symbol))
+            .and_then(|vwap| vwap.value());
+
+        let window_buttons = Row::with_children(
+            DELTA_WINDOWS
+                .map(|window| {
+                    button(text(window.to_string()).size(12))
+                        .padding([2, 6])
+                        .style(if window == self.delta.window {
+                            iced::theme::Button::Custom(Box::new(BetterBtn {}))
+                        } else {
+                            iced::theme::Button::Text
+                        })
+                        .on_press(TradesPaneMessage::DeltaWindowSelected(window))
+                })
+                .map(Element::from),
+        )
+        .spacing(2);
+
         column![
+            row![
+                tb("VWAP").width(Length::Fill),
+                t(vwap.map_or_else(|| "-".to_owned(), |vwap| filter.price(vwap)))
+                    .style(color(Role::Warning))
+                    .width(Length::Fill),
+                button(text("Reset").size(12))
+                    .padding([2, 8])
+                    .style(iced::theme::Button::Text)
+                    .on_press(TradesPaneMessage::VwapReset),
+            ]
+            .align_items(Alignment::Center),
             row![
                 tb("Price").width(Length::Fill),
                 tb("Amount").width(Length::Fill),
@@ -34,24 +223,63 @@ impl TradesPane {
                     .rev()
                     .map(|b| {
                         let c = if b.is_buyer_maker {
-                            Color::from_rgb(1.0, 0.0, 0.0)
+                            color(Role::Danger)
                         } else {
-                            Color::from_rgb(0.0, 1.0, 0.0)
+                            color(Role::Accent)
                         };
 
                         container(row![
-                            t(&b.price).style(c).width(Length::Fill),
-                            t(&b.qty).width(Length::Fill).style(h2c("B7BDB7").unwrap()),
+                            t(filter.price(b.price)).style(c).width(Length::Fill),
+                            t(filter.qty(b.qty))
+                                .width(Length::Fill)
+                                .style(color(Role::Secondary)),
                             t(&b.trade_order_time_formatted)
-                                .style(h2c("B7BDB7").unwrap())
+                                .style(color(Role::Secondary))
                                 .width(Length::Fill),
                         ])
                         .width(Length::Fill)
                     })
                     .map(Element::from),
             )) // .style(ScrollbarStyle::theme())
+            .height(Length::Fill),
+            row![
+                tb("Delta"),
+                t(filter.qty(self.delta.value)).style(color(Role::Secondary))
+            ]
+            .push(Space::new(Length::Fill, 0))
+            .push(window_buttons)
+            .push(
+                button(text("Reset").size(12))
+                    .padding([2, 8])
+                    .style(iced::theme::Button::Text)
+                    .on_press(TradesPaneMessage::DeltaReset),
+            )
+            .spacing(8)
+            .align_items(Alignment::Center),
+            Canvas::new(DeltaChart {
+                history: &self.delta.history
+            })
+            .width(Length::Fill)
+            .height(DELTA_CHART_HEIGHT),
         ]
         .padding([2, 12])
         .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delta_over_window() {
+        let mut delta = CumulativeDelta::new(3);
+        for qty in [1.0, 2.0, -4.0, 5.0] {
+            delta.push(qty);
+        }
+
+        // first trade left the window
+        assert_eq!(delta.value, 3.0);
+        assert_eq!(delta.history, [3.0, -1.0, 3.0]);
+    }
+}
@@ -1,23 +1,93 @@
 use crate::config::Config;
-use crate::data::{AppData, PriceFilter};
-use crate::theme::h2c;
+use crate::data::{AppData, PriceFilter, PriceSort};
+use crate::theme::{color, Role};
 use crate::views::components::loading::loader;
-use crate::views::components::{better_btn::BetterBtn, input::Inp, unstyled_btn::UnstyledBtn};
-use crate::views::dashboard::DashboardMessage;
+use crate::views::components::{
+    better_btn::BetterBtn,
+    copyable::copyable,
+    input::{Inp, InvalidInp},
+    unstyled_btn::UnstyledBtn,
+};
 use iced::Command;
 use iced::{
-    widget::{button, column, container, row, scrollable, text, text_input, Column, Space},
-    Element, Font, Length,
+    widget::{
+        button, column, container, mouse_area, row, scrollable, text, text_input, Column, Row,
+        Space,
+    },
+    Color, Element, Font, Length,
 };
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::{orders::tb, KeyEvent};
+
+/// Regex is compiled after input stops changing for this long
+const REGEX_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Rows added to watchlist at once
+const PAGE_LEN: usize = 100;
+
+/// Time between rendering pages, lets UI handle input in between
+pub(crate) const PAGE_INTERVAL: Duration = Duration::from_millis(16);
+
+fn list_id() -> scrollable::Id {
+    scrollable::Id::new("watchlist")
+}
 
-use super::orders::tb;
+/// Index after moving `by` rows from `selected` in list of `len`, first move selects first row
+fn moved_selection(selected: Option<usize>, by: isize, len: usize) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+
+    Some(match selected {
+        Some(selected) => selected.saturating_add_signed(by).min(len - 1),
+        None => 0,
+    })
+}
+
+/// Shows one more page of rows, false once all symbols are shown
+pub(crate) fn load_next_page(data: &mut AppData) -> bool {
+    if data.prices_loaded_page * PAGE_LEN >= data.prices.len() {
+        return false;
+    }
+
+    data.prices_loaded_page += 1;
+    true
+}
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Rows rendered and number of all symbols
+pub(crate) fn shown_count(data: &AppData) -> (usize, usize) {
+    (
+        data.prices
+            .filtered_len()
+            .min(data.prices_loaded_page * PAGE_LEN),
+        data.prices.len(),
+    )
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) enum WatchlistFilter {
     Favorites,
     Eth,
     Btc,
     Alts,
+
+    /// Biggest 24 hour price increase first
+    Gainers,
+
+    /// Biggest 24 hour price decrease first
+    Losers,
+
+    /// Biggest 24 hour base volume first
+    Volume,
+
+    /// Symbols of assets with balance, most valuable first
+    Portfolio,
+
+    /// Symbols matching typed pattern
+    Regex(String),
 }
 
 macro_rules! filter_button {
@@ -29,26 +99,70 @@ macro_rules! filter_button {
             } else {
                 iced::theme::Button::Text
             })
-            .on_press(DashboardMessage::Watchlist(WatchlistMessage::ApplyFilter(
+            .on_press(WatchlistMessage::ApplyFilter(
                 $filter,
                 $filter == $current_filter,
-            )))
+            ))
     };
 }
 
-fn asset_button<'a>(n: &str, p: f32) -> Element<'a, DashboardMessage> {
+fn asset_button<'a>(
+    n: &str,
+    price: Element<'a, WatchlistMessage>,
+    change: Option<f64>,
+    volume: Option<f64>,
+    extreme_funding: bool,
+    is_favorite: bool,
+    handle: Element<'a, WatchlistMessage>,
+) -> Element<'a, WatchlistMessage> {
+    let volume = volume.map(|v| {
+        let (v, suffix) = match v {
+            v if v >= 1e9 => (v / 1e9, "B"),
+            v if v >= 1e6 => (v / 1e6, "M"),
+            v if v >= 1e3 => (v / 1e3, "K"),
+            v => (v, ""),
+        };
+        text(format!("{v:.2}{suffix}"))
+            .size(14)
+            .style(color(Role::Muted))
+    });
+
+    let change = change.map(|c| {
+        text(format!("{c:+.2}%")).size(14).style(if c >= 0.0 {
+            color(Role::Accent)
+        } else {
+            color(Role::Danger)
+        })
+    });
+
+    let star = button(
+        text(if is_favorite { "\u{F586}" } else { "\u{F588}" })
+            .font(Font::with_name("bootstrap-icons"))
+            .size(12)
+            .style(if is_favorite {
+                color(Role::Warning)
+            } else {
+                color(Role::Muted)
+            }),
+    )
+    .padding([0, 4])
+    .on_press(WatchlistMessage::ToggleFavorite(n.to_string()))
+    .style(iced::theme::Button::Custom(Box::new(UnstyledBtn {})));
+
     container(row![
-        button(tb(n).size(14).style(h2c("EFE1D1").unwrap()))
-            .on_press(DashboardMessage::CurrencyPairSelected(n.to_string()))
-            .style(iced::theme::Button::Custom(Box::new(UnstyledBtn {}))),
-        Space::new(Length::Fill, 1.0),
-        button(
-            text(format!("{p} "))
-                .size(14)
-                .style(h2c("B7BDB76").unwrap())
-        )
-        .on_press(DashboardMessage::CurrencyPairSelected(n.to_string()))
+        handle,
+        star,
+        button(tb(n).size(14).style(if extreme_funding {
+            color(Role::Warning)
+        } else {
+            color(Role::Foreground)
+        }))
+        .on_press(WatchlistMessage::PairSelected(n.to_string()))
         .style(iced::theme::Button::Custom(Box::new(UnstyledBtn {}))),
+        Space::new(Length::Fill, 1.0),
+        Row::with_children(change.map(Element::from)),
+        Row::with_children(volume.map(Element::from)),
+        price,
     ])
     .width(Length::Fill)
     .into()
@@ -58,11 +172,57 @@ fn asset_button<'a>(n: &str, p: f32) -> Element<'a, DashboardMessage> {
 pub(crate) enum WatchlistMessage {
     FilterInput(String),
     ApplyFilter(WatchlistFilter, bool),
+
+    /// Switch text filter between substring and regex
+    FilterToggleMode,
+
+    /// Input stayed unchanged for debounce duration, holds input generation
+    CompileRegex(u64),
+
+    /// Pair clicked, sets market pair
+    PairSelected(String),
+
+    /// Arrow key moved keyboard selection by rows
+    MoveSelection(isize),
+
+    /// Enter pressed, sets selected row as market pair
+    ConfirmSelection,
+
+    /// Price right clicked
+    Copy(String),
+
+    /// Star clicked, adds or removes favorite
+    ToggleFavorite(String),
+
+    /// Symbol grabbed by its handle, can be dropped on favorite or other pane
+    DragStarted(String),
+
+    /// Grabbed symbol released over favorite
+    DragDropped(String),
+
+    /// Grabbed symbol released over watchlist but not over favorite
+    DragCancelled,
+
+    /// Move favorite, indices are positions in config
+    Reorder {
+        from: usize,
+        to: usize,
+    },
 }
 
 pub(crate) struct WatchlistPane {
     filter: WatchlistFilter,
     filter_string: String,
+    /// Text filter is regex instead of substring
+    regex_mode: bool,
+    /// Last typed regex failed to compile
+    regex_invalid: bool,
+    /// Incremented on every input, stale debounce timers are ignored
+    input_generation: u64,
+    /// Symbol being dragged
+    dragging: Option<String>,
+    /// Row chosen with arrow keys, position among shown rows
+    selected_index: Option<usize>,
 }
 
 impl WatchlistPane {
@@ -70,14 +230,87 @@ impl WatchlistPane {
         Self {
             filter: WatchlistFilter::Favorites,
             filter_string: String::new(),
+            regex_mode: false,
+            regex_invalid: false,
+            input_generation: 0,
+            dragging: None,
+            selected_index: None,
         }
     }
 
-    pub(crate) fn view<'a>(&'a self, data: &'a AppData) -> Element<'a, DashboardMessage> {
+    /// Favorites can be reordered only when they are all shown in config order
+    fn is_reorderable(&self, data: &AppData) -> bool {
+        self.filter == WatchlistFilter::Favorites
+            && self.filter_string.is_empty()
+            && data.prices.sort() == PriceSort::Filter
+    }
+
+    /// Follows balance changes while portfolio is shown
+    pub(crate) fn tick(&self, data: &mut AppData) {
+        if self.filter == WatchlistFilter::Portfolio && self.filter_string.is_empty() {
+            data.prices
+                .set_filter(PriceFilter::Matches(data.owned_symbols()));
+        }
+    }
+
+    /// Arrows and Enter move through rows
+    pub(crate) fn key_message(event: &KeyEvent) -> Option<WatchlistMessage> {
+        use iced::keyboard::{key::Named, Key};
+
+        if !event.modifiers.is_empty() {
+            return None;
+        }
+
+        match event.key {
+            Key::Named(Named::ArrowUp) => Some(WatchlistMessage::MoveSelection(-1)),
+            Key::Named(Named::ArrowDown) => Some(WatchlistMessage::MoveSelection(1)),
+            Key::Named(Named::Enter) => Some(WatchlistMessage::ConfirmSelection),
+            _ => None,
+        }
+    }
+
+    /// Symbol was dropped outside of watchlist
+    pub(crate) fn cancel_drag(&mut self) {
+        self.dragging = None;
+    }
+
+    fn drag_handle<'a>(&self, name: &str) -> Element<'a, WatchlistMessage> {
+        let handle_color = if self.dragging.as_deref() == Some(name) {
+            color(Role::Foreground)
+        } else {
+            color(Role::Muted)
+        };
+
+        mouse_area(
+            text("\u{F3FE}")
+                .font(Font::with_name("bootstrap-icons"))
+                .size(14)
+                .style(handle_color),
+        )
+        .on_press(WatchlistMessage::DragStarted(name.to_owned()))
+        .into()
+    }
+
+    pub(crate) fn view<'a>(
+        &'a self,
+        data: &'a AppData,
+        favorites: &'a [String],
+        copied: Option<&str>,
+    ) -> Element<'a, WatchlistMessage> {
         if data.prices.is_empty() {
             return loader!().into();
         };
 
+        let reorderable = self.is_reorderable(data);
+        let (shown, _) = shown_count(data);
+        // filter is applied to all symbols, rows past loaded pages are left out
+        let pending = (shown < data.prices.filtered_len()).then(|| {
+            container(text("Loading…").size(14).style(color(Role::Muted)))
+                .padding([4, 8])
+                .width(Length::Fill)
+                .style(iced::theme::Container::Box)
+        });
+
         column![
             row![
                 filter_button!(
@@ -88,20 +321,96 @@ impl WatchlistPane {
                 filter_button!("BTC", WatchlistFilter::Btc, self.filter),
                 filter_button!("ETH", WatchlistFilter::Eth, self.filter),
                 filter_button!("ALTS", WatchlistFilter::Alts, self.filter),
-                text_input("type to filter", &self.filter_string)
-                    .on_input(|i| WatchlistMessage::FilterInput(i).into())
-                    .style(iced::theme::TextInput::Custom(Box::new(Inp {})))
+                filter_button!("GAIN", WatchlistFilter::Gainers, self.filter),
+                filter_button!("LOSS", WatchlistFilter::Losers, self.filter),
+                filter_button!("VOL", WatchlistFilter::Volume, self.filter),
+                filter_button!("OWN", WatchlistFilter::Portfolio, self.filter),
+                text_input(
+                    if self.regex_mode {
+                        "regex to filter"
+                    } else {
+                        "type to filter"
+                    },
+                    &self.filter_string
+                )
+                .on_input(WatchlistMessage::FilterInput)
+                .style(if self.regex_invalid {
+                    iced::theme::TextInput::Custom(Box::new(InvalidInp {}))
+                } else {
+                    iced::theme::TextInput::Custom(Box::new(Inp {}))
+                }),
+                button(text(".*").size(12))
+                    .padding(8)
+                    .style(if self.regex_mode {
+                        iced::theme::Button::Custom(Box::new(BetterBtn {}))
+                    } else {
+                        iced::theme::Button::Text
+                    })
+                    .on_press(WatchlistMessage::FilterToggleMode),
             ]
             .spacing(2.0),
-            scrollable(
-                Column::with_children(
-                    data.prices
-                        .sorted_and_filtered()
-                        .map(|(n, p)| asset_button(n, *p))
-                        .map(Element::from)
+            mouse_area(
+                scrollable(
+                    Column::with_children(
+                        data.prices
+                            .sorted_and_filtered()
+                            .take(shown)
+                            .enumerate()
+                            .map(|(i, (n, p))| {
+                                let price = copyable(
+                                    button(
+                                        text(format!("{p} "))
+                                            .size(14)
+                                            .style(color(Role::Secondary)),
+                                    )
+                                    .on_press(WatchlistMessage::PairSelected(n.to_string()))
+                                    .style(iced::theme::Button::Custom(Box::new(UnstyledBtn {}))),
+                                    p.to_string(),
+                                    copied,
+                                    WatchlistMessage::Copy,
+                                );
+                                let row = asset_button(
+                                    n,
+                                    price,
+                                    data.prices
+                                        .change(n)
+                                        .filter(|_| data.prices.sort() == PriceSort::Change),
+                                    data.prices
+                                        .volume(n)
+                                        .filter(|_| data.prices.sort() == PriceSort::Volume),
+                                    data.extreme_funding_rate(n).is_some(),
+                                    favorites.contains(n),
+                                    self.drag_handle(n),
+                                );
+                                let row = if self.selected_index == Some(i) {
+                                    container(row)
+                                        .style(container::Appearance {
+                                            background: Some(iced::Background::Color(Color {
+                                                a: 0.25,
+                                                ..color(Role::Muted)
+                                            })),
+                                            ..Default::default()
+                                        })
+                                        .into()
+                                } else {
+                                    row
+                                };
+
+                                if reorderable && self.dragging.is_some() {
+                                    mouse_area(row)
+                                        .on_release(WatchlistMessage::DragDropped(n.clone()))
+                                        .into()
+                                } else {
+                                    row
+                                }
+                            })
+                    )
+                    .push_maybe(pending)
+                    .padding(8)
                 )
-                .padding(8)
+                .id(list_id())
             )
+            .on_release(WatchlistMessage::DragCancelled)
         ]
         .align_items(iced::Alignment::Start)
         .into()
@@ -121,25 +430,187 @@ impl WatchlistPane {
                     data.prices.reset_sort();
                     let filter = match f {
                         WatchlistFilter::Favorites => {
+                            data.prices.set_sort(PriceSort::Filter, true);
                             PriceFilter::Matches(config.watchlist_favorites.clone())
                         }
                         WatchlistFilter::Eth => PriceFilter::Contains("ETH".to_owned()),
                         WatchlistFilter::Btc => PriceFilter::Contains("BTC".to_owned()),
                         WatchlistFilter::Alts => PriceFilter::All,
+                        WatchlistFilter::Gainers => {
+                            data.prices.set_sort(PriceSort::Change, true);
+                            PriceFilter::Contains("USDT".to_owned())
+                        }
+                        WatchlistFilter::Losers => {
+                            data.prices.set_sort(PriceSort::Change, false);
+                            PriceFilter::Contains("USDT".to_owned())
+                        }
+                        WatchlistFilter::Volume => {
+                            data.prices.set_sort(PriceSort::Volume, true);
+                            PriceFilter::Contains("USDT".to_owned())
+                        }
+                        WatchlistFilter::Portfolio => {
+                            data.prices.set_sort(PriceSort::Filter, true);
+                            PriceFilter::Matches(data.owned_symbols())
+                        }
+                        // only set by typing pattern
+                        WatchlistFilter::Regex(_) => return Command::none(),
                     };
                     data.prices.set_filter(filter);
                     self.filter = f;
                 }
+                self.selected_index = None;
 
                 Command::none()
             }
+            WatchlistMessage::DragStarted(name) => {
+                self.dragging = Some(name);
+                Command::none()
+            }
+            WatchlistMessage::DragDropped(target) => {
+                let favorites = &config.watchlist_favorites;
+                let position = |name: &str| favorites.iter().position(|f| f == name);
+
+                match self
+                    .dragging
+                    .take()
+                    .and_then(|from| Some((position(&from)?, position(&target)?)))
+                {
+                    // config is owned by app which handles this too
+                    Some((from, to)) if from != to => {
+                        Command::perform(async {}, move |_| WatchlistMessage::Reorder { from, to })
+                    }
+                    _ => Command::none(),
+                }
+            }
+            WatchlistMessage::DragCancelled => {
+                self.dragging = None;
+                Command::none()
+            }
+            WatchlistMessage::Reorder { .. } | WatchlistMessage::ToggleFavorite(_) => {
+                if self.filter == WatchlistFilter::Favorites {
+                    data.prices
+                        .set_filter(PriceFilter::Matches(config.watchlist_favorites.clone()));
+                }
+                Command::none()
+            }
+            // handled by dashboard
+            WatchlistMessage::PairSelected(_) | WatchlistMessage::Copy(_) => Command::none(),
+            WatchlistMessage::MoveSelection(by) => {
+                let (shown, _) = shown_count(data);
+                self.selected_index = moved_selection(self.selected_index, by, shown);
+
+                match self.selected_index {
+                    // rows are about the same height, so position in list is close enough
+                    Some(selected) if shown > 1 => scrollable::snap_to(
+                        list_id(),
+                        scrollable::RelativeOffset {
+                            x: 0.0,
+                            y: selected as f32 / (shown - 1) as f32,
+                        },
+                    ),
+                    _ => Command::none(),
+                }
+            }
+            WatchlistMessage::ConfirmSelection => {
+                let selected = self
+                    .selected_index
+                    .and_then(|i| data.prices.sorted_and_filtered().nth(i))
+                    .map(|(symbol, _)| symbol.clone());
+
+                match selected {
+                    Some(symbol) => {
+                        Command::perform(async {}, move |_| WatchlistMessage::PairSelected(symbol))
+                    }
+                    None => Command::none(),
+                }
+            }
+            WatchlistMessage::FilterInput(s) if self.regex_mode => {
+                self.filter_string = s;
+                self.input_generation += 1;
+
+                let generation = self.input_generation;
+                Command::perform(tokio::time::sleep(REGEX_DEBOUNCE), move |_| {
+                    WatchlistMessage::CompileRegex(generation)
+                })
+            }
             WatchlistMessage::FilterInput(s) => {
+                self.selected_index = None;
                 self.filter_string = s.to_uppercase();
                 data.prices
                     .set_filter(PriceFilter::Contains(self.filter_string.clone()));
 
                 Command::none()
             }
+            WatchlistMessage::CompileRegex(generation) => {
+                if !self.regex_mode || generation != self.input_generation {
+                    return Command::none();
+                }
+
+                match RegexBuilder::new(&self.filter_string)
+                    .case_insensitive(true)
+                    .build()
+                {
+                    Ok(regex) => {
+                        self.selected_index = None;
+                        self.regex_invalid = false;
+                        self.filter = WatchlistFilter::Regex(self.filter_string.clone());
+                        data.prices.set_filter(PriceFilter::Regex(regex));
+                    }
+                    // previous pattern stays applied
+                    Err(_) => self.regex_invalid = true,
+                }
+
+                Command::none()
+            }
+            WatchlistMessage::FilterToggleMode => {
+                self.regex_mode = !self.regex_mode;
+                self.regex_invalid = false;
+                if !self.regex_mode && matches!(self.filter, WatchlistFilter::Regex(_)) {
+                    // substring filter is applied to all symbols too
+                    self.filter = WatchlistFilter::Alts;
+                }
+
+                let input = self.filter_string.clone();
+                self.update(WatchlistMessage::FilterInput(input), data, config)
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws::prices::AssetDetails;
+
+    #[test]
+    fn selection_stays_in_list() {
+        assert_eq!(moved_selection(None, 1, 3), Some(0));
+        assert_eq!(moved_selection(Some(0), -1, 3), Some(0));
+        assert_eq!(moved_selection(Some(1), 1, 3), Some(2));
+        assert_eq!(moved_selection(Some(2), 1, 3), Some(2));
+        // list shrank under selection
+        assert_eq!(moved_selection(Some(7), -1, 3), Some(2));
+        assert_eq!(moved_selection(Some(0), 1, 0), None);
+    }
+
+    #[test]
+    fn pages_cover_all_symbols() {
+        let mut data = AppData::default();
+        data.prices.set_filter(PriceFilter::All);
+        data.prices.add_many(
+            (0..250)
+                .map(|i| AssetDetails {
+                    name: format!("A{i}USDT"),
+                    price: 1.0,
+                })
+                .collect(),
+        );
+        data.prices_loaded_page = 1;
+        assert_eq!(shown_count(&data), (100, 250));
+
+        assert!(load_next_page(&mut data));
+        assert!(load_next_page(&mut data));
+        assert!(!load_next_page(&mut data));
+        assert_eq!(shown_count(&data), (250, 250));
+    }
+}
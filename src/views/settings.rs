@@ -1,8 +1,17 @@
-use crate::{config::Config, message::Message};
+use std::fs;
+
+use crate::{
+    config::{AccountChoice, AccountConfig, Config},
+    message::Message,
+    theme::{color, Palette, Role, Theme},
+};
 
 use iced::{
-    widget::{button, column, container, row, text, text_input, Container},
-    Color, Command, Length,
+    widget::{
+        button, checkbox, column, container, pick_list, row, slider, text, text_input, Column,
+        Container,
+    },
+    Command, Element, Length,
 };
 
 use super::panes::orders::tb;
@@ -16,9 +25,26 @@ pub(crate) enum SettingsMessage {
     /// Write config from settings to disk and memory
     SaveConfig,
 
+    /// Mainnet account edited below and used after save
+    AccountSelected(AccountChoice),
+    AccountAdded,
+    AccountRemoved,
+
     /// on_input events
+    AccountNameInput(String),
     ApiKeyInput(String),
     ApiSecretInput(String),
+    DefaultPairInput(String),
+    QuoteAssetInput(String),
+    PaperTradeToggled(bool),
+    DemoModeToggled(bool),
+    TestnetToggled(bool),
+    SoundAlertsToggled(bool),
+    AggTradesToggled(bool),
+    SoundVolumeChanged(f32),
+    ThemeSelected(Theme),
+    /// Read custom palette from file in config directory
+    LoadPalette,
 }
 
 impl SettingsView {
@@ -35,40 +61,212 @@ impl SettingsView {
                     async {
                         new_config.save().map_err(|err| err.to_string())?;
 
-                        Ok(new_config)
+                        Ok(Box::new(new_config))
                     },
                     Message::ConfigUpdated,
                 )
             }
+            SettingsMessage::AccountSelected(choice) => {
+                self.new_config.active_account = choice.index;
+                Command::none()
+            }
+            SettingsMessage::AccountAdded => {
+                let name = format!("Account {}", self.new_config.accounts.len() + 1);
+                self.new_config.accounts.push(AccountConfig::new(name));
+                self.new_config.active_account = self.new_config.accounts.len() - 1;
+                Command::none()
+            }
+            SettingsMessage::AccountRemoved => {
+                if self.new_config.active_account < self.new_config.accounts.len() {
+                    self.new_config
+                        .accounts
+                        .remove(self.new_config.active_account);
+                    self.new_config.active_account =
+                        self.new_config.active_account.saturating_sub(1);
+                }
+                Command::none()
+            }
+            SettingsMessage::AccountNameInput(value) => {
+                self.new_config.account_mut().name = value;
+                Command::none()
+            }
             SettingsMessage::ApiKeyInput(value) => {
-                self.new_config.api_key = value;
+                *self.new_config.credentials_mut().0 = value;
                 Command::none()
             }
             SettingsMessage::ApiSecretInput(value) => {
-                self.new_config.api_secret_key = value;
+                *self.new_config.credentials_mut().1 = value;
+                Command::none()
+            }
+            SettingsMessage::DefaultPairInput(value) => {
+                self.new_config.default_pair = value.to_uppercase();
                 Command::none()
             }
+            SettingsMessage::QuoteAssetInput(value) => {
+                self.new_config.preferred_quote_asset = value.to_uppercase();
+                Command::none()
+            }
+            SettingsMessage::PaperTradeToggled(value) => {
+                self.new_config.paper_trade = value;
+                Command::none()
+            }
+            SettingsMessage::DemoModeToggled(value) => {
+                self.new_config.demo_mode = value;
+                Command::none()
+            }
+            SettingsMessage::TestnetToggled(value) => {
+                self.new_config.testnet = value;
+                Command::none()
+            }
+            SettingsMessage::SoundAlertsToggled(value) => {
+                self.new_config.sound_alerts = value;
+                Command::none()
+            }
+            SettingsMessage::AggTradesToggled(value) => {
+                self.new_config.use_agg_trades = value;
+                Command::none()
+            }
+            SettingsMessage::SoundVolumeChanged(value) => {
+                self.new_config.sound_volume = value;
+                Command::none()
+            }
+            SettingsMessage::ThemeSelected(theme) => {
+                self.new_config.theme = theme;
+                Command::none()
+            }
+            SettingsMessage::LoadPalette => {
+                let path = Config::dir().join(Theme::PALETTE_FILE_NAME);
+
+                match fs::read_to_string(&path)
+                    .map_err(|err| err.to_string())
+                    .and_then(|contents| Palette::from_toml(&contents))
+                {
+                    Ok(palette) => {
+                        self.new_config.custom_palette = Some(palette.clone());
+                        self.new_config.theme = Theme::Custom(palette);
+                        Command::none()
+                    }
+                    Err(err) => {
+                        let message = format!("failed to load {}: {err}", path.display());
+                        Command::perform(async {}, move |_| Message::ToastError(message))
+                    }
+                }
+            }
         }
     }
 
+    fn theme_button(&self, label: &str, message: SettingsMessage) -> button::Button<'_, Message> {
+        let is_selected = match (&self.new_config.theme, &message) {
+            (Theme::Custom(_), SettingsMessage::LoadPalette) => true,
+            (current, SettingsMessage::ThemeSelected(theme)) => current == theme,
+            _ => false,
+        };
+
+        button(text(label).size(14))
+            .padding(8)
+            .style(if is_selected {
+                iced::theme::Button::Primary
+            } else {
+                iced::theme::Button::Secondary
+            })
+            .on_press(message.into())
+    }
+
     pub(crate) fn view(&self) -> Container<'_, Message> {
-        let api_key_input = text_input("API Key", &self.new_config.api_key)
+        // inputs edit TestNet credentials while it is enabled
+        let (api_key, api_secret_key) = self.new_config.credentials();
+        let api_key_input = text_input("API Key", api_key)
             .secure(true)
             .width(Length::Fill)
             .on_input(|s| Message::Settings(SettingsMessage::ApiKeyInput(s)));
-        let api_secret_key_input = text_input("API Secret Key", &self.new_config.api_secret_key)
+        let api_secret_key_input = text_input("API Secret Key", api_secret_key)
             .secure(true)
             .width(Length::Fill)
             .on_input(|s| Message::Settings(SettingsMessage::ApiSecretInput(s)));
+        let default_pair_input = text_input("BTCUSDT", &self.new_config.default_pair)
+            .width(Length::Fill)
+            .on_input(|s| Message::Settings(SettingsMessage::DefaultPairInput(s)));
+        let quote_asset_input = text_input("USDT", &self.new_config.preferred_quote_asset)
+            .width(Length::Fill)
+            .on_input(|s| Message::Settings(SettingsMessage::QuoteAssetInput(s)));
+
+        // TestNet has single key pair
+        let accounts = (!self.new_config.testnet).then(|| {
+            let name = self.new_config.account().map_or("", |a| a.name.as_str());
+
+            row![
+                text("Account:").width(Length::Fixed(100.0)),
+                pick_list(
+                    self.new_config.account_choices(),
+                    self.new_config.active_account_choice(),
+                    |choice| Message::Settings(SettingsMessage::AccountSelected(choice)),
+                )
+                .width(Length::Fixed(150.0)),
+                text_input("Main", name)
+                    .width(Length::Fill)
+                    .on_input(|s| Message::Settings(SettingsMessage::AccountNameInput(s))),
+                button(text("Add").size(14))
+                    .padding(8)
+                    .style(iced::theme::Button::Secondary)
+                    .on_press(SettingsMessage::AccountAdded.into()),
+                button(text("Remove").size(14))
+                    .padding(8)
+                    .style(iced::theme::Button::Destructive)
+                    .on_press_maybe(
+                        (self.new_config.accounts.len() > 1)
+                            .then_some(SettingsMessage::AccountRemoved.into())
+                    ),
+            ]
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+        });
 
         container(
             column![
+                Column::with_children(accounts.map(Element::from)),
                 row![text("API Key:").width(Length::Fixed(100.0)), api_key_input].spacing(10),
                 row![
                     text("API Secret Key:").width(Length::Fixed(100.0)),
                     api_secret_key_input,
                 ]
                 .spacing(10),
+                row![
+                    text("Default pair:").width(Length::Fixed(100.0)),
+                    default_pair_input,
+                ]
+                .spacing(10),
+                row![
+                    text("Quote asset:").width(Length::Fixed(100.0)),
+                    quote_asset_input,
+                ]
+                .spacing(10),
+                row![
+                    text("Theme:").width(Length::Fixed(100.0)),
+                    self.theme_button("Dark", SettingsMessage::ThemeSelected(Theme::Dark)),
+                    self.theme_button("Light", SettingsMessage::ThemeSelected(Theme::Light)),
+                    self.theme_button("Custom", SettingsMessage::LoadPalette),
+                ]
+                .spacing(10)
+                .align_items(iced::Alignment::Center),
+                checkbox("Paper trading", self.new_config.paper_trade)
+                    .on_toggle(|v| Message::Settings(SettingsMessage::PaperTradeToggled(v))),
+                checkbox("Demo mode", self.new_config.demo_mode)
+                    .on_toggle(|v| Message::Settings(SettingsMessage::DemoModeToggled(v))),
+                checkbox("Binance TestNet", self.new_config.testnet)
+                    .on_toggle(|v| Message::Settings(SettingsMessage::TestnetToggled(v))),
+                checkbox("Aggregate trades", self.new_config.use_agg_trades)
+                    .on_toggle(|v| Message::Settings(SettingsMessage::AggTradesToggled(v))),
+                row![
+                    checkbox("Sounds", self.new_config.sound_alerts)
+                        .on_toggle(|v| Message::Settings(SettingsMessage::SoundAlertsToggled(v))),
+                    slider(0.0..=1.0, self.new_config.sound_volume, |v| {
+                        Message::Settings(SettingsMessage::SoundVolumeChanged(v))
+                    })
+                    .step(0.05)
+                    .width(Length::Fixed(120.0)),
+                ]
+                .spacing(10)
+                .align_items(iced::Alignment::Center),
                 button(tb("Save")).on_press(SettingsMessage::SaveConfig.into()),
             ]
             .spacing(10)
@@ -78,7 +276,7 @@ impl SettingsView {
             .align_items(iced::Alignment::Center),
         )
         .style(container::Appearance {
-            background: Some(iced::Background::Color(Color::from_rgb(0.07, 0.07, 0.07))),
+            background: Some(iced::Background::Color(color(Role::Surface))),
             border: iced::Border {
                 radius: 16.0.into(),
                 ..Default::default()
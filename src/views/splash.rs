@@ -0,0 +1,154 @@
+//! Overlay covering dashboard until initial data is loaded
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    data::LoaderState,
+    theme::{color, Role},
+    views::components::loading::Loader,
+};
+
+use iced::{
+    widget::{button, column, container, row, text, Column},
+    Alignment, Color, Element, Font, Length, Subscription,
+};
+
+/// Time to fade out after everything is loaded
+const FADE_DURATION: Duration = Duration::from_millis(400);
+
+const FRAME_INTERVAL: Duration = Duration::from_millis(33);
+
+/// Splash is hidden if data has not loaded by then, failed requests would keep it up forever
+pub(crate) const LOAD_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone)]
+pub(crate) enum LoaderMessage {
+    /// Animation step after loading has finished
+    FadeOut(Instant),
+    /// Hide without waiting for remaining data
+    Skip,
+    /// Data did not load in `LOAD_TIMEOUT`
+    TimedOut,
+}
+
+pub(crate) struct SplashView {
+    fade_started: Option<Instant>,
+    opacity: f32,
+}
+
+impl SplashView {
+    pub(crate) fn new() -> Self {
+        Self {
+            fade_started: None,
+            opacity: 1.0,
+        }
+    }
+
+    pub(crate) fn is_visible(&self) -> bool {
+        self.opacity > 0.0
+    }
+
+    pub(crate) fn update(&mut self, msg: LoaderMessage) {
+        match msg {
+            LoaderMessage::FadeOut(now) => {
+                let started = *self.fade_started.get_or_insert(now);
+                let progress =
+                    now.duration_since(started).as_secs_f32() / FADE_DURATION.as_secs_f32();
+                self.opacity = (1.0 - progress).max(0.0);
+            }
+            LoaderMessage::Skip | LoaderMessage::TimedOut => self.opacity = 0.0,
+        }
+    }
+
+    pub(crate) fn subscription(&self, loader: &LoaderState) -> Subscription<LoaderMessage> {
+        if !self.is_visible() {
+            Subscription::none()
+        } else if loader.is_done() {
+            iced::time::every(FRAME_INTERVAL).map(LoaderMessage::FadeOut)
+        } else {
+            iced::time::every(LOAD_TIMEOUT).map(|_| LoaderMessage::TimedOut)
+        }
+    }
+
+    pub(crate) fn view(&self, loader: &LoaderState) -> Element<'_, LoaderMessage> {
+        let fade = |color: Color| Color {
+            a: color.a * self.opacity,
+            ..color
+        };
+
+        let check = |label, loaded| {
+            let (icon, icon_color) = if loaded {
+                ('\u{F26A}', color(Role::Accent))
+            } else {
+                ('\u{F28A}', color(Role::Muted))
+            };
+
+            row![
+                text(icon)
+                    .size(14)
+                    .font(Font::with_name("bootstrap-icons"))
+                    .style(fade(icon_color)),
+                text(label).size(14).style(fade(color(Role::Foreground))),
+            ]
+            .spacing(8)
+            .align_items(Alignment::Center)
+        };
+
+        // spinner cannot be faded, it disappears once everything is loaded
+        let spinner = (!loader.is_done()).then(|| container(Loader::new()).padding(8));
+
+        let content = Column::new()
+            .push_maybe(spinner)
+            .push(
+                column![
+                    check("Prices", loader.prices),
+                    check("Balances", loader.balances),
+                    check("Orders", loader.orders),
+                    check("Exchange info", loader.exchange_info),
+                ]
+                .spacing(6),
+            )
+            .push(
+                button(text("Skip").size(12).style(fade(color(Role::Secondary))))
+                    .style(iced::theme::Button::Text)
+                    .padding(8)
+                    .on_press(LoaderMessage::Skip),
+            )
+            .spacing(12)
+            .align_items(Alignment::Center);
+
+        let background = fade(color(Role::Background));
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .style(move |_: &_| container::Appearance {
+                background: Some(iced::Background::Color(background)),
+                ..Default::default()
+            })
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fades_after_loading() {
+        let mut splash = SplashView::new();
+        let start = Instant::now();
+
+        splash.update(LoaderMessage::FadeOut(start));
+        assert_eq!(splash.opacity, 1.0);
+        splash.update(LoaderMessage::FadeOut(start + FADE_DURATION / 2));
+        assert!((splash.opacity - 0.5).abs() < 1e-3);
+        splash.update(LoaderMessage::FadeOut(start + FADE_DURATION * 2));
+        assert!(!splash.is_visible());
+
+        let mut splash = SplashView::new();
+        splash.update(LoaderMessage::TimedOut);
+        assert!(!splash.is_visible());
+    }
+}
@@ -11,6 +11,8 @@ pub(crate) struct OrderBookDetails {
     pub(crate) sym: String,
     pub(crate) bids: BTreeMap<String, f64>,
     pub(crate) asks: BTreeMap<String, f64>,
+    /// Id of last change in event, increases with every event
+    pub(crate) update_id: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -21,11 +23,12 @@ pub(crate) enum Message {
 #[derive(Debug)]
 pub(crate) struct BookWs {
     pair: String,
+    tls_pin: Option<String>,
 }
 
 impl BookWs {
-    fn new(pair: String) -> Self {
-        Self { pair }
+    fn new(pair: String, tls_pin: Option<String>) -> Self {
+        Self { pair, tls_pin }
     }
 }
 
@@ -38,6 +41,10 @@ impl WsListener for BookWs {
         WsMessage::Book(msg)
     }
 
+    fn tls_pin(&self) -> Option<&str> {
+        self.tls_pin.as_deref()
+    }
+
     async fn endpoint(&self) -> Result<String, Box<dyn Error + Send>> {
         Ok(diff_book_depth_stream(&self.pair, 1000))
     }
@@ -47,7 +54,7 @@ impl WsListener for BookWs {
             event_time: _,
             symbol,
             first_update_id: _,
-            final_update_id: _,
+            final_update_id,
             bids,
             asks,
         } = event;
@@ -78,6 +85,7 @@ impl WsListener for BookWs {
             sym: symbol,
             bids: b,
             asks: a,
+            update_id: final_update_id,
         }
     }
 
@@ -91,10 +99,12 @@ impl WsListener for BookWs {
     }
 }
 
-pub fn connect(pair: String) -> Subscription<WsMessage> {
+pub fn connect(pair: String, testnet: bool, tls_pin: Option<String>) -> Subscription<WsMessage> {
     struct Connect;
 
-    subscription::channel(std::any::TypeId::of::<Connect>(), 100, |output| async {
-        BookWs::new(pair).run(output).await
-    })
+    subscription::channel(
+        (std::any::TypeId::of::<Connect>(), testnet, tls_pin.clone()),
+        100,
+        move |output| async move { BookWs::new(pair, tls_pin).run(output, testnet).await },
+    )
 }
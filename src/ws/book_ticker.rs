@@ -0,0 +1,75 @@
+use std::{error::Error, sync::atomic::AtomicBool};
+
+use binance::{websockets::book_ticker_stream, ws_model::BookTickerEvent};
+use iced::subscription::{self, Subscription};
+
+use super::{WsEvent, WsListener, WsMessage};
+
+/// Top of book
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BookTick {
+    pub(crate) bid: f64,
+    pub(crate) ask: f64,
+}
+
+impl BookTick {
+    /// Distance between bid and ask in percent of mid price
+    pub(crate) fn spread_pct(&self) -> f64 {
+        let mid = (self.bid + self.ask) / 2.0;
+        if mid <= 0.0 {
+            return 0.0;
+        }
+
+        (self.ask - self.bid) / mid * 100.0
+    }
+}
+
+/// Best bid and ask of several symbols over one connection
+///
+/// All market `!bookTicker` stream was retired, so every symbol has its own stream
+#[derive(Debug)]
+pub(crate) struct BookTickerWs {
+    symbols: Vec<String>,
+}
+
+impl WsListener for BookTickerWs {
+    type Event = BookTickerEvent;
+    type Input = ();
+    type Output = (String, BookTick);
+
+    fn message(&self, msg: WsEvent<Self::Input, Self::Output>) -> WsMessage {
+        WsMessage::BookTicker(msg)
+    }
+
+    async fn endpoint(&self) -> Result<String, Box<dyn Error + Send>> {
+        Ok(self
+            .symbols
+            .iter()
+            .map(|symbol| book_ticker_stream(symbol))
+            .collect::<Vec<_>>()
+            .join("/"))
+    }
+
+    fn handle_event(&self, event: Self::Event) -> Self::Output {
+        (
+            event.symbol,
+            BookTick {
+                bid: event.best_bid,
+                ask: event.best_ask,
+            },
+        )
+    }
+
+    fn handle_input(&mut self, _: Self::Input, _: &mut AtomicBool) {}
+}
+
+/// Restarts when list of symbols changes
+pub(crate) fn connect(symbols: Vec<String>, testnet: bool) -> Subscription<WsMessage> {
+    struct Connect;
+
+    subscription::channel(
+        (std::any::TypeId::of::<Connect>(), symbols.clone(), testnet),
+        100,
+        move |output| async move { BookTickerWs { symbols }.run(output, testnet).await },
+    )
+}
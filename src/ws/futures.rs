@@ -0,0 +1,183 @@
+//! Streams of USD-M perpetual futures market
+
+use std::{error::Error, sync::atomic::AtomicBool, time::Duration};
+
+use binance::ws_model::MarkPriceEvent;
+use iced::subscription::{self, Subscription};
+use serde::{de, Deserialize, Deserializer};
+
+use crate::ws::WsEvent;
+
+use super::{WsListener, WsMessage};
+
+fn str_as_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = <&str>::deserialize(deserializer)?;
+    s.parse::<f64>().map_err(de::Error::custom)
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FundingRate {
+    pub(crate) symbol: String,
+    /// Rate of current funding period, not in percent
+    pub(crate) rate: f64,
+    pub(crate) mark_price: f64,
+    /// Milliseconds
+    pub(crate) next_funding_time: u64,
+}
+
+#[derive(Debug)]
+pub(crate) struct FundingWs {}
+
+impl WsListener for FundingWs {
+    type Event = Vec<MarkPriceEvent>;
+    type Input = ();
+    type Output = Vec<FundingRate>;
+
+    const FUTURES: bool = true;
+
+    fn message(&self, msg: WsEvent<Self::Input, Self::Output>) -> WsMessage {
+        WsMessage::Funding(msg)
+    }
+
+    async fn endpoint(&self) -> Result<String, Box<dyn Error + Send>> {
+        Ok("!markPrice@arr@1s".to_owned())
+    }
+
+    fn handle_event(&self, event: Self::Event) -> Self::Output {
+        event
+            .into_iter()
+            .filter_map(|e| {
+                Some(FundingRate {
+                    rate: e.funding_rate.parse().ok()?,
+                    mark_price: e.mark_price.parse().ok()?,
+                    next_funding_time: e.next_funding_time,
+                    symbol: e.symbol,
+                })
+            })
+            .collect()
+    }
+
+    fn handle_input(&mut self, _: Self::Input, _: &mut AtomicBool) {}
+}
+
+/// Forced position close
+#[derive(Debug, Deserialize, Clone)]
+pub(crate) struct Liquidation {
+    #[serde(rename = "s")]
+    pub(crate) symbol: String,
+
+    /// `SELL` closes long position, `BUY` closes short
+    #[serde(rename = "S")]
+    pub(crate) side: String,
+
+    #[serde(rename = "ap", deserialize_with = "str_as_f64")]
+    pub(crate) price: f64,
+
+    #[serde(rename = "z", deserialize_with = "str_as_f64")]
+    pub(crate) qty: f64,
+
+    #[serde(rename = "T")]
+    pub(crate) time: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ForceOrderEvent {
+    #[serde(rename = "o")]
+    order: Liquidation,
+}
+
+/// Liquidations of one symbol or of whole market
+#[derive(Debug)]
+pub(crate) struct LiquidationsWs {
+    symbol: Option<String>,
+}
+
+impl WsListener for LiquidationsWs {
+    type Event = ForceOrderEvent;
+    type Input = ();
+    type Output = Liquidation;
+
+    const FUTURES: bool = true;
+    /// Liquidations can be minutes apart, especially of single pair
+    const STALL_TIMEOUT: Option<Duration> = None;
+
+    fn message(&self, msg: WsEvent<Self::Input, Self::Output>) -> WsMessage {
+        match self.symbol {
+            Some(_) => WsMessage::PairLiquidation(msg),
+            None => WsMessage::Liquidation(msg),
+        }
+    }
+
+    async fn endpoint(&self) -> Result<String, Box<dyn Error + Send>> {
+        Ok(match &self.symbol {
+            Some(symbol) => format!("{symbol}@forceOrder"),
+            None => "!forceOrder@arr".to_owned(),
+        })
+    }
+
+    fn handle_event(&self, event: Self::Event) -> Self::Output {
+        event.order
+    }
+
+    fn handle_input(&mut self, _: Self::Input, _: &mut AtomicBool) {}
+}
+
+pub fn connect_funding(testnet: bool) -> Subscription<WsMessage> {
+    struct Connect;
+
+    subscription::channel(
+        (std::any::TypeId::of::<Connect>(), testnet),
+        100,
+        move |output| async move { FundingWs {}.run(output, testnet).await },
+    )
+}
+
+pub fn connect_liquidations(testnet: bool) -> Subscription<WsMessage> {
+    struct Connect;
+
+    subscription::channel(
+        (std::any::TypeId::of::<Connect>(), testnet),
+        100,
+        move |output| async move { LiquidationsWs { symbol: None }.run(output, testnet).await },
+    )
+}
+
+/// Liquidations of lowercase `symbol` only
+pub fn connect_pair_liquidations(symbol: String, testnet: bool) -> Subscription<WsMessage> {
+    struct Connect;
+
+    subscription::channel(
+        (std::any::TypeId::of::<Connect>(), symbol.clone(), testnet),
+        100,
+        move |output| async move {
+            LiquidationsWs {
+                symbol: Some(symbol),
+            }
+            .run(output, testnet)
+            .await
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_force_order() {
+        let event: ForceOrderEvent = serde_json::from_str(
+            r#"{"e":"forceOrder","E":1568014460893,"o":{"s":"BTCUSDT","S":"SELL","o":"LIMIT",
+            "f":"IOC","q":"0.014","p":"9910","ap":"9910","X":"FILLED","l":"0.014","z":"0.014",
+            "T":1568014460893}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(event.order.symbol, "BTCUSDT");
+        assert_eq!(event.order.side, "SELL");
+        assert_eq!(event.order.price, 9910.0);
+        assert_eq!(event.order.qty, 0.014);
+    }
+}
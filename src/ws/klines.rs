@@ -0,0 +1,78 @@
+use std::{error::Error, sync::atomic::AtomicBool};
+
+use binance::{websockets::kline_stream, ws_model::KlineEvent};
+use iced::subscription::{self, Subscription};
+
+use crate::{data::CandleData, ws::WsEvent};
+
+use super::{WsListener, WsMessage};
+
+/// Interval of candles streamed for charts opened from watchlist
+pub(crate) const SYMBOL_CHART_INTERVAL: &str = "1m";
+
+/// Live candles of one symbol and interval, every chart has its own connection
+#[derive(Debug)]
+pub(crate) struct KlinesWs {
+    pair: String,
+    interval: &'static str,
+}
+
+impl KlinesWs {
+    pub(crate) fn new(pair: String, interval: &'static str) -> Self {
+        Self { pair, interval }
+    }
+}
+
+impl WsListener for KlinesWs {
+    type Event = KlineEvent;
+    type Input = ();
+    /// Symbol, interval and latest candle
+    type Output = (String, &'static str, CandleData);
+
+    fn message(&self, msg: WsEvent<Self::Input, Self::Output>) -> WsMessage {
+        WsMessage::Kline(msg)
+    }
+
+    async fn endpoint(&self) -> Result<String, Box<dyn Error + Send>> {
+        Ok(kline_stream(&self.pair, self.interval))
+    }
+
+    fn handle_event(&self, event: Self::Event) -> Self::Output {
+        let k = event.kline;
+
+        (
+            event.symbol,
+            self.interval,
+            CandleData {
+                open: k.open,
+                high: k.high,
+                low: k.low,
+                close: k.close,
+                volume: k.volume,
+                ts: k.start_time as u64,
+            },
+        )
+    }
+
+    fn handle_input(&mut self, _: Self::Input, _: &mut AtomicBool) {}
+}
+
+/// Subscriptions of different symbols and intervals run side by side
+pub(crate) fn connect(
+    pair: String,
+    interval: &'static str,
+    testnet: bool,
+) -> Subscription<WsMessage> {
+    struct Connect;
+
+    subscription::channel(
+        (
+            std::any::TypeId::of::<Connect>(),
+            pair.clone(),
+            interval,
+            testnet,
+        ),
+        100,
+        move |output| async move { KlinesWs::new(pair, interval).run(output, testnet).await },
+    )
+}
@@ -6,19 +6,83 @@ use serde::de::DeserializeOwned;
 use tokio::sync::mpsc as mpsc_tokio;
 use tracing::info;
 
-use super::{WsEvent, WsHandle, WsMessage};
+use super::{pin, WsEvent, WsHandle, WsMessage};
+use crate::{api::binance_config, network};
+
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Binance pings every 3 minutes, busy streams send events much more often than that
+const STALL_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Exponentially growing delay between failed connection attempts
+#[derive(Default)]
+struct Backoff {
+    failures: u32,
+}
+
+impl Backoff {
+    /// Delay before next attempt: 1s, 2s, 4s… up to `MAX_BACKOFF`
+    fn next_delay(&mut self) -> Duration {
+        let delay = Duration::from_secs(1)
+            .checked_mul(2_u32.saturating_pow(self.failures))
+            .map_or(MAX_BACKOFF, |delay| delay.min(MAX_BACKOFF));
+        self.failures = self.failures.saturating_add(1);
+
+        delay
+    }
+
+    fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
+
+/// Waits for next tick of keepalive interval, never finishes without one
+async fn next_keepalive(interval: &mut Option<tokio::time::Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Waits until connection counts as stalled, never finishes without timeout
+async fn next_stall(at: Option<tokio::time::Instant>) {
+    match at {
+        Some(at) => tokio::time::sleep_until(at).await,
+        None => std::future::pending().await,
+    }
+}
 
 pub(crate) trait WsListener {
     type Event: Send + DeserializeOwned;
     type Input;
     type Output;
 
+    /// Connect to futures websocket endpoint instead of spot one
+    const FUTURES: bool = false;
+
+    /// How often `keepalive` is called while connected
+    const KEEPALIVE: Option<Duration> = None;
+
+    /// Connection without events for this long is dropped and reconnected, None for streams
+    /// that can be quiet for a long time
+    const STALL_TIMEOUT: Option<Duration> = Some(STALL_TIMEOUT);
+
     /// Wrap `WsEvent` in correct variant of `WsMessage`
     fn message(&self, msg: WsEvent<Self::Input, Self::Output>) -> WsMessage;
 
+    /// SHA-256 fingerprint server certificate of websocket connection has to match
+    fn tls_pin(&self) -> Option<&str> {
+        None
+    }
+
     /// Endpoint given to `web_socket.connect`
     async fn endpoint(&self) -> Result<String, Box<dyn Error + Send>>;
 
+    /// Keep connection to `endpoint` from expiring
+    async fn keepalive(&self, _endpoint: &str) {}
+
     /// Handle websocket event
     fn handle_event(&self, event: Self::Event) -> Self::Output;
 
@@ -27,21 +91,28 @@ pub(crate) trait WsListener {
     /// `keep_running` can disconnect websocket if set to false
     fn handle_input(&mut self, input: Self::Input, keep_running: &mut AtomicBool);
 
-    /// Main entrypoint
-    async fn run(&mut self, mut output: mpsc_futures::Sender<WsMessage>) -> ! {
+    /// Main entrypoint, connects to TestNet if `testnet` is set
+    async fn run(&mut self, mut output: mpsc_futures::Sender<WsMessage>, testnet: bool) -> ! {
         // forward messages out of websocket callback
         let (tx, mut rx) = mpsc_tokio::unbounded_channel();
 
-        let mut web_socket = WebSockets::new(|event| {
-            tx.send(event)
-                .map_err(|e| binance::errors::Error::Msg(e.to_string()))
-        });
+        // error type of callback is dictated by binance
+        #[allow(clippy::result_large_err)]
+        let mut web_socket = WebSockets::new_with_options(
+            |event| {
+                tx.send(event)
+                    .map_err(|e| binance::errors::Error::Msg(e.to_string()))
+            },
+            binance_config(testnet),
+        );
 
         let (input_tx, mut input_rx) = mpsc_tokio::unbounded_channel();
 
         let connected = self.message(WsEvent::Created(WsHandle(input_tx)));
         let _ = output.send(connected).await;
 
+        let mut backoff = Backoff::default();
+
         loop {
             let mut keep_running = AtomicBool::new(true);
 
@@ -56,23 +127,68 @@ pub(crate) trait WsListener {
                 Err(e) => {
                     tracing::error!("endpoint error: {e}");
 
-                    tokio::time::sleep(Duration::from_secs(2)).await;
+                    self.wait_reconnect(&mut output, backoff.next_delay()).await;
                     continue;
                 }
             };
 
-            if let Err(e) = web_socket.connect(&endpoint).await {
+            let connection = match self.tls_pin() {
+                // socket is opened with pinned TLS config and given to library to read from
+                Some(tls_pin) => {
+                    let config = binance_config(testnet);
+                    let base = if Self::FUTURES {
+                        &config.futures_ws_endpoint
+                    } else {
+                        &config.ws_endpoint
+                    };
+
+                    match pin::connect(&format!("{base}/ws/{endpoint}"), tls_pin).await {
+                        Ok(socket) => {
+                            web_socket.socket = Some(socket);
+                            Ok(())
+                        }
+                        Err(e) => {
+                            if let pin::PinError::Mismatch(fingerprint) = &e {
+                                let _ = output
+                                    .send(self.message(WsEvent::PinError(fingerprint.clone())))
+                                    .await;
+                            }
+                            Err(e.to_string())
+                        }
+                    }
+                }
+                None if Self::FUTURES => web_socket
+                    .connect_futures(&endpoint)
+                    .await
+                    .map_err(|e| e.to_string()),
+                None => web_socket
+                    .connect(&endpoint)
+                    .await
+                    .map_err(|e| e.to_string()),
+            };
+
+            if let Err(e) = connection {
                 tracing::error!("connection error: {e}");
 
-                tokio::time::sleep(Duration::from_secs(2)).await;
+                self.wait_reconnect(&mut output, backoff.next_delay()).await;
                 continue;
             }
 
             info!("connected {}", &endpoint);
+            backoff.reset();
             let connected = self.message(WsEvent::Connected);
             let _ = output.send(connected).await;
 
+            let mut keepalive = Self::KEEPALIVE.map(|period| {
+                tokio::time::interval_at(tokio::time::Instant::now() + period, period)
+            });
+
+            // pings are answered inside of event loop and never reach here, events are used instead
+            let mut last_event_at = tokio::time::Instant::now();
+
             loop {
+                let stalled_at = Self::STALL_TIMEOUT.map(|timeout| last_event_at + timeout);
+
                 tokio::select! {
                     biased;
 
@@ -85,7 +201,16 @@ pub(crate) trait WsListener {
                     input = input_rx.recv() => {
                         self.handle_input(input.expect("channel closed"), &mut keep_running);
                     }
+                    _ = next_keepalive(&mut keepalive) => {
+                        self.keepalive(&endpoint).await;
+                    }
+                    _ = next_stall(stalled_at) => {
+                        tracing::warn!("no events from {endpoint}, reconnecting");
+                        let _ = output.send(self.message(WsEvent::Stalled)).await;
+                        break;
+                    }
                     event = rx.recv() => {
+                        last_event_at = tokio::time::Instant::now();
                         let handled = self.handle_event(event.expect("channel closed"));
                         let message = self.message(WsEvent::Message(handled));
                         let _ = output.send(message).await;
@@ -96,6 +221,36 @@ pub(crate) trait WsListener {
             info!("disconnected {}", &endpoint);
             let disconnected = self.message(WsEvent::Disconnected);
             let _ = output.send(disconnected).await;
+
+            // reconnect right away if disconnect was requested by input
+            if *keep_running.get_mut() {
+                self.wait_reconnect(&mut output, backoff.next_delay()).await;
+            }
         }
     }
+
+    /// Notify about upcoming reconnect and wait for it
+    async fn wait_reconnect(&self, output: &mut mpsc_futures::Sender<WsMessage>, delay: Duration) {
+        let reconnecting = self.message(WsEvent::Reconnecting(delay));
+        let _ = output.send(reconnecting).await;
+
+        tokio::time::sleep(delay).await;
+        // every attempt would fail anyway
+        network::wait_online().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_cap() {
+        let mut backoff = Backoff::default();
+        let delays: Vec<_> = (0..8).map(|_| backoff.next_delay().as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 32, 60, 60]);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+    }
 }
@@ -1,14 +1,28 @@
-use binance::rest_model::{Order, OrderStatus};
+use std::time::{Duration, Instant};
+
+use binance::rest_model::Balance;
 use iced::Subscription;
-use ringbuf::Rb;
+use ringbuf::{ring_buffer::RbBase, Rb};
 use tokio::sync::mpsc;
 
 use self::listener::WsListener;
-use crate::{data::AppData, message::Message, views::dashboard::DashboardView};
+use crate::{
+    data::AppData,
+    message::Message,
+    views::{
+        dashboard::{DashboardView, PaneType},
+        panes::chart::Timeframe,
+    },
+};
 
 mod book;
+pub(crate) mod book_ticker;
+pub(crate) mod futures;
+mod klines;
 mod listener;
+mod pin;
 pub(crate) mod prices;
+mod symbol_trades;
 pub(crate) mod trades;
 mod user;
 
@@ -25,8 +39,17 @@ pub(crate) enum WsEvent<In, Out> {
     /// Connection closed
     Disconnected,
 
+    /// No events for too long, connection is dropped and reconnected
+    Stalled,
+
+    /// Connection failed, next attempt happens after delay
+    Reconnecting(Duration),
+
     /// Websocket message
     Message(Out),
+
+    /// Server certificate does not match pinned one, contains its fingerprint
+    PinError(String),
 }
 
 #[derive(Debug, Clone)]
@@ -39,6 +62,66 @@ pub(crate) enum WsMessage {
         WsEvent<<prices::PricesWs as WsListener>::Input, <prices::PricesWs as WsListener>::Output>,
     ),
     User(WsEvent<<user::UserWs as WsListener>::Input, <user::UserWs as WsListener>::Output>),
+    Funding(
+        WsEvent<
+            <futures::FundingWs as WsListener>::Input,
+            <futures::FundingWs as WsListener>::Output,
+        >,
+    ),
+    Liquidation(
+        WsEvent<
+            <futures::LiquidationsWs as WsListener>::Input,
+            <futures::LiquidationsWs as WsListener>::Output,
+        >,
+    ),
+    /// Liquidations of market pair
+    PairLiquidation(
+        WsEvent<
+            <futures::LiquidationsWs as WsListener>::Input,
+            <futures::LiquidationsWs as WsListener>::Output,
+        >,
+    ),
+    Kline(
+        WsEvent<<klines::KlinesWs as WsListener>::Input, <klines::KlinesWs as WsListener>::Output>,
+    ),
+    BookTicker(
+        WsEvent<
+            <book_ticker::BookTickerWs as WsListener>::Input,
+            <book_ticker::BookTickerWs as WsListener>::Output,
+        >,
+    ),
+    /// Trades of favorites
+    SymbolTrade(
+        WsEvent<
+            <symbol_trades::SymbolTradesWs as WsListener>::Input,
+            <symbol_trades::SymbolTradesWs as WsListener>::Output,
+        >,
+    ),
+}
+
+/// Last sequence number processed from stream of one symbol
+///
+/// Numbering is per symbol, so it starts over when symbol changes
+#[derive(Debug, Default)]
+struct Sequence {
+    symbol: String,
+    last: u64,
+}
+
+impl Sequence {
+    /// Remembers `seq` of `symbol`, false if it was already processed
+    fn advance(&mut self, symbol: &str, seq: u64) -> bool {
+        if symbol == self.symbol {
+            if seq <= self.last {
+                return false;
+            }
+        } else {
+            symbol.clone_into(&mut self.symbol);
+        }
+        self.last = seq;
+
+        true
+    }
 }
 
 /// Allows communicating with websocket. If you drop this, ws will spin endlessly on closed channel
@@ -54,19 +137,40 @@ impl<T> WsHandle<T> {
 pub(crate) struct Websockets {
     currency_pair: String,
     api_key: String,
+    /// Streams come from TestNet instead of mainnet
+    testnet: bool,
+    /// Certificate fingerprint book and trade streams are checked against
+    tls_pin: Option<String>,
+    /// Trades come from aggregate stream instead of raw one
+    agg_trades: bool,
     user: Option<WsHandle<user::Message>>,
     prices: Option<WsHandle<()>>,
     book: Option<WsHandle<book::Message>>,
     trade: Option<WsHandle<trades::Message>>,
+    /// Final update id of last book diff, replayed ones are dropped after reconnect
+    book_seq: Sequence,
+    /// Aggregate id of last trade
+    trade_seq: Sequence,
 }
 
 impl Websockets {
-    pub(crate) fn new(api_key: String, currency_pair: &str) -> Self {
+    pub(crate) fn new(
+        api_key: String,
+        currency_pair: &str,
+        testnet: bool,
+        tls_pin: Option<String>,
+        agg_trades: bool,
+    ) -> Self {
         Self {
+            testnet,
+            tls_pin,
+            agg_trades,
             user: None,
             prices: None,
             book: None,
             trade: None,
+            book_seq: Sequence::default(),
+            trade_seq: Sequence::default(),
             api_key,
             currency_pair: currency_pair.to_lowercase(),
         }
@@ -78,6 +182,15 @@ impl Websockets {
         };
     }
 
+    /// Switches between aggregate and raw trade stream
+    pub(crate) fn set_agg_trades(&mut self, agg_trades: bool) {
+        if agg_trades != self.agg_trades {
+            self.agg_trades = agg_trades;
+            // ids of streams are numbered separately
+            self.trade_seq = Sequence::default();
+        }
+    }
+
     pub(crate) fn track_new_currency_pair(&self, pair: &str) {
         let pair = pair.to_lowercase();
 
@@ -91,14 +204,89 @@ impl Websockets {
 
     pub(crate) fn subscription(&self) -> Subscription<Message> {
         Subscription::batch([
-            trades::connect(self.currency_pair.clone()),
-            book::connect(self.currency_pair.clone()),
-            prices::connect(),
-            user::connect(self.api_key.clone()),
+            trades::connect(
+                self.currency_pair.clone(),
+                self.testnet,
+                self.tls_pin.clone(),
+                self.agg_trades,
+            ),
+            book::connect(
+                self.currency_pair.clone(),
+                self.testnet,
+                self.tls_pin.clone(),
+            ),
+            prices::connect(self.testnet),
+            user::connect(self.api_key.clone(), self.testnet),
         ])
         .map(Message::from)
     }
 
+    /// Futures streams, only needed while futures tab is open
+    pub(crate) fn futures_subscription(&self) -> Subscription<Message> {
+        Subscription::batch([
+            futures::connect_funding(self.testnet),
+            futures::connect_liquidations(self.testnet),
+        ])
+        .map(Message::from)
+    }
+
+    /// Futures liquidations of market pair, only needed while liquidations pane is open
+    pub(crate) fn liquidations_subscription(&self, pair: &str) -> Subscription<Message> {
+        futures::connect_pair_liquidations(pair.to_lowercase(), self.testnet).map(Message::from)
+    }
+
+    /// Best bid and ask of symbols, only needed while spread monitor or arbitrage is open
+    pub(crate) fn book_ticker_subscription(&self, symbols: &[String]) -> Subscription<Message> {
+        if symbols.is_empty() {
+            return Subscription::none();
+        }
+
+        book_ticker::connect(
+            symbols.iter().map(|s| s.to_lowercase()).collect(),
+            self.testnet,
+        )
+        .map(Message::from)
+    }
+
+    /// Trades of symbols, only needed while sentiment pane is open
+    pub(crate) fn symbol_trades_subscription(&self, symbols: &[String]) -> Subscription<Message> {
+        if symbols.is_empty() {
+            return Subscription::none();
+        }
+
+        symbol_trades::connect(
+            symbols.iter().map(|s| s.to_lowercase()).collect(),
+            self.testnet,
+        )
+        .map(Message::from)
+    }
+
+    /// Candles of symbol charts and of main chart in its timeframe
+    pub(crate) fn klines_subscription(
+        &self,
+        symbols: Vec<String>,
+        chart_pair: &str,
+        chart_timeframe: Timeframe,
+    ) -> Subscription<Message> {
+        Subscription::batch(
+            symbols
+                .into_iter()
+                .map(|symbol| {
+                    klines::connect(
+                        symbol.to_lowercase(),
+                        klines::SYMBOL_CHART_INTERVAL,
+                        self.testnet,
+                    )
+                })
+                .chain([klines::connect(
+                    chart_pair.to_lowercase(),
+                    chart_timeframe.as_str(),
+                    self.testnet,
+                )]),
+        )
+        .map(Message::from)
+    }
+
     pub(crate) fn update(
         &mut self,
         msg: WsMessage,
@@ -109,18 +297,56 @@ impl Websockets {
             WsMessage::Book(event) => {
                 match event {
                     WsEvent::Created(handle) => self.book = Some(handle),
+                    WsEvent::Message(bt) if !self.book_seq.advance(&bt.sym, bt.update_id) => {
+                        tracing::warn!("dropped duplicate {} book update {}", bt.sym, bt.update_id);
+                    }
                     WsEvent::Message(bt) => {
                         data.book = (bt.sym, bt.bids, bt.asks);
+                        dashboard.book_received(data);
+                        dashboard.ws_message_received(PaneType::DepthChart);
+                    }
+                    WsEvent::Connected => {
+                        dashboard.set_reconnecting(PaneType::Book, None);
+                        dashboard.set_reconnecting(PaneType::DepthChart, None);
+                        dashboard.set_stalled(PaneType::Book, false);
+                        dashboard.set_stalled(PaneType::DepthChart, false);
+                        dashboard.ws_message_received(PaneType::Book);
+                        dashboard.ws_message_received(PaneType::DepthChart);
+                    }
+                    WsEvent::Reconnecting(delay) => {
+                        let at = Some(Instant::now() + delay);
+                        dashboard.set_reconnecting(PaneType::Book, at);
+                        dashboard.set_reconnecting(PaneType::DepthChart, at);
+                    }
+                    WsEvent::Stalled => {
+                        dashboard.set_stalled(PaneType::Book, true);
+                        dashboard.set_stalled(PaneType::DepthChart, true);
                     }
-                    WsEvent::Connected | WsEvent::Disconnected => (),
+                    // shown by app
+                    WsEvent::Disconnected | WsEvent::PinError(_) => (),
                 };
             }
             WsMessage::Trade(event) => match event {
                 WsEvent::Created(handle) => self.trade = Some(handle),
+                WsEvent::Message(te) if !self.trade_seq.advance(&te.symbol, te.id) => {
+                    tracing::warn!("dropped duplicate {} trade {}", te.symbol, te.id);
+                }
                 WsEvent::Message(te) => {
+                    data.pending_volume += te.qty;
+                    data.add_vwap_trade(&te.symbol, te.price, te.qty);
+                    dashboard.trade_received(&te);
                     data.trades.push_overwrite(te);
                 }
-                WsEvent::Connected | WsEvent::Disconnected => (),
+                WsEvent::Connected => {
+                    dashboard.set_reconnecting(PaneType::Trades, None);
+                    dashboard.set_stalled(PaneType::Trades, false);
+                    dashboard.ws_message_received(PaneType::Trades);
+                }
+                WsEvent::Reconnecting(delay) => {
+                    dashboard.set_reconnecting(PaneType::Trades, Some(Instant::now() + delay));
+                }
+                WsEvent::Stalled => dashboard.set_stalled(PaneType::Trades, true),
+                WsEvent::Disconnected | WsEvent::PinError(_) => (),
             },
             WsMessage::User(event) => match event {
                 WsEvent::Created(handle) => self.user = Some(handle),
@@ -129,48 +355,17 @@ impl Websockets {
                         for b in p.balances {
                             let ib = data.balances.iter_mut().find(|a| a.asset == b.asset);
                             if let Some(uib) = ib {
-                                *uib = unsafe { std::mem::transmute(b) }
+                                *uib = unsafe {
+                                    std::mem::transmute::<binance::ws_model::EventBalance, Balance>(
+                                        b,
+                                    )
+                                }
                             }
                         }
                     }
                     binance::ws_model::WebsocketEvent::OrderUpdate(o) => {
-                        let existing_order = data.orders.iter_mut().find(|order| {
-                            // order.client_order_id == o.order_id&&
-                            order.symbol == o.symbol
-                                && order.side == o.side
-                                && order.status == OrderStatus::PartiallyFilled
-                        });
-
-                        if let Some(order) = existing_order {
-                            // Update the existing order with the new values
-                            order.executed_qty += o.qty_last_executed;
-                            order.cummulative_quote_qty += o.qty;
-                            order.update_time = o.trade_order_time;
-                        } else {
-                            data.orders.insert(
-                                0,
-                                Order {
-                                    symbol: o.symbol,
-                                    order_id: o.order_id,
-                                    order_list_id: o.order_list_id as i32,
-                                    client_order_id: o.client_order_id.unwrap(),
-                                    price: o.price,
-                                    orig_qty: o.qty,
-                                    executed_qty: o.qty_last_executed,
-                                    cummulative_quote_qty: o.qty,
-                                    status: o.current_order_status,
-                                    time_in_force: o.time_in_force,
-                                    order_type: o.order_type,
-                                    side: o.side,
-                                    stop_price: o.stop_price,
-                                    iceberg_qty: o.iceberg_qty,
-                                    time: o.event_time,
-                                    update_time: o.trade_order_time,
-                                    is_working: false,
-                                    orig_quote_order_qty: o.qty,
-                                },
-                            );
-                        }
+                        user::apply_order_update(&mut data.orders, *o);
+                        data.track_realized_pnl();
                     }
                     binance::ws_model::WebsocketEvent::BalanceUpdate(_p) => {
                         // not needed imo?
@@ -180,23 +375,124 @@ impl Websockets {
                     }
                     _ => unreachable!(),
                 },
-                WsEvent::Connected | WsEvent::Disconnected => (),
+                WsEvent::Connected
+                | WsEvent::Disconnected
+                | WsEvent::Stalled
+                | WsEvent::Reconnecting(_)
+                | WsEvent::PinError(_) => (),
             },
             WsMessage::Price(m) => {
                 match m {
                     WsEvent::Created(handle) => self.prices = Some(handle),
                     WsEvent::Message(assets) => {
+                        data.loader.prices = true;
                         for asset in &assets {
                             if asset.name == dashboard.pair() {
-                                data.price_chart.push_overwrite(f64::from(asset.price));
+                                let scrolled = data.price_chart.is_full();
+                                data.push_chart_price(f64::from(asset.price));
+                                dashboard.price_pushed(f64::from(asset.price), scrolled);
+                                if let Some(candle) = data.candles.iter_mut().last() {
+                                    candle.update(f64::from(asset.price));
+                                }
                                 break;
                             }
                         }
                         data.prices.add_many(assets);
                     }
-                    WsEvent::Connected | WsEvent::Disconnected => (),
+                    WsEvent::Connected => dashboard.set_stalled(PaneType::Prices, false),
+                    WsEvent::Stalled => dashboard.set_stalled(PaneType::Prices, true),
+                    WsEvent::Disconnected | WsEvent::Reconnecting(_) | WsEvent::PinError(_) => (),
                 };
             }
+            WsMessage::Funding(event) => {
+                if let WsEvent::Message(rates) = event {
+                    data.futures.add_funding(rates);
+                }
+            }
+            WsMessage::Liquidation(event) => {
+                if let WsEvent::Message(liquidation) = event {
+                    data.futures.liquidations.push_overwrite(liquidation);
+                }
+            }
+            WsMessage::PairLiquidation(event) => match event {
+                // old subscription can still deliver after pair switch
+                WsEvent::Message(liquidation) if liquidation.symbol != dashboard.pair() => {}
+                WsEvent::Message(liquidation) => {
+                    dashboard.liquidation_received(&liquidation);
+                    data.liquidations.push_overwrite(liquidation);
+                }
+                WsEvent::Connected => {
+                    dashboard.set_reconnecting(PaneType::Liquidations, None);
+                    dashboard.ws_message_received(PaneType::Liquidations);
+                }
+                WsEvent::Reconnecting(delay) => {
+                    dashboard
+                        .set_reconnecting(PaneType::Liquidations, Some(Instant::now() + delay));
+                }
+                WsEvent::Created(_)
+                | WsEvent::Disconnected
+                | WsEvent::Stalled
+                | WsEvent::PinError(_) => (),
+            },
+            WsMessage::BookTicker(event) => match event {
+                WsEvent::Message((symbol, tick)) => {
+                    data.book_tickers.insert(symbol, tick);
+                }
+                WsEvent::Connected => {
+                    dashboard.set_stalled(PaneType::SpreadMonitor, false);
+                    dashboard.set_stalled(PaneType::Arbitrage, false);
+                }
+                WsEvent::Stalled => {
+                    dashboard.set_stalled(PaneType::SpreadMonitor, true);
+                    dashboard.set_stalled(PaneType::Arbitrage, true);
+                }
+                _ => (),
+            },
+            WsMessage::SymbolTrade(event) => match event {
+                WsEvent::Message(trade) => {
+                    data.add_sentiment_trade(
+                        &trade.symbol,
+                        trade.time,
+                        trade.qty,
+                        !trade.is_buyer_maker,
+                    );
+                }
+                WsEvent::Connected => dashboard.set_stalled(PaneType::Sentiment, false),
+                WsEvent::Stalled => dashboard.set_stalled(PaneType::Sentiment, true),
+                _ => (),
+            },
+            WsMessage::Kline(event) => {
+                if let WsEvent::Message((symbol, interval, candle)) = event {
+                    // same stream can feed both main chart and symbol chart
+                    if symbol == dashboard.pair()
+                        && interval == dashboard.chart_timeframe().as_str()
+                    {
+                        data.push_candle(candle);
+                    }
+                    if interval == klines::SYMBOL_CHART_INTERVAL
+                        && dashboard.chart_symbols().contains(&symbol)
+                    {
+                        data.push_symbol_candle(symbol, candle);
+                    }
+                }
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence_drops_replayed() {
+        let mut seq = Sequence::default();
+
+        assert!(seq.advance("BTCUSDT", 10));
+        assert!(!seq.advance("BTCUSDT", 10));
+        assert!(!seq.advance("BTCUSDT", 9));
+        assert!(seq.advance("BTCUSDT", 11));
+        // other pair has its own numbering
+        assert!(seq.advance("ETHUSDT", 3));
+    }
+}
@@ -0,0 +1,157 @@
+//! Certificate pinning of websocket endpoints
+//!
+//! Pinned connections are opened here with verifier in their own TLS config and handed to
+//! websocket library, so the checked handshake is the one carrying stream data
+
+use std::{
+    fmt::Write,
+    sync::{Arc, Mutex},
+};
+
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{ring::default_provider, WebPkiSupportedAlgorithms},
+    pki_types::{CertificateDer, ServerName, UnixTime},
+    ClientConfig, DigitallySignedStruct, SignatureScheme,
+};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    tungstenite::handshake::client::Response, Connector, MaybeTlsStream, WebSocketStream,
+};
+
+/// Socket in form websocket library keeps it
+pub(crate) type Socket = (WebSocketStream<MaybeTlsStream<TcpStream>>, Response);
+
+#[derive(Debug)]
+pub(crate) enum PinError {
+    /// Server presented certificate with this SHA-256 fingerprint
+    Mismatch(String),
+    /// Handshake did not get to certificate
+    Connection(String),
+}
+
+impl std::fmt::Display for PinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PinError::Mismatch(fingerprint) => {
+                write!(f, "certificate {fingerprint} does not match pin")
+            }
+            PinError::Connection(err) => write!(f, "pinned connection failed: {err}"),
+        }
+    }
+}
+
+/// Lowercase hex without separators, pins can be written as `AB:CD:…`
+fn normalize_fingerprint(fingerprint: &str) -> String {
+    fingerprint
+        .chars()
+        .filter(char::is_ascii_hexdigit)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn fingerprint(certificate: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, certificate)
+        .as_ref()
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// Accepts only leaf certificate with pinned fingerprint, remembers the one seen
+#[derive(Debug)]
+struct PinVerifier {
+    pin: String,
+    seen: Mutex<Option<String>>,
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for PinVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let fingerprint = fingerprint(end_entity);
+        let matches = fingerprint == self.pin;
+        *self.seen.lock().expect("poisoned") = Some(fingerprint);
+
+        if matches {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(
+                "certificate does not match pin".to_owned(),
+            ))
+        }
+    }
+
+    // server still has to prove it owns pinned certificate
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+/// Opens websocket `url` if certificate of its server matches `pin`
+pub(crate) async fn connect(url: &str, pin: &str) -> Result<Socket, PinError> {
+    let verifier = Arc::new(PinVerifier {
+        pin: normalize_fingerprint(pin),
+        seen: Mutex::new(None),
+        algorithms: default_provider().signature_verification_algorithms,
+    });
+    let config = ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(verifier.clone())
+        .with_no_client_auth();
+
+    let connection = tokio_tungstenite::connect_async_tls_with_config(
+        url,
+        None,
+        false,
+        Some(Connector::Rustls(Arc::new(config))),
+    )
+    .await;
+
+    let seen = verifier.seen.lock().expect("poisoned").take();
+    match (connection, seen) {
+        (Ok(socket), _) => Ok(socket),
+        (Err(_), Some(seen)) if seen != verifier.pin => Err(PinError::Mismatch(seen)),
+        (Err(e), _) => Err(PinError::Connection(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pin_parsing() {
+        assert_eq!(normalize_fingerprint("AB:cd:01"), "abcd01");
+        assert_eq!(
+            fingerprint(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
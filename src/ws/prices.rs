@@ -1,6 +1,6 @@
 use std::{error::Error, sync::atomic::AtomicBool};
 
-use binance::websockets::all_ticker_stream;
+use binance::websockets::all_mini_ticker_stream;
 use iced::subscription::{self, Subscription};
 use serde::{de, Deserialize, Deserializer};
 
@@ -16,15 +16,20 @@ where
     s.parse::<f32>().map_err(de::Error::custom)
 }
 
+/// Part of mini ticker event
 #[derive(Debug, Deserialize, Clone)]
 pub(crate) struct AssetDetails {
     #[serde(rename = "s")]
     pub(crate) name: String,
 
-    #[serde(rename = "b", deserialize_with = "str_as_f32")]
+    /// Last price
+    #[serde(rename = "c", deserialize_with = "str_as_f32")]
     pub(crate) price: f32,
 }
 
+/// Prices of every symbol changed in the last second
+///
+/// Mini ticker is used because full ticker is a lot heavier and only price is needed
 #[derive(Debug)]
 pub(crate) struct PricesWs {}
 
@@ -44,7 +49,7 @@ impl WsListener for PricesWs {
     }
 
     async fn endpoint(&self) -> Result<String, Box<dyn Error + Send>> {
-        Ok(all_ticker_stream().to_owned())
+        Ok(all_mini_ticker_stream().to_owned())
     }
 
     fn handle_event(&self, event: Self::Event) -> Self::Output {
@@ -54,10 +59,12 @@ impl WsListener for PricesWs {
     fn handle_input(&mut self, _: Self::Input, _: &mut AtomicBool) {}
 }
 
-pub fn connect() -> Subscription<WsMessage> {
+pub fn connect(testnet: bool) -> Subscription<WsMessage> {
     struct Connect;
 
-    subscription::channel(std::any::TypeId::of::<Connect>(), 100, |output| async {
-        PricesWs::new().run(output).await
-    })
+    subscription::channel(
+        (std::any::TypeId::of::<Connect>(), testnet),
+        100,
+        move |output| async move { PricesWs::new().run(output, testnet).await },
+    )
 }
@@ -0,0 +1,48 @@
+use std::{error::Error, sync::atomic::AtomicBool};
+
+use binance::websockets::agg_trade_stream;
+use iced::subscription::{self, Subscription};
+
+use super::{trades::TradesEvent, WsEvent, WsListener, WsMessage};
+
+/// Trades of several symbols over one connection, unlike `TradesWs` which follows market pair
+#[derive(Debug)]
+pub(crate) struct SymbolTradesWs {
+    symbols: Vec<String>,
+}
+
+impl WsListener for SymbolTradesWs {
+    type Event = TradesEvent;
+    type Input = ();
+    type Output = TradesEvent;
+
+    fn message(&self, msg: WsEvent<Self::Input, Self::Output>) -> WsMessage {
+        WsMessage::SymbolTrade(msg)
+    }
+
+    async fn endpoint(&self) -> Result<String, Box<dyn Error + Send>> {
+        Ok(self
+            .symbols
+            .iter()
+            .map(|symbol| agg_trade_stream(symbol))
+            .collect::<Vec<_>>()
+            .join("/"))
+    }
+
+    fn handle_event(&self, event: Self::Event) -> Self::Output {
+        event
+    }
+
+    fn handle_input(&mut self, _: Self::Input, _: &mut AtomicBool) {}
+}
+
+/// Restarts when list of symbols changes
+pub(crate) fn connect(symbols: Vec<String>, testnet: bool) -> Subscription<WsMessage> {
+    struct Connect;
+
+    subscription::channel(
+        (std::any::TypeId::of::<Connect>(), symbols.clone(), testnet),
+        100,
+        move |output| async move { SymbolTradesWs { symbols }.run(output, testnet).await },
+    )
+}
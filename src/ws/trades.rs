@@ -1,6 +1,6 @@
 use std::{error::Error, sync::atomic::AtomicBool};
 
-use binance::websockets::agg_trade_stream;
+use binance::websockets::{agg_trade_stream, trade_stream};
 use iced::subscription::{self, Subscription};
 use serde::{de, Deserialize, Deserializer};
 
@@ -16,22 +16,27 @@ pub(crate) enum Message {
 #[derive(Debug)]
 pub(crate) struct TradesWs {
     pair: String,
+    tls_pin: Option<String>,
+    /// Aggregate trade stream instead of raw one
+    aggregated: bool,
 }
 
 impl TradesWs {
-    pub(crate) fn new(pair: String) -> Self {
-        Self { pair }
+    pub(crate) fn new(pair: String, tls_pin: Option<String>, aggregated: bool) -> Self {
+        Self {
+            pair,
+            tls_pin,
+            aggregated,
+        }
     }
 }
 
-fn str_as_f32_as_str_formatted<'de, D>(deserializer: D) -> Result<String, D::Error>
+fn str_as_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
 where
     D: Deserializer<'de>,
 {
     let s = <&str>::deserialize(deserializer)?;
-    let f = s.parse::<f32>().map_err(de::Error::custom)?;
-
-    Ok(format!("{f:.2}"))
+    s.parse::<f64>().map_err(de::Error::custom)
 }
 
 fn u64_as_time_formatted<'de, D>(deserializer: D) -> Result<String, D::Error>
@@ -46,11 +51,22 @@ where
 
 #[derive(Debug, Deserialize, Clone)]
 pub(crate) struct TradesEvent {
-    #[serde(rename = "p", deserialize_with = "str_as_f32_as_str_formatted")]
-    pub(crate) price: String,
+    #[serde(rename = "s")]
+    pub(crate) symbol: String,
+
+    /// Aggregate trade id, increases with every trade
+    #[serde(rename = "a")]
+    pub(crate) id: u64,
+
+    /// Event time in milliseconds
+    #[serde(rename = "E")]
+    pub(crate) time: u64,
+
+    #[serde(rename = "p", deserialize_with = "str_as_f64")]
+    pub(crate) price: f64,
 
-    #[serde(rename = "q", deserialize_with = "str_as_f32_as_str_formatted")]
-    pub(crate) qty: String,
+    #[serde(rename = "q", deserialize_with = "str_as_f64")]
+    pub(crate) qty: f64,
 
     #[serde(rename = "T", deserialize_with = "u64_as_time_formatted")]
     pub(crate) trade_order_time_formatted: String,
@@ -59,8 +75,58 @@ pub(crate) struct TradesEvent {
     pub(crate) is_buyer_maker: bool,
 }
 
+/// Trade from raw stream, same as aggregate one except for id
+#[derive(Debug, Deserialize, Clone)]
+pub(crate) struct RawTradesEvent {
+    #[serde(rename = "s")]
+    symbol: String,
+
+    /// Trade id, numbered separately from aggregate ids
+    #[serde(rename = "t")]
+    id: u64,
+
+    #[serde(rename = "E")]
+    time: u64,
+
+    #[serde(rename = "p", deserialize_with = "str_as_f64")]
+    price: f64,
+
+    #[serde(rename = "q", deserialize_with = "str_as_f64")]
+    qty: f64,
+
+    #[serde(rename = "T", deserialize_with = "u64_as_time_formatted")]
+    trade_order_time_formatted: String,
+
+    #[serde(rename = "m")]
+    is_buyer_maker: bool,
+}
+
+impl From<RawTradesEvent> for TradesEvent {
+    fn from(value: RawTradesEvent) -> Self {
+        Self {
+            symbol: value.symbol,
+            id: value.id,
+            time: value.time,
+            price: value.price,
+            qty: value.qty,
+            trade_order_time_formatted: value.trade_order_time_formatted,
+            is_buyer_maker: value.is_buyer_maker,
+        }
+    }
+}
+
+/// Event of either stream, told apart by event type
+#[derive(Debug, Deserialize)]
+#[serde(tag = "e")]
+pub(crate) enum TradeStreamEvent {
+    #[serde(rename = "aggTrade")]
+    Aggregated(TradesEvent),
+    #[serde(rename = "trade")]
+    Raw(RawTradesEvent),
+}
+
 impl WsListener for TradesWs {
-    type Event = TradesEvent;
+    type Event = TradeStreamEvent;
     type Input = Message;
     type Output = TradesEvent;
 
@@ -68,12 +134,23 @@ impl WsListener for TradesWs {
         WsMessage::Trade(msg)
     }
 
+    fn tls_pin(&self) -> Option<&str> {
+        self.tls_pin.as_deref()
+    }
+
     async fn endpoint(&self) -> Result<String, Box<dyn Error + Send>> {
-        Ok(agg_trade_stream(&self.pair))
+        Ok(if self.aggregated {
+            agg_trade_stream(&self.pair)
+        } else {
+            trade_stream(&self.pair)
+        })
     }
 
     fn handle_event(&self, event: Self::Event) -> Self::Output {
-        event
+        match event {
+            TradeStreamEvent::Aggregated(trade) => trade,
+            TradeStreamEvent::Raw(trade) => trade.into(),
+        }
     }
 
     fn handle_input(&mut self, input: Self::Input, keep_running: &mut AtomicBool) {
@@ -86,10 +163,48 @@ impl WsListener for TradesWs {
     }
 }
 
-pub(crate) fn connect(pair: String) -> Subscription<WsMessage> {
+/// Aggregate trade stream is used if `aggregated` is set, raw one otherwise
+pub(crate) fn connect(
+    pair: String,
+    testnet: bool,
+    tls_pin: Option<String>,
+    aggregated: bool,
+) -> Subscription<WsMessage> {
     struct Connect;
 
-    subscription::channel(std::any::TypeId::of::<Connect>(), 100, |output| async {
-        TradesWs::new(pair).run(output).await
-    })
+    subscription::channel(
+        (
+            std::any::TypeId::of::<Connect>(),
+            testnet,
+            tls_pin.clone(),
+            aggregated,
+        ),
+        100,
+        move |output| async move {
+            TradesWs::new(pair, tls_pin, aggregated)
+                .run(output, testnet)
+                .await
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn both_streams_parse() {
+        let aggregated = r#"{"e":"aggTrade","E":1700000000001,"s":"BTCUSDT","a":26129,"p":"0.01633102","q":"4.7","f":100,"l":105,"T":1700000000000,"m":true,"M":true}"#;
+        let raw = r#"{"e":"trade","E":1700000000001,"s":"BTCUSDT","t":12345,"p":"0.001","q":"100","T":1700000000000,"m":false,"M":true}"#;
+
+        let ws = TradesWs::new("btcusdt".to_owned(), None, true);
+        let aggregated = ws.handle_event(serde_json::from_str(aggregated).unwrap());
+        assert_eq!(aggregated.id, 26129);
+        assert!(aggregated.is_buyer_maker);
+
+        let raw = ws.handle_event(serde_json::from_str(raw).unwrap());
+        assert_eq!(raw.id, 12345);
+        assert_eq!(raw.qty, 100.0);
+        assert!(!raw.is_buyer_maker);
+    }
 }
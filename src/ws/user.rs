@@ -1,9 +1,14 @@
-use std::{error::Error, sync::atomic::AtomicBool};
+use std::{error::Error, sync::atomic::AtomicBool, time::Duration};
 
-use binance::{api::Binance, userstream::UserStream, ws_model::WebsocketEvent};
+use binance::{
+    api::Binance,
+    rest_model::Order,
+    userstream::UserStream,
+    ws_model::{OrderUpdate, WebsocketEvent},
+};
 use iced::subscription::{self, Subscription};
 
-use crate::ws::WsEvent;
+use crate::{api::binance_config, ws::WsEvent};
 
 use super::{WsListener, WsMessage};
 
@@ -15,11 +20,20 @@ pub(crate) enum Message {
 #[derive(Debug)]
 pub(crate) struct UserWs {
     api_key: String,
+    testnet: bool,
 }
 
 impl UserWs {
-    fn new(api_key: String) -> Self {
-        Self { api_key }
+    fn new(api_key: String, testnet: bool) -> Self {
+        Self { api_key, testnet }
+    }
+
+    fn user_stream(&self) -> UserStream {
+        Binance::new_with_config(
+            Some(self.api_key.clone()),
+            None,
+            &binance_config(self.testnet),
+        )
     }
 }
 
@@ -28,20 +42,30 @@ impl WsListener for UserWs {
     type Input = Message;
     type Output = WebsocketEvent;
 
+    /// Listen key expires after an hour without keepalive
+    const KEEPALIVE: Option<Duration> = Some(Duration::from_secs(30 * 60));
+    /// Only sends events when account changes
+    const STALL_TIMEOUT: Option<Duration> = None;
+
     fn message(&self, msg: WsEvent<Self::Input, Self::Output>) -> WsMessage {
         WsMessage::User(msg)
     }
 
     async fn endpoint(&self) -> Result<String, Box<dyn Error + Send>> {
-        let user_stream: UserStream = Binance::new(Some(self.api_key.clone()), None);
-
-        user_stream
+        self.user_stream()
             .start()
             .await
             .map(|answer| answer.listen_key)
             .map_err(|e| Box::new(e) as _)
     }
 
+    /// Endpoint of user stream is its listen key
+    async fn keepalive(&self, listen_key: &str) {
+        if let Err(e) = self.user_stream().keep_alive(listen_key).await {
+            tracing::error!("listen key keepalive failed: {e}");
+        }
+    }
+
     fn handle_event(&self, event: Self::Event) -> Self::Output {
         event
     }
@@ -56,10 +80,77 @@ impl WsListener for UserWs {
     }
 }
 
-pub fn connect(api_key: String) -> Subscription<WsMessage> {
+/// Updates order in place or inserts it as newest
+pub(crate) fn apply_order_update(orders: &mut Vec<Order>, o: OrderUpdate) {
+    if let Some(order) = orders.iter_mut().find(|order| order.order_id == o.order_id) {
+        order.executed_qty = o.cumulative_filled_qty;
+        order.cummulative_quote_qty = o.cumulative_quote_asset_transacted_qty;
+        order.status = o.current_order_status;
+        order.update_time = o.trade_order_time;
+        order.is_working = o.is_order_on_the_book;
+        return;
+    }
+
+    orders.insert(
+        0,
+        Order {
+            symbol: o.symbol,
+            order_id: o.order_id,
+            order_list_id: o.order_list_id as i32,
+            client_order_id: o.client_order_id.unwrap_or_default(),
+            price: o.price,
+            orig_qty: o.qty,
+            executed_qty: o.cumulative_filled_qty,
+            cummulative_quote_qty: o.cumulative_quote_asset_transacted_qty,
+            status: o.current_order_status,
+            time_in_force: o.time_in_force,
+            order_type: o.order_type,
+            side: o.side,
+            stop_price: o.stop_price,
+            iceberg_qty: o.iceberg_qty,
+            time: o.order_creation_time,
+            update_time: o.trade_order_time,
+            is_working: o.is_order_on_the_book,
+            orig_quote_order_qty: o.quote_order_qty,
+        },
+    );
+}
+
+pub fn connect(api_key: String, testnet: bool) -> Subscription<WsMessage> {
     struct Connect;
 
-    subscription::channel(std::any::TypeId::of::<Connect>(), 100, |output| async {
-        UserWs::new(api_key).run(output).await
-    })
+    subscription::channel(
+        (std::any::TypeId::of::<Connect>(), testnet),
+        100,
+        move |output| async move { UserWs::new(api_key, testnet).run(output, testnet).await },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use binance::rest_model::OrderStatus;
+
+    fn update(status: &str, filled: &str) -> OrderUpdate {
+        serde_json::from_value(serde_json::json!({
+            "e": "executionReport", "E": 2, "s": "BTCUSDT", "c": "client", "S": "BUY",
+            "o": "LIMIT", "f": "GTC", "q": "2.0", "p": "100.0", "P": "0", "F": "0",
+            "g": -1, "C": "", "x": "TRADE", "X": status, "r": "NONE", "i": 7,
+            "l": "1.0", "z": filled, "L": "100.0", "n": "0", "N": null, "T": 3,
+            "t": 1, "w": true, "m": false, "O": 1, "Z": "100.0", "Y": "100.0", "Q": "0",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn order_updates_by_id() {
+        let mut orders = Vec::new();
+        apply_order_update(&mut orders, update("PARTIALLY_FILLED", "1.0"));
+        apply_order_update(&mut orders, update("FILLED", "2.0"));
+
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].status, OrderStatus::Filled);
+        assert_eq!(orders[0].executed_qty, 2.0);
+        assert_eq!(orders[0].time, 1);
+    }
 }